@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::access::base::{
@@ -38,33 +38,95 @@ pub struct RoleNode {
     pub roles: HashMap<Role, Node>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct ChangedPath {
+    pub path: String,
+    pub added: Vec<Permission>,
+    pub removed: Vec<Permission>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct AccessControlDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ChangedPath>,
+}
+
+impl AccessControlDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub struct AccessControl {
     paths: HashMap<String, Vec<Permission>>,
     _root: HashMap<Resource, RoleNode>,
 }
 
-impl From<&str> for AccessControl {
-    fn from(value: &str) -> Self {
-        let root: HashMap<Resource, RoleNode> =
-            serde_json::from_str(value).expect("Invalid JSON string for AccessControl");
+impl TryFrom<&str> for AccessControl {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let root: HashMap<Resource, RoleNode> = serde_json::from_str(value)
+            .map_err(|e| format!("Invalid access control schema: {}", e))?;
+        if root.keys().any(|r| r != &Resource::App) {
+            return Err("Invalid access control schema: the root resource must be APP".to_string());
+        }
         let mut paths: HashMap<String, Vec<Permission>> = HashMap::new();
         collect_paths(&root, "", &mut paths);
-        AccessControl { paths, _root: root }
+        Ok(AccessControl { paths, _root: root })
     }
 }
 
 impl AccessControl {
     pub fn with_default_schema() -> Self {
         let schema_str = include_str!("schema.json");
-        AccessControl::from(schema_str)
+        AccessControl::try_from(schema_str).expect("Invalid default access control schema")
+    }
+
+    pub fn diff(&self, other: &AccessControl) -> AccessControlDiff {
+        let mut diff = AccessControlDiff::default();
+        for (path, permissions) in &other.paths {
+            match self.paths.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(current) => {
+                    let added = permissions
+                        .iter()
+                        .filter(|p| !current.contains(p))
+                        .cloned()
+                        .collect::<Vec<Permission>>();
+                    let removed = current
+                        .iter()
+                        .filter(|p| !permissions.contains(p))
+                        .cloned()
+                        .collect::<Vec<Permission>>();
+                    if !added.is_empty() || !removed.is_empty() {
+                        diff.changed.push(ChangedPath {
+                            path: path.clone(),
+                            added,
+                            removed,
+                        });
+                    }
+                }
+            }
+        }
+        diff.removed = self
+            .paths
+            .keys()
+            .filter(|path| !other.paths.contains_key(*path))
+            .cloned()
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort_by(|a, b| a.path.cmp(&b.path));
+        diff
     }
 
     pub fn who_can(&self, permission: &Permission) -> Vec<AccessPath> {
         self.paths
             .iter()
             .filter(|v| v.1.contains(&permission))
-            .map(|v| AccessPath::from(v.0.as_str()))
+            .filter_map(|v| AccessPath::try_from(v.0.as_str()).ok())
             .collect::<Vec<AccessPath>>()
     }
 
@@ -87,13 +149,16 @@ impl AccessControl {
 
 #[cfg(test)]
 mod schema_variant_tests {
-    use crate::access::base::{access_control, path::AccessPath, permission::Permission};
+    use crate::access::base::{
+        access_control, control::AccessControl, path::AccessPath, permission::Permission,
+        role::Role,
+    };
 
     #[test]
     fn test_app_guest_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->GUEST");
+        let path = AccessPath::try_from("APP->GUEST").unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0); // APP->GUEST has empty permissions array
     }
@@ -102,7 +167,7 @@ mod schema_variant_tests {
     fn test_app_member_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER");
+        let path = AccessPath::try_from("APP->MEMBER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::CreateDiscussion));
         assert_eq!(permissions.len(), 1);
@@ -110,7 +175,7 @@ mod schema_variant_tests {
     #[test]
     fn test_app_admin_permissions() {
         let ac = access_control();
-        let path = AccessPath::from("APP->ADMIN");
+        let path = AccessPath::try_from("APP->ADMIN").unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -119,7 +184,7 @@ mod schema_variant_tests {
     fn test_discussion_public_guest_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->GUEST->DISCUSSION:PUBLIC->GUEST");
+        let path = AccessPath::try_from("APP->GUEST->DISCUSSION:PUBLIC->GUEST").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert_eq!(permissions.len(), 1);
@@ -129,33 +194,97 @@ mod schema_variant_tests {
     fn test_discussion_public_owner_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PUBLIC->OWNER");
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->OWNER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
         assert!(permissions.contains(&Permission::CreatePublicPost));
         assert!(permissions.contains(&Permission::CreateIdeaPost));
+        assert!(permissions.contains(&Permission::CreatePrivatePost));
+        assert!(permissions.contains(&Permission::CreatePaidPost));
+        assert!(permissions.contains(&Permission::MuteMember));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(permissions.contains(&Permission::ManageRoles));
+        assert!(permissions.contains(&Permission::TransferOwnership));
+        assert_eq!(permissions.len(), 10);
+    }
+
+    #[test]
+    fn test_discussion_public_subscriber_permissions() {
+        let ac = access_control();
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->SUBSCRIBER").unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert_eq!(permissions.len(), 1);
+    }
+
+    #[test]
+    fn test_post_private_under_discussion_public() {
+        let ac = access_control();
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->SUBSCRIBER->POST:PRIVATE->GUEST")
+                .unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert!(permissions.contains(&Permission::CreateReply));
+        assert!(permissions.contains(&Permission::CreateReplyForReply));
+        assert!(permissions.contains(&Permission::Like));
         assert_eq!(permissions.len(), 4);
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PRIVATE->GUEST")
+                .unwrap();
+        assert!(!ac.can(&path, &Permission::View));
+    }
+
+    #[test]
+    fn test_post_paid_under_discussion_public() {
+        let ac = access_control();
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PAID->GUEST")
+            .unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert!(permissions.contains(&Permission::Buy));
+        assert_eq!(permissions.len(), 2);
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PAID->BUYER")
+            .unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::ViewContent));
+        assert!(!permissions.contains(&Permission::Buy));
+        assert_eq!(permissions.len(), 5);
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PAID->OWNER")
+            .unwrap();
+        assert!(ac.can(&path, &Permission::ViewContent));
+        assert!(!ac.can(&path, &Permission::Buy));
     }
 
     #[test]
     fn test_admin_discussion_public_owner_permissions() {
         let ac = access_control();
-        let path = AccessPath::from("APP->ADMIN->DISCUSSION:PUBLIC->OWNER");
+        let path = AccessPath::try_from("APP->ADMIN->DISCUSSION:PUBLIC->OWNER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
         assert!(permissions.contains(&Permission::CreatePublicPost));
         assert!(permissions.contains(&Permission::CreatePublicTask));
         assert!(permissions.contains(&Permission::CreatePrivateTask));
-        assert_eq!(permissions.len(), 5);
+        assert!(permissions.contains(&Permission::MuteMember));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(permissions.contains(&Permission::ManageRoles));
+        assert!(permissions.contains(&Permission::TransferOwnership));
+        assert_eq!(permissions.len(), 9);
     }
 
     #[test]
     fn test_discussion_public_guest_nested_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST");
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert_eq!(permissions.len(), 1);
@@ -164,7 +293,7 @@ mod schema_variant_tests {
     #[test]
     fn test_admin_discussion_public_guest_nested_permissions() {
         let ac = access_control();
-        let path = AccessPath::from("APP->ADMIN->DISCUSSION:PUBLIC->GUEST");
+        let path = AccessPath::try_from("APP->ADMIN->DISCUSSION:PUBLIC->GUEST").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert_eq!(permissions.len(), 1);
@@ -174,21 +303,26 @@ mod schema_variant_tests {
     fn test_post_public_guest_under_discussion_public_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
         assert!(permissions.contains(&Permission::CreateReplyForReply));
         assert!(permissions.contains(&Permission::CreatePrivateTask));
         assert!(permissions.contains(&Permission::Like));
-        assert_eq!(permissions.len(), 5);
+        assert!(!permissions.contains(&Permission::Delete));
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert_eq!(permissions.len(), 7);
     }
 
     #[test]
     fn test_admin_discussion_private_owner_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->ADMIN->DISCUSSION:PRIVATE->OWNER");
+        let path = AccessPath::try_from("APP->ADMIN->DISCUSSION:PRIVATE->OWNER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -200,14 +334,18 @@ mod schema_variant_tests {
         assert!(permissions.contains(&Permission::AddMember));
         assert!(permissions.contains(&Permission::RemoveMember));
         assert!(permissions.contains(&Permission::Alias));
-        assert_eq!(permissions.len(), 9);
+        assert!(permissions.contains(&Permission::MuteMember));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(permissions.contains(&Permission::ManageRoles));
+        assert!(permissions.contains(&Permission::TransferOwnership));
+        assert_eq!(permissions.len(), 13);
     }
 
     #[test]
     fn test_admin_discussion_private_member_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->ADMIN->DISCUSSION:PRIVATE->MEMBER");
+        let path = AccessPath::try_from("APP->ADMIN->DISCUSSION:PRIVATE->MEMBER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreatePublicPost));
@@ -223,7 +361,7 @@ mod schema_variant_tests {
     fn test_admin_discussion_private_editor_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->ADMIN->DISCUSSION:PRIVATE->EDITOR");
+        let path = AccessPath::try_from("APP->ADMIN->DISCUSSION:PRIVATE->EDITOR").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -239,7 +377,8 @@ mod schema_variant_tests {
     #[test]
     fn test_post_public_guest_under_discussion_public_guest() {
         let ac = access_control();
-        let path = AccessPath::from("APP->GUEST->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST");
+        let path = AccessPath::try_from("APP->GUEST->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST")
+            .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert_eq!(permissions.len(), 1);
@@ -249,7 +388,9 @@ mod schema_variant_tests {
     fn test_post_public_guest_under_member_discussion_guest() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
@@ -263,9 +404,10 @@ mod schema_variant_tests {
     fn test_task_public_owner_under_post_public_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->OWNER->TASK:PUBLIC->OWNER",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -274,9 +416,10 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_post_public_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->OWNER->TASK:PUBLIC->GUEST",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -285,9 +428,10 @@ mod schema_variant_tests {
     fn test_task_public_participant_under_post_public_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->OWNER->TASK:PUBLIC->PARTICIPANT",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -296,9 +440,10 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_post_public_guest() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->GUEST->TASK:PUBLIC->GUEST",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -307,9 +452,10 @@ mod schema_variant_tests {
     fn test_task_public_participant_under_post_public_guest() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->GUEST->TASK:PUBLIC->PARTICIPANT",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert_eq!(permissions.len(), 0);
     }
@@ -318,9 +464,10 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_guest_path() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->GUEST->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST->TASK:PUBLIC->GUEST",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert_eq!(permissions.len(), 1);
@@ -330,9 +477,10 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_member_discussion_guest() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST->TASK:PRIVATE->GUEST",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Donate));
@@ -343,9 +491,10 @@ mod schema_variant_tests {
     fn test_task_public_participant_under_member_discussion_guest() {
         let ac = access_control();
 
-        let path = AccessPath::from(
+        let path = AccessPath::try_from(
             "APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST->TASK:PRIVATE->PARTICIPANT",
-        );
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::DeliverTask));
         assert!(permissions.contains(&Permission::View));
@@ -357,7 +506,7 @@ mod schema_variant_tests {
     fn test_discussion_private_owner_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER");
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -369,14 +518,18 @@ mod schema_variant_tests {
         assert!(permissions.contains(&Permission::AddMember));
         assert!(permissions.contains(&Permission::RemoveMember));
         assert!(permissions.contains(&Permission::Alias));
-        assert_eq!(permissions.len(), 9);
+        assert!(permissions.contains(&Permission::MuteMember));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(permissions.contains(&Permission::ManageRoles));
+        assert!(permissions.contains(&Permission::TransferOwnership));
+        assert_eq!(permissions.len(), 13);
     }
 
     #[test]
     fn test_discussion_private_member_permissions() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER");
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER").unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreatePublicPost));
@@ -388,12 +541,72 @@ mod schema_variant_tests {
         assert_eq!(permissions.len(), 6);
     }
 
+    #[test]
+    fn test_discussion_public_moderator_permissions() {
+        let ac = access_control();
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->MODERATOR").unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert!(permissions.contains(&Permission::MuteMember));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(!permissions.contains(&Permission::Edit));
+        assert!(!permissions.contains(&Permission::ManageRoles));
+        assert!(!permissions.contains(&Permission::TransferOwnership));
+        assert_eq!(permissions.len(), 6);
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->MODERATOR->POST:PUBLIC->GUEST")
+                .unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::Delete));
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert!(!permissions.contains(&Permission::Edit));
+    }
+
+    #[test]
+    fn test_discussion_muted_permissions() {
+        let ac = access_control();
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->MUTED").unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert_eq!(permissions.len(), 1);
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->MUTED->POST:PUBLIC->GUEST")
+                .unwrap();
+        assert!(ac.can(&path, &Permission::View));
+        assert!(!ac.can(&path, &Permission::CreateReply));
+        assert!(!ac.can(&path, &Permission::Like));
+
+        let path = AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MUTED").unwrap();
+        let permissions = ac.what_can(&path);
+        assert!(permissions.contains(&Permission::View));
+        assert_eq!(permissions.len(), 1);
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MUTED->TASK:PUBLIC->GUEST")
+                .unwrap();
+        assert!(ac.can(&path, &Permission::View));
+        assert!(!ac.can(&path, &Permission::AcceptTask));
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->MUTED->POST:PRIVATE->MEMBER")
+                .unwrap();
+        assert!(ac.can(&path, &Permission::View));
+        assert!(!ac.can(&path, &Permission::CreateReply));
+    }
+
     // Private discussion POST tests
     #[test]
     fn test_post_private_owner_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PRIVATE->OWNER");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PRIVATE->OWNER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -402,19 +615,26 @@ mod schema_variant_tests {
         assert!(permissions.contains(&Permission::AddMember));
         assert!(permissions.contains(&Permission::RemoveMember));
         assert!(permissions.contains(&Permission::Delete));
-        assert_eq!(permissions.len(), 7);
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert_eq!(permissions.len(), 9);
     }
 
     #[test]
     fn test_post_private_member_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PRIVATE->MEMBER");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PRIVATE->MEMBER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
         assert!(permissions.contains(&Permission::Like));
-        assert_eq!(permissions.len(), 3);
+        assert!(!permissions.contains(&Permission::Delete));
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert_eq!(permissions.len(), 5);
     }
 
     #[test]
@@ -422,7 +642,8 @@ mod schema_variant_tests {
         let ac = access_control();
 
         let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->POST:PRIVATE->MEMBER");
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->POST:PRIVATE->MEMBER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
@@ -434,33 +655,44 @@ mod schema_variant_tests {
     fn test_post_public_owner_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PUBLIC->OWNER");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PUBLIC->OWNER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
         assert!(permissions.contains(&Permission::CreateReply));
         assert!(permissions.contains(&Permission::Like));
         assert!(permissions.contains(&Permission::Delete));
-        assert_eq!(permissions.len(), 5);
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert_eq!(permissions.len(), 7);
     }
 
     #[test]
     fn test_post_public_guest_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->POST:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
         assert!(permissions.contains(&Permission::Like));
-        assert_eq!(permissions.len(), 3);
+        assert!(!permissions.contains(&Permission::Delete));
+        assert!(permissions.contains(&Permission::DeleteReply));
+        assert!(permissions.contains(&Permission::Pin));
+        assert_eq!(permissions.len(), 5);
     }
 
     #[test]
     fn test_post_public_guest_under_discussion_private_member() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->POST:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->POST:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::CreateReply));
@@ -473,7 +705,9 @@ mod schema_variant_tests {
     fn test_task_private_owner_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->OWNER");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->OWNER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -485,7 +719,9 @@ mod schema_variant_tests {
     fn test_task_private_guest_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Donate));
@@ -497,7 +733,8 @@ mod schema_variant_tests {
         let ac = access_control();
 
         let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->CANDIDATE");
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->CANDIDATE")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::AcceptTask));
@@ -509,8 +746,10 @@ mod schema_variant_tests {
     fn test_task_private_participant_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->PARTICIPANT");
+        let path = AccessPath::try_from(
+            "APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PRIVATE->PARTICIPANT",
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::DeliverTask));
@@ -521,8 +760,10 @@ mod schema_variant_tests {
     fn test_task_private_candidate_under_discussion_private_member() {
         let ac = access_control();
 
-        let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PRIVATE->CANDIDATE");
+        let path = AccessPath::try_from(
+            "APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PRIVATE->CANDIDATE",
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::AcceptTask));
@@ -534,8 +775,10 @@ mod schema_variant_tests {
     fn test_task_private_participant_under_discussion_private_member() {
         let ac = access_control();
 
-        let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PRIVATE->PARTICIPANT");
+        let path = AccessPath::try_from(
+            "APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PRIVATE->PARTICIPANT",
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::DeliverTask));
         assert!(permissions.contains(&Permission::View));
@@ -546,7 +789,9 @@ mod schema_variant_tests {
     fn test_task_public_owner_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->OWNER");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->OWNER")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::Edit));
@@ -558,7 +803,9 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::AcceptTask));
@@ -570,8 +817,10 @@ mod schema_variant_tests {
     fn test_task_public_participant_under_discussion_private_owner() {
         let ac = access_control();
 
-        let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->PARTICIPANT");
+        let path = AccessPath::try_from(
+            "APP->MEMBER->DISCUSSION:PRIVATE->OWNER->TASK:PUBLIC->PARTICIPANT",
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::DeliverTask));
@@ -582,7 +831,9 @@ mod schema_variant_tests {
     fn test_task_public_guest_under_discussion_private_member() {
         let ac = access_control();
 
-        let path = AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PUBLIC->GUEST");
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PUBLIC->GUEST")
+                .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::AcceptTask));
@@ -594,8 +845,10 @@ mod schema_variant_tests {
     fn test_task_public_participant_under_discussion_private_member() {
         let ac = access_control();
 
-        let path =
-            AccessPath::from("APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PUBLIC->PARTICIPANT");
+        let path = AccessPath::try_from(
+            "APP->MEMBER->DISCUSSION:PRIVATE->MEMBER->TASK:PUBLIC->PARTICIPANT",
+        )
+        .unwrap();
         let permissions = ac.what_can(&path);
         assert!(permissions.contains(&Permission::View));
         assert!(permissions.contains(&Permission::DeliverTask));
@@ -720,4 +973,65 @@ mod schema_variant_tests {
             );
         }
     }
+
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(AccessControl::try_from("{").is_err());
+        assert!(AccessControl::try_from(r#"{"APP": {"UNKNOWN": {}}}"#).is_err());
+        assert!(AccessControl::try_from(r#"{"UNKNOWN": {"GUEST": {}}}"#).is_err());
+        assert!(
+            AccessControl::try_from(r#"{"APP": {"GUEST": {"permissions": ["UNKNOWN"]}}}"#).is_err()
+        );
+        assert!(AccessControl::try_from(r#"{"DISCUSSION:PUBLIC": {"GUEST": {}}}"#).is_err());
+        assert!(AccessControl::try_from(r#"{"APP": {"GUEST": {}}}"#).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_path_is_rejected() {
+        assert!(AccessPath::try_from("").is_err());
+        assert!(AccessPath::try_from("APP").is_err());
+        assert!(AccessPath::try_from("APP->UNKNOWN").is_err());
+        assert!(AccessPath::try_from("UNKNOWN->GUEST").is_err());
+        assert!(AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC").is_err());
+        assert!(AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST").is_ok());
+    }
+
+    #[test]
+    fn test_schema_diff() {
+        let current = AccessControl::try_from(
+            r#"{"APP": {"GUEST": {}, "MEMBER": {"permissions": ["CREATE_DISCUSSION"]}}}"#,
+        )
+        .unwrap();
+        let next = AccessControl::try_from(
+            r#"{"APP": {"MEMBER": {"permissions": ["VIEW"]}, "ADMIN": {}}}"#,
+        )
+        .unwrap();
+        let diff = current.diff(&next);
+        assert_eq!(diff.added, vec!["APP->ADMIN".to_string()]);
+        assert_eq!(diff.removed, vec!["APP->GUEST".to_string()]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path, "APP->MEMBER");
+        assert_eq!(diff.changed[0].added, vec![Permission::View]);
+        assert_eq!(diff.changed[0].removed, vec![Permission::CreateDiscussion]);
+        assert!(next.diff(&next).is_empty());
+    }
+
+    #[test]
+    fn test_explain_denied_permission() {
+        let ac = access_control();
+
+        let path =
+            AccessPath::try_from("APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST")
+                .unwrap();
+        let explain = ac.explain(&path, vec![], Some(Permission::Edit));
+        assert_eq!(explain.allowed, Some(false));
+        assert!(explain.permissions.contains(&Permission::View));
+        assert_eq!(explain.required_roles, vec![Role::Owner]);
+        assert!(explain
+            .required_paths
+            .contains(&"APP->MEMBER->DISCUSSION:PUBLIC->OWNER->POST:PUBLIC->OWNER".to_string()));
+
+        let explain = ac.explain(&path, vec![], Some(Permission::View));
+        assert_eq!(explain.allowed, Some(true));
+    }
 }
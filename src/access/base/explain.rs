@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use surrealdb::sql::Thing;
+
+use crate::{
+    access::base::{control::AccessControl, path::AccessPath, permission::Permission, role::Role},
+    middleware::error::AppError,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRoleView {
+    pub entity: Thing,
+    // None when the user has no record in the access table for the entity
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessExplain {
+    pub path: String,
+    pub roles: Vec<AccessRoleView>,
+    pub permissions: Vec<Permission>,
+    pub permission: Option<Permission>,
+    pub allowed: Option<bool>,
+    // roles on the entity which would grant the permission
+    pub required_roles: Vec<Role>,
+    // all paths of the same resources which would grant the permission
+    pub required_paths: Vec<String>,
+}
+
+impl AccessControl {
+    pub fn explain(
+        &self,
+        path: &AccessPath,
+        roles: Vec<AccessRoleView>,
+        permission: Option<Permission>,
+    ) -> AccessExplain {
+        let mut permissions = self.what_can(path);
+        permissions.sort();
+
+        let (allowed, required_roles, required_paths) = match permission {
+            Some(ref permission) => {
+                let segments = path.segments();
+                let mut paths = self
+                    .who_can(permission)
+                    .into_iter()
+                    .filter(|p| {
+                        let other = p.segments();
+                        other.len() == segments.len()
+                            && other.iter().zip(&segments).all(|(a, b)| a.0 == b.0)
+                    })
+                    .collect::<Vec<AccessPath>>();
+                paths.sort_by_key(|p| p.to_string());
+
+                // the entity roles granting the permission to users with the same app role
+                let mut required_roles = paths
+                    .iter()
+                    .map(|p| p.segments())
+                    .filter(|other| other.first() == segments.first())
+                    .filter_map(|other| other.last().map(|v| v.1.clone()))
+                    .collect::<Vec<Role>>();
+                required_roles.sort_by_key(|r| r.to_string());
+                required_roles.dedup();
+
+                (
+                    Some(self.can(path, permission)),
+                    required_roles,
+                    paths.iter().map(|p| p.to_string()).collect(),
+                )
+            }
+            None => (None, vec![], vec![]),
+        };
+
+        AccessExplain {
+            path: path.to_string(),
+            roles,
+            permissions,
+            permission,
+            allowed,
+            required_roles,
+            required_paths,
+        }
+    }
+}
+
+impl From<AccessExplain> for AppError {
+    fn from(value: AccessExplain) -> Self {
+        AppError::AccessDenied {
+            reason: json!(value),
+        }
+    }
+}
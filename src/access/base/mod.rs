@@ -1,15 +1,22 @@
 use crate::access::base::control::AccessControl;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, RwLock};
 
 pub mod control;
+pub mod explain;
 pub mod path;
 pub mod permission;
 pub mod resource;
 pub mod role;
 
-static GLOBAL_ACCESS_CONTROL: LazyLock<AccessControl> =
-    LazyLock::new(|| AccessControl::with_default_schema());
+static GLOBAL_ACCESS_CONTROL: LazyLock<RwLock<Arc<AccessControl>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(AccessControl::with_default_schema())));
 
-pub fn access_control() -> &'static AccessControl {
-    &GLOBAL_ACCESS_CONTROL
+pub fn access_control() -> Arc<AccessControl> {
+    GLOBAL_ACCESS_CONTROL.read().unwrap().clone()
+}
+
+// swaps the schema for all new checks and returns the previous one
+pub fn set_access_control(access_control: AccessControl) -> Arc<AccessControl> {
+    let mut current = GLOBAL_ACCESS_CONTROL.write().unwrap();
+    std::mem::replace(&mut *current, Arc::new(access_control))
 }
@@ -7,18 +7,22 @@ pub struct AccessPath {
     pub next: Option<Box<AccessPath>>,
 }
 
-impl From<&str> for AccessPath {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for AccessPath {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let segments: Vec<&str> = value.split("->").collect();
         if segments.len() % 2 != 0 {
-            panic!("Invalid path format: segments must be pairs of resource and role");
+            return Err(
+                "Invalid path format: segments must be pairs of resource and role".to_string(),
+            );
         }
 
         let mut resource_roles: Option<Box<AccessPath>> = None;
         let mut i = segments.len();
         while i >= 2 {
-            let resource = Resource::from(segments[i - 2]);
-            let role = Role::from(segments[i - 1]);
+            let resource = Resource::try_from(segments[i - 2])?;
+            let role = Role::try_from(segments[i - 1])?;
             let rr = AccessPath {
                 name: resource,
                 role,
@@ -29,9 +33,21 @@ impl From<&str> for AccessPath {
         }
 
         match resource_roles {
-            Some(rr) => *rr,
-            None => panic!("Invalid path format"),
+            Some(rr) => Ok(*rr),
+            None => Err("Invalid path format".to_string()),
+        }
+    }
+}
+
+impl AccessPath {
+    pub fn segments(&self) -> Vec<(Resource, Role)> {
+        let mut segments = vec![(self.name.clone(), self.role.clone())];
+        let mut item = self;
+        while let Some(next) = item.next.as_deref() {
+            segments.push((next.name.clone(), next.role.clone()));
+            item = next;
         }
+        segments
     }
 }
 
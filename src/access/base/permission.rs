@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash, PartialOrd, Ord)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Permission {
     View,
@@ -8,6 +8,7 @@ pub enum Permission {
     CreatePublicPost,
     CreatePrivatePost,
     CreateIdeaPost,
+    CreatePaidPost,
     CreatePublicTask,
     CreatePrivateTask,
     CreateDiscussion,
@@ -23,4 +24,11 @@ pub enum Permission {
     Donate,
     Alias,
     Delete,
+    ViewContent,
+    Buy,
+    DeleteReply,
+    MuteMember,
+    Pin,
+    ManageRoles,
+    TransferOwnership,
 }
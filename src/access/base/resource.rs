@@ -1,24 +1,26 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
 pub enum Resource {
-    #[serde(alias = "APP")]
+    #[serde(rename = "APP")]
     App,
-    #[serde(alias = "DISCUSSION:PUBLIC")]
+    #[serde(rename = "DISCUSSION:PUBLIC")]
     DiscussionPublic,
-    #[serde(alias = "DISCUSSION:PRIVATE")]
+    #[serde(rename = "DISCUSSION:PRIVATE")]
     DiscussionPrivate,
-    #[serde(alias = "TASK:PRIVATE")]
+    #[serde(rename = "TASK:PRIVATE")]
     TaskPrivate,
-    #[serde(alias = "TASK:PUBLIC")]
+    #[serde(rename = "TASK:PUBLIC")]
     TaskPublic,
-    #[serde(alias = "POST:PUBLIC")]
+    #[serde(rename = "POST:PUBLIC")]
     PostPublic,
-    #[serde(alias = "POST:PRIVATE")]
+    #[serde(rename = "POST:PRIVATE")]
     PostPrivate,
-    #[serde(alias = "POST:IDEA")]
+    #[serde(rename = "POST:IDEA")]
     PostIdea,
+    #[serde(rename = "POST:PAID")]
+    PostPaid,
 }
 
 impl Display for Resource {
@@ -32,22 +34,26 @@ impl Display for Resource {
             Resource::PostPublic => write!(f, "POST:PUBLIC"),
             Resource::PostPrivate => write!(f, "POST:PRIVATE"),
             Resource::PostIdea => write!(f, "POST:IDEA"),
+            Resource::PostPaid => write!(f, "POST:PAID"),
         }
     }
 }
 
-impl From<&str> for Resource {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Resource {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "APP" => Resource::App,
-            "DISCUSSION:PUBLIC" => Resource::DiscussionPublic,
-            "DISCUSSION:PRIVATE" => Resource::DiscussionPrivate,
-            "TASK:PRIVATE" => Resource::TaskPrivate,
-            "TASK:PUBLIC" => Resource::TaskPublic,
-            "POST:PUBLIC" => Resource::PostPublic,
-            "POST:PRIVATE" => Resource::PostPrivate,
-            "POST:IDEA" => Resource::PostIdea,
-            _ => panic!("Unknown resource: {}", value),
+            "APP" => Ok(Resource::App),
+            "DISCUSSION:PUBLIC" => Ok(Resource::DiscussionPublic),
+            "DISCUSSION:PRIVATE" => Ok(Resource::DiscussionPrivate),
+            "TASK:PRIVATE" => Ok(Resource::TaskPrivate),
+            "TASK:PUBLIC" => Ok(Resource::TaskPublic),
+            "POST:PUBLIC" => Ok(Resource::PostPublic),
+            "POST:PRIVATE" => Ok(Resource::PostPrivate),
+            "POST:IDEA" => Ok(Resource::PostIdea),
+            "POST:PAID" => Ok(Resource::PostPaid),
+            _ => Err(format!("Unknown resource: {}", value)),
         }
     }
 }
@@ -1,7 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Role {
     Guest,
@@ -12,6 +12,10 @@ pub enum Role {
     Candidate,
     Participant,
     Editor,
+    Subscriber,
+    Buyer,
+    Moderator,
+    Muted,
 }
 
 impl Display for Role {
@@ -25,22 +29,32 @@ impl Display for Role {
             Role::Donor => write!(f, "DONOR"),
             Role::Editor => write!(f, "EDITOR"),
             Role::Admin => write!(f, "ADMIN"),
+            Role::Subscriber => write!(f, "SUBSCRIBER"),
+            Role::Buyer => write!(f, "BUYER"),
+            Role::Moderator => write!(f, "MODERATOR"),
+            Role::Muted => write!(f, "MUTED"),
         }
     }
 }
 
-impl From<&str> for Role {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Role {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "GUEST" => Role::Guest,
-            "MEMBER" => Role::Member,
-            "OWNER" => Role::Owner,
-            "CANDIDATE" => Role::Candidate,
-            "PARTICIPANT" => Role::Participant,
-            "DONOR" => Role::Donor,
-            "EDITOR" => Role::Editor,
-            "ADMIN" => Role::Admin,
-            _ => panic!("Unknown role: {}", value),
+            "GUEST" => Ok(Role::Guest),
+            "MEMBER" => Ok(Role::Member),
+            "OWNER" => Ok(Role::Owner),
+            "CANDIDATE" => Ok(Role::Candidate),
+            "PARTICIPANT" => Ok(Role::Participant),
+            "DONOR" => Ok(Role::Donor),
+            "EDITOR" => Ok(Role::Editor),
+            "ADMIN" => Ok(Role::Admin),
+            "SUBSCRIBER" => Ok(Role::Subscriber),
+            "BUYER" => Ok(Role::Buyer),
+            "MODERATOR" => Ok(Role::Moderator),
+            "MUTED" => Ok(Role::Muted),
+            _ => Err(format!("Unknown role: {}", value)),
         }
     }
 }
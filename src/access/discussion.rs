@@ -1,36 +1,47 @@
 use crate::{
     access::base::{
-        access_control, control::AccessControl, path::AccessPath, permission::Permission,
-        resource::Resource, role::Role,
+        access_control,
+        control::AccessControl,
+        explain::{AccessExplain, AccessRoleView},
+        path::AccessPath,
+        permission::Permission,
+        resource::Resource,
+        role::Role,
     },
     entities::{
         community::discussion_entity::DiscussionType,
         user_auth::local_user_entity::{LocalUser, UserRole},
     },
+    middleware::error::AppError,
     models::view::access::DiscussionAccessView,
 };
+use std::sync::Arc;
 
 impl AccessPath {
     pub fn from_discussion(
         disc: &DiscussionAccessView,
         user: Option<&LocalUser>,
         next: Option<Box<AccessPath>>,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let (user_role, disc_role) = match user {
             Some(user) => (
                 match user.role {
                     UserRole::Admin => Role::Admin,
                     UserRole::User => Role::Member,
                 },
-                disc.users
-                    .iter()
-                    .find(|u| u.user == *user.id.as_ref().unwrap())
-                    .map_or(Role::Guest, |v| Role::from(v.role.as_str())),
+                match disc.muted_users.contains(user.id.as_ref().unwrap()) {
+                    true => Role::Muted,
+                    false => disc
+                        .users
+                        .iter()
+                        .find(|u| u.user == *user.id.as_ref().unwrap())
+                        .map_or(Ok(Role::Guest), |v| Role::try_from(v.role.as_str()))?,
+                },
             ),
             None => (Role::Guest, Role::Guest),
         };
 
-        AccessPath {
+        Ok(AccessPath {
             name: Resource::App,
             role: user_role,
             next: Some(Box::new(AccessPath {
@@ -41,13 +52,13 @@ impl AccessPath {
                 role: disc_role,
                 next,
             })),
-        }
+        })
     }
 }
 
 pub struct DiscussionAccess<'a> {
     discussion: &'a DiscussionAccessView,
-    access_control: &'static AccessControl,
+    access_control: Arc<AccessControl>,
 }
 
 impl<'a> DiscussionAccess<'a> {
@@ -58,62 +69,90 @@ impl<'a> DiscussionAccess<'a> {
         }
     }
 
+    // the users with an unknown role are denied instead of treated as guests,
+    // the error is returned by denied
+    fn can(&self, user: &LocalUser, permission: Permission) -> bool {
+        AccessPath::from_discussion(self.discussion, Some(user), None)
+            .is_ok_and(|path| self.access_control.can(&path, &permission))
+    }
+
     pub fn can_edit(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::Edit)
+        self.can(user, Permission::Edit)
     }
 
     pub fn can_set_alias(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::Alias)
+        self.can(user, Permission::Alias)
     }
 
     pub fn can_add_member(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::AddMember)
+        self.can(user, Permission::AddMember)
     }
 
     pub fn can_remove_member(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::RemoveMember)
+        self.can(user, Permission::RemoveMember)
     }
 
     pub fn can_view(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::View)
+        self.can(user, Permission::View)
     }
 
     pub fn can_create_public_post(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePublicPost)
+        self.can(user, Permission::CreatePublicPost)
     }
 
     pub fn can_create_private_post(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePrivatePost)
+        self.can(user, Permission::CreatePrivatePost)
+    }
+
+    pub fn can_create_paid_post(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::CreatePaidPost)
     }
 
     pub fn can_idea_post(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control.can(&path, &Permission::CreateIdeaPost)
+        self.can(user, Permission::CreateIdeaPost)
     }
 
     pub fn can_create_private_task(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePrivateTask)
+        self.can(user, Permission::CreatePrivateTask)
     }
 
     pub fn can_create_public_task(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePublicTask)
+        self.can(user, Permission::CreatePublicTask)
     }
     pub fn can_create_post_for_post(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_discussion(self.discussion, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePostForPost)
+        self.can(user, Permission::CreatePostForPost)
+    }
+
+    pub fn can_mute_member(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::MuteMember)
+    }
+
+    pub fn can_manage_roles(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::ManageRoles)
+    }
+
+    pub fn can_transfer_ownership(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::TransferOwnership)
+    }
+
+    pub fn explain(
+        &self,
+        user: &LocalUser,
+        permission: Option<Permission>,
+    ) -> Result<AccessExplain, AppError> {
+        let path = AccessPath::from_discussion(self.discussion, Some(&user), None)
+            .map_err(|description| AppError::Generic { description })?;
+        let roles = vec![AccessRoleView {
+            entity: self.discussion.id.clone(),
+            role: self.discussion.get_user_role(user.id.as_ref().unwrap()),
+        }];
+        Ok(self.access_control.explain(&path, roles, permission))
+    }
+
+    pub fn denied(&self, user: &LocalUser, permission: Permission) -> AppError {
+        match self.explain(user, Some(permission)) {
+            Ok(explain) => explain.into(),
+            Err(err) => err,
+        }
     }
 }
@@ -1,24 +1,31 @@
 use crate::{
     access::base::{
-        access_control, control::AccessControl, path::AccessPath, permission::Permission,
-        resource::Resource, role::Role,
+        access_control,
+        control::AccessControl,
+        explain::{AccessExplain, AccessRoleView},
+        path::AccessPath,
+        permission::Permission,
+        resource::Resource,
+        role::Role,
     },
     entities::{community::post_entity::PostType, user_auth::local_user_entity::LocalUser},
+    middleware::error::AppError,
     models::view::access::PostAccessView,
 };
+use std::sync::Arc;
 
 impl AccessPath {
     pub fn from_post(
         post: &PostAccessView,
         user: Option<&LocalUser>,
         next: Option<Box<AccessPath>>,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let role = match user {
             Some(user) => post
                 .users
                 .iter()
                 .find(|u| u.user == *user.id.as_ref().unwrap())
-                .map_or(Role::Guest, |u| Role::from(u.role.as_str())),
+                .map_or(Ok(Role::Guest), |u| Role::try_from(u.role.as_str()))?,
             None => Role::Guest,
         };
 
@@ -27,6 +34,7 @@ impl AccessPath {
                 PostType::Private => Resource::PostPrivate,
                 PostType::Public => Resource::PostPublic,
                 PostType::Idea => Resource::PostIdea,
+                PostType::Paid => Resource::PostPaid,
             },
             role,
             next,
@@ -38,7 +46,7 @@ impl AccessPath {
 
 pub struct PostAccess<'a> {
     post: &'a PostAccessView,
-    access_control: &'static AccessControl,
+    access_control: Arc<AccessControl>,
 }
 
 impl<'a> PostAccess<'a> {
@@ -49,55 +57,97 @@ impl<'a> PostAccess<'a> {
         }
     }
 
+    // the users with an unknown role are denied instead of treated as guests,
+    // the error is returned by denied
+    fn can(&self, user: &LocalUser, permission: Permission) -> bool {
+        AccessPath::from_post(self.post, Some(user), None)
+            .is_ok_and(|path| self.access_control.can(&path, &permission))
+    }
+
+    // paid posts are viewable as a teaser, the content needs a purchase
+    pub fn can_view_content(&self, user: &LocalUser) -> bool {
+        match self.post.r#type {
+            PostType::Paid => self.can(user, Permission::ViewContent),
+            _ => self.can_view(user),
+        }
+    }
+
+    pub fn can_buy(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::Buy)
+    }
+
     pub fn can_edit(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::Edit)
+        self.can(user, Permission::Edit)
     }
 
     pub fn can_view(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::View)
+        self.can(user, Permission::View)
     }
 
     pub fn can_like(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::Like)
+        self.can(user, Permission::Like)
     }
 
     pub fn can_create_reply(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::CreateReply)
+        self.can(user, Permission::CreateReply)
     }
 
     pub fn can_create_reply_for_reply(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreateReplyForReply)
+        self.can(user, Permission::CreateReplyForReply)
     }
 
     pub fn can_create_public_task(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePublicTask)
+        self.can(user, Permission::CreatePublicTask)
     }
 
     pub fn can_create_private_task(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control
-            .can(&path, &Permission::CreatePrivateTask)
+        self.can(user, Permission::CreatePrivateTask)
     }
 
     pub fn can_add_member(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::AddMember)
+        self.can(user, Permission::AddMember)
     }
 
     pub fn can_remove_member(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::RemoveMember)
+        self.can(user, Permission::RemoveMember)
     }
     pub fn can_delete(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_post(self.post, Some(&user), None);
-        self.access_control.can(&path, &Permission::Delete)
+        self.can(user, Permission::Delete)
+    }
+
+    pub fn can_delete_reply(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::DeleteReply)
+    }
+
+    pub fn can_pin(&self, user: &LocalUser) -> bool {
+        self.can(user, Permission::Pin)
+    }
+
+    pub fn explain(
+        &self,
+        user: &LocalUser,
+        permission: Option<Permission>,
+    ) -> Result<AccessExplain, AppError> {
+        let path = AccessPath::from_post(self.post, Some(&user), None)
+            .map_err(|description| AppError::Generic { description })?;
+        let user_id = user.id.as_ref().unwrap();
+        let roles = vec![
+            AccessRoleView {
+                entity: self.post.discussion.id.clone(),
+                role: self.post.discussion.get_user_role(user_id),
+            },
+            AccessRoleView {
+                entity: self.post.id.clone(),
+                role: self.post.get_user_role(user_id),
+            },
+        ];
+        Ok(self.access_control.explain(&path, roles, permission))
+    }
+
+    pub fn denied(&self, user: &LocalUser, permission: Permission) -> AppError {
+        match self.explain(user, Some(permission)) {
+            Ok(explain) => explain.into(),
+            Err(err) => err,
+        }
     }
 }
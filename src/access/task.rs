@@ -1,22 +1,29 @@
 use crate::{
     access::base::{
-        access_control, control::AccessControl, path::AccessPath, permission::Permission,
-        resource::Resource, role::Role,
+        access_control,
+        control::AccessControl,
+        explain::{AccessExplain, AccessRoleView},
+        path::AccessPath,
+        permission::Permission,
+        resource::Resource,
+        role::Role,
     },
     entities::{
         task_request::TaskRequestType, user_auth::local_user_entity::LocalUser,
     },
+    middleware::error::AppError,
     models::view::access::TaskAccessView,
 };
+use std::sync::Arc;
 
 impl AccessPath {
-    pub fn from_task(task: &TaskAccessView, user: Option<&LocalUser>) -> Self {
+    pub fn from_task(task: &TaskAccessView, user: Option<&LocalUser>) -> Result<Self, String> {
         let role = match user {
             Some(user) => task
                 .users
                 .iter()
                 .find(|u| u.user == *user.id.as_ref().unwrap())
-                .map_or(Role::Guest, |u| Role::from(u.role.as_str())),
+                .map_or(Ok(Role::Guest), |u| Role::try_from(u.role.as_str()))?,
             None => Role::Guest,
         };
 
@@ -34,14 +41,14 @@ impl AccessPath {
         } else if let Some(ref disc_view) = task.discussion {
             AccessPath::from_discussion(disc_view, user, access_path)
         } else {
-            *access_path.unwrap()
+            Ok(*access_path.unwrap())
         }
     }
 }
 
 pub struct TaskAccess<'a> {
     task: &'a TaskAccessView,
-    access_control: &'static AccessControl,
+    access_control: Arc<AccessControl>,
 }
 
 impl<'a> TaskAccess<'a> {
@@ -52,33 +59,72 @@ impl<'a> TaskAccess<'a> {
         }
     }
 
+    // the users with an unknown role are denied instead of treated as guests,
+    // the error is returned by denied
+    fn can(&self, user: &LocalUser, permission: Permission) -> bool {
+        AccessPath::from_task(self.task, Some(user))
+            .is_ok_and(|path| self.access_control.can(&path, &permission))
+    }
+
     pub fn can_edit(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::Edit)
+        self.can(user, Permission::Edit)
     }
 
     pub fn can_donate(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::Donate)
+        self.can(user, Permission::Donate)
     }
 
     pub fn can_accept(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::AcceptTask)
+        self.can(user, Permission::AcceptTask)
     }
 
     pub fn can_deliver(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::DeliverTask)
+        self.can(user, Permission::DeliverTask)
     }
 
     pub fn can_reject(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::RejectTask)
+        self.can(user, Permission::RejectTask)
     }
 
     pub fn can_view(&self, user: &LocalUser) -> bool {
-        let path = AccessPath::from_task(self.task, Some(&user));
-        self.access_control.can(&path, &Permission::View)
+        self.can(user, Permission::View)
+    }
+
+    pub fn explain(
+        &self,
+        user: &LocalUser,
+        permission: Option<Permission>,
+    ) -> Result<AccessExplain, AppError> {
+        let path = AccessPath::from_task(self.task, Some(&user))
+            .map_err(|description| AppError::Generic { description })?;
+        let user_id = user.id.as_ref().unwrap();
+        let mut roles = vec![];
+        if let Some(ref post) = self.task.post {
+            roles.push(AccessRoleView {
+                entity: post.discussion.id.clone(),
+                role: post.discussion.get_user_role(user_id),
+            });
+            roles.push(AccessRoleView {
+                entity: post.id.clone(),
+                role: post.get_user_role(user_id),
+            });
+        } else if let Some(ref disc) = self.task.discussion {
+            roles.push(AccessRoleView {
+                entity: disc.id.clone(),
+                role: disc.get_user_role(user_id),
+            });
+        }
+        roles.push(AccessRoleView {
+            entity: self.task.id.clone(),
+            role: self.task.get_user_role(user_id),
+        });
+        Ok(self.access_control.explain(&path, roles, permission))
+    }
+
+    pub fn denied(&self, user: &LocalUser, permission: Permission) -> AppError {
+        match self.explain(user, Some(permission)) {
+            Ok(explain) => explain.into(),
+            Err(err) => err,
+        }
     }
 }
@@ -31,6 +31,24 @@ pub struct AppConfig {
     pub support_email: String,
     pub twitch_client_id: String,
     pub twitch_client_secret: String,
+    pub access_schema_path: Option<String>,
+    pub report_hide_threshold: u32,
+    pub adjustment_approval_threshold: u64,
+    pub app_url: String,
+    pub apns_key_path: Option<String>,
+    pub apns_key_id: String,
+    pub apns_team_id: String,
+    pub apns_topic: String,
+    pub apns_sandbox: bool,
+    pub fcm_credentials_path: Option<String>,
+    /// local or surrealdb to share the events between the server instances
+    pub event_bus: String,
+    pub instance_id: Option<String>,
+    /// read notifications older than the days are removed
+    pub notification_retention_days: u32,
+    pub notification_max_per_user: u32,
+    /// posts and replies are editable by their authors within the minutes after the creation
+    pub edit_window_minutes: u32,
 }
 
 impl AppConfig {
@@ -101,6 +119,40 @@ impl AppConfig {
         let support_email = std::env::var("SUPPORT_EMAIL").unwrap_or("".to_string());
         let twitch_client_id = std::env::var("TWITCH_CLIENT_ID").unwrap_or("".to_string());
         let twitch_client_secret = std::env::var("TWITCH_CLIENT_SECRET").unwrap_or("".to_string());
+        let access_schema_path = std::env::var("ACCESS_SCHEMA_PATH").ok();
+        let report_hide_threshold = std::env::var("REPORT_HIDE_THRESHOLD")
+            .unwrap_or("5".to_string())
+            .parse::<u32>()
+            .expect("REPORT_HIDE_THRESHOLD must be number");
+        let adjustment_approval_threshold = std::env::var("ADJUSTMENT_APPROVAL_THRESHOLD")
+            .unwrap_or("10000".to_string())
+            .parse::<u64>()
+            .expect("ADJUSTMENT_APPROVAL_THRESHOLD must be number");
+        let app_url = std::env::var("APP_URL").unwrap_or("http://localhost:8080".to_string());
+        let apns_key_path = std::env::var("APNS_KEY_PATH").ok();
+        let apns_key_id = std::env::var("APNS_KEY_ID").unwrap_or("".to_string());
+        let apns_team_id = std::env::var("APNS_TEAM_ID").unwrap_or("".to_string());
+        let apns_topic = std::env::var("APNS_TOPIC").unwrap_or("".to_string());
+        let apns_sandbox = std::env::var("APNS_SANDBOX")
+            .map(|v| v.eq("true"))
+            .unwrap_or(false);
+        let fcm_credentials_path = std::env::var("FCM_CREDENTIALS_PATH").ok();
+        let event_bus = std::env::var("EVENT_BUS").unwrap_or("local".to_string());
+        let instance_id = std::env::var("INSTANCE_ID")
+            .or(std::env::var("FLY_MACHINE_ID"))
+            .ok();
+        let notification_retention_days = std::env::var("NOTIFICATION_RETENTION_DAYS")
+            .unwrap_or("90".to_string())
+            .parse::<u32>()
+            .expect("NOTIFICATION_RETENTION_DAYS must be number");
+        let notification_max_per_user = std::env::var("NOTIFICATION_MAX_PER_USER")
+            .unwrap_or("1000".to_string())
+            .parse::<u32>()
+            .expect("NOTIFICATION_MAX_PER_USER must be number");
+        let edit_window_minutes = std::env::var("EDIT_WINDOW_MINUTES")
+            .unwrap_or("15".to_string())
+            .parse::<u32>()
+            .expect("EDIT_WINDOW_MINUTES must be number");
 
         Self {
             db_namespace,
@@ -132,6 +184,21 @@ impl AppConfig {
             support_email,
             twitch_client_id,
             twitch_client_secret,
+            access_schema_path,
+            report_hide_threshold,
+            adjustment_approval_threshold,
+            app_url,
+            apns_key_path,
+            apns_key_id,
+            apns_team_id,
+            apns_topic,
+            apns_sandbox,
+            fcm_credentials_path,
+            event_bus,
+            instance_id,
+            notification_retention_days,
+            notification_max_per_user,
+            edit_window_minutes,
         }
     }
 }
@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use crate::database::repositories::access::AccessRepository;
+use crate::database::repositories::access_invite::AccessInviteRepository;
+use crate::database::repositories::access_schema::AccessSchemaRepository;
 use crate::database::repositories::discussion_user::DiscussionUserRepository;
+use crate::database::repositories::edit_history::EditHistoryRepository;
 use crate::database::repositories::editor_tags::EditorTagsRepository;
 use crate::database::repositories::like::LikesRepository;
 use crate::database::repositories::post_user::PostUserRepository;
 use crate::database::repositories::reply::RepliesRepository;
+use crate::database::repositories::report::ReportsRepository;
+use crate::database::repositories::subscriptions::SubscriptionsRepository;
 use crate::database::repositories::task_donors::TaskDonorsRepository;
 use crate::database::repositories::task_participants::TaskParticipantsRepository;
 use crate::database::repositories::user_nicknames::NicknamesRepository;
@@ -50,6 +55,11 @@ pub struct Database {
     pub discussion_users: DiscussionUserRepository,
     pub nicknames: NicknamesRepository,
     pub editor_tags: EditorTagsRepository,
+    pub subscriptions: SubscriptionsRepository,
+    pub access_schemas: AccessSchemaRepository,
+    pub access_invites: AccessInviteRepository,
+    pub reports: ReportsRepository,
+    pub edit_history: EditHistoryRepository,
 }
 
 impl Database {
@@ -94,6 +104,11 @@ impl Database {
             post_users: PostUserRepository::new(client.clone()),
             nicknames: NicknamesRepository::new(client.clone()),
             editor_tags: EditorTagsRepository::new(client.clone()),
+            subscriptions: SubscriptionsRepository::new(client.clone()),
+            access_schemas: AccessSchemaRepository::new(client.clone()),
+            access_invites: AccessInviteRepository::new(client.clone()),
+            reports: ReportsRepository::new(client.clone()),
+            edit_history: EditHistoryRepository::new(client.clone()),
             discussion_users: DiscussionUserRepository::new(client),
         }
     }
@@ -112,6 +127,11 @@ impl Database {
         self.discussion_users.mutate_db().await?;
         self.nicknames.mutate_db().await?;
         self.editor_tags.mutate_db().await?;
+        self.subscriptions.mutate_db().await?;
+        self.access_schemas.mutate_db().await?;
+        self.access_invites.mutate_db().await?;
+        self.reports.mutate_db().await?;
+        self.edit_history.mutate_db().await?;
         Ok(())
     }
 }
@@ -1,12 +1,17 @@
 use crate::database::client::Db;
 use crate::database::surrdb_utils::get_thing;
-use crate::database::table_names::ACCESS_TABLE_NAME;
+use crate::database::table_names::{ACCESS_TABLE_NAME, DISC_MUTE_TABLE_NAME};
+use crate::entities::access_user::AccessGrant;
+use crate::entities::community::discussion_entity::TABLE_NAME as DISC_TABLE_NAME;
 use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
 use crate::interfaces::repositories::access::AccessRepositoryInterface;
 use crate::middleware::error::{AppError, AppResult};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
-use surrealdb::sql::Thing;
+use surrealdb::engine::any;
+use surrealdb::method::Query;
+use surrealdb::sql::{Datetime, Thing};
 
 #[derive(Debug)]
 pub struct AccessRepository {
@@ -24,8 +29,14 @@ impl AccessRepository {
     DEFINE INDEX IF NOT EXISTS in_out_unique_idx ON {ACCESS_TABLE_NAME} FIELDS in, out UNIQUE;
     DEFINE FIELD IF NOT EXISTS created_at ON TABLE {ACCESS_TABLE_NAME} TYPE datetime DEFAULT time::now();
     DEFINE FIELD IF NOT EXISTS role ON TABLE {ACCESS_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS expires_at ON TABLE {ACCESS_TABLE_NAME} TYPE option<datetime>;
+    DEFINE INDEX IF NOT EXISTS idx_expires_at ON TABLE {ACCESS_TABLE_NAME} COLUMNS expires_at;
     DEFINE INDEX IF NOT EXISTS idx_role ON TABLE {ACCESS_TABLE_NAME} COLUMNS role;
 
+    DEFINE TABLE IF NOT EXISTS {DISC_MUTE_TABLE_NAME} TYPE RELATION IN {USER_TABLE_NAME} OUT {DISC_TABLE_NAME} ENFORCED SCHEMAFULL PERMISSIONS NONE;
+    DEFINE INDEX IF NOT EXISTS in_out_unique_idx ON {DISC_MUTE_TABLE_NAME} FIELDS in, out UNIQUE;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {DISC_MUTE_TABLE_NAME} TYPE datetime DEFAULT time::now();
+
     ");
         let mutation = self.client.query(sql).await?;
 
@@ -38,20 +49,35 @@ impl AccessRepository {
 #[async_trait]
 impl AccessRepositoryInterface for AccessRepository {
     async fn add(&self, users: Vec<Thing>, entities: Vec<&str>, role: String) -> AppResult<()> {
+        self.add_with_expiry(users, entities, role, None).await
+    }
+
+    async fn add_with_expiry(
+        &self,
+        users: Vec<Thing>,
+        entities: Vec<&str>,
+        role: String,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<()> {
         let mut things = Vec::with_capacity(entities.len());
         for id in entities {
             things.push(get_thing(id).map_err(|e| AppError::SurrealDb {
                 source: e.to_string(),
             })?);
         }
+        // expired grants are kept until the cleanup job and would block the unique index
         let _ = self
             .client
             .query(format!(
-                "RELATE $users->{ACCESS_TABLE_NAME}->$entities SET role=$role"
+                "DELETE $users->{ACCESS_TABLE_NAME} WHERE out IN $entities AND expires_at < time::now();"
+            ))
+            .query(format!(
+                "RELATE $users->{ACCESS_TABLE_NAME}->$entities SET role=$role, expires_at=$expires_at"
             ))
             .bind(("users", users))
             .bind(("entities", things))
             .bind(("role", role))
+            .bind(("expires_at", expires_at.map(Datetime::from)))
             .await?
             .check();
 
@@ -76,6 +102,25 @@ impl AccessRepositoryInterface for AccessRepository {
         Ok(())
     }
 
+    async fn set_muted(&self, user: Thing, entity: &str, muted: bool) -> AppResult<()> {
+        let thing = get_thing(entity).map_err(|e| AppError::SurrealDb {
+            source: e.to_string(),
+        })?;
+        let _ = self
+            .client
+            .query(format!(
+                "DELETE $user->{DISC_MUTE_TABLE_NAME} WHERE out = $entity;
+                IF $muted {{ RELATE $user->{DISC_MUTE_TABLE_NAME}->$entity; }};"
+            ))
+            .bind(("user", user))
+            .bind(("entity", thing))
+            .bind(("muted", muted))
+            .await?
+            .check();
+
+        Ok(())
+    }
+
     async fn remove_by_user(&self, user: Thing, entities: Vec<&str>) -> AppResult<()> {
         let mut things = Vec::with_capacity(entities.len());
         for id in entities {
@@ -96,6 +141,17 @@ impl AccessRepositoryInterface for AccessRepository {
         Ok(())
     }
 
+    async fn remove_expired(&self) -> AppResult<Vec<AccessGrant>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "DELETE {ACCESS_TABLE_NAME} WHERE expires_at < time::now() RETURN BEFORE;"
+            ))
+            .await?;
+        let data = res.take::<Vec<AccessGrant>>(0)?;
+        Ok(data)
+    }
+
     async fn remove_by_entity(&self, entity: &str, users: Vec<Thing>) -> AppResult<()> {
         let thing = get_thing(entity).map_err(|e| AppError::SurrealDb {
             source: e.to_string(),
@@ -112,4 +168,73 @@ impl AccessRepositoryInterface for AccessRepository {
 
         Ok(())
     }
+
+    async fn remove_by_role(&self, entity: &str, users: Vec<Thing>, role: String) -> AppResult<()> {
+        let thing = get_thing(entity).map_err(|e| AppError::SurrealDb {
+            source: e.to_string(),
+        })?;
+        let _ = self
+            .client
+            .query(format!(
+                "DELETE $entity<-{ACCESS_TABLE_NAME} WHERE in IN $users AND role=$role;"
+            ))
+            .bind(("users", users))
+            .bind(("entity", thing))
+            .bind(("role", role))
+            .await?
+            .check();
+
+        Ok(())
+    }
+
+    fn build_add_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        user: &Thing,
+        entity: &Thing,
+        role: String,
+    ) -> Query<'b, any::Any> {
+        query
+            .query(format!(
+                "DELETE $_access_user->{ACCESS_TABLE_NAME} WHERE out=$_access_entity AND expires_at < time::now();"
+            ))
+            .query(format!(
+                "IF $_access_entity NOT IN $_access_user->{ACCESS_TABLE_NAME}.out {{
+                    RELATE $_access_user->{ACCESS_TABLE_NAME}->$_access_entity SET role=$_access_role;
+                }};"
+            ))
+            .bind(("_access_user", user.clone()))
+            .bind(("_access_entity", entity.clone()))
+            .bind(("_access_role", role))
+    }
+
+    /// Sets the role of the user the entity is transferred to without the expiry
+    /// and the other role of the previous user
+    fn build_transfer_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        entity: &Thing,
+        from: &Thing,
+        from_role: String,
+        to: &Thing,
+        to_role: String,
+    ) -> Query<'b, any::Any> {
+        query
+            .query(format!(
+                "UPDATE $_transfer_from->{ACCESS_TABLE_NAME} SET role=$_transfer_from_role WHERE out=$_transfer_entity;"
+            ))
+            .query(format!(
+                "UPDATE $_transfer_to->{ACCESS_TABLE_NAME} SET role=$_transfer_to_role, expires_at=NONE WHERE out=$_transfer_entity;"
+            ))
+            .query(format!(
+                "IF $_transfer_entity NOT IN $_transfer_to->{ACCESS_TABLE_NAME}.out {{
+                    RELATE $_transfer_to->{ACCESS_TABLE_NAME}->$_transfer_entity SET role=$_transfer_to_role;
+                }};"
+            ))
+            .bind(("_transfer_entity", entity.clone()))
+            .bind(("_transfer_from", from.clone()))
+            .bind(("_transfer_from_role", from_role))
+            .bind(("_transfer_to", to.clone()))
+            .bind(("_transfer_to_role", to_role))
+    }
 }
@@ -0,0 +1,140 @@
+use crate::database::client::Db;
+use crate::database::table_names::ACCESS_INVITE_TABLE_NAME;
+use crate::entities::access_invite::AccessInvite;
+use crate::entities::community::discussion_entity::TABLE_NAME as DISC_TABLE_NAME;
+use crate::entities::community::post_entity::TABLE_NAME as POST_TABLE_NAME;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::interfaces::repositories::access_invite::AccessInviteRepositoryInterface;
+use crate::middleware::error::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use surrealdb::sql::{Datetime, Thing};
+
+#[derive(Debug)]
+pub struct AccessInviteRepository {
+    client: Arc<Db>,
+}
+
+impl AccessInviteRepository {
+    pub fn new(client: Arc<Db>) -> Self {
+        Self { client }
+    }
+
+    pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {ACCESS_INVITE_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS entity ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE record<{DISC_TABLE_NAME} | {POST_TABLE_NAME}>;
+    DEFINE INDEX IF NOT EXISTS entity_idx ON TABLE {ACCESS_INVITE_TABLE_NAME} COLUMNS entity;
+    DEFINE FIELD IF NOT EXISTS token ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE string;
+    DEFINE INDEX IF NOT EXISTS token_idx ON TABLE {ACCESS_INVITE_TABLE_NAME} COLUMNS token UNIQUE;
+    DEFINE FIELD IF NOT EXISTS role ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_by ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS max_uses ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE option<number>;
+    DEFINE FIELD IF NOT EXISTS uses_nr ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE number DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS expires_at ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS revoked_at ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {ACCESS_INVITE_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    ");
+        let mutation = self.client.query(sql).await?;
+
+        mutation
+            .check()
+            .expect("should mutate AccessInviteRepository");
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccessInviteRepositoryInterface for AccessInviteRepository {
+    async fn create(
+        &self,
+        entity: Thing,
+        created_by: Thing,
+        role: String,
+        max_uses: Option<u32>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<AccessInvite> {
+        let mut res = self
+            .client
+            .query(format!(
+                "CREATE {ACCESS_INVITE_TABLE_NAME} SET
+                    entity=$entity,
+                    token=rand::string(32),
+                    role=$role,
+                    created_by=$created_by,
+                    max_uses=$max_uses,
+                    expires_at=$expires_at;"
+            ))
+            .bind(("entity", entity))
+            .bind(("created_by", created_by))
+            .bind(("role", role))
+            .bind(("max_uses", max_uses))
+            .bind(("expires_at", expires_at.map(Datetime::from)))
+            .await?;
+        let data = res.take::<Option<AccessInvite>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    async fn get_by_id(&self, invite_id: &str) -> AppResult<AccessInvite> {
+        let data: Option<AccessInvite> = self
+            .client
+            .select((ACCESS_INVITE_TABLE_NAME, invite_id))
+            .await?;
+        Ok(data.ok_or(AppError::EntityFailIdNotFound {
+            ident: invite_id.to_string(),
+        })?)
+    }
+
+    async fn get_by_token(&self, token: &str) -> AppResult<AccessInvite> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {ACCESS_INVITE_TABLE_NAME} WHERE token=$token LIMIT 1;"
+            ))
+            .bind(("token", token.to_string()))
+            .await?;
+        let data = res.take::<Option<AccessInvite>>(0)?;
+        Ok(data.ok_or(AppError::EntityFailIdNotFound {
+            ident: token.to_string(),
+        })?)
+    }
+
+    async fn get_by_entity(&self, entity: Thing) -> AppResult<Vec<AccessInvite>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {ACCESS_INVITE_TABLE_NAME} WHERE entity=$entity ORDER BY created_at DESC;"
+            ))
+            .bind(("entity", entity))
+            .await?;
+        let data = res.take::<Vec<AccessInvite>>(0)?;
+        Ok(data)
+    }
+
+    async fn revoke(&self, invite_id: &str) -> AppResult<()> {
+        let _ = self
+            .client
+            .query("UPDATE $invite SET revoked_at=time::now() WHERE revoked_at IS NONE;")
+            .bind(("invite", Thing::from((ACCESS_INVITE_TABLE_NAME, invite_id))))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn increase_uses(&self, invite_id: &str) -> AppResult<bool> {
+        let mut res = self
+            .client
+            .query(
+                "UPDATE $invite SET uses_nr+=1
+                    WHERE revoked_at IS NONE
+                        AND (expires_at IS NONE OR expires_at > time::now())
+                        AND (max_uses IS NONE OR uses_nr < max_uses);",
+            )
+            .bind(("invite", Thing::from((ACCESS_INVITE_TABLE_NAME, invite_id))))
+            .await?;
+        let data = res.take::<Option<AccessInvite>>(0)?;
+        Ok(data.is_some())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::database::client::Db;
+use crate::database::table_names::ACCESS_SCHEMA_TABLE_NAME;
+use crate::entities::access_schema::AccessSchema;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::interfaces::repositories::access_schema::AccessSchemaRepositoryInterface;
+use crate::middleware::error::{AppError, AppResult};
+use async_trait::async_trait;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+
+#[derive(Debug)]
+pub struct AccessSchemaRepository {
+    client: Arc<Db>,
+}
+
+impl AccessSchemaRepository {
+    pub fn new(client: Arc<Db>) -> Self {
+        Self { client }
+    }
+
+    pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {ACCESS_SCHEMA_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS content ON TABLE {ACCESS_SCHEMA_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_by ON TABLE {ACCESS_SCHEMA_TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {ACCESS_SCHEMA_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE {ACCESS_SCHEMA_TABLE_NAME} COLUMNS created_at;
+    ");
+        let mutation = self.client.query(sql).await?;
+
+        mutation
+            .check()
+            .expect("should mutate AccessSchemaRepository");
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccessSchemaRepositoryInterface for AccessSchemaRepository {
+    async fn get_latest(&self) -> AppResult<Option<AccessSchema>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {ACCESS_SCHEMA_TABLE_NAME} ORDER BY created_at DESC LIMIT 1;"
+            ))
+            .await?;
+        let data = res.take::<Option<AccessSchema>>(0)?;
+        Ok(data)
+    }
+
+    async fn create(&self, content: String, created_by: Option<Thing>) -> AppResult<AccessSchema> {
+        let mut res = self
+            .client
+            .query(format!(
+                "CREATE {ACCESS_SCHEMA_TABLE_NAME} SET content=$content, created_by=$created_by;"
+            ))
+            .bind(("content", content))
+            .bind(("created_by", created_by))
+            .await?;
+        let data = res.take::<Option<AccessSchema>>(0)?;
+        Ok(data.unwrap())
+    }
+}
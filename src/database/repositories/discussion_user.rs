@@ -1,14 +1,18 @@
 use super::super::table_names::{ACCESS_TABLE_NAME, DISC_USER_TABLE_NAME, POST_USER_TABLE_NAME};
 use crate::database::client::Db;
-use crate::entities::community::discussion_entity::TABLE_NAME as DISC_TABLE_NAME;
+use crate::entities::community::discussion_entity::{
+    DiscussionType, TABLE_NAME as DISC_TABLE_NAME,
+};
 use crate::entities::community::post_entity::{
     PostType, PostUserStatus, TABLE_NAME as POST_TABLE_NAME,
 };
 use crate::entities::discussion_user::DiscussionUser;
+use crate::entities::user_auth::block_entity::{BlockType, TABLE_NAME as BLOCK_TABLE_NAME};
 use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
 use crate::interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface;
 use crate::middleware::error::{AppError, AppResult};
 use crate::middleware::utils::db_utils::{Pagination, QryOrder, ViewFieldSelector};
+use crate::models::view::access::ACTIVE_ACCESS_FILTER;
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::sync::Arc;
@@ -33,6 +37,8 @@ impl DiscussionUserRepository {
         DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {DISC_USER_TABLE_NAME} TYPE datetime DEFAULT time::now();
         DEFINE FIELD IF NOT EXISTS latest_post ON TABLE {DISC_USER_TABLE_NAME} TYPE option<record<{POST_TABLE_NAME}>>;
         DEFINE FIELD IF NOT EXISTS alias ON TABLE {DISC_USER_TABLE_NAME} TYPE option<string>;
+        DEFINE FIELD IF NOT EXISTS last_read_post ON TABLE {DISC_USER_TABLE_NAME} TYPE option<record<{POST_TABLE_NAME}>>;
+        DEFINE FIELD IF NOT EXISTS is_request ON TABLE {DISC_USER_TABLE_NAME} TYPE option<bool>;
 
         DEFINE INDEX IF NOT EXISTS latest_post_idx ON {DISC_USER_TABLE_NAME} COLUMNS latest_post;
         DEFINE INDEX IF NOT EXISTS alias_idx ON {DISC_USER_TABLE_NAME} COLUMNS alias;
@@ -85,12 +91,13 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
                     SET latest_post=$post,
                         nr_unread+= (IF out IN $increase_for_users THEN 1 ELSE 0 END),
                         updated_at=time::now()
-                    WHERE out IN $users;"
+                    WHERE out IN $users AND $post.created_by NOT IN out->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out;"
             ))
             .bind(("disc", Thing::from((DISC_TABLE_NAME, disc_id))))
             .bind(("increase_for_users", increase_users))
             .bind(("users", users))
             .bind(("post", Thing::from((POST_TABLE_NAME, latest_post))))
+            .bind(("block_type", BlockType::Block))
             .await?;
 
         let data = res.take::<Vec<DiscussionUser>>(0)?;
@@ -110,13 +117,14 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
              .client
             .query(format!("UPDATE $disc->{DISC_USER_TABLE_NAME}
                     SET nr_unread-= (IF latest_post->{POST_USER_TABLE_NAME}[WHERE out=$parent.out AND status=$read_status] THEN 0 ELSE 1 END),
-                        latest_post=(SELECT id FROM {POST_TABLE_NAME} WHERE belongs_to=$disc AND (type=$public_type OR $parent.out IN <-{ACCESS_TABLE_NAME}.in) ORDER BY id DESC LIMIT 1)[0].id,
+                        latest_post=(SELECT id FROM {POST_TABLE_NAME} WHERE belongs_to=$disc AND hidden != true AND (type=$public_type OR $parent.out IN <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.in) AND created_by NOT IN $parent.out->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out ORDER BY id DESC LIMIT 1)[0].id,
                         updated_at=time::now()
                     WHERE out IN $users;"))
             .bind(("disc", Thing::from((DISC_TABLE_NAME, disc_id))))
             .bind(("users", users))
             .bind(("public_type", PostType::Public))
             .bind(("read_status", PostUserStatus::Seen))
+            .bind(("block_type", BlockType::Block))
             .await?;
         let data = res.take::<Vec<DiscussionUser>>(0)?;
         Ok(data)
@@ -158,6 +166,23 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
             .bind(("_users", users))
     }
 
+    fn build_last_read_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        disc_id: &str,
+        user_id: &str,
+        post: Thing,
+    ) -> Query<'b, any::Any> {
+        query
+            .query(format!(
+                "UPDATE $_read_disc->{DISC_USER_TABLE_NAME} SET last_read_post=$_read_post
+                    WHERE out=$_reader AND (last_read_post=NONE OR last_read_post < $_read_post);"
+            ))
+            .bind(("_read_disc", Thing::from((DISC_TABLE_NAME, disc_id))))
+            .bind(("_reader", Thing::from((USER_TABLE_NAME, user_id))))
+            .bind(("_read_post", post))
+    }
+
     async fn decrease_unread_count(
         &self,
         disc_id: &str,
@@ -181,7 +206,7 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
 
     async fn get_count_of_unread(&self, user_id: &str) -> AppResult<u32> {
         let mut res = self.client
-            .query(format!("SELECT count() as count FROM {DISC_USER_TABLE_NAME} WHERE out=$user AND nr_unread > 0 GROUP ALL;"))
+            .query(format!("SELECT count() as count FROM {DISC_USER_TABLE_NAME} WHERE out=$user AND nr_unread > 0 AND is_request != true GROUP ALL;"))
             .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
             .await?;
         let data = res.take::<Option<u32>>((0, "count"))?;
@@ -213,7 +238,7 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
             .client
             .query(format!(
                 "SELECT {fields}, updated_at FROM {DISC_USER_TABLE_NAME} 
-                WHERE out=$user {latest_post_cond} {search_text_cond} 
+                WHERE out=$user AND in.hidden != true {latest_post_cond} {search_text_cond} 
                 ORDER BY updated_at {order_dir} 
                 LIMIT $limit START $start;"
             ))
@@ -227,6 +252,76 @@ impl DiscussionUserRepositoryInterface for DiscussionUserRepository {
         Ok(data)
     }
 
+    async fn get(&self, disc_id: &str, user_id: &str) -> AppResult<Option<DiscussionUser>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {DISC_USER_TABLE_NAME} WHERE in=$disc AND out=$user LIMIT 1;"
+            ))
+            .bind(("disc", Thing::from((DISC_TABLE_NAME, disc_id))))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .await?;
+        let data = res.take::<Option<DiscussionUser>>(0)?;
+        Ok(data)
+    }
+
+    async fn set_request(&self, disc_id: &str, user_id: &str, is_request: bool) -> AppResult<()> {
+        let _ = self
+            .client
+            .query(format!(
+                "UPDATE $disc->{DISC_USER_TABLE_NAME} SET is_request=$is_request WHERE out=$user"
+            ))
+            .bind(("disc", Thing::from((DISC_TABLE_NAME, disc_id))))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .bind(("is_request", is_request))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn get_request_users(&self, disc_id: &str) -> AppResult<Vec<Thing>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT VALUE out FROM $disc->{DISC_USER_TABLE_NAME} WHERE is_request=true;"
+            ))
+            .bind(("disc", Thing::from((DISC_TABLE_NAME, disc_id))))
+            .await?;
+        let data = res.take::<Vec<Thing>>(0)?;
+        Ok(data)
+    }
+
+    async fn get_inbox<T: for<'b> Deserialize<'b> + ViewFieldSelector>(
+        &self,
+        user_id: &str,
+        pad: Pagination,
+        is_request: bool,
+    ) -> AppResult<Vec<T>> {
+        let fields = T::get_select_query_fields();
+        let is_request_cond = if is_request {
+            "is_request = true"
+        } else {
+            "is_request != true"
+        };
+
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT {fields}, latest_post.created_at as latest_post_at FROM {DISC_USER_TABLE_NAME}
+                WHERE out=$user AND in.type=$private_type AND in.hidden != true AND latest_post != NONE AND {is_request_cond}
+                ORDER BY latest_post_at DESC
+                LIMIT $limit START $start;"
+            ))
+            .bind(("limit", pad.count))
+            .bind(("start", pad.start))
+            .bind(("private_type", DiscussionType::Private))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .await?;
+
+        let data = res.take::<Vec<T>>(0)?;
+        Ok(data)
+    }
+
     async fn remove(&self, disc_id: &str, user_ids: Vec<Thing>) -> AppResult<Vec<Thing>> {
         let mut res = self
             .client
@@ -0,0 +1,68 @@
+use crate::database::client::Db;
+use crate::database::table_names::{EDIT_HISTORY_TABLE_NAME, REPLY_TABLE_NAME};
+use crate::entities::community::post_entity::TABLE_NAME as POST_TABLE_NAME;
+use crate::entities::edit_history::EditHistory;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::middleware::error::{AppError, AppResult};
+use std::sync::Arc;
+use surrealdb::method::Query;
+use surrealdb::sql::Thing;
+
+#[derive(Debug)]
+pub struct EditHistoryRepository {
+    client: Arc<Db>,
+}
+
+impl EditHistoryRepository {
+    pub fn new(client: Arc<Db>) -> Self {
+        Self { client }
+    }
+
+    pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {EDIT_HISTORY_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS entity ON TABLE {EDIT_HISTORY_TABLE_NAME} TYPE record<{POST_TABLE_NAME} | {REPLY_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS title ON TABLE {EDIT_HISTORY_TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS content ON TABLE {EDIT_HISTORY_TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS edited_by ON TABLE {EDIT_HISTORY_TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {EDIT_HISTORY_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS entity_idx ON TABLE {EDIT_HISTORY_TABLE_NAME} COLUMNS entity;
+    ");
+        let mutation = self.client.query(sql).await?;
+
+        mutation
+            .check()
+            .expect("should mutate EditHistoryRepository");
+
+        Ok(())
+    }
+
+    /// Adds the copy of the current title and content of the entity into the query,
+    /// so the previous version is saved in the same transaction as the edit
+    pub(crate) fn build_create_query<'b>(
+        query: Query<'b, surrealdb::engine::any::Any>,
+        entity: Thing,
+        edited_by: Thing,
+    ) -> Query<'b, surrealdb::engine::any::Any> {
+        query
+            .query(format!(
+                "CREATE {EDIT_HISTORY_TABLE_NAME} SET entity=$history_entity, title=$history_entity.title,
+                    content=$history_entity.content, edited_by=$history_edited_by;"
+            ))
+            .bind(("history_entity", entity))
+            .bind(("history_edited_by", edited_by))
+    }
+
+    /// Returns the previous versions of the entity with the latest first
+    pub async fn get(&self, entity: Thing) -> AppResult<Vec<EditHistory>> {
+        let data = self
+            .client
+            .query(format!(
+                "SELECT * FROM {EDIT_HISTORY_TABLE_NAME} WHERE entity=$entity ORDER BY created_at DESC;"
+            ))
+            .bind(("entity", entity))
+            .await?
+            .take::<Vec<EditHistory>>(0)?;
+        Ok(data)
+    }
+}
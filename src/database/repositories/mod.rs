@@ -1,12 +1,17 @@
 pub mod access;
+pub mod access_invite;
+pub mod access_schema;
 pub mod discussion_user;
+pub mod edit_history;
 pub mod editor_tags;
 pub mod like;
 pub mod post_user;
 pub mod reply;
+pub mod report;
 pub mod tags;
 pub mod task_donors;
 pub mod task_participants;
+pub mod subscriptions;
 pub mod task_request_repo;
 pub mod user_nicknames;
 pub mod user_notifications;
@@ -1,7 +1,9 @@
 use crate::database::client::Db;
+use crate::database::repositories::edit_history::EditHistoryRepository;
 use crate::database::table_names::REPLY_TABLE_NAME;
 use crate::entities::community::post_entity::TABLE_NAME as POST_TABLE_NAME;
 use crate::entities::reply::Reply;
+use crate::entities::user_auth::block_entity::{BlockType, TABLE_NAME as BLOCK_TABLE_NAME};
 use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
 use crate::middleware::error::{AppError, AppResult};
 use crate::middleware::utils::db_utils::{Pagination, QryOrder, ViewFieldSelector};
@@ -27,6 +29,8 @@ impl RepliesRepository {
     DEFINE FIELD IF NOT EXISTS content ON TABLE {REPLY_TABLE_NAME} TYPE string ASSERT string::len(string::trim($value))>0;
     DEFINE FIELD IF NOT EXISTS likes_nr ON TABLE {REPLY_TABLE_NAME} TYPE number DEFAULT 0;
     DEFINE FIELD IF NOT EXISTS replies_nr ON TABLE {REPLY_TABLE_NAME} TYPE number DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS hidden ON TABLE {REPLY_TABLE_NAME} TYPE bool DEFAULT false;
+    DEFINE FIELD IF NOT EXISTS edited_at ON TABLE {REPLY_TABLE_NAME} TYPE option<datetime>;
     DEFINE FIELD IF NOT EXISTS created_at ON TABLE {REPLY_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
     DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {REPLY_TABLE_NAME}  TYPE datetime DEFAULT time::now() VALUE time::now();
     ");
@@ -59,6 +63,37 @@ impl RepliesRepository {
         Ok(record.unwrap())
     }
 
+    /// Saves the previous content into the edit history and updates the reply
+    pub async fn update(&self, reply_id: &str, content: &str, edited_by: Thing) -> AppResult<()> {
+        let reply = Thing::from((REPLY_TABLE_NAME, reply_id));
+        let _ = EditHistoryRepository::build_create_query(
+            self.client.query("BEGIN"),
+            reply.clone(),
+            edited_by,
+        )
+        .query("UPDATE $reply SET content=$content, edited_at=time::now();")
+        .query("COMMIT")
+        .bind(("reply", reply))
+        .bind(("content", content.to_string()))
+        .await?
+        .check()?;
+        Ok(())
+    }
+
+    pub async fn get_view_by_id(&self, user_id: &str, reply_id: &str) -> AppResult<ReplyView> {
+        let fields = ReplyView::get_select_query_fields();
+        let data = self
+            .client
+            .query(format!("SELECT {fields} FROM $reply;"))
+            .bind(("reply", Thing::from((REPLY_TABLE_NAME, reply_id))))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .await?
+            .take::<Option<ReplyView>>(0)?;
+        Ok(data.ok_or(AppError::EntityFailIdNotFound {
+            ident: reply_id.to_string(),
+        })?)
+    }
+
     pub async fn get(
         &self,
         user_id: &str,
@@ -72,13 +107,14 @@ impl RepliesRepository {
             .query(
                 format!(
                     "SELECT {fields} FROM {REPLY_TABLE_NAME}
-                            WHERE belongs_to=$belongs_to
+                            WHERE belongs_to=$belongs_to AND hidden != true AND created_by NOT IN $user->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out
                             ORDER BY id {order_dir} LIMIT $limit START $start;"
                 )
                 .as_str(),
             )
             .bind(("belongs_to", belongs_to))
             .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .bind(("block_type", BlockType::Block))
             .bind(("limit", pagination.count))
             .bind(("start", pagination.start))
             .await?
@@ -87,6 +123,23 @@ impl RepliesRepository {
         Ok(data)
     }
 
+    pub async fn delete(&self, reply_id: &str) -> AppResult<()> {
+        let _ = self
+            .client
+            .query("BEGIN")
+            .query("LET $belongs_to = (SELECT VALUE belongs_to FROM ONLY $reply);")
+            .query(format!(
+                "DELETE {REPLY_TABLE_NAME} WHERE belongs_to = $reply;"
+            ))
+            .query("DELETE $reply;")
+            .query("UPDATE $belongs_to SET replies_nr-=1;")
+            .query("COMMIT")
+            .bind(("reply", Thing::from((REPLY_TABLE_NAME, reply_id))))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
     pub async fn get_by_id(&self, reply_id: &str) -> AppResult<Reply> {
         let data: Option<Reply> = self.client.select((REPLY_TABLE_NAME, reply_id)).await?;
         Ok(data.ok_or(AppError::EntityFailIdNotFound {
@@ -0,0 +1,264 @@
+use crate::database::client::Db;
+use crate::database::table_names::{
+    MODERATION_ACTION_TABLE_NAME, REPLY_TABLE_NAME, REPORT_TABLE_NAME, TASK_REQUEST_TABLE_NAME,
+};
+use crate::entities::community::discussion_entity::TABLE_NAME as DISC_TABLE_NAME;
+use crate::entities::community::post_entity::TABLE_NAME as POST_TABLE_NAME;
+use crate::entities::report::{
+    ModerationAction, ModerationActionType, Report, ReportFilter, ReportReason, ReportStatus,
+};
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::interfaces::repositories::report::ReportsRepositoryInterface;
+use crate::middleware::error::{AppError, AppResult};
+use crate::middleware::utils::db_utils::{Pagination, QryOrder};
+use async_trait::async_trait;
+use std::sync::Arc;
+use surrealdb::sql::Thing;
+
+#[derive(Debug)]
+pub struct ReportsRepository {
+    client: Arc<Db>,
+}
+
+impl ReportsRepository {
+    pub fn new(client: Arc<Db>) -> Self {
+        Self { client }
+    }
+
+    pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
+        let entities = format!(
+            "{POST_TABLE_NAME} | {REPLY_TABLE_NAME} | {DISC_TABLE_NAME} | {TASK_REQUEST_TABLE_NAME} | {USER_TABLE_NAME}"
+        );
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {REPORT_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS entity ON TABLE {REPORT_TABLE_NAME} TYPE record<{entities}>;
+    DEFINE FIELD IF NOT EXISTS reporter ON TABLE {REPORT_TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS reason ON TABLE {REPORT_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS text ON TABLE {REPORT_TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE {REPORT_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS assigned_to ON TABLE {REPORT_TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS action ON TABLE {REPORT_TABLE_NAME} TYPE option<record<{MODERATION_ACTION_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {REPORT_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE FIELD IF NOT EXISTS resolved_at ON TABLE {REPORT_TABLE_NAME} TYPE option<datetime>;
+    DEFINE INDEX IF NOT EXISTS entity_idx ON TABLE {REPORT_TABLE_NAME} COLUMNS entity;
+    DEFINE INDEX IF NOT EXISTS status_idx ON TABLE {REPORT_TABLE_NAME} COLUMNS status;
+    DEFINE INDEX IF NOT EXISTS assigned_to_idx ON TABLE {REPORT_TABLE_NAME} COLUMNS assigned_to;
+
+    DEFINE TABLE IF NOT EXISTS {MODERATION_ACTION_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS entity ON TABLE {MODERATION_ACTION_TABLE_NAME} TYPE record<{entities}>;
+    DEFINE FIELD IF NOT EXISTS moderator ON TABLE {MODERATION_ACTION_TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS action ON TABLE {MODERATION_ACTION_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS note ON TABLE {MODERATION_ACTION_TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {MODERATION_ACTION_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS entity_idx ON TABLE {MODERATION_ACTION_TABLE_NAME} COLUMNS entity;
+    ");
+        let mutation = self.client.query(sql).await?;
+
+        mutation.check().expect("should mutate ReportsRepository");
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReportsRepositoryInterface for ReportsRepository {
+    async fn create(
+        &self,
+        entity: Thing,
+        reporter: Thing,
+        reason: ReportReason,
+        text: Option<String>,
+    ) -> AppResult<Report> {
+        let mut res = self
+            .client
+            .query(format!(
+                "CREATE {REPORT_TABLE_NAME} SET
+                    entity=$entity,
+                    reporter=$reporter,
+                    reason=$reason,
+                    text=$text,
+                    status=$status;"
+            ))
+            .bind(("entity", entity))
+            .bind(("reporter", reporter))
+            .bind(("reason", reason))
+            .bind(("text", text))
+            .bind(("status", ReportStatus::Open))
+            .await?;
+        let data = res.take::<Option<Report>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    async fn get_by_id(&self, report_id: &str) -> AppResult<Report> {
+        let data: Option<Report> = self.client.select((REPORT_TABLE_NAME, report_id)).await?;
+        Ok(data.ok_or(AppError::EntityFailIdNotFound {
+            ident: report_id.to_string(),
+        })?)
+    }
+
+    async fn get(&self, filter: ReportFilter, pag: Pagination) -> AppResult<Vec<Report>> {
+        let mut conditions = vec![];
+        if filter.status.is_some() {
+            conditions.push("status=$status");
+        }
+        if filter.reason.is_some() {
+            conditions.push("reason=$reason");
+        }
+        if filter.entity_type.is_some() {
+            conditions.push("record::tb(entity)=$entity_type");
+        }
+        if filter.assigned_to.is_some() {
+            conditions.push("assigned_to=$assigned_to");
+        }
+        if filter.entity.is_some() {
+            conditions.push("entity=$entity");
+        }
+        if filter.reporter.is_some() {
+            conditions.push("reporter=$reporter");
+        }
+        let where_cond = match conditions.is_empty() {
+            true => "".to_string(),
+            false => format!("WHERE {}", conditions.join(" AND ")),
+        };
+        let order_dir = pag.order_dir.unwrap_or(QryOrder::DESC).to_string();
+
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {REPORT_TABLE_NAME} {where_cond}
+                 ORDER BY created_at {order_dir} LIMIT $limit START $start;"
+            ))
+            .bind(("status", filter.status))
+            .bind(("reason", filter.reason))
+            .bind(("entity_type", filter.entity_type))
+            .bind(("assigned_to", filter.assigned_to))
+            .bind(("entity", filter.entity))
+            .bind(("reporter", filter.reporter))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<Report>>(0)?;
+        Ok(data)
+    }
+
+    async fn get_reporters_count(&self, entity: &Thing) -> AppResult<u32> {
+        let mut res = self
+            .client
+            .query(format!(
+                "array::len(array::distinct(SELECT VALUE reporter FROM {REPORT_TABLE_NAME} WHERE entity=$entity AND status=$status AND reporter != NONE));"
+            ))
+            .bind(("entity", entity.clone()))
+            .bind(("status", ReportStatus::Open))
+            .await?;
+        let data = res.take::<Option<u32>>(0)?;
+        Ok(data.unwrap_or(0))
+    }
+
+    async fn assign(&self, report_id: &str, assigned_to: Option<Thing>) -> AppResult<Report> {
+        let mut res = self
+            .client
+            .query("UPDATE ONLY $report SET assigned_to=$assigned_to;")
+            .bind(("report", Thing::from((REPORT_TABLE_NAME, report_id))))
+            .bind(("assigned_to", assigned_to))
+            .await?;
+        let data = res.take::<Option<Report>>(0)?;
+        data.ok_or(AppError::EntityFailIdNotFound {
+            ident: report_id.to_string(),
+        })
+    }
+
+    async fn resolve(
+        &self,
+        entity: &Thing,
+        status: ReportStatus,
+        action: &Thing,
+    ) -> AppResult<Vec<Report>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "UPDATE {REPORT_TABLE_NAME}
+                    SET status=$status, action=$action, resolved_at=time::now()
+                    WHERE entity=$entity AND status=$open_status;"
+            ))
+            .bind(("entity", entity.clone()))
+            .bind(("status", status))
+            .bind(("action", action.clone()))
+            .bind(("open_status", ReportStatus::Open))
+            .await?;
+        let data = res.take::<Vec<Report>>(0)?;
+        Ok(data)
+    }
+
+    async fn create_action(
+        &self,
+        entity: Thing,
+        moderator: Option<Thing>,
+        action: ModerationActionType,
+        note: Option<String>,
+    ) -> AppResult<ModerationAction> {
+        let mut res = self
+            .client
+            .query(format!(
+                "CREATE {MODERATION_ACTION_TABLE_NAME} SET
+                    entity=$entity,
+                    moderator=$moderator,
+                    action=$action,
+                    note=$note;"
+            ))
+            .bind(("entity", entity))
+            .bind(("moderator", moderator))
+            .bind(("action", action))
+            .bind(("note", note))
+            .await?;
+        let data = res.take::<Option<ModerationAction>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    async fn get_actions(
+        &self,
+        entity: Option<Thing>,
+        pag: Pagination,
+    ) -> AppResult<Vec<ModerationAction>> {
+        let entity_cond = match entity {
+            Some(_) => "WHERE entity=$entity",
+            None => "",
+        };
+        let order_dir = pag.order_dir.unwrap_or(QryOrder::DESC).to_string();
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {MODERATION_ACTION_TABLE_NAME} {entity_cond}
+                 ORDER BY created_at {order_dir} LIMIT $limit START $start;"
+            ))
+            .bind(("entity", entity))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<ModerationAction>>(0)?;
+        Ok(data)
+    }
+
+    async fn set_hidden(&self, entity: &Thing, hidden: bool) -> AppResult<()> {
+        let _ = self
+            .client
+            .query("UPDATE $entity SET hidden=$hidden;")
+            .bind(("entity", entity.clone()))
+            .bind(("hidden", hidden))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn get_owner(&self, entity: &Thing) -> AppResult<Option<Thing>> {
+        if entity.tb == USER_TABLE_NAME {
+            return Ok(Some(entity.clone()));
+        }
+        let mut res = self
+            .client
+            .query("SELECT VALUE created_by FROM ONLY $entity;")
+            .bind(("entity", entity.clone()))
+            .await?;
+        let data = res.take::<Option<Thing>>(0)?;
+        Ok(data)
+    }
+}
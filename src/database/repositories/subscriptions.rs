@@ -0,0 +1,352 @@
+use crate::database::client::Db;
+use crate::database::surrdb_utils::get_thing;
+use crate::database::table_names::{SUBSCRIPTION_TABLE_NAME, SUBSCRIPTION_TIER_TABLE_NAME};
+use crate::entities::subscription::{
+    Subscription, SubscriptionStatus, SubscriptionTier, SubscriptionTierCreate,
+    THROW_SUBSCRIPTION_NOT_RENEWABLE,
+};
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::entities::wallet::wallet_entity::CurrencySymbol;
+use crate::interfaces::repositories::subscriptions::SubscriptionsRepositoryInterface;
+use crate::middleware::error::{AppError, AppResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use surrealdb::engine::any;
+use surrealdb::method::Query;
+use surrealdb::sql::{Datetime, Thing};
+
+#[derive(Debug)]
+pub struct SubscriptionsRepository {
+    client: Arc<Db>,
+}
+
+impl SubscriptionsRepository {
+    pub fn new(client: Arc<Db>) -> Self {
+        Self { client }
+    }
+
+    pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
+        let curr_usd = CurrencySymbol::USD.to_string();
+        let curr_reef = CurrencySymbol::REEF.to_string();
+        let curr_eth = CurrencySymbol::ETH.to_string();
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {SUBSCRIPTION_TIER_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS title ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE string ASSERT string::len(string::trim($value))>0;
+    DEFINE FIELD IF NOT EXISTS description ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS price ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE number ASSERT $value > 0;
+    DEFINE FIELD IF NOT EXISTS currency ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE '{curr_usd}'|'{curr_reef}'|'{curr_eth}';
+    DEFINE FIELD IF NOT EXISTS interval ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS perks ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE array<string> DEFAULT [];
+    DEFINE FIELD IF NOT EXISTS is_active ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE bool DEFAULT true;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS user_idx ON TABLE {SUBSCRIPTION_TIER_TABLE_NAME} COLUMNS user;
+
+    DEFINE TABLE IF NOT EXISTS {SUBSCRIPTION_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS tier ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE record<{SUBSCRIPTION_TIER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS subscriber ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS creator ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS price ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE number;
+    DEFINE FIELD IF NOT EXISTS currency ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE '{curr_usd}'|'{curr_reef}'|'{curr_eth}';
+    DEFINE FIELD IF NOT EXISTS interval ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS current_period_end ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE datetime;
+    DEFINE FIELD IF NOT EXISTS grace_period_end ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS canceled_at ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE FIELD IF NOT EXISTS r_updated ON TABLE {SUBSCRIPTION_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
+    DEFINE INDEX IF NOT EXISTS subscriber_creator_unique_idx ON TABLE {SUBSCRIPTION_TABLE_NAME} FIELDS subscriber, creator UNIQUE;
+    DEFINE INDEX IF NOT EXISTS creator_idx ON TABLE {SUBSCRIPTION_TABLE_NAME} COLUMNS creator;
+    DEFINE INDEX IF NOT EXISTS status_idx ON TABLE {SUBSCRIPTION_TABLE_NAME} COLUMNS status;
+    DEFINE INDEX IF NOT EXISTS current_period_end_idx ON TABLE {SUBSCRIPTION_TABLE_NAME} COLUMNS current_period_end;
+    ");
+        let mutation = self.client.query(sql).await?;
+
+        mutation
+            .check()
+            .expect("should mutate SubscriptionsRepository");
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SubscriptionsRepositoryInterface for SubscriptionsRepository {
+    async fn create_tier(&self, data: SubscriptionTierCreate) -> AppResult<SubscriptionTier> {
+        let mut res = self
+            .client
+            .query(format!(
+                "CREATE {SUBSCRIPTION_TIER_TABLE_NAME} SET
+                    user=$user,
+                    title=$title,
+                    description=$description,
+                    price=$price,
+                    currency=$currency,
+                    interval=$interval,
+                    perks=$perks;"
+            ))
+            .bind(("user", data.user))
+            .bind(("title", data.title))
+            .bind(("description", data.description))
+            .bind(("price", data.price))
+            .bind(("currency", data.currency))
+            .bind(("interval", data.interval))
+            .bind(("perks", data.perks))
+            .await?;
+
+        let tier = res.take::<Option<SubscriptionTier>>(0)?;
+        Ok(tier.unwrap())
+    }
+
+    async fn get_tier(&self, tier_id: &str) -> AppResult<SubscriptionTier> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TIER_TABLE_NAME} WHERE id=$tier LIMIT 1;"
+            ))
+            .bind(("tier", get_thing(tier_id)?))
+            .await?;
+
+        let tier = res.take::<Option<SubscriptionTier>>(0)?;
+        tier.ok_or(AppError::EntityFailIdNotFound {
+            ident: tier_id.to_string(),
+        })
+    }
+
+    async fn get_tiers_by_user(
+        &self,
+        user: &Thing,
+        only_active: bool,
+    ) -> AppResult<Vec<SubscriptionTier>> {
+        let query_by_active = match only_active {
+            true => "AND is_active=true",
+            false => "",
+        };
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TIER_TABLE_NAME} WHERE user=$user {query_by_active} ORDER BY price ASC;"
+            ))
+            .bind(("user", user.clone()))
+            .await?;
+
+        Ok(res.take::<Vec<SubscriptionTier>>(0)?)
+    }
+
+    async fn set_tier_active(&self, tier_id: &str, is_active: bool) -> AppResult<()> {
+        let _ = self
+            .client
+            .query(format!(
+                "UPDATE {SUBSCRIPTION_TIER_TABLE_NAME} SET is_active=$is_active WHERE id=$tier;"
+            ))
+            .bind(("tier", get_thing(tier_id)?))
+            .bind(("is_active", is_active))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    fn build_upsert_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        subscription_id: &Thing,
+        tier: &SubscriptionTier,
+        subscriber: &Thing,
+        period_end: DateTime<Utc>,
+    ) -> Query<'b, any::Any> {
+        query
+            .query(
+                "LET $subscription = UPSERT ONLY $_sub_id SET
+                    tier=$_sub_tier,
+                    subscriber=$_sub_subscriber,
+                    creator=$_sub_creator,
+                    price=$_sub_price,
+                    currency=$_sub_currency,
+                    interval=$_sub_interval,
+                    status=$_sub_status,
+                    current_period_end=$_sub_period_end,
+                    grace_period_end=NONE,
+                    canceled_at=NONE;",
+            )
+            .bind(("_sub_id", subscription_id.clone()))
+            .bind(("_sub_tier", tier.id.clone()))
+            .bind(("_sub_subscriber", subscriber.clone()))
+            .bind(("_sub_creator", tier.user.clone()))
+            .bind(("_sub_price", tier.price))
+            .bind(("_sub_currency", tier.currency.clone()))
+            .bind(("_sub_interval", tier.interval.clone()))
+            .bind(("_sub_status", SubscriptionStatus::Active))
+            .bind(("_sub_period_end", Datetime::from(period_end)))
+    }
+
+    fn build_renew_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        subscription_id: &Thing,
+        old_period_end: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Query<'b, any::Any> {
+        // canceled in the meantime or already renewed by another run
+        query
+            .query(format!(
+                "IF array::len(SELECT id FROM $_sub_id WHERE status IN $_sub_renewable AND current_period_end=$_sub_old_period_end) = 0 {{
+                    THROW \"{THROW_SUBSCRIPTION_NOT_RENEWABLE}\";
+                }};"
+            ))
+            .query(
+                "LET $subscription = UPDATE ONLY $_sub_id SET
+                    status=$_sub_status,
+                    current_period_end=$_sub_period_end,
+                    grace_period_end=NONE;",
+            )
+            .bind(("_sub_id", subscription_id.clone()))
+            .bind((
+                "_sub_renewable",
+                vec![SubscriptionStatus::Active, SubscriptionStatus::PastDue],
+            ))
+            .bind(("_sub_old_period_end", Datetime::from(old_period_end)))
+            .bind(("_sub_status", SubscriptionStatus::Active))
+            .bind(("_sub_period_end", Datetime::from(period_end)))
+    }
+
+    async fn get_by_id(&self, subscription_id: &str) -> AppResult<Subscription> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE id=$subscription LIMIT 1;"
+            ))
+            .bind(("subscription", get_thing(subscription_id)?))
+            .await?;
+
+        let subscription = res.take::<Option<Subscription>>(0)?;
+        subscription.ok_or(AppError::EntityFailIdNotFound {
+            ident: subscription_id.to_string(),
+        })
+    }
+
+    async fn get_by_subscriber_and_creator(
+        &self,
+        subscriber: &Thing,
+        creator: &Thing,
+    ) -> AppResult<Option<Subscription>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE subscriber=$subscriber AND creator=$creator LIMIT 1;"
+            ))
+            .bind(("subscriber", subscriber.clone()))
+            .bind(("creator", creator.clone()))
+            .await?;
+
+        Ok(res.take::<Option<Subscription>>(0)?)
+    }
+
+    async fn get_by_subscriber(&self, subscriber: &Thing) -> AppResult<Vec<Subscription>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE subscriber=$subscriber AND status!=$expired ORDER BY created_at DESC;"
+            ))
+            .bind(("subscriber", subscriber.clone()))
+            .bind(("expired", SubscriptionStatus::Expired))
+            .await?;
+
+        Ok(res.take::<Vec<Subscription>>(0)?)
+    }
+
+    async fn get_by_creator(&self, creator: &Thing) -> AppResult<Vec<Subscription>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE creator=$creator AND status!=$expired ORDER BY created_at DESC;"
+            ))
+            .bind(("creator", creator.clone()))
+            .bind(("expired", SubscriptionStatus::Expired))
+            .await?;
+
+        Ok(res.take::<Vec<Subscription>>(0)?)
+    }
+
+    async fn get_due_for_renewal(&self) -> AppResult<Vec<Subscription>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE status IN $statuses AND current_period_end <= time::now();"
+            ))
+            .bind((
+                "statuses",
+                vec![SubscriptionStatus::Active, SubscriptionStatus::PastDue],
+            ))
+            .await?;
+
+        Ok(res.take::<Vec<Subscription>>(0)?)
+    }
+
+    async fn get_ended_canceled(&self) -> AppResult<Vec<Subscription>> {
+        let mut res = self
+            .client
+            .query(format!(
+                "SELECT * FROM {SUBSCRIPTION_TABLE_NAME} WHERE status=$status AND current_period_end <= time::now();"
+            ))
+            .bind(("status", SubscriptionStatus::Canceled))
+            .await?;
+
+        Ok(res.take::<Vec<Subscription>>(0)?)
+    }
+
+    async fn set_past_due(
+        &self,
+        subscription_id: &Thing,
+        grace_period_end: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let _ = self
+            .client
+            .query("UPDATE $subscription SET status=$status, grace_period_end=$grace_period_end;")
+            .bind(("subscription", subscription_id.clone()))
+            .bind(("status", SubscriptionStatus::PastDue))
+            .bind(("grace_period_end", Datetime::from(grace_period_end)))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn cancel(&self, subscription_id: &Thing) -> AppResult<Subscription> {
+        let mut res = self
+            .client
+            .query("UPDATE ONLY $subscription SET status=$status, canceled_at=time::now();")
+            .bind(("subscription", subscription_id.clone()))
+            .bind(("status", SubscriptionStatus::Canceled))
+            .await?;
+
+        let subscription = res.take::<Option<Subscription>>(0)?;
+        subscription.ok_or(AppError::EntityFailIdNotFound {
+            ident: subscription_id.to_raw(),
+        })
+    }
+
+    async fn resume(&self, subscription_id: &Thing) -> AppResult<Subscription> {
+        let mut res = self
+            .client
+            .query("UPDATE ONLY $subscription SET status=$status, canceled_at=NONE;")
+            .bind(("subscription", subscription_id.clone()))
+            .bind(("status", SubscriptionStatus::Active))
+            .await?;
+
+        let subscription = res.take::<Option<Subscription>>(0)?;
+        subscription.ok_or(AppError::EntityFailIdNotFound {
+            ident: subscription_id.to_raw(),
+        })
+    }
+
+    async fn expire(&self, subscription_id: &Thing) -> AppResult<()> {
+        let _ = self
+            .client
+            .query("UPDATE $subscription SET status=$status, grace_period_end=NONE;")
+            .bind(("subscription", subscription_id.clone()))
+            .bind(("status", SubscriptionStatus::Expired))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
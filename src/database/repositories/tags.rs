@@ -7,6 +7,8 @@ use crate::middleware::utils::db_utils::{Pagination, QryOrder};
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::sync::Arc;
+use surrealdb::engine::any;
+use surrealdb::method::Query;
 use surrealdb::sql::Thing;
 
 #[derive(Debug)]
@@ -51,6 +53,38 @@ impl TagsRepositoryInterface for TagsRepository {
         Ok(())
     }
 
+    async fn remove_relates(&self, entity: Thing) -> AppResult<()> {
+        let _ = self
+            .client
+            .query(format!("DELETE {TAG_REL_TABLE_NAME} WHERE out=$entity;"))
+            .bind(("entity", entity))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    fn build_replace_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        tags: Vec<String>,
+        entity: Thing,
+    ) -> Query<'b, any::Any> {
+        let query = query.query(format!(
+            "DELETE {TAG_REL_TABLE_NAME} WHERE out=$tagged_entity;"
+        ));
+        let query = if tags.is_empty() {
+            query
+        } else {
+            query
+                .query(format!(
+                    "LET $tag_ids = UPSERT $replaced_tags.map(|$v| type::thing('{TAG_TABLE_NAME}', $v));",
+                ))
+                .query(format!("RELATE $tag_ids->{TAG_REL_TABLE_NAME}->$tagged_entity;"))
+                .bind(("replaced_tags", tags))
+        };
+        query.bind(("tagged_entity", entity))
+    }
+
     async fn get_by_tag<T: for<'de> Deserialize<'de>>(
         &self,
         tag: &str,
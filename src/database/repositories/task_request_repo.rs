@@ -24,6 +24,8 @@ use surrealdb::method::Query;
 use surrealdb::sql::{Datetime, Thing};
 
 const TABLE_COL_USER: &str = local_user_entity::TABLE_NAME;
+const FINISHED_STATUSES: [TaskRequestStatus; 2] =
+    [TaskRequestStatus::Completed, TaskRequestStatus::Cancelled];
 
 impl Repository<TaskRequestEntity> {
     pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
@@ -48,6 +50,7 @@ impl Repository<TaskRequestEntity> {
     DEFINE FIELD IF NOT EXISTS delivery_period ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE number;
     DEFINE FIELD IF NOT EXISTS goal_amount ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE option<number>;
     DEFINE FIELD IF NOT EXISTS wallet_id ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE record<{WALLET_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS hidden ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE bool DEFAULT false;
     DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE datetime DEFAULT time::now()  VALUE $before OR time::now();
     DEFINE FIELD IF NOT EXISTS r_updated ON TABLE {TASK_REQUEST_TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
     DEFINE INDEX IF NOT EXISTS idx_status ON TABLE {TASK_REQUEST_TABLE_NAME} COLUMNS status;
@@ -122,7 +125,7 @@ impl TaskRequestRepositoryInterface for Repository<TaskRequestEntity> {
         let fields = T::get_select_query_fields();
         let query = format!("
             SELECT {fields} FROM {TASK_REQUEST_TABLE_NAME}
-            WHERE belongs_to IN $posts
+            WHERE belongs_to IN $posts AND hidden != true
                 AND (belongs_to.type IN $public_post_types OR $user IN belongs_to<-{ACCESS_TABLE_NAME}.in)
                 AND (belongs_to.belongs_to.type=$disc_type OR $user IN belongs_to.belongs_to<-{ACCESS_TABLE_NAME}.in)");
         let mut res = self
@@ -176,7 +179,7 @@ impl TaskRequestRepositoryInterface for Repository<TaskRequestEntity> {
 
         let fields = T::get_select_query_fields();
         let query = format!("SELECT {fields}, created_at FROM {TASK_REQUEST_TABLE_NAME}
-                WHERE belongs_to=$disc {type_condition} {date_condition} {acceptance_condition} AND (type=$task_type OR $user IN <-{ACCESS_TABLE_NAME}.in)
+                WHERE belongs_to=$disc AND hidden != true {type_condition} {date_condition} {acceptance_condition} AND (type=$task_type OR $user IN <-{ACCESS_TABLE_NAME}.in)
                 {pagination_str};");
 
         let mut res = self
@@ -232,7 +235,7 @@ impl TaskRequestRepositoryInterface for Repository<TaskRequestEntity> {
         let fields = T::get_select_query_fields();
         let query = format!(
             "SELECT {fields}, created_at FROM {TASK_REQUEST_TABLE_NAME}
-            WHERE belongs_to=$disc {type_condition} {date_condition} {acceptance_condition} AND $user IN belongs_to<-{ACCESS_TABLE_NAME}.in
+            WHERE belongs_to=$disc AND hidden != true {type_condition} {date_condition} {acceptance_condition} AND $user IN belongs_to<-{ACCESS_TABLE_NAME}.in
             {pagination_str};"
         );
         let mut res = self
@@ -367,14 +370,14 @@ impl TaskRequestRepositoryInterface for Repository<TaskRequestEntity> {
                 SELECT id, wallet_id.* AS wallet, currency, request_txt, belongs_to,
                     ->task_participant.{{ status, id, user: out.* }} AS participants,
                     ->task_donor.{{ id: out, amount: transaction.amount_out }} AS donors
-                FROM $task WHERE status != $status
+                FROM $task WHERE status NOT IN $statuses
             )"
         );
         let mut res = self
             .client
             .query(query)
             .bind(("task", get_thing(task_id)?))
-            .bind(("status", TaskRequestStatus::Completed))
+            .bind(("statuses", FINISHED_STATUSES))
             .await?;
 
         let data = res.take::<Option<TaskForReward>>(0)?;
@@ -391,13 +394,37 @@ impl TaskRequestRepositoryInterface for Repository<TaskRequestEntity> {
                     ->task_participant.{{ status, id, user: out.* }} AS participants,
                     ->task_donor.{{ id: out, amount: transaction.amount_out }} AS donors
                 FROM {TASK_REQUEST_TABLE_NAME}
-                WHERE status != $status AND due_at <= time::now()
+                WHERE status NOT IN $statuses AND due_at <= time::now()
             )"
         );
         let mut res = self
             .client
             .query(query)
-            .bind(("status", TaskRequestStatus::Completed))
+            .bind(("statuses", FINISHED_STATUSES))
+            .await?;
+        let data = res.take::<Vec<TaskForReward>>(0)?;
+        Ok(data)
+    }
+
+    async fn get_unfinished_by_creator(
+        &self,
+        user: &Thing,
+    ) -> Result<Vec<TaskForReward>, surrealdb::Error> {
+        let query = format!(
+            "SELECT *, wallet.transaction_head[currency].balance as balance
+             FROM (
+                SELECT id, wallet_id.* AS wallet, currency, request_txt, belongs_to,
+                    ->task_participant.{{ status, id, user: out.* }} AS participants,
+                    ->task_donor.{{ id: out, amount: transaction.amount_out }} AS donors
+                FROM {TASK_REQUEST_TABLE_NAME}
+                WHERE created_by=$user AND status NOT IN $statuses
+            )"
+        );
+        let mut res = self
+            .client
+            .query(query)
+            .bind(("user", user.clone()))
+            .bind(("statuses", FINISHED_STATUSES))
             .await?;
         let data = res.take::<Vec<TaskForReward>>(0)?;
         Ok(data)
@@ -3,7 +3,7 @@ use crate::{
     entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME,
     entities::user_notification::UserNotification,
     interfaces::repositories::user_notifications::{
-        GetNotificationOptions, UserNotificationsInterface,
+        GetNotificationOptions, NotificationGroupOptions, UserNotificationsInterface,
     },
     middleware::{
         error::AppError,
@@ -12,10 +12,19 @@ use crate::{
     models::view::notification::UserNotificationView,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use serde_json::Value;
 use std::sync::Arc;
 use surrealdb::sql::Thing;
 
+#[derive(Debug, Serialize)]
+struct GroupedNotificationRow {
+    id: Thing,
+    receiver: Thing,
+    key: String,
+}
+
 #[derive(Debug)]
 pub struct UserNotificationsRepository {
     client: Arc<Db>,
@@ -26,6 +35,12 @@ impl UserNotificationsRepository {
         Self { client }
     }
 
+    fn to_notification_things(ids: &Vec<String>) -> Vec<Thing> {
+        ids.iter()
+            .map(|id| Thing::from(("notifications", id.as_str())))
+            .collect()
+    }
+
     pub(in crate::database) async fn mutate_db(&self) -> Result<(), AppError> {
         let sql = format!(
             " 
@@ -35,9 +50,20 @@ impl UserNotificationsRepository {
         DEFINE FIELD IF NOT EXISTS created_by  ON TABLE notifications TYPE record<{USER_TABLE_NAME}>;
         DEFINE FIELD IF NOT EXISTS metadata    ON TABLE notifications FLEXIBLE TYPE option<object>;
         DEFINE FIELD IF NOT EXISTS created_at  ON TABLE notifications TYPE datetime DEFAULT time::now();
+        DEFINE FIELD IF NOT EXISTS updated_at  ON TABLE notifications TYPE option<datetime>;
+        DEFINE FIELD IF NOT EXISTS group_key   ON TABLE notifications TYPE option<string>;
+        DEFINE FIELD IF NOT EXISTS actors      ON TABLE notifications TYPE option<array<record<{USER_TABLE_NAME}>>>;
+        DEFINE FIELD IF NOT EXISTS all_actors  ON TABLE notifications TYPE option<set<record<{USER_TABLE_NAME}>>>;
+        DEFINE FIELD IF NOT EXISTS actors_count ON TABLE notifications TYPE option<int>;
+        DEFINE INDEX IF NOT EXISTS group_key_idx ON TABLE notifications COLUMNS group_key;
 
         DEFINE TABLE IF NOT EXISTS user_notifications TYPE RELATION IN local_user OUT notifications ENFORCED SCHEMAFULL PERMISSIONS NONE;
         DEFINE FIELD IF NOT EXISTS is_read    ON TABLE user_notifications TYPE bool DEFAULT false;
+        DEFINE FIELD IF NOT EXISTS is_archived ON TABLE user_notifications TYPE option<bool>;
+        DEFINE INDEX IF NOT EXISTS in_idx ON TABLE user_notifications COLUMNS in;
+        DEFINE INDEX IF NOT EXISTS in_is_read_idx ON TABLE user_notifications COLUMNS in, is_read;
+        DEFINE INDEX IF NOT EXISTS out_idx ON TABLE user_notifications COLUMNS out;
+        DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE notifications COLUMNS created_at;
     "
         );
         let mutation = self.client.query(sql).await?;
@@ -57,6 +83,7 @@ impl UserNotificationsInterface for UserNotificationsRepository {
         n_type: &str,
         receivers: &Vec<String>,
         metadata: Option<Value>,
+        group_key: Option<String>,
     ) -> Result<UserNotification, AppError> {
         let receiver_things = receivers
             .iter()
@@ -71,6 +98,10 @@ impl UserNotificationsInterface for UserNotificationsRepository {
                 title: $title,
                 created_by:$created_by,
                 metadata: $metadata,
+                group_key: $group_key,
+                actors: [$created_by],
+                all_actors: [$created_by],
+                actors_count: 1,
             };
             LET $n_id = $notification.id;
 
@@ -90,6 +121,7 @@ impl UserNotificationsInterface for UserNotificationsRepository {
             .bind(("title", title.to_string()))
             .bind(("created_by", Thing::from((USER_TABLE_NAME, creator))))
             .bind(("metadata", metadata))
+            .bind(("group_key", group_key))
             .bind(("receivers", receiver_things))
             .await
             .map_err(|e| AppError::SurrealDb {
@@ -106,6 +138,100 @@ impl UserNotificationsInterface for UserNotificationsRepository {
         Ok(data)
     }
 
+    async fn upsert_groups(
+        &self,
+        creator: &str,
+        title: &str,
+        n_type: &str,
+        receivers: &Vec<String>,
+        metadata: Option<Value>,
+        group: NotificationGroupOptions<'_>,
+    ) -> Result<Vec<UserNotification>, AppError> {
+        // keyed by the receiver, event and target, so the concurrent events
+        // of the group can't create the second notification
+        let groups = receivers
+            .iter()
+            .map(|receiver| GroupedNotificationRow {
+                id: Thing::from((
+                    "notifications",
+                    format!("{}_{}_{}", receiver, n_type, group.key)
+                        .replace(':', "_")
+                        .as_str(),
+                )),
+                receiver: Thing::from((USER_TABLE_NAME, receiver.as_str())),
+                key: format!("{}:{}", group.key, receiver),
+            })
+            .collect::<Vec<GroupedNotificationRow>>();
+        let ids = groups.iter().map(|g| g.id.clone()).collect::<Vec<Thing>>();
+
+        let query = r#"
+            BEGIN TRANSACTION;
+
+            FOR $group IN $groups {
+                LET $n = $group.id;
+                LET $receiver = $group.receiver;
+
+                UPDATE $n SET
+                    actors = [],
+                    all_actors = [],
+                    actors_count = 0,
+                    created_at = time::now(),
+                    updated_at = NONE
+                WHERE created_at < <datetime>$since;
+
+                UPSERT $n SET
+                    updated_at = IF array::len(actors ?? []) = 0 THEN NONE ELSE time::now() END,
+                    actors_count = IF $created_by IN (all_actors ?? []) THEN actors_count ?? 1 ELSE (actors_count ?? 0) + 1 END,
+                    all_actors = array::union(all_actors ?? [], [$created_by]),
+                    actors = array::slice(array::concat([$created_by], array::complement(actors ?? [], [$created_by])), 0, $max_actors),
+                    event = $event,
+                    title = $title,
+                    created_by = $created_by,
+                    metadata = $metadata,
+                    group_key = $group.key;
+
+                UPDATE $n SET title = IF actors_count <= 1 THEN string::concat($actor, ' ', $action)
+                    ELSE IF actors_count = 2 THEN string::concat($actor, ' and 1 other ', $action)
+                    ELSE string::concat($actor, ' and ', <string>(actors_count - 1), ' others ', $action)
+                    END;
+
+                UPDATE user_notifications SET is_read = false WHERE out = $n AND in = $receiver;
+                IF array::len(SELECT id FROM user_notifications WHERE out = $n AND in = $receiver) = 0 {
+                    RELATE $receiver->user_notifications->$n SET is_read = false;
+                };
+            };
+
+            COMMIT TRANSACTION;
+            SELECT * FROM $ids;
+        "#;
+
+        let mut res = self
+            .client
+            .query(query)
+            .bind(("groups", groups))
+            .bind(("ids", ids))
+            .bind(("event", n_type.to_string()))
+            .bind(("title", title.to_string()))
+            .bind(("created_by", Thing::from((USER_TABLE_NAME, creator))))
+            .bind(("metadata", metadata))
+            .bind(("actor", group.actor.to_string()))
+            .bind(("action", group.action.to_string()))
+            .bind(("max_actors", group.max_actors))
+            .bind(("since", group.since))
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?;
+
+        let data = res
+            .take::<Vec<UserNotification>>(res.num_statements() - 1)
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?;
+
+        Ok(data)
+    }
+
     async fn get_by_user(
         &self,
         user_id: &str,
@@ -121,12 +247,17 @@ impl UserNotificationsInterface for UserNotificationsRepository {
         } else {
             ""
         };
+        let is_archived_query = if options.is_archived {
+            "AND is_archived = true"
+        } else {
+            "AND is_archived != true"
+        };
 
         let fields = UserNotificationView::get_fields();
         let query = format!(
             "SELECT out.{{{fields}}}, out.created_at as created_at, is_read as out.is_read
              FROM user_notifications
-             WHERE in=$user AND out.created_at < <datetime>$start {is_read_query} {types_query}
+             WHERE in=$user AND out.created_at < <datetime>$start {is_read_query} {types_query} {is_archived_query}
              ORDER BY created_at DESC
              LIMIT $limit;"
         );
@@ -180,7 +311,7 @@ impl UserNotificationsInterface for UserNotificationsRepository {
             ""
         };
         let query = format!(
-            "SELECT count() FROM user_notifications WHERE in = $user_id {} GROUP ALL;",
+            "SELECT count() FROM user_notifications WHERE in = $user_id AND is_archived != true {} GROUP ALL;",
             is_read_query,
         );
         let mut res = self
@@ -195,4 +326,94 @@ impl UserNotificationsInterface for UserNotificationsRepository {
         let data = res.take::<Option<u64>>((0, "count"))?;
         Ok(data.unwrap_or(0))
     }
+
+    async fn archive(
+        &self,
+        ids: &Vec<String>,
+        user_id: &str,
+        is_archived: bool,
+    ) -> Result<(), AppError> {
+        let _ = self
+            .client
+            .query("UPDATE user_notifications SET is_archived=$is_archived WHERE in=$user AND out IN $ids")
+            .bind(("ids", Self::to_notification_things(ids)))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .bind(("is_archived", is_archived))
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?
+            .check()?;
+        Ok(())
+    }
+
+    async fn delete(&self, ids: &Vec<String>, user_id: &str) -> Result<(), AppError> {
+        let query = r#"
+            BEGIN TRANSACTION;
+            DELETE user_notifications WHERE in=$user AND out IN $ids;
+            DELETE notifications WHERE id IN $ids AND count(<-user_notifications) = 0;
+            COMMIT TRANSACTION;
+        "#;
+        let _ = self
+            .client
+            .query(query)
+            .bind(("ids", Self::to_notification_things(ids)))
+            .bind(("user", Thing::from((USER_TABLE_NAME, user_id))))
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?
+            .check()?;
+        Ok(())
+    }
+
+    async fn remove_read_before(&self, before: DateTime<Utc>) -> Result<(), AppError> {
+        let query = r#"
+            DELETE user_notifications WHERE is_read = true AND (out.updated_at ?? out.created_at) < <datetime>$before;
+            DELETE notifications WHERE (updated_at ?? created_at) < <datetime>$before AND count(<-user_notifications) = 0;
+        "#;
+        let _ = self
+            .client
+            .query(query)
+            .bind(("before", before))
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?
+            .check()?;
+        Ok(())
+    }
+
+    async fn remove_over_limit(
+        &self,
+        max_per_user: u32,
+        since: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let query = r#"
+            LET $users = array::distinct(array::flatten(
+                (SELECT VALUE <-user_notifications.in FROM notifications WHERE created_at > <datetime>$since)
+            ));
+            FOR $user IN $users {
+                LET $rows = (SELECT id, out, (out.updated_at ?? out.created_at) AS created_at FROM user_notifications
+                    WHERE in = $user
+                    ORDER BY created_at DESC
+                    START $max);
+                IF array::len($rows) > 0 {
+                    DELETE $rows.id;
+                    DELETE $rows.out WHERE count(<-user_notifications) = 0;
+                };
+            };
+        "#;
+        let _ = self
+            .client
+            .query(query)
+            .bind(("max", max_per_user))
+            .bind(("since", since))
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?
+            .check()?;
+        Ok(())
+    }
 }
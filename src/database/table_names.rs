@@ -3,6 +3,7 @@ pub const EDITOR_TAG_TABLE_NAME: &'static str = "editor_tag";
 pub const TAG_REL_TABLE_NAME: &'static str = "tag_rel";
 pub const LIKE_TABLE_NAME: &'static str = "like";
 pub const ACCESS_TABLE_NAME: &'static str = "has_access";
+pub const DISC_MUTE_TABLE_NAME: &str = "discussion_mute";
 pub const REPLY_TABLE_NAME: &'static str = "reply";
 pub const POST_USER_TABLE_NAME: &'static str = "post_user";
 pub const DISC_USER_TABLE_NAME: &'static str = "discussion_user";
@@ -10,3 +11,11 @@ pub const NICKNAME_TABLE_NAME: &'static str = "nickname";
 pub const DELIVERY_RESULT_TABLE_NAME: &'static str = "delivery_result";
 pub const TASK_PARTICIPANT_TABLE_NAME: &'static str = "task_participant";
 pub const TASK_REQUEST_TABLE_NAME: &str = "task_request";
+pub const SUBSCRIPTION_TIER_TABLE_NAME: &str = "subscription_tier";
+pub const SUBSCRIPTION_TABLE_NAME: &str = "subscription";
+pub const ACCESS_SCHEMA_TABLE_NAME: &str = "access_schema";
+pub const ACCESS_INVITE_TABLE_NAME: &str = "access_invite";
+pub const REPORT_TABLE_NAME: &str = "report";
+pub const MODERATION_ACTION_TABLE_NAME: &str = "moderation_action";
+pub const NOTIFICATION_TABLE_NAME: &str = "notifications";
+pub const EDIT_HISTORY_TABLE_NAME: &str = "edit_history";
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccessInvite {
+    pub id: Thing,
+    pub entity: Thing,
+    pub token: String,
+    pub role: String,
+    pub created_by: Thing,
+    pub max_uses: Option<u32>,
+    pub uses_nr: u32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccessInvite {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at.map_or(true, |v| v > Utc::now())
+            && self.max_uses.map_or(true, |v| self.uses_nr < v)
+    }
+}
@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccessSchema {
+    pub id: Thing,
+    pub content: String,
+    pub created_by: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+}
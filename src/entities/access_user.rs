@@ -9,3 +9,13 @@ pub struct AccessUser {
     pub user: Thing,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessGrant {
+    #[serde(alias = "in")]
+    pub user: Thing,
+    #[serde(alias = "out")]
+    pub entity: Thing,
+    pub role: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
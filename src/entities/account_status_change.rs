@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+
+use crate::database::client::Db;
+use crate::database::table_names::{REPLY_TABLE_NAME, TASK_REQUEST_TABLE_NAME};
+use crate::entities::community::post_entity::TABLE_NAME as POST_TABLE_NAME;
+use crate::entities::user_auth::local_user_entity::{AccountStatus, TABLE_NAME as USER_TABLE_NAME};
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+    utils::db_utils::Pagination,
+};
+
+/// Audit record of the account restrictions and reinstatements
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountStatusChange {
+    pub id: Thing,
+    pub user: Thing,
+    pub status: AccountStatus,
+    pub reason: String,
+    pub suspended_until: Option<DateTime<Utc>>,
+    /// none if the change was made by the system
+    pub created_by: Option<Thing>,
+    /// the content hidden by the restriction
+    pub hidden_entities: Vec<Thing>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct AccountStatusChangeDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "account_status_change";
+
+impl<'a> AccountStatusChangeDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS reason ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS suspended_until ON TABLE {TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS created_by ON TABLE {TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS hidden_entities ON TABLE {TABLE_NAME} TYPE array<record> DEFAULT [];
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS user_idx ON TABLE {TABLE_NAME} COLUMNS user;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation
+            .check()
+            .expect("should mutate account_status_change");
+
+        Ok(())
+    }
+
+    pub async fn create(
+        &self,
+        user: Thing,
+        status: AccountStatus,
+        reason: String,
+        suspended_until: Option<DateTime<Utc>>,
+        created_by: Option<Thing>,
+        hidden_entities: Vec<Thing>,
+    ) -> CtxResult<AccountStatusChange> {
+        let mut res = self
+            .db
+            .query(format!(
+                "CREATE {TABLE_NAME} SET user=$user, status=$status, reason=$reason,
+                    suspended_until=$until, created_by=$created_by, hidden_entities=$entities;"
+            ))
+            .bind(("user", user))
+            .bind(("status", status))
+            .bind(("reason", reason))
+            .bind(("until", suspended_until.map(Datetime::from)))
+            .bind(("created_by", created_by))
+            .bind(("entities", hidden_entities))
+            .await?;
+        let data = res.take::<Option<AccountStatusChange>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    pub async fn get_by_user(
+        &self,
+        user: Thing,
+        pag: Pagination,
+    ) -> CtxResult<Vec<AccountStatusChange>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} WHERE user=$user
+                 ORDER BY created_at DESC LIMIT $limit START $start;"
+            ))
+            .bind(("user", user))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<AccountStatusChange>>(0)?;
+        Ok(data)
+    }
+
+    /// Hides the visible posts, replies and tasks of the user and returns their ids
+    pub async fn hide_content(&self, user: Thing) -> CtxResult<Vec<Thing>> {
+        let mut res = self
+            .db
+            .query("BEGIN")
+            .query(format!(
+                "LET $posts = UPDATE {POST_TABLE_NAME} SET hidden=true
+                    WHERE created_by=$user AND hidden != true RETURN VALUE id;"
+            ))
+            .query(format!(
+                "LET $replies = UPDATE {REPLY_TABLE_NAME} SET hidden=true
+                    WHERE created_by=$user AND hidden != true RETURN VALUE id;"
+            ))
+            .query(format!(
+                "LET $tasks = UPDATE {TASK_REQUEST_TABLE_NAME} SET hidden=true
+                    WHERE created_by=$user AND hidden != true RETURN VALUE id;"
+            ))
+            .query("RETURN array::concat($posts, $replies, $tasks);")
+            .query("COMMIT")
+            .bind(("user", user))
+            .await?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Vec<Thing>>(index)?;
+        Ok(data)
+    }
+
+    /// Shows again the content hidden by the restrictions since the last reinstatement
+    pub async fn restore_content(&self, user: Thing) -> CtxResult<()> {
+        self.db
+            .query(format!(
+                "LET $since = (SELECT created_at FROM {TABLE_NAME}
+                    WHERE user=$user AND status=$active ORDER BY created_at DESC LIMIT 1)[0].created_at;"
+            ))
+            .query(format!(
+                "LET $entities = array::flatten(SELECT VALUE hidden_entities FROM {TABLE_NAME}
+                    WHERE user=$user AND ($since == NONE OR created_at > $since));"
+            ))
+            .query("IF array::len($entities) > 0 { UPDATE $entities SET hidden=false; };")
+            .bind(("user", user))
+            .bind(("active", AccountStatus::Active))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
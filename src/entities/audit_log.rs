@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum::{Display, EnumString};
+use surrealdb::method::Query;
+use surrealdb::sql::{Datetime, Thing};
+
+use crate::database::client::Db;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+    utils::db_utils::Pagination,
+};
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum AuditAction {
+    VerifyEmail,
+    ResetOtp,
+    DisableOtp,
+    ResetPassword,
+    ChangeRole,
+    DeletePost,
+    DeleteDiscussion,
+    RestrictAccount,
+    ReinstateAccount,
+    Withdraw,
+    AdjustWallet,
+    RejectWalletAdjustment,
+}
+
+/// Trail of the sensitive operations, the records are never updated or deleted
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditLog {
+    pub id: Thing,
+    pub actor: Thing,
+    pub action: AuditAction,
+    pub target: Thing,
+    pub note: Option<String>,
+    /// snapshot of the changed fields of the target
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The user making the change and the origin of the request
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub user: Thing,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditLog {
+    pub actor: AuditActor,
+    pub action: AuditAction,
+    pub target: Thing,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditLogFilter {
+    pub actor: Option<Thing>,
+    pub action: Option<AuditAction>,
+    pub target: Option<Thing>,
+    /// table name of the target
+    pub target_type: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub struct AuditLogDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "audit_log";
+
+impl<'a> AuditLogDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL PERMISSIONS FOR select, create FULL, FOR update, delete NONE;
+    DEFINE FIELD IF NOT EXISTS actor ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}> READONLY;
+    DEFINE FIELD IF NOT EXISTS action ON TABLE {TABLE_NAME} TYPE string READONLY;
+    DEFINE FIELD IF NOT EXISTS target ON TABLE {TABLE_NAME} TYPE record READONLY;
+    DEFINE FIELD IF NOT EXISTS note ON TABLE {TABLE_NAME} TYPE option<string> READONLY;
+    DEFINE FIELD IF NOT EXISTS before ON TABLE {TABLE_NAME} FLEXIBLE TYPE option<object> READONLY;
+    DEFINE FIELD IF NOT EXISTS after ON TABLE {TABLE_NAME} FLEXIBLE TYPE option<object> READONLY;
+    DEFINE FIELD IF NOT EXISTS ip ON TABLE {TABLE_NAME} TYPE option<string> READONLY;
+    DEFINE FIELD IF NOT EXISTS user_agent ON TABLE {TABLE_NAME} TYPE option<string> READONLY;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() READONLY;
+    DEFINE INDEX IF NOT EXISTS actor_idx ON TABLE {TABLE_NAME} COLUMNS actor;
+    DEFINE INDEX IF NOT EXISTS action_idx ON TABLE {TABLE_NAME} COLUMNS action;
+    DEFINE INDEX IF NOT EXISTS target_idx ON TABLE {TABLE_NAME} COLUMNS target;
+    DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE {TABLE_NAME} COLUMNS created_at;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate audit_log");
+
+        Ok(())
+    }
+
+    /// Adds the audit record into the query, so it is written in the same transaction as the action.
+    /// The snapshots are taken from `$audit_before` and `$audit_after` if the query sets them.
+    pub(crate) fn build_create_qry<'b>(
+        query: Query<'b, surrealdb::engine::any::Any>,
+        data: CreateAuditLog,
+    ) -> Query<'b, surrealdb::engine::any::Any> {
+        query
+            .query(format!(
+                "CREATE {TABLE_NAME} SET actor=$audit_actor, action=$audit_action, target=$audit_target,
+                    note=$audit_note, before=$audit_before, after=$audit_after, ip=$audit_ip, user_agent=$audit_user_agent;"
+            ))
+            .bind(("audit_actor", data.actor.user))
+            .bind(("audit_action", data.action))
+            .bind(("audit_target", data.target))
+            .bind(("audit_note", data.note))
+            .bind(("audit_ip", data.actor.ip))
+            .bind(("audit_user_agent", data.actor.user_agent))
+    }
+
+    /// For the actions which can not share the transaction with the audit record
+    pub async fn create(
+        &self,
+        data: CreateAuditLog,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) -> CtxResult<AuditLog> {
+        let mut res = Self::build_create_qry(self.db.query("BEGIN"), data)
+            .query("COMMIT")
+            .bind(("audit_before", before))
+            .bind(("audit_after", after))
+            .await?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Option<AuditLog>>(index)?;
+        Ok(data.unwrap())
+    }
+
+    pub async fn get(&self, filter: AuditLogFilter, pag: Pagination) -> CtxResult<Vec<AuditLog>> {
+        let mut conditions = vec![];
+        if filter.actor.is_some() {
+            conditions.push("actor=$actor");
+        }
+        if filter.action.is_some() {
+            conditions.push("action=$action");
+        }
+        if filter.target.is_some() {
+            conditions.push("target=$target");
+        }
+        if filter.target_type.is_some() {
+            conditions.push("record::tb(target)=$target_type");
+        }
+        if filter.from.is_some() {
+            conditions.push("created_at>=$from");
+        }
+        if filter.to.is_some() {
+            conditions.push("created_at<$to");
+        }
+        let where_cond = match conditions.is_empty() {
+            true => "".to_string(),
+            false => format!("WHERE {}", conditions.join(" AND ")),
+        };
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} {where_cond}
+                 ORDER BY created_at DESC LIMIT $limit START $start;"
+            ))
+            .bind(("actor", filter.actor))
+            .bind(("action", filter.action))
+            .bind(("target", filter.target))
+            .bind(("target_type", filter.target_type))
+            .bind(("from", filter.from.map(Datetime::from)))
+            .bind(("to", filter.to.map(Datetime::from)))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<AuditLog>>(0)?;
+        Ok(data)
+    }
+}
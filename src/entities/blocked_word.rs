@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use surrealdb::method::Query;
+use surrealdb::sql::Thing;
+
+use crate::database::client::Db;
+use crate::database::table_names::REPORT_TABLE_NAME;
+use crate::entities::report::{ReportReason, ReportStatus};
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+    utils::db_utils::Pagination,
+};
+use crate::utils::content_filter::normalize;
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockedWordCategory {
+    Profanity,
+    HateSpeech,
+    Sexual,
+    Violence,
+    Spam,
+    /// only rejected as usernames, the content filter skips them
+    Reserved,
+}
+
+impl BlockedWordCategory {
+    pub fn all() -> Vec<BlockedWordCategory> {
+        vec![
+            BlockedWordCategory::Profanity,
+            BlockedWordCategory::HateSpeech,
+            BlockedWordCategory::Sexual,
+            BlockedWordCategory::Violence,
+            BlockedWordCategory::Spam,
+        ]
+    }
+
+    /// used until an admin configures the category
+    pub fn default_action(&self) -> ContentFilterAction {
+        match self {
+            BlockedWordCategory::HateSpeech | BlockedWordCategory::Reserved => {
+                ContentFilterAction::Reject
+            }
+            BlockedWordCategory::Profanity | BlockedWordCategory::Sexual => {
+                ContentFilterAction::Mask
+            }
+            BlockedWordCategory::Violence | BlockedWordCategory::Spam => ContentFilterAction::Flag,
+        }
+    }
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ContentFilterAction {
+    /// the content is not saved
+    Reject,
+    /// the word is replaced by `*`
+    Mask,
+    /// the content is saved and reported to the moderators
+    Flag,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockedWord {
+    pub id: Thing,
+    /// normalized form of the word
+    pub word: String,
+    pub category: BlockedWordCategory,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockedWordCategoryAction {
+    pub category: BlockedWordCategory,
+    pub action: ContentFilterAction,
+}
+
+pub struct BlockedWordDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "blocked_word";
+pub const CATEGORY_TABLE_NAME: &str = "blocked_word_category";
+const SEED_WORDS: &str = include_str!("../../blocked_words.txt");
+const SEED_CHUNK_SIZE: usize = 5000;
+
+impl<'a> BlockedWordDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS word ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS category ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS word_idx ON TABLE {TABLE_NAME} COLUMNS word UNIQUE;
+    DEFINE INDEX IF NOT EXISTS category_idx ON TABLE {TABLE_NAME} COLUMNS category;
+
+    DEFINE TABLE IF NOT EXISTS {CATEGORY_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS category ON TABLE {CATEGORY_TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS action ON TABLE {CATEGORY_TABLE_NAME} TYPE string;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate blocked_word");
+
+        self.seed().await
+    }
+
+    /// Fills the empty word list with the reserved usernames of `blocked_words.txt`
+    async fn seed(&self) -> Result<(), AppError> {
+        let mut res = self
+            .db
+            .query(format!("SELECT VALUE id FROM {TABLE_NAME} LIMIT 1;"))
+            .await?;
+        if !res.take::<Vec<Thing>>(0)?.is_empty() {
+            return Ok(());
+        }
+
+        let words = SEED_WORDS
+            .lines()
+            .map(normalize)
+            .filter(|w| !w.is_empty())
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect::<Vec<String>>();
+        for chunk in words.chunks(SEED_CHUNK_SIZE) {
+            self.db
+                .query(format!(
+                    "FOR $word IN $words {{
+                        CREATE type::thing('{TABLE_NAME}', $word) SET word=$word, category=$category;
+                    }};"
+                ))
+                .bind(("words", chunk.to_vec()))
+                .bind(("category", BlockedWordCategory::Reserved))
+                .await?
+                .check()?;
+        }
+        Ok(())
+    }
+
+    /// `words` must be normalized already
+    pub async fn get_by_words(&self, words: Vec<String>) -> CtxResult<Vec<BlockedWord>> {
+        if words.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut res = self
+            .db
+            .query(format!("SELECT * FROM {TABLE_NAME} WHERE word IN $words;"))
+            .bind(("words", words))
+            .await?;
+        let data = res.take::<Vec<BlockedWord>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn get(
+        &self,
+        category: Option<BlockedWordCategory>,
+        pag: Pagination,
+    ) -> CtxResult<Vec<BlockedWord>> {
+        let category_cond = match category {
+            Some(_) => "WHERE category=$category",
+            None => "",
+        };
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} {category_cond} ORDER BY word LIMIT $limit START $start;"
+            ))
+            .bind(("category", category))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<BlockedWord>>(0)?;
+        Ok(data)
+    }
+
+    /// adds the words or moves the existing ones into the category
+    pub async fn add(
+        &self,
+        words: Vec<String>,
+        category: BlockedWordCategory,
+    ) -> CtxResult<Vec<BlockedWord>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "FOR $word IN $words {{
+                    UPSERT type::thing('{TABLE_NAME}', $word) SET word=$word, category=$category;
+                }};"
+            ))
+            .query(format!("SELECT * FROM {TABLE_NAME} WHERE word IN $words;"))
+            .bind(("words", words))
+            .bind(("category", category))
+            .await?;
+        let data = res.take::<Vec<BlockedWord>>(1)?;
+        Ok(data)
+    }
+
+    pub async fn remove(&self, words: Vec<String>) -> CtxResult<()> {
+        self.db
+            .query(format!("DELETE {TABLE_NAME} WHERE word IN $words;"))
+            .bind(("words", words))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn get_category_actions(&self) -> CtxResult<Vec<BlockedWordCategoryAction>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT category, action FROM {CATEGORY_TABLE_NAME};"
+            ))
+            .await?;
+        let data = res.take::<Vec<BlockedWordCategoryAction>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn set_category_action(
+        &self,
+        category: BlockedWordCategory,
+        action: ContentFilterAction,
+    ) -> CtxResult<()> {
+        self.db
+            .query(format!(
+                "UPSERT type::thing('{CATEGORY_TABLE_NAME}', $category) SET category=$category, action=$action;"
+            ))
+            .bind(("category", category))
+            .bind(("action", action))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    /// opens a report without reporter for the moderation queue
+    pub async fn flag(&self, entity: Thing, words: Vec<String>) -> CtxResult<()> {
+        Self::build_flag_query(self.db.query("BEGIN TRANSACTION;"), entity, words)
+            .query("COMMIT TRANSACTION;")
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub(crate) fn build_flag_query<'b>(
+        query: Query<'b, surrealdb::engine::any::Any>,
+        entity: Thing,
+        words: Vec<String>,
+    ) -> Query<'b, surrealdb::engine::any::Any> {
+        query
+            .query(format!(
+                "CREATE {REPORT_TABLE_NAME} SET entity=$flagged_entity, reason=$flag_reason, text=$flag_text, status=$flag_status;"
+            ))
+            .bind(("flagged_entity", entity))
+            .bind(("flag_reason", ReportReason::Other))
+            .bind(("flag_text", format!("Flagged words: {}", words.join(", "))))
+            .bind(("flag_status", ReportStatus::Open))
+    }
+}
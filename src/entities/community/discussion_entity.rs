@@ -1,6 +1,8 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use surrealdb::sql::Thing;
+use surrealdb::{engine::any, method::Query, sql::Thing};
 
 use middleware::utils::db_utils::{
     get_entity_view, with_not_found_err, IdentIdName, ViewFieldSelector,
@@ -16,6 +18,7 @@ use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME
 use crate::middleware;
 use crate::middleware::utils::db_utils::{Pagination, QryOrder};
 use crate::middleware::utils::string_utils::get_str_thing;
+use crate::models::view::access::ACTIVE_ACCESS_FILTER;
 
 use super::{community_entity, post_entity};
 
@@ -74,6 +77,7 @@ impl<'a> DiscussionDbService<'a> {
     DEFINE FIELD IF NOT EXISTS image_uri ON TABLE {TABLE_NAME} TYPE option<string>;
     DEFINE FIELD IF NOT EXISTS type ON TABLE {TABLE_NAME} TYPE string;
     DEFINE FIELD IF NOT EXISTS created_by ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS hidden ON TABLE {TABLE_NAME} TYPE bool DEFAULT false;
     DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
     DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
     DEFINE INDEX IF NOT EXISTS idx_type ON TABLE {TABLE_NAME} COLUMNS type;
@@ -137,7 +141,7 @@ impl<'a> DiscussionDbService<'a> {
         let fields = T::get_select_query_fields();
 
         let query = format!(
-            "SELECT {fields} FROM {TABLE_NAME} WHERE {query_by_type} <-{ACCESS_TABLE_NAME}.in CONTAINS $user
+            "SELECT {fields} FROM {TABLE_NAME} WHERE {query_by_type} hidden != true AND <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.in CONTAINS $user
                  ORDER BY {order_by} {order_dir} LIMIT $limit START $start;",
         );
         let mut res = self
@@ -178,7 +182,27 @@ impl<'a> DiscussionDbService<'a> {
         })?)
     }
 
+    pub(crate) fn build_update_owner_query<'b>(
+        query: Query<'b, any::Any>,
+        disc: &Thing,
+        owner: &Thing,
+    ) -> Query<'b, any::Any> {
+        query
+            .query("UPDATE $_owned_disc SET created_by=$_disc_owner;")
+            .bind(("_owned_disc", disc.clone()))
+            .bind(("_disc_owner", owner.clone()))
+    }
+
     pub fn get_profile_discussion_id(user_id: &Thing) -> Thing {
         Thing::from((TABLE_NAME.to_string(), format!("{}", user_id.id.to_raw())))
     }
+
+    /// The same users always get the same private discussion
+    pub fn get_private_discussion_id(mut user_ids: Vec<String>) -> Thing {
+        user_ids.sort();
+        let mut hasher = DefaultHasher::new();
+        user_ids.join("").hash(&mut hasher);
+        let hash_id = hasher.finish();
+        Thing::from((TABLE_NAME, format!("{:x}", hash_id).as_str()))
+    }
 }
@@ -1,3 +1,4 @@
+use crate::access::base::role::Role;
 use crate::database::table_names::{
     ACCESS_TABLE_NAME, TAG_REL_TABLE_NAME, TAG_TABLE_NAME, TASK_REQUEST_TABLE_NAME,
 };
@@ -11,14 +12,20 @@ use middleware::{
     error::{AppError, CtxError, CtxResult},
 };
 use serde::{Deserialize, Serialize, Serializer};
+use surrealdb::method::Query;
 use surrealdb::sql::{Id, Thing};
 use validator::Validate;
 
 use crate::database::client::Db;
+use crate::database::repositories::edit_history::EditHistoryRepository;
+use crate::entities::audit_log::{AuditLogDbService, CreateAuditLog};
+use crate::entities::user_auth::block_entity::{BlockType, TABLE_NAME as BLOCK_TABLE_NAME};
 use crate::entities::user_auth::follow_entity::TABLE_NAME as FOLLOW_TABLE_NAME;
 use crate::entities::user_auth::local_user_entity;
+use crate::entities::wallet::wallet_entity::{check_transaction_custom_error, CurrencySymbol};
 use crate::middleware;
 use crate::middleware::utils::string_utils::get_str_thing;
+use crate::models::view::access::ACTIVE_ACCESS_FILTER;
 use crate::models::view::post::PostView;
 
 use super::discussion_entity;
@@ -28,6 +35,7 @@ pub enum PostType {
     Public,
     Private,
     Idea,
+    Paid,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -80,6 +88,10 @@ pub struct Post {
     pub likes_nr: i64,
     pub r#type: PostType,
     pub reply_to: Option<Thing>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencySymbol>,
 }
 
 #[derive(Debug, Serialize)]
@@ -93,6 +105,8 @@ pub struct CreatePost {
     pub r#type: PostType,
     pub delivered_for_task: Option<Thing>,
     pub reply_to: Option<Thing>,
+    pub price: Option<u64>,
+    pub currency: Option<CurrencySymbol>,
 }
 
 pub struct PostDbService<'a> {
@@ -106,6 +120,7 @@ pub const TABLE_NAME: &str = "post";
 const TABLE_COL_DISCUSSION: &str = discussion_entity::TABLE_NAME;
 const TABLE_COL_USER: &str = local_user_entity::TABLE_NAME;
 const TABLE_COL_BELONGS_TO: &str = "belongs_to";
+const THROW_POST_NOT_DELETED: &str = "Post with tasks can not be deleted";
 const INDEX_BELONGS_TO: &str = "belongs_to_idx";
 
 impl<'a> PostDbService<'a> {
@@ -128,6 +143,11 @@ impl<'a> PostDbService<'a> {
     DEFINE FIELD IF NOT EXISTS tasks_nr ON TABLE {TABLE_NAME} TYPE number DEFAULT 0;
     DEFINE FIELD IF NOT EXISTS type ON TABLE {TABLE_NAME} TYPE string;
     DEFINE FIELD IF NOT EXISTS reply_to ON TABLE {TABLE_NAME} TYPE option<record<{TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS price ON TABLE {TABLE_NAME} TYPE option<number>;
+    DEFINE FIELD IF NOT EXISTS currency ON TABLE {TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS pinned_at ON TABLE {TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS edited_at ON TABLE {TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS hidden ON TABLE {TABLE_NAME} TYPE bool DEFAULT false;
     DEFINE FIELD IF NOT EXISTS delivered_for_task ON TABLE {TABLE_NAME} TYPE option<record<{TASK_REQUEST_TABLE_NAME}>>;
     DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
     DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
@@ -176,20 +196,27 @@ impl<'a> PostDbService<'a> {
             None => "",
         };
 
+        // the pinned posts are listed on top in both directions, the pinned cursor
+        // is followed by the rest of the pinned posts and then by the other posts
         let query_by_id = match pag.cursor {
-            Some(_) => match pag.order_dir {
-                QryOrder::DESC => "AND id < $cursor",
-                _ => "AND id > $cursor",
-            },
-            None => "",
+            Some(_) => {
+                let cmp = match pag.order_dir {
+                    QryOrder::DESC => "<",
+                    _ => ">",
+                };
+                format!("AND (((pinned_at IS NONE) = ($cursor.pinned_at IS NONE) AND id {cmp} $cursor) OR (pinned_at IS NONE AND $cursor.pinned_at IS NOT NONE))")
+            }
+            None => "".to_string(),
         };
 
         let fields = PostView::get_select_query_fields();
 
         let query = format!(
-            "SELECT {fields} FROM {TABLE_NAME} 
-            WHERE belongs_to=$disc {query_by_id} {query_by_type} AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}.in)
-            ORDER BY id {order_dir} LIMIT $limit;"
+            "SELECT {fields}, pinned_at IS NOT NONE AS is_pinned FROM {TABLE_NAME} 
+            WHERE belongs_to=$disc {query_by_id} {query_by_type} AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.in OR (belongs_to.type = $subscriber_disc_type AND type = $subscriber_post_type AND $user IN belongs_to<-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}[WHERE role=$subscriber_role].in))
+                AND hidden != true AND belongs_to.hidden != true
+                AND created_by NOT IN $user->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out
+            ORDER BY is_pinned DESC, id {order_dir} LIMIT $limit;"
         );
 
         let mut res = self
@@ -198,7 +225,14 @@ impl<'a> PostDbService<'a> {
             .bind(("limit", pag.count))
             .bind(("cursor", pag.cursor))
             .bind(("filter_by_type", filter_by_type))
-            .bind(("public_post_types", vec![PostType::Public, PostType::Idea]))
+            .bind((
+                "public_post_types",
+                vec![PostType::Public, PostType::Idea, PostType::Paid],
+            ))
+            .bind(("subscriber_role", Role::Subscriber.to_string()))
+            .bind(("subscriber_disc_type", DiscussionType::Public))
+            .bind(("subscriber_post_type", PostType::Private))
+            .bind(("block_type", BlockType::Block))
             .bind(("disc", Thing::from((TABLE_COL_DISCUSSION, disc_id))))
             .bind(("user", Thing::from((TABLE_COL_USER, user_id))))
             .await?;
@@ -221,7 +255,9 @@ impl<'a> PostDbService<'a> {
         let query = format!(
             "count(SELECT id FROM {TABLE_NAME} WHERE 
                 belongs_to=$disc {query_by_type} 
-                AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}.in)
+                AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.in OR (belongs_to.type = $subscriber_disc_type AND type = $subscriber_post_type AND $user IN belongs_to<-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}[WHERE role=$subscriber_role].in))
+                AND hidden != true AND belongs_to.hidden != true
+                AND created_by NOT IN $user->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out
             )"
         );
 
@@ -229,7 +265,14 @@ impl<'a> PostDbService<'a> {
             .db
             .query(query)
             .bind(("filter_by_type", filter_by_type))
-            .bind(("public_post_types", vec![PostType::Public, PostType::Idea]))
+            .bind((
+                "public_post_types",
+                vec![PostType::Public, PostType::Idea, PostType::Paid],
+            ))
+            .bind(("subscriber_role", Role::Subscriber.to_string()))
+            .bind(("subscriber_disc_type", DiscussionType::Public))
+            .bind(("subscriber_post_type", PostType::Private))
+            .bind(("block_type", BlockType::Block))
             .bind(("disc", Thing::from((TABLE_COL_DISCUSSION, disc_id))))
             .bind(("user", Thing::from((TABLE_COL_USER, user_id))))
             .await?;
@@ -250,7 +293,9 @@ impl<'a> PostDbService<'a> {
 
         let query = format!(
             "SELECT {fields} FROM {TABLE_NAME}
-            WHERE record::id(belongs_to) IN $user_ids AND type IN $types AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}.in)
+            WHERE record::id(belongs_to) IN $user_ids AND type IN $types AND (type IN $public_post_types OR $user IN <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.in OR (belongs_to.type = $subscriber_disc_type AND type = $subscriber_post_type AND $user IN belongs_to<-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}[WHERE role=$subscriber_role].in))
+                AND hidden != true AND belongs_to.hidden != true
+                AND created_by NOT IN $user->{BLOCK_TABLE_NAME}[WHERE type=$block_type].out
             ORDER BY id {order_dir} LIMIT $limit START $start;"
         );
 
@@ -263,7 +308,14 @@ impl<'a> PostDbService<'a> {
             .bind(("limit", pag.count))
             .bind(("start", pag.start))
             .bind(("types", types))
-            .bind(("public_post_types", vec![PostType::Public, PostType::Idea]))
+            .bind((
+                "public_post_types",
+                vec![PostType::Public, PostType::Idea, PostType::Paid],
+            ))
+            .bind(("subscriber_role", Role::Subscriber.to_string()))
+            .bind(("subscriber_disc_type", DiscussionType::Public))
+            .bind(("subscriber_post_type", PostType::Private))
+            .bind(("block_type", BlockType::Block))
             .bind(("user", Thing::from((TABLE_COL_USER, user_id))))
             .await?;
 
@@ -278,7 +330,7 @@ impl<'a> PostDbService<'a> {
         let fields = PostView::get_fields();
         let query = format!(
             "SELECT *, out.{{{fields}}} AS entity FROM $tag->{TAG_REL_TABLE_NAME}
-             WHERE out.type IN $public_types AND out.belongs_to.type = $disc_type
+             WHERE out.type IN $public_types AND out.belongs_to.type = $disc_type AND out.hidden != true AND out.belongs_to.hidden != true
              ORDER BY out.{} {} LIMIT $limit START $start;",
             order_by, order_dir
         );
@@ -296,16 +348,42 @@ impl<'a> PostDbService<'a> {
         Ok(posts)
     }
 
-    pub async fn delete(&self, post_id: &str) -> AppResult<()> {
-        let _ = self
+    pub async fn delete(&self, post_id: &str, audit: Option<CreateAuditLog>) -> AppResult<()> {
+        let mut qry = self
             .db
             .query("BEGIN TRANSACTION;")
+            .query("LET $audit_before = SELECT title, content, created_by, belongs_to FROM ONLY $post;")
             .query("LET $reply_ids = (SELECT VALUE id FROM reply WHERE belongs_to = $post);")
             .query("DELETE reply WHERE belongs_to IN $reply_ids;")
             .query("DELETE reply WHERE belongs_to = $post;")
-            .query("DELETE $post WHERE tasks_nr = 0;")
+            .query("LET $deleted_post = DELETE $post WHERE tasks_nr = 0 RETURN BEFORE;")
+            .query(format!(
+                "IF array::len($deleted_post) = 0 {{ THROW \"{THROW_POST_NOT_DELETED}\"; }};"
+            ));
+        if let Some(audit) = audit {
+            qry = AuditLogDbService::build_create_qry(qry, audit);
+        }
+        let mut res = qry
             .query("COMMIT TRANSACTION;")
             .bind(("post", Thing::from((TABLE_NAME, post_id))))
+            .await?;
+        check_transaction_custom_error(&mut res).map_err(|e| match e {
+            AppError::SurrealDb { source } if source.contains(THROW_POST_NOT_DELETED) => {
+                AppError::Generic {
+                    description: THROW_POST_NOT_DELETED.to_string(),
+                }
+            }
+            e => e,
+        })?;
+        Ok(())
+    }
+
+    pub async fn set_pinned(&self, post_id: &str, pinned: bool) -> AppResult<()> {
+        let _ = self
+            .db
+            .query("UPDATE $post SET pinned_at = IF $pinned THEN time::now() ELSE NONE END;")
+            .bind(("post", Thing::from((TABLE_NAME, post_id))))
+            .bind(("pinned", pinned))
             .await
             .map_err(|e| AppError::SurrealDb {
                 source: e.to_string(),
@@ -314,6 +392,24 @@ impl<'a> PostDbService<'a> {
         Ok(())
     }
 
+    /// Adds saving the previous title and content into the edit history and the update
+    /// of the post into the query
+    pub(crate) fn build_update_query<'b>(
+        query: Query<'b, surrealdb::engine::any::Any>,
+        post: Thing,
+        title: String,
+        content: Option<String>,
+        edited_by: Thing,
+    ) -> Query<'b, surrealdb::engine::any::Any> {
+        EditHistoryRepository::build_create_query(query, post.clone(), edited_by)
+            .query(
+                "UPDATE $updated_post SET title=$updated_title, content=$updated_content, edited_at=time::now();",
+            )
+            .bind(("updated_post", post))
+            .bind(("updated_title", title))
+            .bind(("updated_content", content))
+    }
+
     pub async fn create(&self, data: CreatePost) -> CtxResult<PostView> {
         let mut res = self
             .db
@@ -9,7 +9,10 @@ pub struct DiscussionUser {
     #[serde(alias = "in")]
     pub discussion: Thing,
     pub latest_post: Option<Thing>,
+    pub last_read_post: Option<Thing>,
     pub nr_unread: u32,
+    #[serde(default)]
+    pub is_request: bool,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+/// Previous version of the edited post or reply
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EditHistory {
+    pub id: Thing,
+    pub entity: Thing,
+    /// replies have no title
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub edited_by: Thing,
+    pub created_at: DateTime<Utc>,
+}
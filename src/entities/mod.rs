@@ -1,8 +1,19 @@
+pub mod access_invite;
+pub mod access_schema;
 pub mod access_user;
+pub mod account_status_change;
+pub mod audit_log;
+pub mod blocked_word;
 pub mod community;
 pub mod discussion_user;
+pub mod edit_history;
 pub mod nickname;
+pub mod notification_email;
+pub mod notification_settings;
+pub mod push_device;
 pub mod reply;
+pub mod report;
+pub mod subscription;
 pub mod tag;
 pub mod task_donor;
 pub mod task_request;
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use crate::database::client::Db;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::entities::user_notification::UserNotificationEvent;
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+};
+
+/// Notification waiting to be sent by email
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationEmail {
+    pub id: Thing,
+    pub user: Thing,
+    pub event: UserNotificationEvent,
+    pub title: String,
+    /// the failed sending attempts
+    #[serde(default)]
+    pub attempts: u8,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct NotificationEmailDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "notification_email";
+
+impl<'a> NotificationEmailDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS event ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS title ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS attempts ON TABLE {TABLE_NAME} TYPE int DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE {TABLE_NAME} COLUMNS created_at;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate notification_email");
+
+        Ok(())
+    }
+
+    pub async fn create(
+        &self,
+        users: Vec<Thing>,
+        event: UserNotificationEvent,
+        title: &str,
+    ) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query(format!(
+                "FOR $user IN $users {{
+                    CREATE {TABLE_NAME} SET user=$user, event=$event, title=$title;
+                }};"
+            ))
+            .bind(("users", users))
+            .bind(("event", event))
+            .bind(("title", title.to_string()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn get_list(&self, limit: u16) -> CtxResult<Vec<NotificationEmail>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} ORDER BY created_at ASC LIMIT $limit;"
+            ))
+            .bind(("limit", limit))
+            .await?;
+        let data = res.take::<Vec<NotificationEmail>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn increase_attempts(&self, ids: Vec<Thing>) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query("UPDATE $ids SET attempts += 1;")
+            .bind(("ids", ids))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, ids: Vec<Thing>) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query("DELETE $ids;")
+            .bind(("ids", ids))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{Datetime, Thing};
+use validator::Validate;
+
+use crate::database::client::Db;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::entities::user_notification::UserNotificationEvent;
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+};
+
+const MINUTES_IN_DAY: i32 = 24 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationChannel {
+    /// stored notification listed in the app
+    InApp,
+    /// live event of the notifications stream
+    Sse,
+    Email,
+    Push,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestMode {
+    /// emails are sent right away
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestMode {
+    pub fn period(&self) -> Option<Duration> {
+        match self {
+            DigestMode::Off => None,
+            DigestMode::Daily => Some(Duration::days(1)),
+            DigestMode::Weekly => Some(Duration::weeks(1)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DisabledEvent {
+    pub event: UserNotificationEvent,
+    pub channels: Vec<NotificationChannel>,
+}
+
+/// Daily period without the interrupting channels, the in-app notifications are still stored
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Validate)]
+pub struct QuietHours {
+    /// minutes from the local midnight
+    #[validate(range(max = 1439, message = "Start must be lower than 1440 minutes"))]
+    pub start: u16,
+    /// minutes from the local midnight, lower than start if the period goes over midnight
+    #[validate(range(max = 1439, message = "End must be lower than 1440 minutes"))]
+    pub end: u16,
+    /// offset of the user time zone in minutes
+    #[validate(range(min = -720, max = 840, message = "Invalid UTC offset"))]
+    pub utc_offset: i16,
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        let utc_minutes = (time.hour() * 60 + time.minute()) as i32;
+        let local = (utc_minutes + self.utc_offset as i32).rem_euclid(MINUTES_IN_DAY) as u16;
+        match self.start <= self.end {
+            true => self.start <= local && local < self.end,
+            false => local >= self.start || local < self.end,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationSettings {
+    pub user: Thing,
+    /// channels turned off for all events
+    pub disabled_channels: Vec<NotificationChannel>,
+    pub disabled_events: Vec<DisabledEvent>,
+    pub quiet_hours: Option<QuietHours>,
+    pub digest: DigestMode,
+    #[serde(default)]
+    pub last_digest_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationSettings {
+    pub fn default_for(user: Thing) -> Self {
+        Self {
+            user,
+            disabled_channels: vec![],
+            disabled_events: vec![],
+            quiet_hours: None,
+            digest: DigestMode::Off,
+            last_digest_at: None,
+        }
+    }
+
+    pub fn is_enabled(&self, event: &UserNotificationEvent, channel: NotificationChannel) -> bool {
+        if self.disabled_channels.contains(&channel) {
+            return false;
+        }
+        !self
+            .disabled_events
+            .iter()
+            .any(|d| &d.event == event && d.channels.contains(&channel))
+    }
+
+    /// Checks the preferences and the quiet hours at the given time
+    pub fn allows(
+        &self,
+        event: &UserNotificationEvent,
+        channel: NotificationChannel,
+        time: DateTime<Utc>,
+    ) -> bool {
+        if !self.is_enabled(event, channel) {
+            return false;
+        }
+        match (channel, &self.quiet_hours) {
+            (NotificationChannel::InApp, _) | (_, None) => true,
+            (_, Some(quiet_hours)) => !quiet_hours.contains(time),
+        }
+    }
+}
+
+pub struct NotificationSettingsDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "notification_settings";
+
+impl<'a> NotificationSettingsDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS disabled_channels ON TABLE {TABLE_NAME} TYPE array<string> DEFAULT [];
+    DEFINE FIELD IF NOT EXISTS disabled_events ON TABLE {TABLE_NAME} FLEXIBLE TYPE array<object> DEFAULT [];
+    DEFINE FIELD IF NOT EXISTS quiet_hours ON TABLE {TABLE_NAME} FLEXIBLE TYPE option<object>;
+    DEFINE FIELD IF NOT EXISTS digest ON TABLE {TABLE_NAME} TYPE string DEFAULT 'Off';
+    DEFINE FIELD IF NOT EXISTS last_digest_at ON TABLE {TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS unsubscribe_token ON TABLE {TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
+    DEFINE INDEX IF NOT EXISTS user_idx ON TABLE {TABLE_NAME} COLUMNS user UNIQUE;
+    DEFINE INDEX IF NOT EXISTS unsubscribe_token_idx ON TABLE {TABLE_NAME} COLUMNS unsubscribe_token;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation
+            .check()
+            .expect("should mutate notification_settings");
+
+        Ok(())
+    }
+
+    fn get_settings_id(user: &Thing) -> Thing {
+        Thing::from((TABLE_NAME, user.id.clone()))
+    }
+
+    /// Returns the default settings if the user has not saved any
+    pub async fn get_by_user(&self, user: &Thing) -> CtxResult<NotificationSettings> {
+        let mut res = self
+            .db
+            .query("SELECT * FROM ONLY $id;")
+            .bind(("id", Self::get_settings_id(user)))
+            .await?;
+        let data = res.take::<Option<NotificationSettings>>(0)?;
+        Ok(data.unwrap_or_else(|| NotificationSettings::default_for(user.clone())))
+    }
+
+    /// Returns only the saved settings, the users without them use the defaults
+    pub async fn get_by_users(&self, users: Vec<Thing>) -> CtxResult<Vec<NotificationSettings>> {
+        let ids = users
+            .iter()
+            .map(Self::get_settings_id)
+            .collect::<Vec<Thing>>();
+        let mut res = self
+            .db
+            .query("SELECT * FROM $ids;")
+            .bind(("ids", ids))
+            .await?;
+        let data = res.take::<Vec<NotificationSettings>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn upsert(&self, settings: NotificationSettings) -> CtxResult<NotificationSettings> {
+        let mut res = self
+            .db
+            .query(
+                "UPSERT ONLY $id SET user=$user, disabled_channels=$disabled_channels,
+                    disabled_events=$disabled_events, quiet_hours=$quiet_hours, digest=$digest;",
+            )
+            .bind(("id", Self::get_settings_id(&settings.user)))
+            .bind(("user", settings.user))
+            .bind(("disabled_channels", settings.disabled_channels))
+            .bind(("disabled_events", settings.disabled_events))
+            .bind(("quiet_hours", settings.quiet_hours))
+            .bind(("digest", settings.digest))
+            .await?;
+        let data = res.take::<Option<NotificationSettings>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    /// Returns the token of the unsubscribe link of the emails, creates it on the first use
+    pub async fn get_unsubscribe_token(&self, user: &Thing) -> CtxResult<String> {
+        let mut res = self
+            .db
+            .query(
+                "UPSERT ONLY $id SET user=$user, unsubscribe_token = unsubscribe_token OR rand::uuid::v4()
+                    RETURN VALUE unsubscribe_token;",
+            )
+            .bind(("id", Self::get_settings_id(user)))
+            .bind(("user", user.clone()))
+            .await?;
+        let data = res.take::<Option<String>>(0)?;
+        Ok(data.unwrap())
+    }
+
+    /// Disables the email channel of the user with the token
+    pub async fn unsubscribe_email(&self, token: String) -> CtxResult<()> {
+        let mut res = self
+            .db
+            .query(format!(
+                "UPDATE {TABLE_NAME} SET disabled_channels = array::union(disabled_channels, [$channel])
+                    WHERE unsubscribe_token = $token RETURN VALUE id;"
+            ))
+            .bind(("channel", NotificationChannel::Email))
+            .bind(("token", token))
+            .await?;
+        let data = res.take::<Vec<Thing>>(0)?;
+        if data.is_empty() {
+            return Err(self.ctx.to_ctx_error(AppError::EntityFailIdNotFound {
+                ident: "unsubscribe token".to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Returns the settings of the users with the email digest to be sent at the given time
+    pub async fn get_digest_due(
+        &self,
+        time: DateTime<Utc>,
+    ) -> CtxResult<Vec<NotificationSettings>> {
+        let daily_before = time - DigestMode::Daily.period().unwrap();
+        let weekly_before = time - DigestMode::Weekly.period().unwrap();
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} WHERE $channel NOTINSIDE disabled_channels AND (
+                    (digest = $daily AND (last_digest_at = NONE OR last_digest_at <= $daily_before))
+                    OR (digest = $weekly AND (last_digest_at = NONE OR last_digest_at <= $weekly_before))
+                );"
+            ))
+            .bind(("channel", NotificationChannel::Email))
+            .bind(("daily", DigestMode::Daily))
+            .bind(("weekly", DigestMode::Weekly))
+            .bind(("daily_before", Datetime::from(daily_before)))
+            .bind(("weekly_before", Datetime::from(weekly_before)))
+            .await?;
+        let data = res.take::<Vec<NotificationSettings>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn set_last_digest_at(&self, user: &Thing, time: DateTime<Utc>) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query("UPDATE $id SET last_digest_at = $time;")
+            .bind(("id", Self::get_settings_id(user)))
+            .bind(("time", Datetime::from(time)))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
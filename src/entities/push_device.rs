@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use crate::database::client::Db;
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum DevicePlatform {
+    Ios,
+    Android,
+}
+
+/// Mobile device registered for the push notifications
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PushDevice {
+    pub id: Thing,
+    pub user: Thing,
+    pub token: String,
+    pub platform: DevicePlatform,
+    /// the last badge count sent to the device
+    pub badge: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct PushDeviceDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "push_device";
+
+impl<'a> PushDeviceDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS token ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS platform ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS badge ON TABLE {TABLE_NAME} TYPE int DEFAULT 0;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
+    DEFINE INDEX IF NOT EXISTS user_idx ON TABLE {TABLE_NAME} COLUMNS user;
+    DEFINE INDEX IF NOT EXISTS token_idx ON TABLE {TABLE_NAME} COLUMNS token UNIQUE;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate push_device");
+
+        Ok(())
+    }
+
+    /// Registers the device token for the user, the token of another user is moved to the user
+    pub async fn upsert(
+        &self,
+        user: Thing,
+        token: String,
+        platform: DevicePlatform,
+    ) -> CtxResult<PushDevice> {
+        let mut res = self
+            .db
+            .query("BEGIN")
+            .query(format!(
+                "DELETE {TABLE_NAME} WHERE token=$token;
+                 CREATE ONLY {TABLE_NAME} SET user=$user, token=$token, platform=$platform;"
+            ))
+            .query("COMMIT")
+            .bind(("user", user))
+            .bind(("token", token))
+            .bind(("platform", platform))
+            .await?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Option<PushDevice>>(index)?;
+        Ok(data.unwrap())
+    }
+
+    pub async fn get_by_users(&self, users: Vec<Thing>) -> CtxResult<Vec<PushDevice>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} WHERE user IN $users ORDER BY created_at DESC;"
+            ))
+            .bind(("users", users))
+            .await?;
+        let data = res.take::<Vec<PushDevice>>(0)?;
+        Ok(data)
+    }
+
+    pub async fn delete(&self, user: Thing, token: String) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query(format!(
+                "DELETE {TABLE_NAME} WHERE user=$user AND token=$token;"
+            ))
+            .bind(("user", user))
+            .bind(("token", token))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn delete_by_ids(&self, ids: Vec<Thing>) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query("DELETE $ids;")
+            .bind(("ids", ids))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn set_badge(&self, ids: Vec<Thing>, badge: u64) -> CtxResult<()> {
+        let _ = self
+            .db
+            .query("UPDATE $ids SET badge=$badge;")
+            .bind(("ids", ids))
+            .bind(("badge", badge))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
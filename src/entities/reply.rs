@@ -12,4 +12,7 @@ pub struct Reply {
     pub replies_nr: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub hidden: bool,
 }
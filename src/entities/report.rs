@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use surrealdb::sql::Thing;
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    HateSpeech,
+    Violence,
+    Nudity,
+    Scam,
+    Impersonation,
+    Other,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum ReportStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum ModerationActionType {
+    Dismiss,
+    Hide,
+    Delete,
+    Warn,
+    Suspend,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Report {
+    pub id: Thing,
+    pub entity: Thing,
+    /// is none for the content flagged by the words filter
+    pub reporter: Option<Thing>,
+    pub reason: ReportReason,
+    pub text: Option<String>,
+    pub status: ReportStatus,
+    pub assigned_to: Option<Thing>,
+    pub action: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModerationAction {
+    pub id: Thing,
+    pub entity: Thing,
+    /// is none for the actions applied automatically
+    pub moderator: Option<Thing>,
+    pub action: ModerationActionType,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct ReportFilter {
+    pub status: Option<ReportStatus>,
+    pub reason: Option<ReportReason>,
+    pub entity_type: Option<String>,
+    pub assigned_to: Option<Thing>,
+    pub entity: Option<Thing>,
+    pub reporter: Option<Thing>,
+}
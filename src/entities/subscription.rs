@@ -0,0 +1,74 @@
+use crate::entities::wallet::wallet_entity::CurrencySymbol;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+pub const THROW_SUBSCRIPTION_NOT_RENEWABLE: &str = "Subscription not renewable";
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum BillingInterval {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl BillingInterval {
+    pub fn period(&self) -> TimeDelta {
+        match self {
+            BillingInterval::Weekly => TimeDelta::days(7),
+            BillingInterval::Monthly => TimeDelta::days(30),
+            BillingInterval::Yearly => TimeDelta::days(365),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub enum SubscriptionStatus {
+    Active,
+    // the last renewal charge failed, access is kept until grace_period_end
+    PastDue,
+    // canceled by the subscriber, access is kept until current_period_end
+    Canceled,
+    Expired,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SubscriptionTier {
+    pub id: Thing,
+    pub user: Thing,
+    pub title: String,
+    pub description: Option<String>,
+    pub price: u64,
+    pub currency: CurrencySymbol,
+    pub interval: BillingInterval,
+    pub perks: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionTierCreate {
+    pub user: Thing,
+    pub title: String,
+    pub description: Option<String>,
+    pub price: u64,
+    pub currency: CurrencySymbol,
+    pub interval: BillingInterval,
+    pub perks: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Subscription {
+    pub id: Thing,
+    pub tier: Thing,
+    pub subscriber: Thing,
+    pub creator: Thing,
+    pub price: u64,
+    pub currency: CurrencySymbol,
+    pub interval: BillingInterval,
+    pub status: SubscriptionStatus,
+    pub current_period_end: DateTime<Utc>,
+    pub grace_period_end: Option<DateTime<Utc>>,
+    pub canceled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
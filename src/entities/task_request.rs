@@ -42,6 +42,8 @@ pub enum TaskRequestStatus {
     Init,
     InProgress,
     Completed,
+    /// cancelled with the refunds because the creator's account was restricted
+    Cancelled,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
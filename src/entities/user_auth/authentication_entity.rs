@@ -13,6 +13,7 @@ use crate::{
 use middleware::{
     ctx::Ctx,
     error::{AppError, CtxResult},
+    utils::db_utils::ViewFieldSelector,
 };
 
 #[derive(Debug, Serialize)]
@@ -112,16 +113,20 @@ impl<'a> AuthenticationDbService<'a> {
         Ok(res.take::<Option<Authentication>>(0)?)
     }
 
-    pub async fn get_by_user(&self, user: Thing) -> CtxResult<Vec<Authentication>> {
+    pub async fn get_by_user<T: for<'b> Deserialize<'b> + ViewFieldSelector>(
+        &self,
+        user: Thing,
+    ) -> CtxResult<Vec<T>> {
+        let fields = T::get_select_query_fields();
         let mut res = self
             .db
-            .query("SELECT * FROM type::table($table) WHERE local_user=<record>$user AND auth_type=$a_type;")
+            .query(format!(
+                "SELECT {fields} FROM type::table($table) WHERE local_user=<record>$user;"
+            ))
             .bind(("table", TABLE_NAME))
             .bind(("user", user))
             .await?;
-        Ok(res
-            .take::<Option<Vec<Authentication>>>(0)?
-            .unwrap_or_default())
+        Ok(res.take::<Vec<T>>(0)?)
     }
 
     pub async fn update_token(
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use surrealdb::sql::Thing;
+
+use middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+};
+
+use super::local_user_entity::{self, LocalUser};
+use crate::database::client::Db;
+use crate::middleware;
+
+#[derive(EnumString, Display, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BlockType {
+    /// hides the user's content and prevents any interaction
+    Block,
+    /// suppresses notifications caused by the user only
+    Mute,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserBlock {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub r#in: Thing,
+    pub out: Thing,
+    pub r#type: BlockType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r_created: Option<String>,
+}
+
+pub struct BlockDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "user_block";
+const TABLE_USER: &str = local_user_entity::TABLE_NAME;
+
+impl<'a> BlockDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let block = BlockType::Block.to_string();
+        let mute = BlockType::Mute.to_string();
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} TYPE RELATION IN {TABLE_USER} OUT {TABLE_USER} ENFORCED SCHEMAFULL PERMISSIONS NONE;
+    DEFINE INDEX IF NOT EXISTS in_out_unique_idx ON {TABLE_NAME} FIELDS in, out UNIQUE;
+    DEFINE INDEX IF NOT EXISTS out_idx ON {TABLE_NAME} COLUMNS out;
+    DEFINE FIELD IF NOT EXISTS type ON TABLE {TABLE_NAME} TYPE '{block}'|'{mute}';
+    DEFINE FIELD IF NOT EXISTS r_created ON TABLE {TABLE_NAME} TYPE option<datetime> DEFAULT time::now() VALUE $before OR time::now();
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate domain");
+
+        Ok(())
+    }
+
+    /// a block replaces an existing mute of the same user and the other way around
+    pub async fn set(&self, user: Thing, target: Thing, r#type: BlockType) -> CtxResult<()> {
+        let res = self
+            .db
+            .query(format!(
+                "LET $res=(UPDATE $in->{TABLE_NAME} SET type=$type WHERE out=$out)[0].id;"
+            ))
+            .query(format!(
+                "IF $res = NONE THEN RELATE $in->{TABLE_NAME}->$out SET type=$type END;"
+            ))
+            .bind(("in", user))
+            .bind(("out", target))
+            .bind(("type", r#type))
+            .await?;
+        res.check()?;
+        Ok(())
+    }
+
+    pub async fn remove(&self, user: Thing, target: Thing, r#type: BlockType) -> CtxResult<()> {
+        self.db
+            .query(format!(
+                "DELETE $in->{TABLE_NAME} WHERE out=$out AND type=$type;"
+            ))
+            .bind(("in", user))
+            .bind(("out", target))
+            .bind(("type", r#type))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_users(&self, user: Thing, r#type: BlockType) -> CtxResult<Vec<LocalUser>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT VALUE out.* FROM $in->{TABLE_NAME} WHERE type=$type ORDER BY r_created DESC;"
+            ))
+            .bind(("in", user))
+            .bind(("type", r#type))
+            .await?;
+        let data = res.take::<Vec<LocalUser>>(0)?;
+        Ok(data)
+    }
+
+    /// true if any of the two users has blocked the other one
+    pub async fn is_blocked_between(&self, user: Thing, other: Thing) -> CtxResult<bool> {
+        let qry = format!(
+            "SELECT count() FROM {TABLE_NAME} WHERE type=$type AND ((in=$user AND out=$other) OR (in=$other AND out=$user)) GROUP ALL;"
+        );
+        let mut res = self
+            .db
+            .query(qry)
+            .bind(("user", user))
+            .bind(("other", other))
+            .bind(("type", BlockType::Block))
+            .await?;
+        let res: Option<i64> = res.take("count")?;
+        Ok(res.unwrap_or(0) > 0)
+    }
+
+    /// returns the users from `users` who have blocked (or muted, depending on `types`) the `target`
+    pub async fn get_blocking_users(
+        &self,
+        target: Thing,
+        users: Vec<Thing>,
+        types: Vec<BlockType>,
+    ) -> CtxResult<Vec<Thing>> {
+        if users.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT VALUE in FROM {TABLE_NAME} WHERE out=$target AND in IN $users AND type IN $types;"
+            ))
+            .bind(("target", target))
+            .bind(("users", users))
+            .bind(("types", types))
+            .await?;
+        let data = res.take::<Vec<Thing>>(0)?;
+        Ok(data)
+    }
+
+    /// fails if any of `users` has blocked the `user`
+    pub async fn check_not_blocked_by(&self, user: Thing, users: Vec<Thing>) -> CtxResult<()> {
+        let blocking_users = self
+            .get_blocking_users(user, users, vec![BlockType::Block])
+            .await?;
+        if !blocking_users.is_empty() {
+            return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use surrealdb::sql::Thing;
+use surrealdb::sql::{Datetime, Thing};
 
 use crate::database::client::Db;
 use crate::database::repositories::verification_code_repo::VERIFICATION_CODE_TABLE_NAME;
 use crate::database::surrdb_utils::{get_entity, get_str_id_thing};
+use crate::entities::audit_log::{AuditLogDbService, CreateAuditLog};
 use crate::entities::user_auth::authentication_entity::{AuthType, Authentication};
 use crate::entities::verification_code::VerificationCodeFor;
 use crate::middleware;
@@ -16,7 +17,7 @@ use middleware::utils::db_utils::{
 use middleware::utils::string_utils::get_string_thing;
 use middleware::{
     ctx::Ctx,
-    error::{AppError, CtxResult},
+    error::{AppError, AppResult, CtxResult},
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -25,6 +26,57 @@ pub enum UserRole {
     User,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    Suspended,
+    Banned,
+    Deactivated,
+}
+
+impl AccountStatus {
+    /// Fails if the account with the status can not be used now.
+    /// An expired suspension does not block the account anymore.
+    pub fn check(&self, suspended_until: Option<DateTime<Utc>>) -> AppResult<()> {
+        match self {
+            AccountStatus::Active => Ok(()),
+            AccountStatus::Suspended => match suspended_until {
+                Some(until) if until <= Utc::now() => Ok(()),
+                _ => Err(AppError::AccountSuspended {
+                    until: suspended_until.map(|v| v.to_rfc3339()),
+                }),
+            },
+            AccountStatus::Banned => Err(AppError::AccountBanned),
+            AccountStatus::Deactivated => Err(AppError::AccountDeactivated),
+        }
+    }
+}
+
+/// The account data checked on every authenticated request
+#[derive(Debug, Deserialize)]
+pub struct UserAuthState {
+    pub status: Option<AccountStatus>,
+    pub suspended_until: Option<DateTime<Utc>>,
+    /// tokens issued before or in the same second are not valid anymore
+    pub tokens_valid_from: Option<DateTime<Utc>>,
+}
+
+impl UserAuthState {
+    pub fn check(&self, token_issued_at: usize) -> AppResult<()> {
+        self.status
+            .clone()
+            .unwrap_or_default()
+            .check(self.suspended_until)?;
+        match self.tokens_valid_from {
+            Some(from) if (token_issued_at as i64) <= from.timestamp() => {
+                Err(AppError::AuthFailNoJwtCookie)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LocalUser {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,6 +104,10 @@ pub struct LocalUser {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_seen: Option<DateTime<Utc>>,
     pub role: UserRole,
+    #[serde(default)]
+    pub status: AccountStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspended_until: Option<DateTime<Utc>>,
 }
 
 impl LocalUser {
@@ -71,6 +127,8 @@ impl LocalUser {
             credits: 0,
             last_seen: None,
             role: UserRole::User,
+            status: AccountStatus::Active,
+            suspended_until: None,
         }
     }
 }
@@ -97,6 +155,14 @@ pub struct UpdateUser {
     pub username: Option<Option<String>>,
 }
 
+#[derive(Debug, Default)]
+pub struct UserFilter {
+    /// part of the username, full name or email
+    pub search: Option<String>,
+    pub role: Option<UserRole>,
+    pub status: Option<AccountStatus>,
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize)]
 struct UsernameView {
@@ -138,6 +204,9 @@ impl<'a> LocalUserDbService<'a> {
     DEFINE FIELD IF NOT EXISTS credits ON TABLE {TABLE_NAME} TYPE number DEFAULT 0;
     DEFINE FIELD IF NOT EXISTS last_seen ON TABLE {TABLE_NAME} TYPE option<datetime>;
     DEFINE FIELD IF NOT EXISTS role ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE {TABLE_NAME} TYPE string DEFAULT 'Active';
+    DEFINE FIELD IF NOT EXISTS suspended_until ON TABLE {TABLE_NAME} TYPE option<datetime>;
+    DEFINE FIELD IF NOT EXISTS tokens_valid_from ON TABLE {TABLE_NAME} TYPE option<datetime>;
 
     DEFINE INDEX IF NOT EXISTS local_user_username_idx ON TABLE {TABLE_NAME} COLUMNS username UNIQUE;
     DEFINE INDEX IF NOT EXISTS local_user_email_verified_idx ON TABLE {TABLE_NAME} COLUMNS email_verified UNIQUE;
@@ -316,6 +385,48 @@ impl<'a> LocalUserDbService<'a> {
         Ok(res)
     }
 
+    pub async fn get_filtered<T: for<'b> Deserialize<'b> + ViewFieldSelector>(
+        &self,
+        filter: UserFilter,
+        pag: Pagination,
+    ) -> CtxResult<Vec<T>> {
+        let mut conditions = vec![];
+        if filter.search.is_some() {
+            conditions.push(
+                "(string::contains(username, $search)
+                    OR string::contains(string::lowercase(full_name ?? ''), $search)
+                    OR string::contains(email_verified ?? '', $search))",
+            );
+        }
+        if filter.role.is_some() {
+            conditions.push("role=$role");
+        }
+        if filter.status.is_some() {
+            // the users created before the status field have no status
+            conditions.push("(status ?? $active)=$status");
+        }
+        let where_cond = match conditions.is_empty() {
+            true => "".to_string(),
+            false => format!("WHERE {}", conditions.join(" AND ")),
+        };
+        let fields = T::get_select_query_fields();
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT {fields} FROM {TABLE_NAME} {where_cond}
+                 ORDER BY username LIMIT $limit START $start;"
+            ))
+            .bind(("search", filter.search.map(|v| v.to_lowercase())))
+            .bind(("role", filter.role))
+            .bind(("status", filter.status))
+            .bind(("active", AccountStatus::Active))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<T>>(0)?;
+        Ok(data)
+    }
+
     pub async fn get_view<T: for<'b> Deserialize<'b> + ViewFieldSelector>(
         &self,
         ident_id_name: IdentIdName,
@@ -367,6 +478,87 @@ impl<'a> LocalUserDbService<'a> {
         Ok(())
     }
 
+    pub async fn set_role(
+        &self,
+        user: Thing,
+        role: UserRole,
+        audit: CreateAuditLog,
+    ) -> CtxResult<()> {
+        let qry = self
+            .db
+            .query("BEGIN")
+            .query("LET $audit_before = SELECT role FROM ONLY $user;")
+            .query("UPDATE $user SET role=$role;")
+            .query("LET $audit_after = SELECT role FROM ONLY $user;");
+        let _ = AuditLogDbService::build_create_qry(qry, audit)
+            .query("COMMIT")
+            .bind(("user", user))
+            .bind(("role", role))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn reset_otp(&self, user: Thing, audit: CreateAuditLog) -> CtxResult<()> {
+        let qry = self
+            .db
+            .query("BEGIN")
+            .query("LET $audit_before = SELECT is_otp_enabled FROM ONLY $user;")
+            .query("UPDATE $user SET is_otp_enabled=false, otp_secret=NONE;")
+            .query("LET $audit_after = SELECT is_otp_enabled FROM ONLY $user;");
+        let _ = AuditLogDbService::build_create_qry(qry, audit)
+            .query("COMMIT")
+            .bind(("user", user))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    pub async fn get_auth_state(&self, user: Thing) -> CtxResult<Option<UserAuthState>> {
+        let mut res = self
+            .db
+            .query("SELECT status, suspended_until, tokens_valid_from FROM ONLY $user;")
+            .bind(("user", user))
+            .await?;
+        Ok(res.take::<Option<UserAuthState>>(0)?)
+    }
+
+    /// Restricting the account invalidates all its issued tokens
+    pub async fn set_status(
+        &self,
+        user: Thing,
+        status: AccountStatus,
+        suspended_until: Option<DateTime<Utc>>,
+    ) -> CtxResult<()> {
+        let tokens_cond = match status {
+            AccountStatus::Active => "",
+            _ => ", tokens_valid_from=time::now()",
+        };
+        let _ = self
+            .db
+            .query(format!(
+                "UPDATE $user SET status=$status, suspended_until=$until{tokens_cond};"
+            ))
+            .bind(("user", user))
+            .bind(("status", status))
+            .bind(("until", suspended_until.map(Datetime::from)))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    /// Users with the expired suspension which still have to be reinstated
+    pub async fn get_expired_suspensions(&self) -> CtxResult<Vec<Thing>> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT VALUE id FROM {TABLE_NAME} WHERE status=$status AND suspended_until <= time::now();"
+            ))
+            .bind(("status", AccountStatus::Suspended))
+            .await?;
+        Ok(res.take::<Vec<Thing>>(0)?)
+    }
+
     pub async fn users_len(&self) -> CtxResult<i32> {
         let q = format!("SELECT count() FROM {TABLE_NAME} limit 1");
         let res: Option<i32> = self.db.query(q).await?.take("count")?;
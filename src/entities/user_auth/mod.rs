@@ -1,3 +1,4 @@
 pub mod authentication_entity;
+pub mod block_entity;
 pub mod follow_entity;
 pub mod local_user_entity;
@@ -1,4 +1,6 @@
-use crate::utils::validate_utils::deserialize_thing_or_string_id;
+use crate::utils::validate_utils::{
+    deserialize_thing_or_string_id, deserialize_thing_or_string_ids,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -15,6 +17,23 @@ pub struct UserNotification {
     pub is_read: bool,
     pub metadata: Option<Value>,
     pub created_at: DateTime<Utc>,
+    /// latest users who raised the grouped events, the first one is the newest
+    #[serde(default, deserialize_with = "deserialize_thing_or_string_ids")]
+    pub actors: Vec<String>,
+    /// all users who raised the grouped events, so the repeated ones are counted once
+    #[serde(
+        default,
+        skip_serializing,
+        deserialize_with = "deserialize_thing_or_string_ids"
+    )]
+    pub all_actors: Vec<String>,
+    #[serde(default)]
+    pub actors_count: u64,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// only sent live to the receivers without the in-app channel,
+    /// it isn't stored so it can't be read or fetched by its id
+    #[serde(default)]
+    pub is_ephemeral: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -34,6 +53,7 @@ pub enum UserNotificationEvent {
     DepositCompleted,
     WithdrawCompleted,
     CreatedDiscussion,
+    ModerationWarning,
 }
 
 impl UserNotificationEvent {
@@ -54,6 +74,19 @@ impl UserNotificationEvent {
             UserNotificationEvent::WithdrawCompleted => "WithdrawCompleted",
             UserNotificationEvent::CreatedDiscussion => "CreatedDiscussion",
             UserNotificationEvent::UserTaskRequestRejected => "UserTaskRequestRejected",
+            UserNotificationEvent::ModerationWarning => "ModerationWarning",
         }
     }
+
+    /// Events delivered by email as well, if the user allows it
+    pub fn is_emailed(&self) -> bool {
+        matches!(
+            self,
+            UserNotificationEvent::UserTaskRequestReceived
+                | UserNotificationEvent::UserTaskRequestDelivered
+                | UserNotificationEvent::TaskRewardReceived
+                | UserNotificationEvent::DepositCompleted
+                | UserNotificationEvent::WithdrawCompleted
+        )
+    }
 }
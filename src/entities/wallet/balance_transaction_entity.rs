@@ -6,6 +6,7 @@ use crate::entities::wallet::wallet_entity::check_transaction_custom_error;
 use crate::middleware;
 use crate::middleware::error::CtxError;
 use crate::models::view::balance_tx::CurrencyTransactionView;
+use crate::models::view::revenue::{RevenueByCurrencyView, RevenueByPeriodView, RevenueByTypeView};
 use chrono::{DateTime, Utc};
 use middleware::utils::db_utils::{
     get_entity, get_entity_list_view, with_not_found_err, IdentIdName, Pagination,
@@ -16,8 +17,10 @@ use middleware::{
 };
 use serde::{Deserialize, Serialize};
 use surrealdb::method::Query;
-use surrealdb::sql::Thing;
-use wallet_entity::{CurrencySymbol, WalletDbService, APP_GATEWAY_WALLET};
+use surrealdb::sql::{Datetime, Thing};
+use wallet_entity::{
+    CurrencySymbol, WalletDbService, ADJUSTMENTS_WALLET, APP_GATEWAY_WALLET, THROW_WALLET_FROZEN,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct TransferCurrencyResponse {
@@ -44,6 +47,24 @@ pub struct CurrencyTransaction {
     pub r#type: Option<TransactionType>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum RevenuePeriod {
+    Day,
+    Week,
+    Month,
+}
+
+impl Display for RevenuePeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevenuePeriod::Day => write!(f, "day"),
+            RevenuePeriod::Week => write!(f, "week"),
+            RevenuePeriod::Month => write!(f, "month"),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub enum TransactionType {
     Withdraw,
@@ -52,6 +73,9 @@ pub enum TransactionType {
     Donate,
     Reward,
     Fee,
+    Subscription,
+    Purchase,
+    Adjustment,
 }
 
 impl Display for TransactionType {
@@ -63,6 +87,9 @@ impl Display for TransactionType {
             TransactionType::Donate => write!(f, "Donate"),
             TransactionType::Reward => write!(f, "Reward"),
             TransactionType::Fee => write!(f, "Fee"),
+            TransactionType::Subscription => write!(f, "Subscription"),
+            TransactionType::Purchase => write!(f, "Purchase"),
+            TransactionType::Adjustment => write!(f, "Adjustment"),
         }
     }
 }
@@ -104,15 +131,16 @@ impl<'a> BalanceTransactionDbService<'a> {
         let mutation = self.db.query(sql).await?;
 
         mutation.check().expect("should mutate currencyTransaction");
-        let g_wallet = WalletDbService {
+        let wallets_repository = WalletDbService {
             db: self.db,
             ctx: self.ctx,
-        }
-        .init_app_gateway_wallet()
-        .await;
-        if let Err(err) = g_wallet {
-            if !err.error.to_string().contains("Wallet already exists") {
-                return Err(err.error);
+        };
+        for wallet_id in [APP_GATEWAY_WALLET.clone(), ADJUSTMENTS_WALLET.clone()] {
+            let res = wallets_repository.init_system_wallet(&wallet_id).await;
+            if let Err(err) = res {
+                if !err.error.to_string().contains("Wallet already exists") {
+                    return Err(err.error);
+                }
             }
         }
         Ok(())
@@ -245,6 +273,82 @@ impl<'a> BalanceTransactionDbService<'a> {
         with_not_found_err(opt, self.ctx, &ident.to_string().as_str())
     }
 
+    pub async fn get_income_by_period(
+        &self,
+        wallet_id: &Thing,
+        period: &RevenuePeriod,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> CtxResult<Vec<RevenueByPeriodView>> {
+        let condition = Self::build_income_condition(&from, &to);
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT time::group(created_at, $period) AS period, currency, math::sum(amount_in) AS amount, count() AS count
+                FROM {TABLE_NAME} WHERE {condition}
+                GROUP BY period, currency ORDER BY period ASC;"
+            ))
+            .bind(("wallet", wallet_id.clone()))
+            .bind(("period", period.to_string()))
+            .bind(("from", from.map(Datetime::from)))
+            .bind(("to", to.map(Datetime::from)))
+            .await?;
+        Ok(res.take::<Vec<RevenueByPeriodView>>(0)?)
+    }
+
+    pub async fn get_income_by_type(
+        &self,
+        wallet_id: &Thing,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> CtxResult<Vec<RevenueByTypeView>> {
+        let condition = Self::build_income_condition(&from, &to);
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT type, currency, math::sum(amount_in) AS amount, count() AS count
+                FROM {TABLE_NAME} WHERE {condition}
+                GROUP BY type, currency;"
+            ))
+            .bind(("wallet", wallet_id.clone()))
+            .bind(("from", from.map(Datetime::from)))
+            .bind(("to", to.map(Datetime::from)))
+            .await?;
+        Ok(res.take::<Vec<RevenueByTypeView>>(0)?)
+    }
+
+    pub async fn get_income_by_currency(
+        &self,
+        wallet_id: &Thing,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> CtxResult<Vec<RevenueByCurrencyView>> {
+        let condition = Self::build_income_condition(&from, &to);
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT currency, math::sum(amount_in) AS amount, count() AS count
+                FROM {TABLE_NAME} WHERE {condition}
+                GROUP BY currency;"
+            ))
+            .bind(("wallet", wallet_id.clone()))
+            .bind(("from", from.map(Datetime::from)))
+            .bind(("to", to.map(Datetime::from)))
+            .await?;
+        Ok(res.take::<Vec<RevenueByCurrencyView>>(0)?)
+    }
+
+    fn build_income_condition(from: &Option<DateTime<Utc>>, to: &Option<DateTime<Utc>>) -> String {
+        let mut condition = "wallet=$wallet AND amount_in != NONE".to_string();
+        if from.is_some() {
+            condition.push_str(" AND created_at >= $from");
+        }
+        if to.is_some() {
+            condition.push_str(" AND created_at <= $to");
+        }
+        condition
+    }
+
     pub(crate) fn build_transfer_qry<'b>(
         query: Query<'b, surrealdb::engine::any::Any>,
         wallet_from: &Thing,
@@ -270,10 +374,14 @@ impl<'a> BalanceTransactionDbService<'a> {
             LET ${uniq}_tx_amt = type::number(${uniq}_amt);
             LET ${uniq}_updated_from_balance = ${uniq}_balance - ${uniq}_tx_amt;
 
-            IF ${uniq}_w_from_id != ${uniq}_app_gateway_wallet_id && ${uniq}_updated_from_balance < 0 {{
+            IF ${uniq}_w_from_id NOT IN ${uniq}_unlimited_wallet_ids && ${uniq}_updated_from_balance < 0 {{
                 THROW \"{THROW_BALANCE_TOO_LOW}\";
             }};
 
+            IF ${uniq}_w_from.frozen == true {{
+                THROW \"{THROW_WALLET_FROZEN}\";
+            }};
+
             LET ${uniq}_tx_out = INSERT INTO {TABLE_NAME} {{
                 id: rand::ulid(),
                 wallet: ${uniq}_w_from_id,
@@ -316,8 +424,8 @@ impl<'a> BalanceTransactionDbService<'a> {
             .bind((format!("{uniq}_amt"), amount))
             .bind((format!("{uniq}_currency"), currency.clone()))
             .bind((
-                format!("{uniq}_app_gateway_wallet_id"),
-                APP_GATEWAY_WALLET.clone(),
+                format!("{uniq}_unlimited_wallet_ids"),
+                vec![APP_GATEWAY_WALLET.clone(), ADJUSTMENTS_WALLET.clone()],
             ))
             .bind((format!("{uniq}_gateway_tx_id"), gateway_tx))
             .bind((
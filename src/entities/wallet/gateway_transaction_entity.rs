@@ -17,6 +17,7 @@ use wallet_entity::{
 
 use crate::database::client::Db;
 use crate::database::surrdb_utils::get_entity_list;
+use crate::entities::audit_log::{AuditAction, AuditActor, AuditLogDbService, CreateAuditLog};
 use crate::entities::user_auth::local_user_entity;
 use crate::entities::wallet::balance_transaction_entity::{self, TransactionType};
 use crate::entities::wallet::wallet_entity::{self, check_transaction_custom_error, DARVE_WALLET};
@@ -193,6 +194,7 @@ impl<'a> GatewayTransactionDbService<'a> {
         amount: u64,
         description: Option<String>,
         withdraw_fee: f64,
+        actor: Option<AuditActor>,
     ) -> CtxResult<GatewayTransaction> {
         let user_wallet = WalletDbService::get_user_wallet_id(user);
         let wallet_to = WalletDbService::generate_id();
@@ -214,6 +216,18 @@ impl<'a> GatewayTransactionDbService<'a> {
             "",
         );
 
+        if let Some(actor) = actor {
+            let audit = CreateAuditLog {
+                actor,
+                action: AuditAction::Withdraw,
+                target: id.clone(),
+                note: None,
+            };
+            tx_qry =
+                tx_qry.query("LET $audit_after = { amount: $fund_amt, fee_amount: $fee_amount };");
+            tx_qry = AuditLogDbService::build_create_qry(tx_qry, audit);
+        }
+
         tx_qry = tx_qry
             .query(format!(
                 "LET $fund_tx = INSERT INTO {TABLE_NAME} {{
@@ -1,3 +1,4 @@
 pub mod balance_transaction_entity;
 pub mod gateway_transaction_entity;
+pub mod wallet_adjustment_entity;
 pub mod wallet_entity;
@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use surrealdb::engine::any;
+use surrealdb::method::Query;
+use surrealdb::sql::{Id, Thing};
+
+use super::balance_transaction_entity::{BalanceTransactionDbService, TransactionType};
+use super::wallet_entity::{
+    check_transaction_custom_error, CurrencySymbol, WalletDbService, ADJUSTMENTS_WALLET,
+};
+use crate::database::client::Db;
+use crate::entities::audit_log::{AuditLogDbService, CreateAuditLog};
+use crate::entities::user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME;
+use crate::middleware::{
+    ctx::Ctx,
+    error::{AppError, CtxResult},
+    utils::db_utils::{get_entity, with_not_found_err, IdentIdName, Pagination},
+};
+
+#[derive(Display, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum AdjustmentStatus {
+    /// waits for the approval of another admin
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Manual correction of the user wallet balance made by admins
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WalletAdjustment {
+    pub id: Thing,
+    pub user: Thing,
+    /// positive amount credits and negative debits the user wallet
+    pub amount: i64,
+    pub currency: CurrencySymbol,
+    pub reason: String,
+    pub status: AdjustmentStatus,
+    pub created_by: Thing,
+    pub reviewed_by: Option<Thing>,
+    pub review_note: Option<String>,
+    /// the ledger entry of the user wallet
+    pub balance_tx: Option<Thing>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct WalletAdjustmentDbService<'a> {
+    pub db: &'a Db,
+    pub ctx: &'a Ctx,
+}
+
+pub const TABLE_NAME: &str = "wallet_adjustment";
+const BALANCE_TX_TABLE_NAME: &str = super::balance_transaction_entity::TABLE_NAME;
+const THROW_ADJUSTMENT_REVIEWED: &str = "Adjustment already reviewed";
+
+impl<'a> WalletAdjustmentDbService<'a> {
+    pub async fn mutate_db(&self) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS user ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS amount ON TABLE {TABLE_NAME} TYPE int ASSERT $value != 0;
+    DEFINE FIELD IF NOT EXISTS currency ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS reason ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS status ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_by ON TABLE {TABLE_NAME} TYPE record<{USER_TABLE_NAME}>;
+    DEFINE FIELD IF NOT EXISTS reviewed_by ON TABLE {TABLE_NAME} TYPE option<record<{USER_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS review_note ON TABLE {TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS balance_tx ON TABLE {TABLE_NAME} TYPE option<record<{BALANCE_TX_TABLE_NAME}>>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE FIELD IF NOT EXISTS updated_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE time::now();
+    DEFINE INDEX IF NOT EXISTS user_idx ON TABLE {TABLE_NAME} COLUMNS user;
+    DEFINE INDEX IF NOT EXISTS status_idx ON TABLE {TABLE_NAME} COLUMNS status;
+");
+
+        let mutation = self.db.query(sql).await?;
+        mutation.check().expect("should mutate wallet_adjustment");
+
+        Ok(())
+    }
+
+    pub fn get_new_id() -> Thing {
+        Thing::from((TABLE_NAME, Id::ulid()))
+    }
+
+    /// Creates the pending adjustment, or with the audit record the applied one
+    /// together with its transfer in the same transaction
+    pub async fn create(
+        &self,
+        id: Thing,
+        user: Thing,
+        amount: i64,
+        currency: CurrencySymbol,
+        reason: String,
+        created_by: Thing,
+        apply_audit: Option<CreateAuditLog>,
+    ) -> CtxResult<WalletAdjustment> {
+        let mut qry = self.db.query("BEGIN");
+        let (status, balance_tx) = match apply_audit {
+            Some(audit) => {
+                let (query, user_tx) =
+                    Self::build_transfer_qry(qry, &user, amount, &currency, &reason);
+                qry = AuditLogDbService::build_create_qry(
+                    query.query(format!(
+                        "LET $audit_after = {{ status: $status, balance_tx: ${user_tx} }};"
+                    )),
+                    audit,
+                );
+                (AdjustmentStatus::Approved, format!("${user_tx}"))
+            }
+            None => (AdjustmentStatus::Pending, "NONE".to_string()),
+        };
+        let mut res = qry
+            .query(format!(
+                "CREATE ONLY $id SET user=$user, amount=$amount, currency=$currency,
+                    reason=$reason, status=$status, created_by=$created_by, balance_tx={balance_tx};"
+            ))
+            .query("COMMIT")
+            .bind(("id", id))
+            .bind(("user", user))
+            .bind(("amount", amount))
+            .bind(("currency", currency))
+            .bind(("reason", reason))
+            .bind(("status", status))
+            .bind(("created_by", created_by))
+            .await?;
+        check_transaction_custom_error(&mut res)?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Option<WalletAdjustment>>(index)?;
+        Ok(data.unwrap())
+    }
+
+    /// Sum of the amounts the admin applied to the user wallet without the approval of another admin
+    pub async fn get_unreviewed_total(&self, user: Thing, created_by: Thing) -> CtxResult<u64> {
+        let mut res = self
+            .db
+            .query(format!(
+                "RETURN math::sum(SELECT VALUE math::abs(amount) FROM {TABLE_NAME}
+                    WHERE user=$user AND created_by=$created_by AND status=$approved AND reviewed_by IS NONE);"
+            ))
+            .bind(("user", user))
+            .bind(("created_by", created_by))
+            .bind(("approved", AdjustmentStatus::Approved))
+            .await?;
+        let data = res.take::<Option<u64>>(0)?;
+        Ok(data.unwrap_or_default())
+    }
+
+    pub async fn get(&self, id: Thing) -> CtxResult<WalletAdjustment> {
+        let ident = IdentIdName::Id(id);
+        let opt = get_entity::<WalletAdjustment>(self.db, TABLE_NAME.to_string(), &ident).await?;
+        with_not_found_err(opt, self.ctx, &ident.to_string())
+    }
+
+    pub async fn get_list(
+        &self,
+        status: Option<AdjustmentStatus>,
+        pag: Pagination,
+    ) -> CtxResult<Vec<WalletAdjustment>> {
+        let where_cond = match status {
+            Some(_) => "WHERE status=$status",
+            None => "",
+        };
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT * FROM {TABLE_NAME} {where_cond}
+                 ORDER BY created_at DESC LIMIT $limit START $start;"
+            ))
+            .bind(("status", status))
+            .bind(("limit", pag.count))
+            .bind(("start", pag.start))
+            .await?;
+        let data = res.take::<Vec<WalletAdjustment>>(0)?;
+        Ok(data)
+    }
+
+    /// Transfers the amount between the user and the adjustments wallet
+    /// and marks the pending adjustment as approved in the same transaction
+    pub async fn apply(
+        &self,
+        adjustment: &WalletAdjustment,
+        reviewed_by: Option<Thing>,
+        audit: CreateAuditLog,
+    ) -> CtxResult<WalletAdjustment> {
+        let query = self.db.query("BEGIN").query(format!(
+            "IF $adjustment.status != $pending {{ THROW \"{THROW_ADJUSTMENT_REVIEWED}\"; }};"
+        ));
+        let (query, user_tx) = Self::build_transfer_qry(
+            query,
+            &adjustment.user,
+            adjustment.amount,
+            &adjustment.currency,
+            &adjustment.reason,
+        );
+        let mut qry = query.query(format!(
+            "LET $audit_after = {{ status: $status, balance_tx: ${user_tx} }};"
+        ));
+        qry = AuditLogDbService::build_create_qry(qry, audit);
+        let mut res = qry
+            .query(format!(
+                "UPDATE ONLY $adjustment SET status=$status, reviewed_by=$reviewed_by, balance_tx=${user_tx};"
+            ))
+            .query("COMMIT")
+            .bind(("adjustment", adjustment.id.clone()))
+            .bind(("pending", AdjustmentStatus::Pending))
+            .bind(("status", AdjustmentStatus::Approved))
+            .bind(("reviewed_by", reviewed_by))
+            .await?;
+        check_transaction_custom_error(&mut res)?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Option<WalletAdjustment>>(index)?;
+        Ok(data.unwrap())
+    }
+
+    pub async fn reject(
+        &self,
+        adjustment: &Thing,
+        reviewed_by: Thing,
+        review_note: Option<String>,
+        audit: CreateAuditLog,
+    ) -> CtxResult<WalletAdjustment> {
+        let qry = self.db.query("BEGIN").query(format!(
+            "IF $adjustment.status != $pending {{ THROW \"{THROW_ADJUSTMENT_REVIEWED}\"; }};
+             LET $audit_after = {{ status: $status }};"
+        ));
+        let mut res = AuditLogDbService::build_create_qry(qry, audit)
+            .query(
+                "UPDATE ONLY $adjustment SET status=$status, reviewed_by=$reviewed_by, review_note=$review_note;",
+            )
+            .query("COMMIT")
+            .bind(("adjustment", adjustment.clone()))
+            .bind(("pending", AdjustmentStatus::Pending))
+            .bind(("status", AdjustmentStatus::Rejected))
+            .bind(("reviewed_by", reviewed_by))
+            .bind(("review_note", review_note))
+            .await?;
+        check_transaction_custom_error(&mut res)?;
+        let index = res.num_statements() - 1;
+        let data = res.take::<Option<WalletAdjustment>>(index)?;
+        Ok(data.unwrap())
+    }
+
+    // returns the name of the variable with the ledger entry of the user wallet
+    fn build_transfer_qry<'b>(
+        query: Query<'b, any::Any>,
+        user: &Thing,
+        amount: i64,
+        currency: &CurrencySymbol,
+        reason: &str,
+    ) -> (Query<'b, any::Any>, &'static str) {
+        let user_wallet = WalletDbService::get_user_wallet_id(user);
+        let (wallet_from, wallet_to, user_tx) = match amount > 0 {
+            true => (ADJUSTMENTS_WALLET.clone(), user_wallet, "adj_tx_in_id"),
+            false => (user_wallet, ADJUSTMENTS_WALLET.clone(), "adj_tx_out_id"),
+        };
+        let query = BalanceTransactionDbService::build_transfer_qry(
+            query,
+            &wallet_from,
+            &wallet_to,
+            amount.abs(),
+            currency,
+            None,
+            Some(reason.to_string()),
+            TransactionType::Adjustment,
+            "adj",
+        );
+        (query, user_tx)
+    }
+}
@@ -1,4 +1,5 @@
 use askama_axum::Template;
+use axum_typed_multipart::TryFromField;
 use surrealdb::sql::{Id, Thing};
 
 use crate::database::client::Db;
@@ -21,6 +22,7 @@ use crate::entities::wallet::balance_transaction_entity::{
 };
 use crate::middleware;
 use crate::middleware::error::{AppResult, CtxError};
+use crate::models::view::revenue::BalanceTotalsView;
 
 pub fn check_transaction_custom_error(query_response: &mut Response) -> AppResult<()> {
     let query_err = query_response
@@ -33,6 +35,9 @@ pub fn check_transaction_custom_error(query_response: &mut Response) -> AppResul
             if let Some(AppError::BalanceTooLow) = ret {
                 return ret;
             }
+            if let Some(AppError::WalletFrozen) = ret {
+                return ret;
+            }
 
             match error {
                 surrealdb::Error::Db(Error::Thrown(throw_val))
@@ -45,6 +50,11 @@ pub fn check_transaction_custom_error(query_response: &mut Response) -> AppResul
                 {
                     Some(AppError::BalanceTooLow)
                 }
+                surrealdb::Error::Db(Error::Thrown(throw_val))
+                    if throw_val == THROW_WALLET_FROZEN =>
+                {
+                    Some(AppError::WalletFrozen)
+                }
                 surrealdb::Error::Api(surrealdb::error::Api::Query(msg))
                     if msg.contains(THROW_BALANCE_TOO_LOW) =>
                 {
@@ -65,8 +75,12 @@ pub fn check_transaction_custom_error(query_response: &mut Response) -> AppResul
 pub static APP_GATEWAY_WALLET: Lazy<Thing> =
     Lazy::new(|| Thing::from((TABLE_NAME, "app_gateway_wallet")));
 pub const THROW_WALLET_LOCKED: &str = "Wallet locked";
+pub const THROW_WALLET_FROZEN: &str = "Wallet frozen";
 
 pub static DARVE_WALLET: Lazy<Thing> = Lazy::new(|| Thing::from((TABLE_NAME, "darve_wallet")));
+// counterparty of the manual corrections made by admins, its balance can go negative
+pub static ADJUSTMENTS_WALLET: Lazy<Thing> =
+    Lazy::new(|| Thing::from((TABLE_NAME, "adjustments_wallet")));
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Wallet {
@@ -86,7 +100,7 @@ pub struct WalletCurrencyTxHeads {
     reef: Option<Thing>,
 }
 
-#[derive(Display, Clone, Serialize, Deserialize, Debug)]
+#[derive(Display, Clone, Serialize, Deserialize, Debug, TryFromField)]
 pub enum CurrencySymbol {
     USD,
     REEF,
@@ -168,6 +182,8 @@ impl<'a> WalletDbService<'a> {
     }} ELSE {{
         THROW \"{THROW_WALLET_LOCKED}\"
     }} }};
+    // outgoing transfers are rejected while the owner's account is restricted
+    DEFINE FIELD IF NOT EXISTS frozen ON TABLE {TABLE_NAME} TYPE bool DEFAULT false;
     DEFINE FIELD IF NOT EXISTS r_created ON TABLE {TABLE_NAME} TYPE option<datetime> DEFAULT time::now() VALUE $before OR time::now();
     // DEFINE INDEX IF NOT EXISTS r_created_idx ON TABLE {TABLE_NAME} COLUMNS r_created;
     DEFINE FIELD IF NOT EXISTS r_updated ON TABLE {TABLE_NAME} TYPE option<datetime> DEFAULT time::now() VALUE time::now();
@@ -216,6 +232,40 @@ impl<'a> WalletDbService<'a> {
         }
     }
 
+    pub async fn get_total_balances(
+        &self,
+        exclude_wallets: Vec<Thing>,
+    ) -> CtxResult<BalanceTotalsView> {
+        let curr_usd = CurrencySymbol::USD.to_string();
+        let curr_reef = CurrencySymbol::REEF.to_string();
+        let curr_eth = CurrencySymbol::ETH.to_string();
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT
+                    math::sum({TRANSACTION_HEAD_F}.{curr_usd}.*.balance||0) AS balance_usd,
+                    math::sum({TRANSACTION_HEAD_F}.{curr_reef}.*.balance||0) AS balance_reef,
+                    math::sum({TRANSACTION_HEAD_F}.{curr_eth}.*.balance||0) AS balance_eth
+                FROM {TABLE_NAME} WHERE id NOT IN $exclude_wallets GROUP ALL;"
+            ))
+            .bind(("exclude_wallets", exclude_wallets))
+            .await?;
+        let totals = res.take::<Option<BalanceTotalsView>>(0)?;
+        Ok(totals.unwrap_or_default())
+    }
+
+    pub async fn set_user_wallet_frozen(&self, user_id: &Thing, frozen: bool) -> CtxResult<()> {
+        self.db
+            .query(format!(
+                "UPSERT $wallet SET frozen=$frozen, {TRANSACTION_HEAD_F}={TRANSACTION_HEAD_F} OR {{}};"
+            ))
+            .bind(("wallet", Self::get_user_wallet_id(user_id)))
+            .bind(("frozen", frozen))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
     pub fn is_wallet_id(ctx: Ctx, wallet_id: &Thing) -> CtxResult<()> {
         if wallet_id.tb != TABLE_NAME {
             return Err(ctx.to_ctx_error(AppError::Generic {
@@ -225,8 +275,10 @@ impl<'a> WalletDbService<'a> {
         Ok(())
     }
 
-    pub(crate) async fn init_app_gateway_wallet(&self) -> CtxResult<WalletBalanceView> {
-        let wallet_id: &Thing = &APP_GATEWAY_WALLET.clone();
+    pub(crate) async fn init_system_wallet(
+        &self,
+        wallet_id: &Thing,
+    ) -> CtxResult<WalletBalanceView> {
         Self::is_wallet_id(self.ctx.clone(), wallet_id)?;
         if record_exists(self.db, &wallet_id).await.is_ok() {
             return Err(self.ctx.to_ctx_error(AppError::Generic {
@@ -10,15 +10,17 @@ use crate::{
         mw_ctx::CtxState,
     },
     routes::{
-        admin, auth_routes,
+        access, admin, auth_routes,
         community::profile_routes,
-        discussions, editor_tags, follows, notifications, posts, reply, swagger, tags, tasks,
+        direct_messages, discussions, editor_tags, follows, notifications, posts, reply, reports,
+        subscriptions, swagger, tags, tasks,
         user_auth::{
             login_routes, register_routes,
             webauthn::webauthn_routes::{self, WebauthnConfig},
         },
         user_otp, users, wallet,
         webhooks::{paypal, stripe},
+        websocket,
     },
     services::auth_service::{AuthRegisterInput, AuthService},
 };
@@ -27,9 +29,16 @@ use axum::{
     routing::get,
     Router,
 };
+use entities::account_status_change::AccountStatusChangeDbService;
+use entities::audit_log::AuditLogDbService;
+use entities::blocked_word::BlockedWordDbService;
 use entities::community::discussion_entity::DiscussionDbService;
 use entities::community::post_entity::PostDbService;
+use entities::notification_email::NotificationEmailDbService;
+use entities::notification_settings::NotificationSettingsDbService;
+use entities::push_device::PushDeviceDbService;
 use entities::user_auth::authentication_entity::AuthenticationDbService;
+use entities::user_auth::block_entity::BlockDbService;
 use entities::user_auth::follow_entity::FollowDbService;
 use entities::wallet::balance_transaction_entity::BalanceTransactionDbService;
 use entities::wallet::wallet_entity::WalletDbService;
@@ -47,6 +56,8 @@ use tracing::{debug, error, info, warn};
 
 use crate::database::client::Database;
 use crate::entities::wallet::gateway_transaction_entity::GatewayTransactionDbService;
+use crate::entities::wallet::wallet_adjustment_entity::WalletAdjustmentDbService;
+use crate::utils::sse_buffer::SseBuffer;
 
 pub async fn create_default_profiles(ctx_state: &CtxState, password: &str) {
     let c = Ctx::new(Ok("create_drave_profiles".parse().unwrap()), false);
@@ -95,6 +106,14 @@ pub async fn run_migrations(database: &Database) -> AppResult<()> {
     PostDbService { db: &db, ctx: &c }.mutate_db().await?;
     CommunityDbService { db: &db, ctx: &c }.mutate_db().await?;
     FollowDbService { db: &db, ctx: &c }.mutate_db().await?;
+    BlockDbService { db: &db, ctx: &c }.mutate_db().await?;
+    BlockedWordDbService { db: &db, ctx: &c }
+        .mutate_db()
+        .await?;
+    AccountStatusChangeDbService { db: &db, ctx: &c }
+        .mutate_db()
+        .await?;
+    AuditLogDbService { db: &db, ctx: &c }.mutate_db().await?;
     WalletDbService { db: &db, ctx: &c }.mutate_db().await?;
     BalanceTransactionDbService { db: &db, ctx: &c }
         .mutate_db()
@@ -102,6 +121,17 @@ pub async fn run_migrations(database: &Database) -> AppResult<()> {
     GatewayTransactionDbService { db: &db, ctx: &c }
         .mutate_db()
         .await?;
+    WalletAdjustmentDbService { db: &db, ctx: &c }
+        .mutate_db()
+        .await?;
+    NotificationSettingsDbService { db: &db, ctx: &c }
+        .mutate_db()
+        .await?;
+    NotificationEmailDbService { db: &db, ctx: &c }
+        .mutate_db()
+        .await?;
+    PushDeviceDbService { db: &db, ctx: &c }.mutate_db().await?;
+    SseBuffer::mutate_db(&db).await?;
     Ok(())
 }
 
@@ -134,6 +164,11 @@ pub fn main_router(
         .merge(editor_tags::routes())
         .merge(reply::routes())
         .merge(admin::routes())
+        .merge(subscriptions::routes())
+        .merge(access::routes())
+        .merge(reports::routes())
+        .merge(websocket::routes())
+        .merge(direct_messages::routes())
         .with_state(ctx_state.clone())
         .layer(CookieManagerLayer::new())
         .layer(
@@ -0,0 +1,12 @@
+use tokio::sync::broadcast;
+
+use crate::middleware::mw_ctx::AppEvent;
+
+pub trait EventBusInterface {
+    /// Sends the event to the subscribers on all the server instances
+    fn send(&self, event: AppEvent) -> Result<(), String>;
+    /// Subscribes to the events raised on all the server instances
+    fn subscribe(&self) -> broadcast::Receiver<AppEvent>;
+    /// Subscribes to the events raised on the current server instance only
+    fn subscribe_own(&self) -> broadcast::Receiver<AppEvent>;
+}
@@ -1,3 +1,5 @@
+pub mod event_bus;
 pub mod file_storage;
+pub mod push_sender;
 pub mod repositories;
 pub mod send_email;
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::entities::push_device::DevicePlatform;
+
+#[derive(Debug, Clone)]
+pub struct PushMessage {
+    pub title: String,
+    /// count of the unread notifications shown on the app icon
+    pub badge: u64,
+    pub data: Option<Value>,
+}
+
+#[derive(Debug)]
+pub enum PushError {
+    /// the provider does not accept the device token anymore
+    InvalidToken,
+    Provider(String),
+}
+
+#[async_trait]
+pub trait PushSenderInterface {
+    async fn send(
+        &self,
+        platform: &DevicePlatform,
+        token: &str,
+        message: &PushMessage,
+    ) -> Result<(), PushError>;
+}
@@ -1,11 +1,38 @@
-use crate::middleware::error::AppResult;
+use crate::{entities::access_user::AccessGrant, middleware::error::AppResult};
 use async_trait::async_trait;
-use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+use surrealdb::{engine::any, method::Query, sql::Thing};
 
 #[async_trait]
 pub trait AccessRepositoryInterface {
     async fn add(&self, users: Vec<Thing>, entities: Vec<&str>, role: String) -> AppResult<()>;
+    async fn add_with_expiry(
+        &self,
+        users: Vec<Thing>,
+        entities: Vec<&str>,
+        role: String,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<()>;
     async fn update(&self, user: Thing, entity: &str, role: String) -> AppResult<()>;
+    async fn set_muted(&self, user: Thing, entity: &str, muted: bool) -> AppResult<()>;
     async fn remove_by_entity(&self, entity: &str, users: Vec<Thing>) -> AppResult<()>;
+    async fn remove_by_role(&self, entity: &str, users: Vec<Thing>, role: String) -> AppResult<()>;
     async fn remove_by_user(&self, user: Thing, entities: Vec<&str>) -> AppResult<()>;
+    async fn remove_expired(&self) -> AppResult<Vec<AccessGrant>>;
+    fn build_add_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        user: &Thing,
+        entity: &Thing,
+        role: String,
+    ) -> Query<'b, any::Any>;
+    fn build_transfer_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        entity: &Thing,
+        from: &Thing,
+        from_role: String,
+        to: &Thing,
+        to_role: String,
+    ) -> Query<'b, any::Any>;
 }
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use surrealdb::sql::Thing;
+
+use crate::{entities::access_invite::AccessInvite, middleware::error::AppResult};
+
+#[async_trait]
+pub trait AccessInviteRepositoryInterface {
+    async fn create(
+        &self,
+        entity: Thing,
+        created_by: Thing,
+        role: String,
+        max_uses: Option<u32>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> AppResult<AccessInvite>;
+    async fn get_by_id(&self, invite_id: &str) -> AppResult<AccessInvite>;
+    async fn get_by_token(&self, token: &str) -> AppResult<AccessInvite>;
+    async fn get_by_entity(&self, entity: Thing) -> AppResult<Vec<AccessInvite>>;
+    async fn revoke(&self, invite_id: &str) -> AppResult<()>;
+    /// Counts one usage of the invite, returns false when the invite is not usable anymore
+    async fn increase_uses(&self, invite_id: &str) -> AppResult<bool>;
+}
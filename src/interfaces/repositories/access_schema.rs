@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use surrealdb::sql::Thing;
+
+use crate::{entities::access_schema::AccessSchema, middleware::error::AppResult};
+
+#[async_trait]
+pub trait AccessSchemaRepositoryInterface {
+    async fn get_latest(&self) -> AppResult<Option<AccessSchema>>;
+    async fn create(&self, content: String, created_by: Option<Thing>) -> AppResult<AccessSchema>;
+}
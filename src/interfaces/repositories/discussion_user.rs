@@ -49,6 +49,31 @@ pub trait DiscussionUserRepositoryInterface {
         user_ids: Vec<String>,
     ) -> Query<'b, any::Any>;
 
+    /// Moves the last read post marker of the user forward to the post
+    fn build_last_read_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        disc_id: &str,
+        user_id: &str,
+        post: Thing,
+    ) -> Query<'b, any::Any>;
+
+    async fn get(&self, disc_id: &str, user_id: &str) -> AppResult<Option<DiscussionUser>>;
+
+    /// Marks the discussion of the user as the message request waiting for the acceptance
+    async fn set_request(&self, disc_id: &str, user_id: &str, is_request: bool) -> AppResult<()>;
+
+    /// Returns the users who have not accepted the message request of the discussion yet
+    async fn get_request_users(&self, disc_id: &str) -> AppResult<Vec<Thing>>;
+
+    /// Returns the private discussions of the user with the latest posts first
+    async fn get_inbox<T: for<'b> Deserialize<'b> + ViewFieldSelector>(
+        &self,
+        user_id: &str,
+        pad: Pagination,
+        is_request: bool,
+    ) -> AppResult<Vec<T>>;
+
     async fn remove(&self, disc_id: &str, user_ids: Vec<Thing>) -> AppResult<Vec<Thing>>;
 
     async fn get_count_of_unread(&self, user_id: &str) -> AppResult<u32>;
@@ -1,9 +1,13 @@
 pub mod access;
+pub mod access_invite;
+pub mod access_schema;
 pub mod discussion_user;
 pub mod editor_tags;
 pub mod like;
 pub mod nickname;
 pub mod post_user;
+pub mod report;
+pub mod subscriptions;
 pub mod tags;
 pub mod task_donors;
 pub mod task_participants;
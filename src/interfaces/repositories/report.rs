@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use surrealdb::sql::Thing;
+
+use crate::{
+    entities::report::{
+        ModerationAction, ModerationActionType, Report, ReportFilter, ReportReason, ReportStatus,
+    },
+    middleware::{error::AppResult, utils::db_utils::Pagination},
+};
+
+#[async_trait]
+pub trait ReportsRepositoryInterface {
+    async fn create(
+        &self,
+        entity: Thing,
+        reporter: Thing,
+        reason: ReportReason,
+        text: Option<String>,
+    ) -> AppResult<Report>;
+    async fn get_by_id(&self, report_id: &str) -> AppResult<Report>;
+    async fn get(&self, filter: ReportFilter, pag: Pagination) -> AppResult<Vec<Report>>;
+    /// Number of distinct users with an open report of the entity
+    async fn get_reporters_count(&self, entity: &Thing) -> AppResult<u32>;
+    async fn assign(&self, report_id: &str, assigned_to: Option<Thing>) -> AppResult<Report>;
+    /// Closes all open reports of the entity and links them to the moderation action
+    async fn resolve(
+        &self,
+        entity: &Thing,
+        status: ReportStatus,
+        action: &Thing,
+    ) -> AppResult<Vec<Report>>;
+
+    async fn create_action(
+        &self,
+        entity: Thing,
+        moderator: Option<Thing>,
+        action: ModerationActionType,
+        note: Option<String>,
+    ) -> AppResult<ModerationAction>;
+    async fn get_actions(
+        &self,
+        entity: Option<Thing>,
+        pag: Pagination,
+    ) -> AppResult<Vec<ModerationAction>>;
+
+    async fn set_hidden(&self, entity: &Thing, hidden: bool) -> AppResult<()>;
+    async fn get_owner(&self, entity: &Thing) -> AppResult<Option<Thing>>;
+}
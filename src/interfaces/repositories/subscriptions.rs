@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use surrealdb::{engine::any, method::Query, sql::Thing};
+
+use crate::{
+    entities::subscription::{Subscription, SubscriptionTier, SubscriptionTierCreate},
+    middleware::error::AppResult,
+};
+
+#[async_trait]
+pub trait SubscriptionsRepositoryInterface {
+    async fn create_tier(&self, data: SubscriptionTierCreate) -> AppResult<SubscriptionTier>;
+    async fn get_tier(&self, tier_id: &str) -> AppResult<SubscriptionTier>;
+    async fn get_tiers_by_user(
+        &self,
+        user: &Thing,
+        only_active: bool,
+    ) -> AppResult<Vec<SubscriptionTier>>;
+    async fn set_tier_active(&self, tier_id: &str, is_active: bool) -> AppResult<()>;
+
+    fn build_upsert_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        subscription_id: &Thing,
+        tier: &SubscriptionTier,
+        subscriber: &Thing,
+        period_end: DateTime<Utc>,
+    ) -> Query<'b, any::Any>;
+    fn build_renew_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        subscription_id: &Thing,
+        old_period_end: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+    ) -> Query<'b, any::Any>;
+
+    async fn get_by_id(&self, subscription_id: &str) -> AppResult<Subscription>;
+    async fn get_by_subscriber_and_creator(
+        &self,
+        subscriber: &Thing,
+        creator: &Thing,
+    ) -> AppResult<Option<Subscription>>;
+    async fn get_by_subscriber(&self, subscriber: &Thing) -> AppResult<Vec<Subscription>>;
+    async fn get_by_creator(&self, creator: &Thing) -> AppResult<Vec<Subscription>>;
+    async fn get_due_for_renewal(&self) -> AppResult<Vec<Subscription>>;
+    async fn get_ended_canceled(&self) -> AppResult<Vec<Subscription>>;
+    async fn set_past_due(
+        &self,
+        subscription_id: &Thing,
+        grace_period_end: DateTime<Utc>,
+    ) -> AppResult<()>;
+    async fn cancel(&self, subscription_id: &Thing) -> AppResult<Subscription>;
+    async fn resume(&self, subscription_id: &Thing) -> AppResult<Subscription>;
+    async fn expire(&self, subscription_id: &Thing) -> AppResult<()>;
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use serde::Deserialize;
-use surrealdb::sql::Thing;
+use surrealdb::{engine::any, method::Query, sql::Thing};
 
 use crate::{
     entities::tag::Tag,
@@ -10,6 +10,13 @@ use crate::{
 #[async_trait]
 pub trait TagsRepositoryInterface {
     async fn create_with_relate(&self, tags: Vec<String>, entity: Thing) -> AppResult<()>;
+    async fn remove_relates(&self, entity: Thing) -> AppResult<()>;
+    fn build_replace_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        tags: Vec<String>,
+        entity: Thing,
+    ) -> Query<'b, any::Any>;
     async fn get_by_tag<T: for<'de> Deserialize<'de>>(
         &self,
         tag: &str,
@@ -92,6 +92,12 @@ pub trait TaskRequestRepositoryInterface: RepositoryCore {
     /// Get all tasks ready for payment
     async fn get_ready_for_payment(&self) -> Result<Vec<TaskForReward>, surrealdb::Error>;
 
+    /// Get not finished task requests created by user
+    async fn get_unfinished_by_creator(
+        &self,
+        user: &Thing,
+    ) -> Result<Vec<TaskForReward>, surrealdb::Error>;
+
     async fn get_by_id<T: for<'de> Deserialize<'de> + ViewFieldSelector + Send>(
         &self,
         id: &str,
@@ -14,9 +14,21 @@ pub struct GetNotificationOptions {
     pub start: DateTime<Utc>,
     pub filter_by_types: Option<Vec<String>>,
     pub is_read: Option<bool>,
+    pub is_archived: bool,
     pub order_dir: QryOrder,
 }
 
+/// The grouped notification of the receivers, e.g. "X and 12 others liked the post"
+#[derive(Debug)]
+pub struct NotificationGroupOptions<'a> {
+    pub key: &'a str,
+    pub actor: &'a str,
+    pub action: &'a str,
+    pub max_actors: usize,
+    /// the groups created before start again
+    pub since: DateTime<Utc>,
+}
+
 #[async_trait]
 pub trait UserNotificationsInterface {
     async fn create(
@@ -26,7 +38,20 @@ pub trait UserNotificationsInterface {
         n_type: &str,
         receivers: &Vec<String>,
         metadata: Option<Value>,
+        group_key: Option<String>,
     ) -> Result<UserNotification, AppError>;
+    /// Creates or updates the grouped notification of every receiver in one transaction,
+    /// adds the creator to the actors and marks it unread. Returns the notifications
+    /// in the order of the receivers
+    async fn upsert_groups(
+        &self,
+        creator: &str,
+        title: &str,
+        n_type: &str,
+        receivers: &Vec<String>,
+        metadata: Option<Value>,
+        group: NotificationGroupOptions<'_>,
+    ) -> Result<Vec<UserNotification>, AppError>;
     async fn get_by_user(
         &self,
         user_id: &str,
@@ -35,4 +60,20 @@ pub trait UserNotificationsInterface {
     async fn read(&self, id: &str, user_id: &str) -> Result<(), AppError>;
     async fn read_all(&self, user_id: &str) -> Result<(), AppError>;
     async fn get_count(&self, user_id: &str, is_read: Option<bool>) -> Result<u64, AppError>;
+    async fn archive(
+        &self,
+        ids: &Vec<String>,
+        user_id: &str,
+        is_archived: bool,
+    ) -> Result<(), AppError>;
+    /// Removes the notifications of the user and the ones left without receivers
+    async fn delete(&self, ids: &Vec<String>, user_id: &str) -> Result<(), AppError>;
+    /// Removes the read notifications last updated before the date
+    async fn remove_read_before(&self, before: DateTime<Utc>) -> Result<(), AppError>;
+    /// Removes the oldest notifications over the max of the users who received notifications since the date
+    async fn remove_over_limit(
+        &self,
+        max_per_user: u32,
+        since: DateTime<Utc>,
+    ) -> Result<(), AppError>;
 }
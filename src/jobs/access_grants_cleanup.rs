@@ -0,0 +1,30 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    middleware::{ctx::Ctx, mw_ctx::CtxState},
+    services::access_grant_service::AccessGrantService,
+};
+
+use tokio::task::JoinHandle;
+
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let ctx = Ctx::new(Ok("".to_string()), false);
+            let grant_service = AccessGrantService::new(
+                &state.db.client,
+                &ctx,
+                &state.db.access_invites,
+                &state.db.access,
+                &state.db.discussion_users,
+            );
+
+            if let Err(err) = grant_service.prune_expired().await {
+                println!("Error pruning expired access grants: {:?}", err);
+            }
+        }
+    })
+}
@@ -0,0 +1,38 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{middleware::mw_ctx::CtxState, services::access_schema_service::AccessSchemaService};
+
+use tokio::task::JoinHandle;
+
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let access_schema_service = AccessSchemaService::new(
+            &state.db.access_schemas,
+            state.access_schema_path.as_deref(),
+        );
+        let mut last_content = access_schema_service.get_content().await.ok();
+
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let content = match access_schema_service.get_content().await {
+                Ok(value) => value,
+                Err(err) => {
+                    println!("Error loading access schema: {:?}", err);
+                    continue;
+                }
+            };
+
+            if last_content.as_ref() == Some(&content) {
+                continue;
+            }
+
+            match access_schema_service.apply(content.as_deref()) {
+                Ok(diff) => println!("Access schema reloaded: {:?}", diff),
+                Err(err) => println!("Error reloading access schema: {:?}", err),
+            }
+            last_content = Some(content);
+        }
+    })
+}
@@ -1 +1,10 @@
+pub mod access_grants_cleanup;
+pub mod access_schema_reload;
+pub mod notification_emails;
+pub mod notification_retention;
+pub mod presence_sync;
+pub mod push_notifications;
+pub mod sse_events;
+pub mod subscription_payment;
+pub mod suspension_expiry;
 pub mod task_payment;
@@ -0,0 +1,27 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    middleware::{ctx::Ctx, mw_ctx::CtxState},
+    services::notification_email_service::NotificationEmailService,
+};
+
+use tokio::task::JoinHandle;
+
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let ctx = Ctx::new(Ok("".to_string()), false);
+            let email_service = NotificationEmailService::new(&state, &ctx);
+
+            if let Err(err) = email_service.send_queued().await {
+                println!("Error sending notification emails: {:?}", err);
+            }
+            if let Err(err) = email_service.send_digests().await {
+                println!("Error sending notification digests: {:?}", err);
+            }
+        }
+    })
+}
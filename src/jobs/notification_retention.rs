@@ -0,0 +1,36 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    interfaces::repositories::user_notifications::UserNotificationsInterface,
+    middleware::mw_ctx::CtxState,
+};
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+/// Removes the old read notifications and the ones over the limit per user every delay
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        // only the users who received notifications since the last check can be over the limit
+        let mut since = Utc::now();
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let notifications = &state.db.user_notifications;
+            let before = Utc::now() - state.notification_retention;
+
+            if let Err(err) = notifications.remove_read_before(before).await {
+                println!("Error removing old notifications: {:?}", err);
+            }
+            let now = Utc::now();
+            match notifications
+                .remove_over_limit(state.notification_max_per_user, since)
+                .await
+            {
+                Ok(_) => since = now,
+                Err(err) => println!("Error removing notifications over the limit: {:?}", err),
+            }
+        }
+    })
+}
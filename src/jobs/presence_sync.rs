@@ -0,0 +1,49 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use crate::middleware::mw_ctx::{AppEvent, AppEventType, CtxState};
+
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+/// Shares the online users of the instance on change or every heartbeat
+/// and saves the online users of the other instances
+pub async fn run(state: Arc<CtxState>, delay: Duration, heartbeat: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    let mut rx = state.event_sender.subscribe();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(delay);
+        let mut last_users = HashSet::new();
+        let mut last_sent_at = tokio::time::Instant::now();
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(AppEvent { event: AppEventType::InstancePresence(presence), .. }) => {
+                        state.presence.apply(presence)
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(count)) => {
+                        println!("Presence sync skipped {} events", count);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = interval.tick() => {
+                    state.presence.remove_stale();
+
+                    let snapshot = state.presence.snapshot();
+                    let users = snapshot.users.iter().cloned().collect::<HashSet<String>>();
+                    if users == last_users && last_sent_at.elapsed() < heartbeat {
+                        continue;
+                    }
+                    last_users = users;
+                    last_sent_at = tokio::time::Instant::now();
+                    let _ = state.event_sender.send(AppEvent {
+                        user_id: "".to_string(),
+                        metadata: None,
+                        content: None,
+                        receivers: vec![],
+                        event: AppEventType::InstancePresence(snapshot),
+                    });
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{
+    middleware::{
+        ctx::Ctx,
+        mw_ctx::{AppEventType, CtxState},
+    },
+    services::push_service::PushService,
+};
+
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+pub async fn run(state: Arc<CtxState>) -> JoinHandle<()> {
+    let state = state.clone();
+    let mut rx = state.event_sender.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(count)) => {
+                    println!("Push notifications skipped {} events", count);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            if let AppEventType::PushNotification(notification) = event.event {
+                let ctx = Ctx::new(Ok("".to_string()), false);
+                let push_service = PushService::new(&state, &ctx);
+
+                if let Err(err) = push_service.send(&notification, &event.receivers).await {
+                    println!("Error sending push notifications: {:?}", err);
+                }
+            }
+        }
+    })
+}
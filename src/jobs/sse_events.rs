@@ -0,0 +1,182 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    middleware::mw_ctx::{AppEvent, AppEventType, CtxState},
+    utils::sse_buffer::SseEvent,
+};
+
+use serde_json::json;
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+/// Stores the app events raised on the instance in the sse buffer, sends the ephemeral events
+/// of all the instances and removes the expired ones every delay
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    let mut rx = state.event_sender.subscribe();
+    let mut own_rx = state.event_sender.subscribe_own();
+    state
+        .sse_buffer
+        .clone()
+        .listen()
+        .await
+        .expect("SSE buffer should listen to the stored events");
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(delay);
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(event) => publish_ephemeral(&state, event),
+                    Err(RecvError::Lagged(count)) => {
+                        println!("SSE buffer skipped {} ephemeral events", count)
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                event = own_rx.recv() => match event {
+                    Ok(event) => publish(&state, event).await,
+                    Err(RecvError::Lagged(count)) => {
+                        println!("SSE buffer skipped {} events", count);
+                        // the receivers of the skipped events are unknown
+                        state.sse_buffer.resync(None);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                _ = interval.tick() => {
+                    if let Err(err) = state.sse_buffer.remove_expired().await {
+                        println!("Error removing expired sse events: {:?}", err);
+                    }
+                }
+            }
+        }
+    })
+}
+
+async fn publish(state: &CtxState, event: AppEvent) {
+    let event = match to_sse_event(event) {
+        Some((event, true)) => event,
+        _ => return,
+    };
+    let receivers = event.receivers.unwrap_or_default();
+    let res = state
+        .sse_buffer
+        .publish(&event.user_id, &event.name, event.data, receivers.clone())
+        .await;
+    if let Err(err) = res {
+        println!("Error storing sse event: {:?}", err);
+        state.sse_buffer.resync(Some(receivers));
+    }
+}
+
+fn publish_ephemeral(state: &CtxState, event: AppEvent) {
+    if let Some((event, false)) = to_sse_event(event) {
+        state.sse_buffer.publish_ephemeral(
+            &event.user_id,
+            &event.name,
+            event.data,
+            event.receivers,
+        );
+    }
+}
+
+/// Returns the sse event and whether it is stored for the replay
+fn to_sse_event(event: AppEvent) -> Option<(SseEvent, bool)> {
+    let user_id = event.user_id.clone();
+    let new_event = |name: &str, data: Option<String>, receivers: Option<Vec<String>>| SseEvent {
+        id: None,
+        user_id: user_id.clone(),
+        name: name.to_string(),
+        data,
+        receivers,
+    };
+    let res = match event.event {
+        AppEventType::UserNotificationEvent(data) => (
+            new_event(
+                "Notifications",
+                Some(json!(data).to_string()),
+                Some(event.receivers),
+            ),
+            true,
+        ),
+        AppEventType::UserNotificationUpdated(data) => (
+            new_event(
+                "NotificationUpdated",
+                Some(json!(data).to_string()),
+                Some(event.receivers),
+            ),
+            true,
+        ),
+        AppEventType::UserStatus(data) => (
+            new_event(
+                "UserStatus",
+                Some(json!({ "is_online": data.is_online, "user_id": user_id }).to_string()),
+                None,
+            ),
+            false,
+        ),
+        AppEventType::UpdateDiscussionsUsers(_) => (
+            new_event("UnreadDiscussionsCount", None, Some(event.receivers)),
+            true,
+        ),
+        AppEventType::UpdatedUserBalance => (
+            new_event("UpdatedUserBalance", None, Some(event.receivers)),
+            true,
+        ),
+        AppEventType::DiscussionPostAdded => (
+            new_event("DiscussionPostAdded", event.content, Some(event.receivers)),
+            true,
+        ),
+        AppEventType::DiscussionPostUpdated => (
+            new_event(
+                "DiscussionPostUpdated",
+                event.content,
+                Some(event.receivers),
+            ),
+            true,
+        ),
+        AppEventType::DiscussionReplyUpdated => (
+            new_event(
+                "DiscussionReplyUpdated",
+                event.content,
+                Some(event.receivers),
+            ),
+            true,
+        ),
+        AppEventType::DiscussionTyping => {
+            let discussion_id = event
+                .metadata
+                .and_then(|m| m.discussion_id)
+                .map(|id| id.to_raw());
+            (
+                new_event(
+                    "DiscussionTyping",
+                    Some(json!({ "discussion_id": discussion_id, "user_id": user_id }).to_string()),
+                    Some(event.receivers),
+                ),
+                false,
+            )
+        }
+        AppEventType::DiscussionPostRead => {
+            let (discussion_id, post_id) = event
+                .metadata
+                .map(|m| {
+                    (
+                        m.discussion_id.map(|id| id.to_raw()),
+                        m.post_id.map(|id| id.to_raw()),
+                    )
+                })
+                .unwrap_or_default();
+            (
+                new_event(
+                    "DiscussionPostRead",
+                    Some(
+                        json!({ "discussion_id": discussion_id, "post_id": post_id, "user_id": user_id })
+                            .to_string(),
+                    ),
+                    Some(event.receivers),
+                ),
+                true,
+            )
+        }
+        AppEventType::PushNotification(_) | AppEventType::InstancePresence(_) => return None,
+    };
+    Some(res)
+}
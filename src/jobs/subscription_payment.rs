@@ -0,0 +1,31 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    middleware::{ctx::Ctx, mw_ctx::CtxState},
+    services::subscription_service::SubscriptionService,
+};
+
+use tokio::task::JoinHandle;
+
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let ctx = Ctx::new(Ok("".to_string()), false);
+            let subscription_service = SubscriptionService::new(
+                &state.db.client,
+                &ctx,
+                &state.event_sender,
+                &state.db.user_notifications,
+                &state.db.subscriptions,
+                &state.db.access,
+            );
+
+            if let Err(err) = subscription_service.process_subscriptions().await {
+                println!("Error processing subscriptions: {:?}", err);
+            }
+        }
+    })
+}
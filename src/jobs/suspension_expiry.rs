@@ -0,0 +1,24 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    middleware::{ctx::Ctx, mw_ctx::CtxState},
+    services::account_status_service::AccountStatusService,
+};
+
+use tokio::task::JoinHandle;
+
+pub async fn run(state: Arc<CtxState>, delay: Duration) -> JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay).await;
+
+            let ctx = Ctx::new(Ok("".to_string()), false);
+            let account_status_service = AccountStatusService::new(&state, &ctx);
+
+            if let Err(err) = account_status_service.lift_expired().await {
+                println!("Error lifting expired suspensions: {:?}", err);
+            }
+        }
+    })
+}
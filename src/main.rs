@@ -64,6 +64,14 @@ async fn async_main(config: AppConfig) {
     init::run_migrations(&ctx_state.db).await.unwrap();
     init::create_default_profiles(&ctx_state, &config.init_server_password.as_str()).await;
 
+    services::access_schema_service::AccessSchemaService::new(
+        &ctx_state.db.access_schemas,
+        ctx_state.access_schema_path.as_deref(),
+    )
+    .load()
+    .await
+    .expect("Failed to load access control schema");
+
     let wa_config = webauthn_routes::create_webauth_config();
     let routes_all = init::main_router(&ctx_state, wa_config);
 
@@ -71,6 +79,27 @@ async fn async_main(config: AppConfig) {
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
 
     let _task_handle = jobs::task_payment::run(ctx_state.clone(), Duration::from_secs(30)).await;
+    let _subscription_handle =
+        jobs::subscription_payment::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let _access_schema_handle =
+        jobs::access_schema_reload::run(ctx_state.clone(), Duration::from_secs(30)).await;
+    let _access_grants_handle =
+        jobs::access_grants_cleanup::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let _suspension_expiry_handle =
+        jobs::suspension_expiry::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let _notification_emails_handle =
+        jobs::notification_emails::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let _notification_retention_handle =
+        jobs::notification_retention::run(ctx_state.clone(), Duration::from_secs(60 * 60)).await;
+    let _push_notifications_handle = jobs::push_notifications::run(ctx_state.clone()).await;
+    let _sse_events_handle =
+        jobs::sse_events::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let _presence_sync_handle = jobs::presence_sync::run(
+        ctx_state.clone(),
+        Duration::from_secs(2),
+        Duration::from_secs(10),
+    )
+    .await;
 
     axum::serve(listener, routes_all.into_make_service())
         .await
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use surrealdb::sql::Thing;
+
+use crate::{
+    entities::{
+        audit_log::AuditActor,
+        user_auth::local_user_entity::{LocalUser, LocalUserDbService, UserRole},
+    },
+    middleware::{
+        bearer_auth::BearerAuth, ctx::Ctx, error::AppError, mw_ctx::CtxState,
+        request_meta::RequestMeta,
+    },
+};
+
+/// Bearer authentication of the users with the admin role
+pub struct AdminAuth {
+    pub user: LocalUser,
+    pub ctx: Ctx,
+    pub meta: RequestMeta,
+}
+
+impl AdminAuth {
+    pub fn user_thing(&self) -> Thing {
+        self.user.id.clone().unwrap()
+    }
+
+    pub fn actor(&self) -> AuditActor {
+        self.meta.actor(self.user_thing())
+    }
+}
+
+impl FromRequestParts<Arc<CtxState>> for AdminAuth {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<CtxState>,
+    ) -> Result<Self, Self::Rejection> {
+        let auth_data = BearerAuth::from_request_parts(parts, state).await?;
+
+        let user = LocalUserDbService {
+            db: &state.db.client,
+            ctx: &auth_data.ctx,
+        }
+        .get_by_id(&auth_data.user_thing_id())
+        .await
+        .map_err(|e| e.into_response())?;
+
+        if user.role != UserRole::Admin {
+            return Err(auth_data
+                .ctx
+                .to_ctx_error(AppError::Forbidden)
+                .into_response());
+        }
+
+        let Ok(meta) = RequestMeta::from_request_parts(parts, state).await;
+        Ok(AdminAuth {
+            user,
+            ctx: auth_data.ctx,
+            meta,
+        })
+    }
+}
@@ -3,12 +3,17 @@ use std::sync::Arc;
 use axum::{
     extract::{FromRequestParts, State},
     http::request::Parts,
+    response::{IntoResponse, Response},
 };
 use axum_extra::headers::{authorization::Bearer, Authorization, HeaderMapExt};
 use reqwest::StatusCode;
 
 use crate::{
-    middleware::{ctx::Ctx, mw_ctx::CtxState},
+    middleware::{
+        ctx::{check_auth_claims, Ctx},
+        error::{AppError, CtxError},
+        mw_ctx::CtxState,
+    },
     utils::jwt::TokenType,
 };
 
@@ -27,7 +32,7 @@ impl BearerAuth {
 }
 
 impl FromRequestParts<Arc<CtxState>> for BearerAuth {
-    type Rejection = StatusCode;
+    type Rejection = Response;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -35,20 +40,31 @@ impl FromRequestParts<Arc<CtxState>> for BearerAuth {
     ) -> Result<Self, Self::Rejection> {
         let State(app_state): State<Arc<CtxState>> = State::from_request_parts(parts, state)
             .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
-        match parts.headers.typed_get::<Authorization<Bearer>>() {
-            Some(token) => match app_state
+        let claims = match parts.headers.typed_get::<Authorization<Bearer>>() {
+            Some(token) => app_state
                 .jwt
                 .decode_by_type(token.token(), TokenType::Login)
-            {
-                Ok(claims) => Ok(BearerAuth {
-                    user_id: claims.auth.clone(),
-                    ctx: Ctx::new(Ok(claims.auth), false),
-                }),
-                Err(_) => Err(StatusCode::UNAUTHORIZED),
-            },
-            _ => Err(StatusCode::UNAUTHORIZED),
-        }
+                .map_err(|_| StatusCode::UNAUTHORIZED.into_response())?,
+            _ => return Err(StatusCode::UNAUTHORIZED.into_response()),
+        };
+
+        // restricted accounts get the reason instead of a plain status
+        check_auth_claims(&app_state, &claims)
+            .await
+            .map_err(|error| match error {
+                AppError::AuthFailNoJwtCookie => StatusCode::UNAUTHORIZED.into_response(),
+                error => CtxError {
+                    error,
+                    is_htmx: false,
+                }
+                .into_response(),
+            })?;
+
+        Ok(BearerAuth {
+            user_id: claims.auth.clone(),
+            ctx: Ctx::new(Ok(claims.auth), false),
+        })
     }
 }
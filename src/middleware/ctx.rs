@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
 use super::error::{AppError, AppResult, CtxError, CtxResult};
+use crate::entities::user_auth::local_user_entity::LocalUserDbService;
 use crate::middleware::mw_ctx::{CtxState, JWT_KEY};
-use crate::utils::jwt::TokenType;
+use crate::middleware::utils::string_utils::get_str_thing;
+use crate::utils::jwt::{Claims, TokenType};
 use askama::Template;
 use axum::{
     extract::{FromRequestParts, State},
@@ -94,26 +96,43 @@ impl FromRequestParts<Arc<CtxState>> for Ctx {
             true
         };
 
-        let jwt_user_id: Result<String, AppError> = match cookies.get(JWT_KEY) {
-            Some(cookie) => match app_state
+        let jwt_claims: Result<Claims, AppError> = match cookies.get(JWT_KEY) {
+            Some(cookie) => app_state
                 .jwt
                 .decode_by_type(cookie.value(), TokenType::Login)
-            {
-                Ok(claims) => Ok(claims.auth),
-                Err(_) => Err(AppError::AuthFailNoJwtCookie),
-            },
+                .map_err(|_| AppError::AuthFailNoJwtCookie),
             None => match parts.headers.typed_get::<Authorization<Bearer>>() {
-                Some(token) => match app_state
+                Some(token) => app_state
                     .jwt
                     .decode_by_type(token.token(), TokenType::Login)
-                {
-                    Ok(claims) => Ok(claims.auth),
-                    Err(_) => Err(AppError::AuthFailNoJwtCookie),
-                },
+                    .map_err(|_| AppError::AuthFailNoJwtCookie),
                 None => Err(AppError::AuthFailNoJwtCookie),
             },
         };
 
+        let jwt_user_id = match jwt_claims {
+            Ok(claims) => check_auth_claims(&app_state, &claims)
+                .await
+                .map(|_| claims.auth),
+            Err(err) => Err(err),
+        };
+
         Ok(Ctx::new(jwt_user_id, prefers_html))
     }
 }
+
+/// Rejects the tokens of the restricted accounts and the tokens issued before the restriction
+pub(crate) async fn check_auth_claims(state: &CtxState, claims: &Claims) -> AppResult<()> {
+    let ctx = Ctx::new(Ok(claims.auth.clone()), false);
+    let user_repository = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &ctx,
+    };
+    let auth_state = user_repository
+        .get_auth_state(get_str_thing(&claims.auth)?)
+        .await?;
+    match auth_state {
+        Some(value) => value.check(claims.iat),
+        None => Ok(()),
+    }
+}
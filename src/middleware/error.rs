@@ -18,6 +18,7 @@ pub struct CtxError {
 pub enum AppError {
     Generic { description: String },
     Forbidden,
+    AccessDenied { reason: Value },
     AuthenticationFail,
     RegisterFail,
     AuthorizationFail { required: String },
@@ -33,6 +34,10 @@ pub enum AppError {
     ValidationErrors { value: Value },
     BalanceTooLow,
     WalletLocked,
+    WalletFrozen,
+    AccountSuspended { until: Option<String> },
+    AccountBanned,
+    AccountDeactivated,
 }
 
 /// ApiError has to have the req_id to report to the client and implements IntoResponse.
@@ -123,7 +128,15 @@ impl fmt::Display for AppError {
             AppError::ValidationErrors { value } => write!(f, "{value}"),
             AppError::BalanceTooLow => write!(f, "Balance too low"),
             AppError::WalletLocked => write!(f, "Wallet locked"),
+            AppError::WalletFrozen => write!(f, "Wallet is frozen"),
+            AppError::AccountSuspended { until } => match until {
+                Some(until) => write!(f, "Your account is suspended until {until}"),
+                None => write!(f, "Your account is suspended"),
+            },
+            AppError::AccountBanned => write!(f, "Your account is banned"),
+            AppError::AccountDeactivated => write!(f, "Your account is deactivated"),
             AppError::Forbidden => write!(f, "Forbidden"),
+            AppError::AccessDenied { .. } => write!(f, "Forbidden"),
         }
     }
 }
@@ -132,6 +145,8 @@ impl fmt::Display for AppError {
 pub struct ErrorResponseBody {
     error: String,
     req_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<Value>,
 }
 
 impl ErrorResponseBody {
@@ -139,9 +154,15 @@ impl ErrorResponseBody {
         ErrorResponseBody {
             error,
             req_id: req_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            reason: None,
         }
     }
 
+    pub fn with_reason(mut self, reason: Option<Value>) -> Self {
+        self.reason = reason;
+        self
+    }
+
     pub fn get_err(&self) -> String {
         self.error.clone()
     }
@@ -170,6 +191,11 @@ impl IntoResponse for CtxError {
             | AppError::AuthFailJwtInvalid { .. }
             | AppError::AuthorizationFail { .. }
             | AppError::Forbidden
+            | AppError::AccessDenied { .. }
+            | AppError::WalletFrozen
+            | AppError::AccountSuspended { .. }
+            | AppError::AccountBanned
+            | AppError::AccountDeactivated
             | AppError::AuthFailCtxNotInRequestExt => StatusCode::FORBIDDEN,
             AppError::ValidationErrors { .. } => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::AuthFailNoJwtCookie => StatusCode::UNAUTHORIZED,
@@ -189,7 +215,15 @@ impl IntoResponse for CtxError {
 fn get_error_body(err: &CtxError, is_htmx: bool) -> String {
     match is_htmx {
         true => to_err_html(err.error.to_string()),
-        false => ErrorResponseBody::new(err.error.to_string(), None).into(),
+        false => {
+            let reason = match &err.error {
+                AppError::AccessDenied { reason } => Some(reason.clone()),
+                _ => None,
+            };
+            ErrorResponseBody::new(err.error.to_string(), None)
+                .with_reason(reason)
+                .into()
+        }
     }
 }
 
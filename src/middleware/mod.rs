@@ -1,7 +1,9 @@
+pub mod admin_auth;
 pub mod auth_with_otp_access;
 pub mod bearer_auth;
 pub mod ctx;
 pub mod error;
 pub mod limit;
 pub mod mw_ctx;
+pub mod request_meta;
 pub mod utils;
@@ -2,47 +2,66 @@ use crate::config::AppConfig;
 use crate::database::client::Database;
 use crate::entities::discussion_user::DiscussionUser;
 use crate::entities::user_notification::UserNotification;
+use crate::interfaces::event_bus::EventBusInterface;
 use crate::interfaces::file_storage::FileStorageInterface;
+use crate::interfaces::push_sender::PushSenderInterface;
 use crate::interfaces::send_email::SendEmailInterface;
+use crate::utils::cluster_presence::{ClusterPresence, InstancePresence};
 use crate::utils::darve_tasks::DarveTasksUtils;
 use crate::utils::email_sender::EmailSender;
+use crate::utils::event_bus::create_event_bus;
 use crate::utils::file::google_cloud_file_storage::GoogleCloudFileStorage;
 use crate::utils::jwt::JWT;
+use crate::utils::push::create_push_sender;
+use crate::utils::sse_buffer::SseBuffer;
 use crate::utils::verification::twitch::TwitchService;
 use chrono::Duration;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
-use surrealdb::sql::Thing;
-use tokio::sync::broadcast;
+use surrealdb::sql::{Id, Thing};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppEventType {
     UserNotificationEvent(UserNotification),
+    /// grouped notification updated in place with a new actor
+    UserNotificationUpdated(UserNotification),
     DiscussionPostAdded,
+    /// title or content of the post edited
+    DiscussionPostUpdated,
+    /// content of the reply edited
+    DiscussionReplyUpdated,
     UpdatedUserBalance,
     UpdateDiscussionsUsers(Vec<DiscussionUser>),
     UserStatus(AppEventUsetStatus),
+    /// ephemeral event of the user typing in the discussion
+    DiscussionTyping,
+    /// read receipt of the post in the private discussion
+    DiscussionPostRead,
+    /// notification for the devices of the offline receivers
+    PushNotification(UserNotification),
+    /// online users of the server instance
+    InstancePresence(InstancePresence),
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppEventUsetStatus {
     pub is_online: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppEventMetadata {
     pub discussion_id: Option<Thing>,
     pub post_id: Option<Thing>,
 }
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppEvent {
     pub user_id: String,
     pub metadata: Option<AppEventMetadata>,
     pub content: Option<String>,
     pub event: AppEventType,
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub receivers: Vec<String>,
 }
 
@@ -57,7 +76,7 @@ pub struct CtxState {
     pub apple_mobile_client_id: String,
     pub google_ios_client_id: String,
     pub google_android_client_id: String,
-    pub event_sender: broadcast::Sender<AppEvent>,
+    pub event_sender: Arc<dyn EventBusInterface + Send + Sync>,
     pub verification_code_ttl: Duration,
     pub jwt: JWT,
     pub email_sender: Arc<dyn SendEmailInterface + Send + Sync>,
@@ -66,10 +85,21 @@ pub struct CtxState {
     pub paypal_client_id: String,
     pub paypal_client_key: String,
     pub withdraw_fee: f64,
+    pub paid_post_fee: f64,
     pub online_users: Arc<DashMap<String, usize>>,
     pub support_email: String,
     pub darve_tasks: Arc<DarveTasksUtils>,
     pub twitch_service: TwitchService,
+    pub access_schema_path: Option<String>,
+    pub report_hide_threshold: u32,
+    pub adjustment_approval_threshold: u64,
+    pub app_url: String,
+    pub push_sender: Arc<dyn PushSenderInterface + Send + Sync>,
+    pub sse_buffer: Arc<SseBuffer>,
+    pub presence: Arc<ClusterPresence>,
+    pub notification_retention: Duration,
+    pub notification_max_per_user: u32,
+    pub edit_window: Duration,
 }
 
 impl Debug for CtxState {
@@ -79,8 +109,18 @@ impl Debug for CtxState {
 }
 
 pub async fn create_ctx_state(db: Database, config: &AppConfig) -> Arc<CtxState> {
-    let (event_sender, _) = broadcast::channel(100);
+    let instance_id = config
+        .instance_id
+        .clone()
+        .unwrap_or_else(|| Id::ulid().to_raw());
+    let event_sender = create_event_bus(config, db.client.clone(), &instance_id).await;
+    let online_users = Arc::new(DashMap::new());
     let database = Arc::new(db);
+    let sse_buffer = Arc::new(SseBuffer::new(
+        database.client.clone(),
+        200,
+        Duration::minutes(10),
+    ));
     let file_storage = Arc::new(
         GoogleCloudFileStorage::new(
             &config.gcs_bucket,
@@ -113,13 +153,28 @@ pub async fn create_ctx_state(db: Database, config: &AppConfig) -> Arc<CtxState>
         paypal_client_id: config.paypal_client_id.clone(),
         paypal_client_key: config.paypal_client_key.clone(),
         withdraw_fee: 0.05,
-        online_users: Arc::new(DashMap::new()),
+        paid_post_fee: 0.1,
+        online_users: online_users.clone(),
         support_email: config.support_email.clone(),
         darve_tasks: Arc::new(DarveTasksUtils::new(database, file_storage.clone())),
         twitch_service: TwitchService::new(
             config.twitch_client_id.clone(),
             config.twitch_client_secret.clone(),
         ),
+        access_schema_path: config.access_schema_path.clone(),
+        report_hide_threshold: config.report_hide_threshold,
+        adjustment_approval_threshold: config.adjustment_approval_threshold,
+        app_url: config.app_url.clone(),
+        push_sender: create_push_sender(config),
+        sse_buffer,
+        presence: Arc::new(ClusterPresence::new(
+            instance_id,
+            online_users,
+            Duration::seconds(30),
+        )),
+        notification_retention: Duration::days(config.notification_retention_days as i64),
+        notification_max_per_user: config.notification_max_per_user,
+        edit_window: Duration::minutes(config.edit_window_minutes as i64),
     };
     Arc::new(ctx_state)
 }
@@ -0,0 +1,52 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    extract::{FromRequestParts, Request},
+    http::{header::USER_AGENT, request::Parts},
+};
+use surrealdb::sql::Thing;
+use tower_governor::key_extractor::KeyExtractor;
+
+use crate::{entities::audit_log::AuditActor, middleware::limit::RobustIpKeyExtractor};
+
+/// Origin of the request recorded by the audit log
+#[derive(Debug, Clone, Default)]
+pub struct RequestMeta {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl RequestMeta {
+    pub fn actor(&self, user: Thing) -> AuditActor {
+        AuditActor {
+            user,
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestMeta {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // the ip is resolved the same way as for the rate limits
+        let mut req = Request::new(());
+        *req.headers_mut() = parts.headers.clone();
+        if let Some(addr) = parts.extensions.get::<SocketAddr>() {
+            req.extensions_mut().insert(*addr);
+        }
+        let ip = RobustIpKeyExtractor
+            .extract(&req)
+            .ok()
+            .filter(|ip| ip != "unknown");
+
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(|v| v.to_string());
+
+        Ok(RequestMeta { ip, user_agent })
+    }
+}
@@ -29,3 +29,20 @@ pub struct PaypalUnclaimed<'a> {
     pub amount: &'a str,
     pub paypal_email: &'a str,
 }
+
+#[derive(Template, Debug)]
+#[template(path = "emails/notifications.html")]
+pub struct NotificationsEmail<'a> {
+    pub titles: Vec<&'a str>,
+    pub app_url: &'a str,
+    pub unsubscribe_url: &'a str,
+}
+
+#[derive(Template, Debug)]
+#[template(path = "emails/notifications_digest.html")]
+pub struct NotificationsDigestEmail<'a> {
+    pub period: &'a str,
+    pub titles: Vec<&'a str>,
+    pub app_url: &'a str,
+    pub unsubscribe_url: &'a str,
+}
@@ -1,20 +1,30 @@
-use crate::database::table_names::ACCESS_TABLE_NAME;
+use crate::database::table_names::{ACCESS_TABLE_NAME, DISC_MUTE_TABLE_NAME};
 use crate::entities::community::post_entity::PostType;
 use crate::entities::community::{
-    discussion_entity::TABLE_NAME as DISC_TABLE_NAME, post_entity::TABLE_NAME as POST_TABLE_NAME,
+    discussion_entity::{DiscussionDbService, TABLE_NAME as DISC_TABLE_NAME},
+    post_entity::TABLE_NAME as POST_TABLE_NAME,
 };
 use crate::entities::task_request::TaskRequestType;
+use crate::entities::wallet::wallet_entity::CurrencySymbol;
 use crate::entities::{access_user::AccessUser, community::discussion_entity::DiscussionType};
 use crate::middleware::utils::db_utils::{ViewFieldSelector, ViewRelateField};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 
+// time limited grants stay in the table until the cleanup job prunes them
+pub const ACTIVE_ACCESS_FILTER: &str = "[WHERE expires_at IS NONE OR expires_at > time::now()]";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiscussionAccessView {
     pub id: Thing,
     pub r#type: DiscussionType,
     pub created_by: Thing,
     pub users: Vec<AccessUser>,
+    /// muted users keep their role but can only view
+    #[serde(default)]
+    pub muted_users: Vec<Thing>,
+    #[serde(default)]
+    pub hidden: bool,
 }
 
 impl DiscussionAccessView {
@@ -32,17 +42,28 @@ impl DiscussionAccessView {
             .map(|u| u.user.clone())
             .collect::<Vec<Thing>>()
     }
+
+    pub fn get_user_role(&self, user: &Thing) -> Option<String> {
+        self.users
+            .iter()
+            .find(|u| &u.user == user)
+            .map(|u| u.role.clone())
+    }
+
+    pub fn is_profile(&self) -> bool {
+        self.id == DiscussionDbService::get_profile_discussion_id(&self.created_by)
+    }
 }
 
 impl ViewFieldSelector for DiscussionAccessView {
     fn get_select_query_fields() -> String {
-        format!("id, type, created_by, <-{ACCESS_TABLE_NAME}.* as users")
+        format!("id, type, created_by, hidden, <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.* as users, <-{DISC_MUTE_TABLE_NAME}.in as muted_users")
     }
 }
 
 impl ViewRelateField for DiscussionAccessView {
     fn get_fields() -> String {
-        "id, type, created_by, users: <-has_access.*".to_string()
+        format!("id, type, created_by, hidden, users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*, muted_users: <-{DISC_MUTE_TABLE_NAME}.in")
     }
 }
 
@@ -54,9 +75,18 @@ pub struct PostAccessView {
     pub users: Vec<AccessUser>,
     pub media_links: Option<Vec<String>>,
     pub tasks_nr: u32,
+    pub price: Option<u64>,
+    pub currency: Option<CurrencySymbol>,
+    #[serde(default)]
+    pub hidden: bool,
 }
 
 impl PostAccessView {
+    /// the post is hidden by itself or by its discussion
+    pub fn is_hidden(&self) -> bool {
+        self.hidden || self.discussion.hidden
+    }
+
     pub fn get_user_ids(&self) -> Vec<Thing> {
         self.users
             .iter()
@@ -71,12 +101,28 @@ impl PostAccessView {
             .map(|u| u.user.clone())
             .collect::<Vec<Thing>>()
     }
+
+    pub fn get_user_role(&self, user: &Thing) -> Option<String> {
+        self.users
+            .iter()
+            .find(|u| &u.user == user)
+            .map(|u| u.role.clone())
+    }
+
+    /// the private posts of the private discussions are sent to their members only
+    pub fn get_receiver_ids(&self) -> Vec<String> {
+        let users = match (&self.r#type, &self.discussion.r#type) {
+            (PostType::Private, DiscussionType::Private) => self.get_user_ids(),
+            _ => self.discussion.get_user_ids(),
+        };
+        users.into_iter().map(|id| id.id.to_raw()).collect()
+    }
 }
 
 impl ViewFieldSelector for PostAccessView {
     fn get_select_query_fields() -> String {
         let disc_fields = DiscussionAccessView::get_fields();
-        format!("*, <-{ACCESS_TABLE_NAME}.* as users, belongs_to.{{{disc_fields}}} as discussion")
+        format!("*, <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.* as users, belongs_to.{{{disc_fields}}} as discussion")
     }
 }
 
@@ -103,19 +149,26 @@ impl TaskAccessView {
             .map(|u| u.user.clone())
             .collect::<Vec<Thing>>()
     }
+
+    pub fn get_user_role(&self, user: &Thing) -> Option<String> {
+        self.users
+            .iter()
+            .find(|u| &u.user == user)
+            .map(|u| u.role.clone())
+    }
 }
 
 impl ViewFieldSelector for TaskAccessView {
     fn get_select_query_fields() -> String {
         let disc_fields = DiscussionAccessView::get_fields();
         format!(
-            "id, type, <-{ACCESS_TABLE_NAME}.* as users, 
+            "id, type, <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.* as users, 
                 IF record::tb(belongs_to) = '{POST_TABLE_NAME}' THEN belongs_to.{{ 
                         id, 
                         type,
                         tasks_nr,
                         media_links,
-                        users: <-{ACCESS_TABLE_NAME}.*,
+                        users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*,
                         discussion: belongs_to.{{{disc_fields}}} 
                 }} END AS post,
                 IF record::tb(belongs_to) = '{DISC_TABLE_NAME}' THEN belongs_to.{{{disc_fields}}} END AS discussion"
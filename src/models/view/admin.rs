@@ -0,0 +1,44 @@
+use crate::{
+    entities::user_auth::local_user_entity::{AccountStatus, UserRole},
+    middleware::utils::db_utils::ViewFieldSelector,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdminUserView {
+    pub id: Thing,
+    pub username: String,
+    pub full_name: Option<String>,
+    pub email_verified: Option<String>,
+    pub phone: Option<String>,
+    pub role: UserRole,
+    #[serde(default)]
+    pub status: AccountStatus,
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub is_otp_enabled: bool,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl ViewFieldSelector for AdminUserView {
+    fn get_select_query_fields() -> String {
+        "id, username, full_name, email_verified, phone, role, status, suspended_until,
+        is_otp_enabled, last_seen"
+            .to_string()
+    }
+}
+
+/// Authentication without its secrets
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthMethodView {
+    pub id: Thing,
+    pub auth_type: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ViewFieldSelector for AuthMethodView {
+    fn get_select_query_fields() -> String {
+        "id, auth_type, updated_at".to_string()
+    }
+}
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
 
 use crate::{
     middleware::utils::db_utils::{ViewFieldSelector, ViewRelateField},
@@ -10,11 +11,22 @@ use crate::{
 pub struct DiscussionUserView {
     pub discussion: DiscussionView,
     pub latest_post: Option<LatestPostView>,
+    pub last_read_post: Option<Thing>,
     pub nr_unread: u32,
+    #[serde(default)]
+    pub is_request: bool,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
 
+impl DiscussionUserView {
+    pub fn hide_locked_content(&mut self, user: &Thing) {
+        if let Some(post) = self.latest_post.as_mut() {
+            post.hide_locked_content(user);
+        }
+    }
+}
+
 impl ViewFieldSelector for DiscussionUserView {
     fn get_select_query_fields() -> String {
         let disc_view_fields = DiscussionView::get_fields();
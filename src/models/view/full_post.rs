@@ -1,5 +1,7 @@
 use crate::database::table_names::ACCESS_TABLE_NAME;
 use crate::entities::access_user::AccessUser;
+use crate::entities::wallet::wallet_entity::CurrencySymbol;
+use crate::models::view::post::get_teaser_content;
 use crate::{
     entities::community::post_entity::PostType,
     middleware::utils::db_utils::{ViewFieldSelector, ViewRelateField},
@@ -26,6 +28,18 @@ pub struct FullPostView {
     pub liked_by: Option<Vec<Thing>>,
     pub users: Option<Vec<AccessUser>>,
     pub delivered_for_task: Option<Thing>,
+    pub price: Option<u64>,
+    pub currency: Option<CurrencySymbol>,
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// set when the title or content was edited
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl FullPostView {
+    pub fn lock_content(&mut self) {
+        self.media_links = None;
+        self.content = get_teaser_content(self.content.take());
+    }
 }
 
 impl ViewFieldSelector for FullPostView {
@@ -45,6 +59,10 @@ impl ViewFieldSelector for FullPostView {
         likes_nr,
         <-{ACCESS_TABLE_NAME}.* as users,
         delivered_for_task,
+        price,
+        currency,
+        pinned_at,
+        edited_at,
         <-like[WHERE in=$user].in as liked_by"
         )
     }
@@ -66,6 +84,10 @@ impl ViewRelateField for FullPostView {
         likes_nr,
         users: <-has_access.*,
         delivered_for_task,
+        price,
+        currency,
+        pinned_at,
+        edited_at,
         liked_by: <-like[WHERE in=$user].in"
             .to_string()
     }
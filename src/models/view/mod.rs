@@ -1,5 +1,6 @@
 pub mod access;
 pub mod access_user;
+pub mod admin;
 pub mod balance_tx;
 pub mod discussion;
 pub mod discussion_user;
@@ -7,5 +8,6 @@ pub mod full_post;
 pub mod notification;
 pub mod post;
 pub mod reply;
+pub mod revenue;
 pub mod task;
 pub mod user;
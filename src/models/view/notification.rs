@@ -23,10 +23,14 @@ pub struct UserNotificationView {
     pub is_follower: bool,
     #[serde(default)]
     pub is_following: bool,
+    pub actors: Option<Vec<UserView>>,
+    #[serde(default)]
+    pub actors_count: u64,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl ViewRelateField for UserNotificationView {
     fn get_fields() -> String {
-        "id, is_following: created_by IN $user->follow.out, is_follower: created_by IN $user<-follow.in, created_by: created_by.*, title, event, is_read, metadata, created_at".to_string()
+        "id, is_following: created_by IN $user->follow.out, is_follower: created_by IN $user<-follow.in, created_by: created_by.*, title, event, is_read, metadata, created_at, actors: actors.*, actors_count, updated_at".to_string()
     }
 }
@@ -1,17 +1,20 @@
+use crate::access::base::role::Role;
 use crate::database::table_names::ACCESS_TABLE_NAME;
 use crate::database::table_names::POST_USER_TABLE_NAME;
 use crate::entities::access_user::AccessUser;
 use crate::entities::community::post_entity::PostUserStatus;
+use crate::entities::wallet::wallet_entity::CurrencySymbol;
+use crate::models::view::access::ACTIVE_ACCESS_FILTER;
 use crate::models::view::access_user::AccessUserView;
 use crate::{
-    entities::community::post_entity::PostType,
+    entities::community::{discussion_entity::DiscussionType, post_entity::PostType},
     middleware::utils::db_utils::{ViewFieldSelector, ViewRelateField},
     models::view::user::UserView,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PostView {
     pub id: Thing,
     pub created_by: UserView,
@@ -28,10 +31,60 @@ pub struct PostView {
     pub liked_by: Option<Vec<Thing>>,
     pub users: Option<Vec<AccessUser>>,
     pub reply_to: Option<Box<PostView>>,
+    pub price: Option<u64>,
+    pub currency: Option<CurrencySymbol>,
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// set when the title or content was edited
+    pub edited_at: Option<DateTime<Utc>>,
+    /// users who have seen the post, set only in the private discussions
+    pub read_by: Option<Vec<Thing>>,
+}
+
+impl PostView {
+    pub fn is_unlocked_for(&self, user: &Thing) -> bool {
+        self.r#type != PostType::Paid
+            || &self.created_by.id == user
+            || has_unlocking_access(self.users.as_ref(), user)
+    }
+
+    pub fn lock_content(&mut self) {
+        self.media_links = None;
+        self.content = get_teaser_content(self.content.take());
+    }
+
+    pub fn hide_locked_content(&mut self, user: &Thing) {
+        if !self.is_unlocked_for(user) {
+            self.lock_content();
+        }
+        if let Some(reply_to) = self.reply_to.as_mut() {
+            if !reply_to.is_unlocked_for(user) {
+                reply_to.lock_content();
+            }
+        }
+    }
+}
+
+// the users are selected with the active grants only
+fn has_unlocking_access(users: Option<&Vec<AccessUser>>, user: &Thing) -> bool {
+    let roles = [Role::Buyer, Role::Owner, Role::Subscriber].map(|r| r.to_string());
+    users.map_or(false, |users| {
+        users
+            .iter()
+            .any(|u| &u.user == user && roles.contains(&u.role))
+    })
+}
+
+// only the beginning of a paid post content is shown until it is bought
+const TEASER_CONTENT_LENGTH: usize = 150;
+
+pub fn get_teaser_content(content: Option<String>) -> Option<String> {
+    content.map(|value| value.chars().take(TEASER_CONTENT_LENGTH).collect())
 }
 
 impl ViewFieldSelector for PostView {
     fn get_select_query_fields() -> String {
+        let seen = PostUserStatus::Seen as u8;
+        let private = DiscussionType::Private;
         format!(
             "id,
         created_by.* as created_by, 
@@ -45,16 +98,24 @@ impl ViewFieldSelector for PostView {
         belongs_to,
         replies_nr,
         likes_nr,
-        <-{ACCESS_TABLE_NAME}.* as users,
+        <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.* as users,
         <-like[WHERE in=$user].in as liked_by,
-        reply_to.{{id, created_by: created_by.*, title, type, tasks_nr, content, media_links, created_at, updated_at, belongs_to, replies_nr, likes_nr}} as reply_to"
+        price,
+        currency,
+        pinned_at,
+        edited_at,
+        IF belongs_to.type = '{private:?}' THEN ->{POST_USER_TABLE_NAME}[WHERE status={seen}].out END as read_by,
+        reply_to.{{id, created_by: created_by.*, title, type, price, currency, tasks_nr, content, media_links, created_at, updated_at, belongs_to, replies_nr, likes_nr, users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*}} as reply_to"
         )
     }
 }
 
 impl ViewRelateField for PostView {
     fn get_fields() -> String {
-        "id,
+        let seen = PostUserStatus::Seen as u8;
+        let private = DiscussionType::Private;
+        format!(
+            "id,
         created_by: created_by.*, 
         title, 
         type,
@@ -66,11 +127,17 @@ impl ViewRelateField for PostView {
         belongs_to,
         replies_nr,
         likes_nr,
-        users: <-has_access.*,
+        users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*,
         liked_by: <-like[WHERE in=$user].in,
-        reply_to: reply_to.{
-            id, created_by: created_by.*, title, type, tasks_nr, content, media_links, created_at, updated_at, belongs_to, replies_nr, likes_nr}"
-            .to_string()
+        price,
+        currency,
+        pinned_at,
+        edited_at,
+        read_by: IF belongs_to.type = '{private:?}' THEN ->{POST_USER_TABLE_NAME}[WHERE status={seen}].out END,
+        reply_to: reply_to.{{
+            id, created_by: created_by.*, title, type, price, currency, tasks_nr, content, media_links, created_at, updated_at, belongs_to, replies_nr, likes_nr,
+            users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*}}"
+        )
     }
 }
 
@@ -111,6 +178,19 @@ pub struct LatestPostView {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub users_status: Option<Vec<PostUserStatusView>>,
+    pub users: Option<Vec<AccessUser>>,
+}
+
+impl LatestPostView {
+    pub fn hide_locked_content(&mut self, user: &Thing) {
+        let is_unlocked = self.r#type != PostType::Paid
+            || &self.created_by.id == user
+            || has_unlocking_access(self.users.as_ref(), user);
+        if !is_unlocked {
+            self.media_links = None;
+            self.content = get_teaser_content(self.content.take());
+        }
+    }
 }
 
 impl ViewFieldSelector for LatestPostView {
@@ -125,14 +205,16 @@ impl ViewFieldSelector for LatestPostView {
         created_at,
         updated_at,
         belongs_to,
-        ->{POST_USER_TABLE_NAME}.* as users_status"
+        ->{POST_USER_TABLE_NAME}.* as users_status,
+        <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.* as users"
         )
     }
 }
 
 impl ViewRelateField for LatestPostView {
     fn get_fields() -> String {
-        "id,
+        format!(
+            "id,
         created_by: created_by.*, 
         title, 
         type,
@@ -141,7 +223,8 @@ impl ViewRelateField for LatestPostView {
         created_at,
         updated_at,
         belongs_to,
-        users_status: ->post_user.*"
-            .to_string()
+        users_status: ->post_user.*,
+        users: <-{ACCESS_TABLE_NAME}{ACTIVE_ACCESS_FILTER}.*"
+        )
     }
 }
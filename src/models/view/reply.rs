@@ -13,6 +13,8 @@ pub struct ReplyView {
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// set when the content was edited
+    pub edited_at: Option<DateTime<Utc>>,
     pub liked_by: Option<Vec<Thing>>,
 }
 impl ViewFieldSelector for ReplyView {
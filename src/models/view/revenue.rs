@@ -0,0 +1,46 @@
+use crate::entities::wallet::{
+    balance_transaction_entity::TransactionType, wallet_entity::CurrencySymbol,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevenueByPeriodView {
+    pub period: DateTime<Utc>,
+    pub currency: CurrencySymbol,
+    pub amount: i64,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevenueByTypeView {
+    pub r#type: Option<TransactionType>,
+    pub currency: CurrencySymbol,
+    pub amount: i64,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevenueByCurrencyView {
+    pub currency: CurrencySymbol,
+    pub amount: i64,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BalanceTotalsView {
+    pub balance_usd: i64,
+    pub balance_reef: i64,
+    pub balance_eth: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiabilitiesView {
+    // sum of the balances of all user and task wallets
+    pub user_liabilities: BalanceTotalsView,
+    // funds deposited through the payment gateways and not withdrawn yet
+    pub gateway_holdings: BalanceTotalsView,
+    pub platform_balance: BalanceTotalsView,
+    // net of the manual corrections made by admins
+    pub adjustments_balance: BalanceTotalsView,
+}
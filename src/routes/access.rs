@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
+
+use crate::{
+    access::base::explain::AccessExplain,
+    entities::access_invite::AccessInvite,
+    middleware::{
+        bearer_auth::BearerAuth, error::CtxResult, mw_ctx::CtxState,
+        utils::extractor_utils::JsonOrFormValidated,
+    },
+    services::{
+        access_grant_service::{AccessGrantService, CreateAccessInvite},
+        access_service::{AccessExplainQuery, AccessService},
+    },
+};
+
+pub fn routes() -> Router<Arc<CtxState>> {
+    Router::new()
+        .route("/api/access/explain", get(explain))
+        .route("/api/access/invites", post(create_invite))
+        .route("/api/access/invites", get(get_invites))
+        .route("/api/access/invites/{invite_id}", delete(revoke_invite))
+        .route("/api/access/invites/{token}/redeem", post(redeem_invite))
+}
+
+async fn explain(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<AccessExplainQuery>,
+) -> CtxResult<Json<AccessExplain>> {
+    let data = AccessService::new(&state.db.client, &auth_data.ctx, &state.db.task_request)
+        .explain(&auth_data.user_thing_id(), query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn create_invite(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<CreateAccessInvite>,
+) -> CtxResult<Json<AccessInvite>> {
+    let invite = AccessGrantService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.db.access_invites,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .create_invite(&auth_data.user_thing_id(), data)
+    .await?;
+    Ok(Json(invite))
+}
+
+#[derive(Debug, Deserialize)]
+struct GetInvitesQuery {
+    entity_id: String,
+}
+
+async fn get_invites(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<GetInvitesQuery>,
+) -> CtxResult<Json<Vec<AccessInvite>>> {
+    let invites = AccessGrantService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.db.access_invites,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .get_invites(&auth_data.user_thing_id(), &query.entity_id)
+    .await?;
+    Ok(Json(invites))
+}
+
+async fn revoke_invite(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(invite_id): Path<String>,
+) -> CtxResult<()> {
+    AccessGrantService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.db.access_invites,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .revoke_invite(&auth_data.user_thing_id(), &invite_id)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedeemInviteResponse {
+    pub entity: Thing,
+}
+
+async fn redeem_invite(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(token): Path<String>,
+) -> CtxResult<Json<RedeemInviteResponse>> {
+    let entity = AccessGrantService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.db.access_invites,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .redeem_invite(&auth_data.user_thing_id(), &token)
+    .await?;
+    Ok(Json(RedeemInviteResponse { entity }))
+}
@@ -1,41 +1,607 @@
-use axum::{extract::State, routing::get, Json, Router};
+use axum::{
+    extract::{Path, Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
 use std::sync::Arc;
 
 use crate::{
-    entities::user_auth::local_user_entity::LocalUserDbService,
-    middleware::{bearer_auth::BearerAuth, error::CtxResult, mw_ctx::CtxState},
-    models::view::task::TaskRequestView,
+    access::base::control::AccessControlDiff,
+    entities::{
+        account_status_change::AccountStatusChange,
+        audit_log::AuditLog,
+        blocked_word::{BlockedWord, BlockedWordCategory, BlockedWordCategoryAction},
+        report::{ModerationAction, Report},
+        wallet::{
+            gateway_transaction_entity::GatewayTransaction,
+            wallet_adjustment_entity::WalletAdjustment, wallet_entity::WalletBalancesView,
+        },
+    },
+    middleware::{
+        admin_auth::AdminAuth,
+        error::CtxResult,
+        mw_ctx::CtxState,
+        utils::{
+            db_utils::{Pagination, QryOrder},
+            extractor_utils::JsonOrFormValidated,
+        },
+    },
+    models::view::{
+        admin::{AdminUserView, AuthMethodView},
+        balance_tx::CurrencyTransactionView,
+        revenue::{LiabilitiesView, RevenueByCurrencyView, RevenueByPeriodView, RevenueByTypeView},
+    },
+    routes::wallet::{GetGatewayWalletHistoryQuery, GetWalletHistoryQuery},
+    services::{
+        access_schema_service::AccessSchemaService,
+        account_status_service::{
+            AccountStatusChangesQuery, AccountStatusService, ReinstateAccountInput,
+            RestrictAccountInput,
+        },
+        admin_service::{
+            AdminService, AuditLogExportFormat, AuditLogExportQuery, AuditLogQuery,
+            ChangeRoleInput, DeleteContentInput, UsersQuery, VerifyEmailInput,
+        },
+        content_filter_service::{
+            BlockedWordsInput, BlockedWordsQuery, CategoryActionInput, ContentFilterService,
+            RemoveBlockedWordsInput,
+        },
+        report_service::{
+            AssignReport, ModerationActionsQuery, ReportService, ReportsQuery, TakeModerationAction,
+        },
+        revenue_service::{RevenueQuery, RevenueService},
+        wallet_adjustment_service::{
+            AdjustmentsQuery, CreateAdjustmentInput, RejectAdjustmentInput, WalletAdjustmentService,
+        },
+    },
 };
 
 pub fn routes() -> Router<Arc<CtxState>> {
-    Router::new().route("/api/admin/tasks", get(get_tasks))
+    Router::new()
+        .route("/api/admin/users", get(get_users))
+        .route("/api/admin/users/{user_id}/wallet", get(get_user_wallet))
+        .route(
+            "/api/admin/users/{user_id}/wallet/history",
+            get(get_user_wallet_history),
+        )
+        .route(
+            "/api/admin/users/{user_id}/gateway_wallet/history",
+            get(get_user_gateway_wallet_history),
+        )
+        .route(
+            "/api/admin/users/{user_id}/wallet/adjustments",
+            post(create_wallet_adjustment),
+        )
+        .route("/api/admin/wallet/adjustments", get(get_wallet_adjustments))
+        .route(
+            "/api/admin/wallet/adjustments/{adjustment_id}/approve",
+            post(approve_wallet_adjustment),
+        )
+        .route(
+            "/api/admin/wallet/adjustments/{adjustment_id}/reject",
+            post(reject_wallet_adjustment),
+        )
+        .route(
+            "/api/admin/users/{user_id}/auth_methods",
+            get(get_user_auth_methods),
+        )
+        .route(
+            "/api/admin/users/{user_id}/email/verify",
+            post(verify_user_email),
+        )
+        .route("/api/admin/users/{user_id}/otp", delete(reset_user_otp))
+        .route("/api/admin/users/{user_id}/role", put(change_user_role))
+        .route("/api/admin/posts/{post_id}", delete(delete_content))
+        .route(
+            "/api/admin/discussions/{discussion_id}",
+            delete(delete_content),
+        )
+        .route("/api/admin/audit_log", get(get_audit_log))
+        .route("/api/admin/audit_log/export", get(export_audit_log))
+        .route("/api/admin/revenue", get(get_revenue_by_period))
+        .route("/api/admin/revenue/types", get(get_revenue_by_type))
+        .route(
+            "/api/admin/revenue/currencies",
+            get(get_revenue_by_currency),
+        )
+        .route("/api/admin/revenue/liabilities", get(get_liabilities))
+        .route("/api/admin/access/schema", put(update_access_schema))
+        .route(
+            "/api/admin/access/schema/reload",
+            post(reload_access_schema),
+        )
+        .route("/api/admin/reports", get(get_reports))
+        .route("/api/admin/reports/{report_id}/assign", post(assign_report))
+        .route(
+            "/api/admin/reports/{report_id}/actions",
+            post(take_report_action),
+        )
+        .route("/api/admin/moderation/actions", get(get_moderation_actions))
+        .route("/api/admin/blocked_words", get(get_blocked_words))
+        .route("/api/admin/blocked_words", post(add_blocked_words))
+        .route("/api/admin/blocked_words", delete(remove_blocked_words))
+        .route(
+            "/api/admin/blocked_words/categories",
+            get(get_blocked_word_categories),
+        )
+        .route(
+            "/api/admin/blocked_words/categories/{category}",
+            put(set_blocked_word_category_action),
+        )
+        .route(
+            "/api/admin/users/{user_id}/suspension",
+            post(restrict_account),
+        )
+        .route(
+            "/api/admin/users/{user_id}/suspension",
+            delete(reinstate_account),
+        )
+        .route(
+            "/api/admin/users/{user_id}/status_history",
+            get(get_account_status_history),
+        )
+}
+
+async fn get_users(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<UsersQuery>,
+) -> CtxResult<Json<Vec<AdminUserView>>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_users(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_user_wallet(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+) -> CtxResult<Json<WalletBalancesView>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_wallet(&user_id)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_user_wallet_history(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<GetWalletHistoryQuery>,
+) -> CtxResult<Json<Vec<CurrencyTransactionView>>> {
+    let pagination = Pagination {
+        order_by: params.order_by.or(Some("created_at".to_string())),
+        order_dir: params.order_dir.or(Some(QryOrder::DESC)),
+        count: params.count.unwrap_or(20),
+        start: params.start.unwrap_or(0),
+    };
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_transactions(&user_id, params.r#type, pagination)
+        .await?;
+    Ok(Json(data))
 }
 
-async fn get_tasks(
-    auth_data: BearerAuth,
+async fn get_user_gateway_wallet_history(
+    auth_data: AdminAuth,
     State(state): State<Arc<CtxState>>,
-) -> CtxResult<Json<Vec<TaskRequestView>>> {
-    let user_id = auth_data.user_thing_id();
-    let user_repository = LocalUserDbService {
-        db: &state.db.client,
-        ctx: &auth_data.ctx,
+    Path(user_id): Path<String>,
+    Query(params): Query<GetGatewayWalletHistoryQuery>,
+) -> CtxResult<Json<Vec<GatewayTransaction>>> {
+    let pagination = Pagination {
+        order_by: params.order_by.or(Some("created_at".to_string())),
+        order_dir: params.order_dir.or(Some(QryOrder::DESC)),
+        count: params.count.unwrap_or(20),
+        start: params.start.unwrap_or(0),
     };
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_gateway_transactions(&user_id, params.status, params.r#type, pagination)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn create_wallet_adjustment(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Json(data): Json<CreateAdjustmentInput>,
+) -> CtxResult<Json<WalletAdjustment>> {
+    let data = WalletAdjustmentService::new(&state, &auth_data.ctx)
+        .create(auth_data.actor(), &user_id, data)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_wallet_adjustments(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<AdjustmentsQuery>,
+) -> CtxResult<Json<Vec<WalletAdjustment>>> {
+    let data = WalletAdjustmentService::new(&state, &auth_data.ctx)
+        .get_list(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn approve_wallet_adjustment(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(adjustment_id): Path<String>,
+) -> CtxResult<Json<WalletAdjustment>> {
+    let data = WalletAdjustmentService::new(&state, &auth_data.ctx)
+        .approve(auth_data.actor(), &adjustment_id)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn reject_wallet_adjustment(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(adjustment_id): Path<String>,
+    Json(data): Json<RejectAdjustmentInput>,
+) -> CtxResult<Json<WalletAdjustment>> {
+    let data = WalletAdjustmentService::new(&state, &auth_data.ctx)
+        .reject(auth_data.actor(), &adjustment_id, data)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_user_auth_methods(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+) -> CtxResult<Json<Vec<AuthMethodView>>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_auth_methods(&user_id)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn verify_user_email(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Json(data): Json<VerifyEmailInput>,
+) -> CtxResult<Json<AdminUserView>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .verify_email(auth_data.actor(), &user_id, data)
+        .await?;
+    Ok(Json(data))
+}
 
-    let _ = user_repository.get_by_id(&user_id).await?;
+async fn reset_user_otp(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+) -> CtxResult<Json<AdminUserView>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .reset_otp(auth_data.actor(), &user_id)
+        .await?;
+    Ok(Json(data))
+}
 
-    let super_tasks = state
-        .darve_tasks
-        .create_public(&user_id, &state.event_sender)
+async fn change_user_role(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Json(data): Json<ChangeRoleInput>,
+) -> CtxResult<Json<AdminUserView>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .change_role(auth_data.actor(), &user_id, data)
         .await?;
+    Ok(Json(data))
+}
 
-    let weekly_tasks = state
-        .darve_tasks
-        .create_private(&user_id, &state.event_sender)
+async fn delete_content(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(entity_id): Path<String>,
+    Json(data): Json<DeleteContentInput>,
+) -> CtxResult<Json<AuditLog>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .delete_content(auth_data.actor(), &entity_id, data)
         .await?;
+    Ok(Json(data))
+}
 
-    let tasks = super_tasks
-        .into_iter()
-        .chain(weekly_tasks)
-        .collect::<Vec<TaskRequestView>>();
-    Ok(Json(tasks))
+async fn get_audit_log(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<AuditLogQuery>,
+) -> CtxResult<Json<Vec<AuditLog>>> {
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .get_audit_log(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn export_audit_log(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<AuditLogQuery>,
+    Query(export): Query<AuditLogExportQuery>,
+) -> CtxResult<Response> {
+    let format = export.format.unwrap_or(AuditLogExportFormat::Csv);
+    let data = AdminService::new(&state, &auth_data.ctx)
+        .export_audit_log(query)
+        .await?;
+    let res = match format {
+        AuditLogExportFormat::Json => Json(data).into_response(),
+        AuditLogExportFormat::Csv => (
+            [
+                (CONTENT_TYPE, "text/csv"),
+                (
+                    CONTENT_DISPOSITION,
+                    "attachment; filename=\"audit_log.csv\"",
+                ),
+            ],
+            audit_log_to_csv(&data),
+        )
+            .into_response(),
+    };
+    Ok(res)
+}
+
+fn audit_log_to_csv(data: &[AuditLog]) -> String {
+    let escape = |value: String| match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value,
+    };
+    let mut csv = "id,created_at,actor,action,target,note,before,after,ip,user_agent\n".to_string();
+    for log in data {
+        let row = [
+            log.id.to_raw(),
+            log.created_at.to_rfc3339(),
+            log.actor.to_raw(),
+            log.action.to_string(),
+            log.target.to_raw(),
+            log.note.clone().unwrap_or_default(),
+            log.before
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            log.after
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            log.ip.clone().unwrap_or_default(),
+            log.user_agent.clone().unwrap_or_default(),
+        ];
+        csv.push_str(&row.map(escape).join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+async fn get_revenue_by_period(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<RevenueQuery>,
+) -> CtxResult<Json<Vec<RevenueByPeriodView>>> {
+    let data = RevenueService::new(&state.db.client, &auth_data.ctx)
+        .get_by_period(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_revenue_by_type(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<RevenueQuery>,
+) -> CtxResult<Json<Vec<RevenueByTypeView>>> {
+    let data = RevenueService::new(&state.db.client, &auth_data.ctx)
+        .get_by_type(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_revenue_by_currency(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<RevenueQuery>,
+) -> CtxResult<Json<Vec<RevenueByCurrencyView>>> {
+    let data = RevenueService::new(&state.db.client, &auth_data.ctx)
+        .get_by_currency(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_liabilities(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<LiabilitiesView>> {
+    let data = RevenueService::new(&state.db.client, &auth_data.ctx)
+        .get_liabilities()
+        .await?;
+    Ok(Json(data))
+}
+
+async fn update_access_schema(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(schema): Json<serde_json::Value>,
+) -> CtxResult<Json<AccessControlDiff>> {
+    let diff = AccessSchemaService::new(
+        &state.db.access_schemas,
+        state.access_schema_path.as_deref(),
+    )
+    .update(auth_data.user_thing(), schema.to_string())
+    .await?;
+    Ok(Json(diff))
+}
+
+async fn reload_access_schema(
+    _auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<AccessControlDiff>> {
+    let diff = AccessSchemaService::new(
+        &state.db.access_schemas,
+        state.access_schema_path.as_deref(),
+    )
+    .reload()
+    .await?;
+    Ok(Json(diff))
+}
+
+async fn get_reports(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<ReportsQuery>,
+) -> CtxResult<Json<Vec<Report>>> {
+    let data = ReportService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.reports,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    )
+    .get_queue(query)
+    .await?;
+    Ok(Json(data))
+}
+
+async fn assign_report(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(report_id): Path<String>,
+    Json(data): Json<AssignReport>,
+) -> CtxResult<Json<Report>> {
+    let data = ReportService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.reports,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    )
+    .assign(&report_id, data)
+    .await?;
+    Ok(Json(data))
+}
+
+async fn take_report_action(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(report_id): Path<String>,
+    JsonOrFormValidated(data): JsonOrFormValidated<TakeModerationAction>,
+) -> CtxResult<Json<ModerationAction>> {
+    let data = ReportService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.reports,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    )
+    .take_action(auth_data.actor(), &report_id, data)
+    .await?;
+    Ok(Json(data))
+}
+
+async fn get_moderation_actions(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<ModerationActionsQuery>,
+) -> CtxResult<Json<Vec<ModerationAction>>> {
+    let data = ReportService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.reports,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    )
+    .get_actions(query)
+    .await?;
+    Ok(Json(data))
+}
+
+async fn get_blocked_words(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<BlockedWordsQuery>,
+) -> CtxResult<Json<Vec<BlockedWord>>> {
+    let data = ContentFilterService::new(&state.db.client, &auth_data.ctx)
+        .get_words(query)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn add_blocked_words(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(data): Json<BlockedWordsInput>,
+) -> CtxResult<Json<Vec<BlockedWord>>> {
+    let data = ContentFilterService::new(&state.db.client, &auth_data.ctx)
+        .add_words(data)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn remove_blocked_words(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(data): Json<RemoveBlockedWordsInput>,
+) -> CtxResult<()> {
+    ContentFilterService::new(&state.db.client, &auth_data.ctx)
+        .remove_words(data)
+        .await?;
+    Ok(())
+}
+
+async fn get_blocked_word_categories(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<BlockedWordCategoryAction>>> {
+    let data = ContentFilterService::new(&state.db.client, &auth_data.ctx)
+        .get_categories()
+        .await?;
+    Ok(Json(data))
+}
+
+async fn set_blocked_word_category_action(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(category): Path<BlockedWordCategory>,
+    Json(data): Json<CategoryActionInput>,
+) -> CtxResult<()> {
+    ContentFilterService::new(&state.db.client, &auth_data.ctx)
+        .set_category_action(category, data)
+        .await?;
+    Ok(())
+}
+
+async fn restrict_account(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Json(data): Json<RestrictAccountInput>,
+) -> CtxResult<Json<AccountStatusChange>> {
+    let data = AccountStatusService::new(&state, &auth_data.ctx)
+        .restrict(auth_data.actor(), &user_id, data)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn reinstate_account(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Json(data): Json<ReinstateAccountInput>,
+) -> CtxResult<Json<AccountStatusChange>> {
+    let data = AccountStatusService::new(&state, &auth_data.ctx)
+        .reinstate(auth_data.actor(), &user_id, data)
+        .await?;
+    Ok(Json(data))
+}
+
+async fn get_account_status_history(
+    auth_data: AdminAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+    Query(query): Query<AccountStatusChangesQuery>,
+) -> CtxResult<Json<Vec<AccountStatusChange>>> {
+    let data = AccountStatusService::new(&state, &auth_data.ctx)
+        .get_changes(&user_id, query)
+        .await?;
+    Ok(Json(data))
 }
@@ -13,7 +13,8 @@ use serde_json::json;
 
 use crate::{
     middleware::{
-        ctx::Ctx, error::CtxResult, mw_ctx::CtxState, utils::extractor_utils::JsonOrFormValidated,
+        ctx::Ctx, error::CtxResult, mw_ctx::CtxState, request_meta::RequestMeta,
+        utils::extractor_utils::JsonOrFormValidated,
     },
     models::view::user::LoggedUserView,
     services::auth_service::{
@@ -240,6 +241,7 @@ async fn forgot_password_start(
 async fn forgot_password_confirm(
     State(state): State<Arc<CtxState>>,
     ctx: Ctx,
+    meta: RequestMeta,
     Json(body): Json<ResetPasswordInput>,
 ) -> CtxResult<Response> {
     let auth_service = AuthService::new(
@@ -254,7 +256,7 @@ async fn forgot_password_confirm(
         &state.twitch_service,
     );
 
-    let _ = auth_service.reset_password(body).await?;
+    let _ = auth_service.reset_password(body, meta).await?;
     Ok((StatusCode::OK).into_response())
 }
 
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::entities::community::discussion_entity::Discussion;
+use crate::interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface;
+use crate::middleware::bearer_auth::BearerAuth;
+use crate::middleware::error::CtxResult;
+use crate::middleware::mw_ctx::CtxState;
+use crate::middleware::utils::db_utils::Pagination;
+use crate::middleware::utils::extractor_utils::JsonOrFormValidated;
+use crate::middleware::utils::string_utils::get_str_thing;
+use crate::models::view::discussion_user::DiscussionUserView;
+use crate::services::discussion_service::DiscussionService;
+
+pub fn routes() -> Router<Arc<CtxState>> {
+    Router::new()
+        .route("/api/direct_messages", get(get_inbox))
+        .route("/api/direct_messages", post(create_direct_message))
+        .route("/api/direct_messages/requests", get(get_requests))
+        .route(
+            "/api/direct_messages/requests/{discussion_id}/accept",
+            post(accept_request),
+        )
+        .route(
+            "/api/direct_messages/requests/{discussion_id}",
+            delete(decline_request),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct GetInboxQuery {
+    start: Option<u32>,
+    count: Option<u16>,
+}
+
+impl From<GetInboxQuery> for Pagination {
+    fn from(query: GetInboxQuery) -> Self {
+        Pagination {
+            order_by: None,
+            order_dir: None,
+            count: query.count.unwrap_or(20),
+            start: query.start.unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct CreateDirectMessage {
+    user_id: String,
+}
+
+async fn get_inbox(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<GetInboxQuery>,
+) -> CtxResult<Json<Vec<DiscussionUserView>>> {
+    let mut data = state
+        .db
+        .discussion_users
+        .get_inbox::<DiscussionUserView>(&auth_data.user_thing_id(), query.into(), false)
+        .await?;
+    let user = get_str_thing(&auth_data.user_id)?;
+    data.iter_mut().for_each(|v| v.hide_locked_content(&user));
+    Ok(Json(data))
+}
+
+async fn get_requests(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Query(query): Query<GetInboxQuery>,
+) -> CtxResult<Json<Vec<DiscussionUserView>>> {
+    let mut data = state
+        .db
+        .discussion_users
+        .get_inbox::<DiscussionUserView>(&auth_data.user_thing_id(), query.into(), true)
+        .await?;
+    let user = get_str_thing(&auth_data.user_id)?;
+    data.iter_mut().for_each(|v| v.hide_locked_content(&user));
+    Ok(Json(data))
+}
+
+async fn create_direct_message(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<CreateDirectMessage>,
+) -> CtxResult<Json<Discussion>> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    let other_user = get_str_thing(&data.user_id)?;
+    let disc = disc_service
+        .get_or_create_direct(&auth_data.user_thing_id(), &other_user.id.to_raw())
+        .await?;
+    Ok(Json(disc))
+}
+
+async fn accept_request(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(discussion_id): Path<String>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .accept_request(&auth_data.user_thing_id(), &discussion_id)
+        .await?;
+    Ok(())
+}
+
+async fn decline_request(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(discussion_id): Path<String>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .decline_request(&auth_data.user_thing_id(), &discussion_id)
+        .await?;
+    Ok(())
+}
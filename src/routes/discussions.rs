@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::access::base::permission::Permission;
 use crate::access::discussion::DiscussionAccess;
 use crate::entities::community::discussion_entity::{self, DiscussionType};
 use crate::entities::community::post_entity::PostType;
@@ -23,6 +24,7 @@ use axum::{Json, Router};
 use axum_typed_multipart::TypedMultipart;
 use discussion_entity::{Discussion, DiscussionDbService};
 
+use chrono::{DateTime, Utc};
 use middleware::error::{AppError, CtxResult};
 use middleware::mw_ctx::CtxState;
 use middleware::utils::extractor_utils::JsonOrFormValidated;
@@ -37,6 +39,7 @@ pub fn routes(upload_max_size_mb: u64) -> Router<Arc<CtxState>> {
         .route("/api/discussions/{discussion_id}", patch(update_discussion))
         .route("/api/discussions/{discussion_id}", get(get_discussion))
         .route("/api/discussions/{discussion_id}/alias", post(update_alias))
+        .route("/api/discussions/{discussion_id}/typing", post(typing))
         .route("/api/discussions/{discussion_id}/tasks", post(create_task))
         .route("/api/discussions/{discussion_id}/tasks", get(get_tasks))
         .route(
@@ -47,6 +50,26 @@ pub fn routes(upload_max_size_mb: u64) -> Router<Arc<CtxState>> {
             "/api/discussions/{discussion_id}/chat_users",
             delete(delete_discussion_users),
         )
+        .route(
+            "/api/discussions/{discussion_id}/moderators",
+            post(add_moderator),
+        )
+        .route(
+            "/api/discussions/{discussion_id}/moderators/{user_id}",
+            delete(remove_moderator),
+        )
+        .route(
+            "/api/discussions/{discussion_id}/muted_users",
+            post(mute_user),
+        )
+        .route(
+            "/api/discussions/{discussion_id}/muted_users/{user_id}",
+            delete(unmute_user),
+        )
+        .route(
+            "/api/discussions/{discussion_id}/transfer_ownership",
+            post(transfer_ownership),
+        )
         .route(
             "/api/discussions/{discussion_id}/posts",
             post(create_post).layer(DefaultBodyLimit::max(max_bytes_val)),
@@ -112,6 +135,8 @@ async fn get_discussions(
 #[derive(Debug, Deserialize, Validate)]
 struct DiscussionUsers {
     user_ids: Vec<String>,
+    // members added with an expiration lose the access after it
+    expires_at: Option<DateTime<Utc>>,
 }
 
 async fn add_discussion_users(
@@ -128,7 +153,12 @@ async fn add_discussion_users(
         &state.db.user_notifications,
     );
     disc_service
-        .add_chat_users(&auth_data.user_thing_id(), &discussion_id, data.user_ids)
+        .add_chat_users(
+            &auth_data.user_thing_id(),
+            &discussion_id,
+            data.user_ids,
+            data.expires_at,
+        )
         .await?;
     Ok(())
 }
@@ -152,6 +182,109 @@ async fn delete_discussion_users(
     Ok(())
 }
 
+#[derive(Debug, Deserialize, Validate)]
+struct DiscussionUser {
+    user_id: String,
+}
+
+async fn add_moderator(
+    auth_data: BearerAuth,
+    Path(discussion_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<DiscussionUser>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .add_moderator(&auth_data.user_thing_id(), &discussion_id, &data.user_id)
+        .await?;
+    Ok(())
+}
+
+async fn remove_moderator(
+    auth_data: BearerAuth,
+    Path((discussion_id, user_id)): Path<(String, String)>,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .remove_moderator(&auth_data.user_thing_id(), &discussion_id, &user_id)
+        .await?;
+    Ok(())
+}
+
+async fn mute_user(
+    auth_data: BearerAuth,
+    Path(discussion_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<DiscussionUser>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .set_muted(
+            &auth_data.user_thing_id(),
+            &discussion_id,
+            &data.user_id,
+            true,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn unmute_user(
+    auth_data: BearerAuth,
+    Path((discussion_id, user_id)): Path<(String, String)>,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .set_muted(&auth_data.user_thing_id(), &discussion_id, &user_id, false)
+        .await?;
+    Ok(())
+}
+
+async fn transfer_ownership(
+    auth_data: BearerAuth,
+    Path(discussion_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<DiscussionUser>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .transfer_ownership(&auth_data.user_thing_id(), &discussion_id, &data.user_id)
+        .await?;
+    Ok(())
+}
+
 async fn update_discussion(
     auth_data: BearerAuth,
     State(state): State<Arc<CtxState>>,
@@ -196,6 +329,25 @@ async fn update_alias(
     Ok(())
 }
 
+async fn typing(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(discussion_id): Path<String>,
+) -> CtxResult<()> {
+    let disc_service = DiscussionService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.access,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    );
+    disc_service
+        .typing(&auth_data.user_thing_id(), &discussion_id)
+        .await?;
+
+    Ok(())
+}
+
 async fn get_tasks(
     auth_data: BearerAuth,
     State(state): State<Arc<CtxState>>,
@@ -218,7 +370,9 @@ async fn get_tasks(
         .await?;
 
     if !DiscussionAccess::new(&disc).can_view(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(DiscussionAccess::new(&disc)
+            .denied(&user, Permission::View)
+            .into());
     }
 
     let tasks = match disc.r#type {
@@ -10,6 +10,7 @@ use askama_axum::Template;
 use axum::extract::{Path, State};
 use axum::routing::{delete, get, post};
 use axum::{Json, Router};
+use block_entity::{BlockDbService, BlockType};
 use follow_entity::FollowDbService;
 use local_user_entity::{LocalUser, LocalUserDbService};
 use middleware::ctx::Ctx;
@@ -18,7 +19,7 @@ use middleware::mw_ctx::CtxState;
 use middleware::utils::string_utils::get_string_thing;
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
-use user_auth::{follow_entity, local_user_entity};
+use user_auth::{block_entity, follow_entity, local_user_entity};
 
 pub fn routes() -> Router<Arc<CtxState>> {
     Router::new()
@@ -36,6 +37,12 @@ pub fn routes() -> Router<Arc<CtxState>> {
         .route("/api/following/{follow_user_id}", delete(unfollow_user))
         .route("/api/followers/{follow_user_id}", delete(remove_user))
         .route("/api/following/{follow_user_id}", get(is_following_user))
+        .route("/api/users/current/blocked", get(get_blocked_users))
+        .route("/api/users/current/muted", get(get_muted_users))
+        .route("/api/users/{user_id}/block", post(block_user))
+        .route("/api/users/{user_id}/block", delete(unblock_user))
+        .route("/api/users/{user_id}/mute", post(mute_user))
+        .route("/api/users/{user_id}/mute", delete(unmute_user))
 }
 
 #[derive(Template, Serialize, Deserialize, Debug)]
@@ -145,6 +152,20 @@ async fn follow_user(
         return Err(AppError::Forbidden.into());
     }
 
+    let is_blocked = BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .is_blocked_between(
+        current_user.id.as_ref().unwrap().clone(),
+        following_user.id.as_ref().unwrap().clone(),
+    )
+    .await?;
+
+    if is_blocked {
+        return Err(AppError::Forbidden.into());
+    }
+
     let follow_db_service = FollowDbService {
         db: &ctx_state.db.client,
         ctx: &auth_data.ctx,
@@ -238,3 +259,151 @@ async fn is_following_user(
     .await?;
     Ok(Json(is_following))
 }
+
+async fn get_blocked_users(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+) -> CtxResult<Json<Vec<UserItemView>>> {
+    get_users_by_block_type(&ctx_state, &auth_data, BlockType::Block).await
+}
+
+async fn get_muted_users(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+) -> CtxResult<Json<Vec<UserItemView>>> {
+    get_users_by_block_type(&ctx_state, &auth_data, BlockType::Mute).await
+}
+
+async fn block_user(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    Path(user_id): Path<String>,
+) -> CtxResult<()> {
+    let (current_user, target) = get_block_users(&ctx_state, &auth_data, &user_id).await?;
+
+    BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .set(current_user.clone(), target.clone(), BlockType::Block)
+    .await?;
+
+    let follow_db_service = FollowDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    };
+    follow_db_service
+        .remove_follow(current_user.clone(), target.clone())
+        .await?;
+    follow_db_service
+        .remove_follow(target, current_user)
+        .await?;
+
+    Ok(())
+}
+
+async fn unblock_user(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    Path(user_id): Path<String>,
+) -> CtxResult<()> {
+    let (current_user, target) = get_block_users(&ctx_state, &auth_data, &user_id).await?;
+    BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .remove(current_user, target, BlockType::Block)
+    .await?;
+    Ok(())
+}
+
+async fn mute_user(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    Path(user_id): Path<String>,
+) -> CtxResult<()> {
+    let (current_user, target) = get_block_users(&ctx_state, &auth_data, &user_id).await?;
+    let block_db_service = BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    };
+
+    // a blocked user is already muted
+    let blocked = block_db_service
+        .get_blocking_users(
+            target.clone(),
+            vec![current_user.clone()],
+            vec![BlockType::Block],
+        )
+        .await?;
+    if !blocked.is_empty() {
+        return Ok(());
+    }
+
+    block_db_service
+        .set(current_user, target, BlockType::Mute)
+        .await?;
+    Ok(())
+}
+
+async fn unmute_user(
+    State(ctx_state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    Path(user_id): Path<String>,
+) -> CtxResult<()> {
+    let (current_user, target) = get_block_users(&ctx_state, &auth_data, &user_id).await?;
+    BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .remove(current_user, target, BlockType::Mute)
+    .await?;
+    Ok(())
+}
+
+async fn get_block_users(
+    ctx_state: &CtxState,
+    auth_data: &BearerAuth,
+    user_id: &str,
+) -> CtxResult<(Thing, Thing)> {
+    let local_user_db_service = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    };
+    let current_user = local_user_db_service.get_ctx_user_thing().await?;
+    let target = get_str_thing(user_id)?;
+    local_user_db_service
+        .exists_by_id(&target.id.to_raw())
+        .await?;
+
+    if current_user == target {
+        return Err(AppError::Forbidden.into());
+    }
+
+    Ok((current_user, target))
+}
+
+async fn get_users_by_block_type(
+    ctx_state: &CtxState,
+    auth_data: &BearerAuth,
+    r#type: BlockType,
+) -> CtxResult<Json<Vec<UserItemView>>> {
+    let user_id = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let users = BlockDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_users(user_id, r#type)
+    .await?
+    .into_iter()
+    .map(UserItemView::from)
+    .collect();
+
+    Ok(Json(users))
+}
@@ -1,12 +1,16 @@
+pub mod access;
 pub mod admin;
 pub mod auth_routes;
 pub mod community;
+pub mod direct_messages;
 pub mod discussions;
 pub mod editor_tags;
 pub mod follows;
 pub mod notifications;
 pub mod posts;
 pub mod reply;
+pub mod reports;
+pub mod subscriptions;
 pub mod swagger;
 pub mod tags;
 pub mod tasks;
@@ -15,3 +19,4 @@ pub mod user_otp;
 pub mod users;
 pub mod wallet;
 pub mod webhooks;
+pub mod websocket;
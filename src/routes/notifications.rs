@@ -1,3 +1,8 @@
+use crate::entities::notification_settings::{
+    DigestMode, DisabledEvent, NotificationChannel, NotificationSettings,
+    NotificationSettingsDbService, QuietHours,
+};
+use crate::entities::push_device::PushDevice;
 use crate::entities::user_notification::UserNotificationEvent;
 use crate::interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface;
 use crate::interfaces::repositories::user_notifications::{
@@ -5,38 +10,75 @@ use crate::interfaces::repositories::user_notifications::{
 };
 use crate::middleware;
 use crate::middleware::bearer_auth::BearerAuth;
-use crate::middleware::mw_ctx::AppEventType;
 use crate::middleware::mw_ctx::CtxState;
 use crate::middleware::utils::db_utils::QryOrder;
 use crate::models::view::notification::UserNotificationView;
+use crate::services::push_service::{PushService, RegisterDeviceInput};
+use crate::utils::sse_buffer::{SseCursor, SseEvent};
 use crate::utils::user_presence_guard::UserPresenceGuard;
 use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive};
-use axum::response::Sse;
-use axum::routing::{get, post};
+use axum::response::{Html, Sse};
+use axum::routing::{delete, get, post, put};
 use axum::{Json, Router};
 use axum_extra::extract::Query as ExQuery;
 use chrono::{DateTime, Utc};
 use futures::{stream, Stream};
 use middleware::ctx::Ctx;
 use serde::Deserialize;
-use serde_json::json;
 use std::convert::Infallible;
 use std::sync::Arc;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::BroadcastStream;
+use validator::Validate;
 
 use crate::{
     entities::user_auth::local_user_entity::LocalUserDbService, middleware::error::CtxResult,
 };
 use futures::StreamExt;
 
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
 pub fn routes() -> Router<Arc<CtxState>> {
     Router::new()
         .route("/api/notifications", get(get_notifications))
+        .route("/api/notifications", delete(delete_notifications))
         .route("/api/notifications/read", post(read_all))
+        .route("/api/notifications/archive", post(archive_notifications))
         .route("/api/notifications/sse", get(sse))
         .route("/api/notifications/count", get(get_count))
         .route("/api/notifications/{notification_id}/read", post(read))
+        .route(
+            "/api/notifications/{notification_id}",
+            delete(delete_notification),
+        )
+        .route(
+            "/api/notifications/{notification_id}/archive",
+            post(archive),
+        )
+        .route(
+            "/api/notifications/{notification_id}/archive",
+            delete(unarchive),
+        )
+        .route(
+            "/api/users/current/notification_settings",
+            get(get_settings),
+        )
+        .route(
+            "/api/notifications/email/unsubscribe",
+            get(unsubscribe_email),
+        )
+        .route("/api/users/current/devices", get(get_devices))
+        .route("/api/users/current/devices", post(register_device))
+        .route(
+            "/api/users/current/devices/{token}",
+            delete(unregister_device),
+        )
+        .route(
+            "/api/users/current/notification_settings",
+            put(update_settings),
+        )
 }
 
 async fn read(
@@ -73,12 +115,88 @@ async fn read_all(State(state): State<Arc<CtxState>>, ctx: Ctx) -> CtxResult<()>
     Ok(())
 }
 
+async fn delete_notification(
+    Path(notification_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+) -> CtxResult<()> {
+    state
+        .db
+        .user_notifications
+        .delete(&vec![notification_id], &auth_data.user_thing_id())
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationIdsQuery {
+    ids: Vec<String>,
+}
+
+async fn delete_notifications(
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    ExQuery(query): ExQuery<NotificationIdsQuery>,
+) -> CtxResult<()> {
+    state
+        .db
+        .user_notifications
+        .delete(&query.ids, &auth_data.user_thing_id())
+        .await?;
+
+    Ok(())
+}
+
+async fn archive(
+    Path(notification_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+) -> CtxResult<()> {
+    state
+        .db
+        .user_notifications
+        .archive(&vec![notification_id], &auth_data.user_thing_id(), true)
+        .await?;
+
+    Ok(())
+}
+
+async fn unarchive(
+    Path(notification_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+) -> CtxResult<()> {
+    state
+        .db
+        .user_notifications
+        .archive(&vec![notification_id], &auth_data.user_thing_id(), false)
+        .await?;
+
+    Ok(())
+}
+
+async fn archive_notifications(
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    ExQuery(query): ExQuery<NotificationIdsQuery>,
+) -> CtxResult<()> {
+    state
+        .db
+        .user_notifications
+        .archive(&query.ids, &auth_data.user_thing_id(), true)
+        .await?;
+
+    Ok(())
+}
+
 #[derive(Debug, Deserialize)]
 struct GetNotificationsQuery {
     filter_by_types: Option<Vec<UserNotificationEvent>>,
     start: Option<DateTime<Utc>>,
     count: Option<u8>,
     is_read: Option<bool>,
+    is_archived: Option<bool>,
     order_dir: Option<QryOrder>,
 }
 
@@ -104,6 +222,7 @@ async fn get_notifications(
                 start: query.start.unwrap_or(Utc::now()),
                 order_dir: query.order_dir.map_or(QryOrder::DESC, |v| v),
                 is_read: query.is_read,
+                is_archived: query.is_archived.unwrap_or(false),
                 filter_by_types: query.filter_by_types.map(|e| {
                     e.iter()
                         .map(|e| e.as_str().to_string())
@@ -115,6 +234,144 @@ async fn get_notifications(
     Ok(Json(notifications))
 }
 
+async fn get_settings(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<NotificationSettings>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let settings = NotificationSettingsDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_by_user(&user)
+    .await?;
+
+    Ok(Json(settings))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct NotificationSettingsInput {
+    #[serde(default)]
+    disabled_channels: Vec<NotificationChannel>,
+    #[serde(default)]
+    disabled_events: Vec<DisabledEvent>,
+    #[validate(nested)]
+    quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    digest: DigestMode,
+}
+
+async fn update_settings(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(data): Json<NotificationSettingsInput>,
+) -> CtxResult<Json<NotificationSettings>> {
+    data.validate()?;
+
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let settings_repository = NotificationSettingsDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    };
+    // the time of the last digest is kept to not repeat its notifications
+    let current = settings_repository.get_by_user(&user).await?;
+    let settings = settings_repository
+        .upsert(NotificationSettings {
+            disabled_channels: data.disabled_channels,
+            disabled_events: data.disabled_events,
+            quiet_hours: data.quiet_hours,
+            digest: data.digest,
+            ..current
+        })
+        .await?;
+
+    Ok(Json(settings))
+}
+
+#[derive(Debug, Deserialize)]
+struct UnsubscribeQuery {
+    token: String,
+}
+
+async fn unsubscribe_email(
+    State(state): State<Arc<CtxState>>,
+    ctx: Ctx,
+    Query(query): Query<UnsubscribeQuery>,
+) -> CtxResult<Html<&'static str>> {
+    NotificationSettingsDbService {
+        db: &state.db.client,
+        ctx: &ctx,
+    }
+    .unsubscribe_email(query.token)
+    .await?;
+
+    Ok(Html("You are unsubscribed from the notification emails."))
+}
+
+async fn get_devices(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<PushDevice>>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let devices = PushService::new(&state, &auth_data.ctx)
+        .get_devices(user)
+        .await?;
+    Ok(Json(devices))
+}
+
+async fn register_device(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(data): Json<RegisterDeviceInput>,
+) -> CtxResult<Json<PushDevice>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let device = PushService::new(&state, &auth_data.ctx)
+        .register_device(user, data)
+        .await?;
+    Ok(Json(device))
+}
+
+async fn unregister_device(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(token): Path<String>,
+) -> CtxResult<()> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    PushService::new(&state, &auth_data.ctx)
+        .unregister_device(user, token)
+        .await
+}
+
 #[derive(Debug, Deserialize)]
 struct GetCountQuery {
     is_read: Option<bool>,
@@ -144,6 +401,7 @@ async fn get_count(
 async fn sse(
     State(state): State<Arc<CtxState>>,
     auth_data: BearerAuth,
+    headers: HeaderMap,
 ) -> CtxResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
     let user = LocalUserDbService {
         db: &state.db.client,
@@ -155,76 +413,96 @@ async fn sse(
     let user_id = user.id.to_raw();
     let indicator = Arc::new(UserPresenceGuard::new(state.clone(), user_id.clone()));
 
-    let get_unread_count_ev = {
-        let state = state.clone();
-        let user_id = user_id.clone();
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
 
-        move || async move {
-            let count = state
-                .db
-                .discussion_users
-                .get_count_of_unread(&user_id)
-                .await
-                .unwrap_or_default();
-
-            Event::default()
-                .event("UnreadDiscussionsCount")
-                .data(count.to_string())
-        }
+    // the events published while subscribing are both replayed and received, the ids skip the duplicates
+    let start_id = match last_event_id {
+        Some(id) => id,
+        None => state.sse_buffer.last_id(&user_id).await?,
     };
+    let rx = state.sse_buffer.subscribe();
+    let replayed = state.sse_buffer.replay_or_resync(&user_id, start_id).await;
+    let cursor = Arc::new(SseCursor::new(start_id));
 
     let initial = stream::once({
-        let get_unread_count_ev = get_unread_count_ev.clone();
-        async move { Ok(get_unread_count_ev().await) }
-    });
-
-    let rx = state.event_sender.subscribe();
-    let broadcast_stream = BroadcastStream::new(rx).filter_map(move |msg| {
-        let indicator = indicator.clone();
+        let state = state.clone();
         let user_id = user_id.clone();
-        let get_unread_count_ev = get_unread_count_ev.clone();
-
+        let cursor = cursor.clone();
         async move {
-            let _tracker = indicator.clone();
-            match msg {
-                Err(_) => None,
-                Ok(msg) => match msg.event {
-                    AppEventType::UserNotificationEvent(data)
-                        if msg.receivers.contains(&user_id) =>
-                    {
-                        Some(Ok(Event::default()
-                            .event("Notifications")
-                            .data(json!(data).to_string())))
-                    }
-                    AppEventType::UserStatus(data) => {
-                        Some(Ok(Event::default().event("UserStatus").data(
-                            json!({ "is_online": data.is_online , "user_id": msg.user_id.clone() })
-                                .to_string(),
-                        )))
-                    }
+            let mut events = vec![Ok(get_unread_count_event(&state, &user_id).await)];
+            events.extend(to_events(&state, &user_id, &cursor, replayed).await);
+            stream::iter(events)
+        }
+    })
+    .flatten();
 
-                    AppEventType::UpdateDiscussionsUsers(_users)
-                        if msg.receivers.contains(&user_id) =>
-                    {
-                        Some(Ok(get_unread_count_ev().await))
-                    }
+    let broadcast_stream = BroadcastStream::new(rx)
+        .then(move |msg| {
+            let indicator = indicator.clone();
+            let state = state.clone();
+            let user_id = user_id.clone();
+            let cursor = cursor.clone();
 
-                    AppEventType::UpdatedUserBalance if msg.receivers.contains(&user_id) => {
-                        Some(Ok(Event::default().event("UpdatedUserBalance")))
+            async move {
+                let _tracker = indicator.clone();
+                let events = match msg {
+                    Ok(event) if event.is_for(&user_id) => vec![event],
+                    Ok(_) => vec![],
+                    Err(BroadcastStreamRecvError::Lagged(_)) => {
+                        state
+                            .sse_buffer
+                            .replay_or_resync(&user_id, cursor.last_id())
+                            .await
                     }
-
-                    AppEventType::DiscussionPostAdded if msg.receivers.contains(&user_id) => {
-                        Some(Ok(Event::default()
-                            .event("DiscussionPostAdded")
-                            .data(msg.content.unwrap())))
-                    }
-
-                    _ => None,
-                },
+                };
+                stream::iter(to_events(&state, &user_id, &cursor, events).await)
             }
-        }
-    });
+        })
+        .flatten();
 
     let stream = initial.chain(broadcast_stream);
     Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
+
+async fn to_events(
+    state: &CtxState,
+    user_id: &str,
+    cursor: &SseCursor,
+    events: Vec<Arc<SseEvent>>,
+) -> Vec<Result<Event, Infallible>> {
+    let mut result = vec![];
+    for event in events {
+        if !cursor.accept(&event) {
+            continue;
+        }
+
+        let mut sse_event = match event.name.as_str() {
+            "UnreadDiscussionsCount" => get_unread_count_event(state, user_id).await,
+            name => Event::default().event(name),
+        };
+        if let Some(ref data) = event.data {
+            sse_event = sse_event.data(data);
+        }
+        if let Some(id) = event.id {
+            sse_event = sse_event.id(id.to_string());
+        }
+        result.push(Ok(sse_event));
+    }
+    result
+}
+
+async fn get_unread_count_event(state: &CtxState, user_id: &str) -> Event {
+    let count = state
+        .db
+        .discussion_users
+        .get_count_of_unread(user_id)
+        .await
+        .unwrap_or_default();
+
+    Event::default()
+        .event("UnreadDiscussionsCount")
+        .data(count.to_string())
+}
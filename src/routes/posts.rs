@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use axum::extract::{Path, Query, State};
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, patch, post};
 use axum::{Json, Router};
+use chrono::{DateTime, Utc};
 use local_user_entity::LocalUserDbService;
 use middleware::ctx::Ctx;
 use middleware::error::CtxResult;
@@ -10,8 +11,10 @@ use middleware::mw_ctx::CtxState;
 use post_entity::{Post, PostDbService};
 use serde::{Deserialize, Serialize};
 
+use crate::access::base::permission::Permission;
 use crate::access::post::PostAccess;
 use crate::entities::community::post_entity::{self};
+use crate::entities::edit_history::EditHistory;
 use crate::entities::task_request::TaskRequestEntity;
 use crate::entities::user_auth::local_user_entity;
 use crate::interfaces::repositories::task_request_ifce::TaskRequestRepositoryInterface;
@@ -19,6 +22,7 @@ use crate::interfaces::repositories::task_request_ifce::TaskRequestRepositoryInt
 use crate::middleware;
 use crate::middleware::bearer_auth::BearerAuth;
 use crate::middleware::error::AppError;
+use crate::middleware::request_meta::RequestMeta;
 use crate::middleware::utils::db_utils::{Pagination, QryOrder};
 use crate::middleware::utils::extractor_utils::JsonOrFormValidated;
 use crate::models::view::access::PostAccessView;
@@ -28,8 +32,9 @@ use crate::models::view::reply::ReplyView;
 use crate::models::view::task::TaskRequestView;
 use crate::models::view::user::UserView;
 use crate::routes::reply::ReplyInput;
+use crate::services::content_filter_service::ContentFilterService;
 use crate::services::notification_service::NotificationService;
-use crate::services::post_service::{PostLikeData, PostService};
+use crate::services::post_service::{PostLikeData, PostService, PostUpdateInput};
 use crate::services::post_user_service::PostUserService;
 use crate::services::task_service::{TaskRequestInput, TaskService};
 
@@ -38,6 +43,8 @@ pub fn routes() -> Router<Arc<CtxState>> {
         .route("/api/posts", get(get_posts))
         .route("/api/posts/{post_id}", get(get_post))
         .route("/api/posts/{post_id}", delete(delete_post))
+        .route("/api/posts/{post_id}", patch(update_post))
+        .route("/api/posts/{post_id}/history", get(get_post_history))
         .route("/api/posts/{post_id}/tasks", post(create_task))
         .route("/api/posts/{post_id}/tasks", get(get_post_tasks))
         .route("/api/posts/{post_id}/like", post(like))
@@ -49,6 +56,9 @@ pub fn routes() -> Router<Arc<CtxState>> {
         .route("/api/posts/{post_id}/add_users", post(add_members))
         .route("/api/posts/{post_id}/remove_users", post(remove_members))
         .route("/api/posts/{post_id}/users", get(get_members))
+        .route("/api/posts/{post_id}/buy", post(buy_post))
+        .route("/api/posts/{post_id}/pin", post(pin_post))
+        .route("/api/posts/{post_id}/pin", delete(unpin_post))
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,7 +126,9 @@ async fn get_post_tasks(
         .await?;
 
     if !PostAccess::new(&post).can_view(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::View)
+            .into());
     }
 
     let tasks = state
@@ -228,8 +240,14 @@ async fn get_replies(
         .get_view_by_id::<PostAccessView>(&post_id, None)
         .await?;
 
+    if post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: post_id }.into());
+    }
+
     if !PostAccess::new(&post).can_view(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::View)
+            .into());
     }
 
     let replies = state
@@ -270,19 +288,30 @@ async fn create_reply(
         .get_view_by_id::<PostAccessView>(&post_id, None)
         .await?;
 
+    if post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: post_id }.into());
+    }
+
     if !PostAccess::new(&post).can_create_reply(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::CreateReply)
+            .into());
     }
 
+    let content_filter = ContentFilterService::new(&state.db.client, &auth_data.ctx);
+    let content = content_filter.filter(&reply_input.content).await?;
     let reply = state
         .db
         .replies
         .create(
             post.id.clone(),
             user.id.as_ref().unwrap().id.to_raw().as_ref(),
-            &reply_input.content,
+            &content.text,
         )
         .await?;
+    content_filter
+        .flag(&reply.id, content.flagged_words)
+        .await?;
 
     let n_service = NotificationService::new(
         &state.db.client,
@@ -300,6 +329,7 @@ async fn create_reply(
         content: reply.content,
         created_at: reply.created_at,
         updated_at: reply.updated_at,
+        edited_at: reply.edited_at,
         liked_by: None,
         replies_nr: reply.replies_nr,
         belongs_to: reply.belongs_to,
@@ -311,6 +341,7 @@ async fn create_reply(
 #[derive(Debug, Deserialize, Serialize)]
 struct PostMember {
     user_ids: Vec<String>,
+    expires_at: Option<DateTime<Utc>>,
 }
 
 async fn add_members(
@@ -330,7 +361,12 @@ async fn add_members(
         &ctx_state.db.access,
         &ctx_state.db.discussion_users,
     )
-    .add_members(&auth_data.user_thing_id(), &post_id, body.user_ids)
+    .add_members(
+        &auth_data.user_thing_id(),
+        &post_id,
+        body.user_ids,
+        body.expires_at,
+    )
     .await?;
 
     Ok(())
@@ -447,8 +483,73 @@ async fn get_post(
     Ok(Json(post_view))
 }
 
+async fn buy_post(
+    auth_data: BearerAuth,
+    Path(post_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<FullPostView>> {
+    let post_view = PostService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        state.file_storage.clone(),
+        &state.db.tags,
+        &state.db.likes,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .buy(&auth_data.user_thing_id(), &post_id, state.paid_post_fee)
+    .await?;
+
+    Ok(Json(post_view))
+}
+
+async fn pin_post(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(post_id): Path<String>,
+) -> CtxResult<()> {
+    PostService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        state.file_storage.clone(),
+        &state.db.tags,
+        &state.db.likes,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .pin(&auth_data.user_thing_id(), &post_id, true)
+    .await?;
+    Ok(())
+}
+
+async fn unpin_post(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(post_id): Path<String>,
+) -> CtxResult<()> {
+    PostService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        state.file_storage.clone(),
+        &state.db.tags,
+        &state.db.likes,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .pin(&auth_data.user_thing_id(), &post_id, false)
+    .await?;
+    Ok(())
+}
+
 async fn delete_post(
     auth_data: BearerAuth,
+    meta: RequestMeta,
     State(state): State<Arc<CtxState>>,
     Path(post_id): Path<String>,
 ) -> CtxResult<()> {
@@ -463,7 +564,63 @@ async fn delete_post(
         &state.db.access,
         &state.db.discussion_users,
     )
-    .delete_post(&auth_data.user_thing_id(), &post_id)
+    .delete_post(&auth_data.user_thing_id(), &post_id, &meta)
     .await?;
     Ok(())
 }
+
+async fn update_post(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(post_id): Path<String>,
+    JsonOrFormValidated(data): JsonOrFormValidated<PostUpdateInput>,
+) -> CtxResult<Json<PostView>> {
+    let post = PostService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        state.file_storage.clone(),
+        &state.db.tags,
+        &state.db.likes,
+        &state.db.access,
+        &state.db.discussion_users,
+    )
+    .update(
+        &auth_data.user_thing_id(),
+        &post_id,
+        data,
+        state.edit_window,
+    )
+    .await?;
+    Ok(Json(post))
+}
+
+async fn get_post_history(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(post_id): Path<String>,
+) -> CtxResult<Json<Vec<EditHistory>>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_by_id(&auth_data.user_thing_id())
+    .await?;
+
+    let post = PostDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_view_by_id::<PostAccessView>(&post_id, None)
+    .await?;
+
+    if !PostAccess::new(&post).can_view_content(&user) {
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::ViewContent)
+            .into());
+    }
+
+    let history = state.db.edit_history.get(post.id).await?;
+    Ok(Json(history))
+}
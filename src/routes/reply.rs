@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
+use crate::access::base::permission::Permission;
+use crate::access::base::role::Role;
 use crate::access::post::PostAccess;
 use crate::database::table_names::REPLY_TABLE_NAME;
 use crate::entities::community::post_entity::PostDbService;
+use crate::entities::edit_history::EditHistory;
 use crate::entities::user_auth::local_user_entity;
 use crate::interfaces::repositories::like::LikesRepositoryInterface;
 use crate::middleware;
@@ -14,16 +17,19 @@ use crate::middleware::utils::string_utils::get_str_thing;
 use crate::models::view::access::PostAccessView;
 use crate::models::view::reply::ReplyView;
 use crate::models::view::user::UserView;
+use crate::services::content_filter_service::ContentFilterService;
 use crate::services::notification_service::NotificationService;
 use crate::services::post_service::PostLikeData;
 use crate::utils::validate_utils::trim_string;
 use axum::extract::{Path, Query, State};
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, patch, post};
 use axum::{Json, Router};
+use chrono::Utc;
 use local_user_entity::LocalUserDbService;
 use middleware::error::CtxResult;
 use middleware::mw_ctx::CtxState;
 use serde::{Deserialize, Serialize};
+use surrealdb::sql::Thing;
 use validator::Validate;
 
 pub fn routes() -> Router<Arc<CtxState>> {
@@ -32,6 +38,9 @@ pub fn routes() -> Router<Arc<CtxState>> {
         .route("/api/comments/{comment_id}/replies", post(create_reply))
         .route("/api/comments/{comment_id}/replies", get(get_replies))
         .route("/api/replies/{reply_id}/like", post(like))
+        .route("/api/replies/{reply_id}", delete(delete_reply))
+        .route("/api/replies/{reply_id}", patch(update_reply))
+        .route("/api/replies/{reply_id}/history", get(get_reply_history))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -80,11 +89,17 @@ async fn like(
         .get_view_by_id::<PostAccessView>(&belongs_to.to_raw(), None)
         .await?;
 
+    if reply.hidden || post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: reply_id }.into());
+    }
+
     let likes = body.count.unwrap_or(1);
     let by_credits = body.count.is_some();
 
     if !PostAccess::new(&post).can_like(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::Like)
+            .into());
     }
 
     if by_credits && user.credits < likes as u64 {
@@ -118,6 +133,176 @@ async fn like(
     Ok(Json(LikeResponse { likes_count: count }))
 }
 
+async fn delete_reply(
+    auth_data: BearerAuth,
+    Path(reply_id): Path<String>,
+    State(ctx_state): State<Arc<CtxState>>,
+) -> CtxResult<()> {
+    let user = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user()
+    .await?;
+
+    let reply_thing = get_str_thing(&reply_id)?;
+
+    let reply = ctx_state
+        .db
+        .replies
+        .get_by_id(&reply_thing.id.to_raw())
+        .await?;
+
+    let mut belongs_to = reply.belongs_to.clone();
+
+    if belongs_to.tb == REPLY_TABLE_NAME {
+        belongs_to = ctx_state
+            .db
+            .replies
+            .get_by_id(&belongs_to.id.to_raw())
+            .await?
+            .belongs_to;
+    }
+
+    let post = PostDbService {
+        db: &ctx_state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_view_by_id::<PostAccessView>(&belongs_to.to_raw(), None)
+    .await?;
+
+    // authors remove their own replies, moderators remove any reply of the discussion
+    if &reply.created_by != user.id.as_ref().unwrap()
+        && !PostAccess::new(&post).can_delete_reply(&user)
+    {
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::DeleteReply)
+            .into());
+    }
+
+    ctx_state.db.replies.delete(&reply.id.id.to_raw()).await?;
+
+    Ok(())
+}
+
+/// Authors edit their replies within the edit window, the editors of the discussion at any time
+async fn update_reply(
+    auth_data: BearerAuth,
+    Path(reply_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(reply_input): JsonOrFormValidated<ReplyInput>,
+) -> CtxResult<Json<ReplyView>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_by_id(&auth_data.user_thing_id())
+    .await?;
+    let user_thing = user.id.as_ref().unwrap();
+
+    let reply_thing = get_str_thing(&reply_id)?;
+    let reply = state.db.replies.get_by_id(&reply_thing.id.to_raw()).await?;
+    let post = get_reply_post(&state, &auth_data, &reply.belongs_to).await?;
+
+    if !PostAccess::new(&post).can_view(&user) {
+        return Err(AppError::Forbidden.into());
+    }
+
+    // the post authors can't edit the replies of the others, only the editors of the discussion
+    let is_editor = post.discussion.get_user_role(user_thing) == Some(Role::Editor.to_string());
+    if &reply.created_by != user_thing && !(is_editor && PostAccess::new(&post).can_edit(&user)) {
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::Edit)
+            .into());
+    }
+
+    if !is_editor && reply.created_at + state.edit_window < Utc::now() {
+        return Err(AppError::Generic {
+            description: "The edit window of the reply has expired".to_string(),
+        }
+        .into());
+    }
+
+    let content_filter = ContentFilterService::new(&state.db.client, &auth_data.ctx);
+    let content = content_filter.filter(&reply_input.content).await?;
+    state
+        .db
+        .replies
+        .update(&reply.id.id.to_raw(), &content.text, user_thing.clone())
+        .await?;
+    content_filter
+        .flag(&reply.id, content.flagged_words)
+        .await?;
+
+    let reply_view = state
+        .db
+        .replies
+        .get_view_by_id(&user_thing.id.to_raw(), &reply.id.id.to_raw())
+        .await?;
+
+    NotificationService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+    )
+    .on_discussion_reply_updated(&user_thing.id.to_raw(), &post, &reply_view)
+    .await?;
+
+    Ok(Json(reply_view))
+}
+
+async fn get_reply_history(
+    auth_data: BearerAuth,
+    Path(reply_id): Path<String>,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<EditHistory>>> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_by_id(&auth_data.user_thing_id())
+    .await?;
+
+    let reply_thing = get_str_thing(&reply_id)?;
+    let reply = state.db.replies.get_by_id(&reply_thing.id.to_raw()).await?;
+    let post = get_reply_post(&state, &auth_data, &reply.belongs_to).await?;
+
+    if !PostAccess::new(&post).can_view(&user) {
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::View)
+            .into());
+    }
+
+    let history = state.db.edit_history.get(reply.id).await?;
+    Ok(Json(history))
+}
+
+// the replies belong to the post or to the reply of the post
+async fn get_reply_post(
+    state: &CtxState,
+    auth_data: &BearerAuth,
+    belongs_to: &Thing,
+) -> CtxResult<PostAccessView> {
+    let post_id = if belongs_to.tb == REPLY_TABLE_NAME {
+        state
+            .db
+            .replies
+            .get_by_id(&belongs_to.id.to_raw())
+            .await?
+            .belongs_to
+    } else {
+        belongs_to.clone()
+    };
+
+    PostDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_view_by_id::<PostAccessView>(&post_id.to_raw(), None)
+    .await
+}
+
 async fn unlike(
     auth_data: BearerAuth,
     Path(reply_id): Path<String>,
@@ -158,8 +343,14 @@ async fn unlike(
         .get_view_by_id::<PostAccessView>(&belongs_to.to_raw(), None)
         .await?;
 
+    if reply.hidden || post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: reply_id }.into());
+    }
+
     if !PostAccess::new(&post).can_like(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::Like)
+            .into());
     }
 
     let count = ctx_state
@@ -205,19 +396,30 @@ async fn create_reply(
         .get_view_by_id::<PostAccessView>(&comment.belongs_to.to_raw(), None)
         .await?;
 
+    if comment.hidden || post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: comment_id }.into());
+    }
+
     if !PostAccess::new(&post).can_create_reply_for_reply(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::CreateReplyForReply)
+            .into());
     }
 
+    let content_filter = ContentFilterService::new(&state.db.client, &auth_data.ctx);
+    let content = content_filter.filter(&reply_input.content).await?;
     let reply = state
         .db
         .replies
         .create(
             comment.id,
             user.id.as_ref().unwrap().id.to_raw().as_ref(),
-            &reply_input.content,
+            &content.text,
         )
         .await?;
+    content_filter
+        .flag(&reply.id, content.flagged_words)
+        .await?;
 
     let reply_view = ReplyView {
         id: reply.id,
@@ -226,6 +428,7 @@ async fn create_reply(
         content: reply.content,
         created_at: reply.created_at,
         updated_at: reply.updated_at,
+        edited_at: reply.edited_at,
         liked_by: None,
         replies_nr: reply.replies_nr,
         belongs_to: reply.belongs_to,
@@ -268,8 +471,14 @@ async fn get_replies(
         .get_view_by_id::<PostAccessView>(&comment.belongs_to.to_raw(), None)
         .await?;
 
+    if comment.hidden || post.is_hidden() {
+        return Err(AppError::EntityFailIdNotFound { ident: comment_id }.into());
+    }
+
     if !PostAccess::new(&post).can_view(&user) {
-        return Err(AppError::Forbidden.into());
+        return Err(PostAccess::new(&post)
+            .denied(&user, Permission::View)
+            .into());
     }
 
     let replies = state
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::post, Json, Router};
+
+use crate::{
+    entities::report::Report,
+    middleware::{
+        bearer_auth::BearerAuth, error::CtxResult, mw_ctx::CtxState,
+        utils::extractor_utils::JsonOrFormValidated,
+    },
+    services::report_service::{CreateReport, ReportService},
+};
+
+pub fn routes() -> Router<Arc<CtxState>> {
+    Router::new().route("/api/reports", post(create_report))
+}
+
+async fn create_report(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    JsonOrFormValidated(data): JsonOrFormValidated<CreateReport>,
+) -> CtxResult<Json<Report>> {
+    let report = ReportService::new(
+        &state,
+        &auth_data.ctx,
+        &state.db.reports,
+        &state.db.discussion_users,
+        &state.db.user_notifications,
+    )
+    .create(&auth_data.user_thing_id(), data)
+    .await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+
+use crate::{
+    entities::subscription::{Subscription, SubscriptionTier},
+    middleware::{
+        bearer_auth::BearerAuth, error::CtxResult, mw_ctx::CtxState,
+        utils::string_utils::get_str_thing,
+    },
+    services::subscription_service::{SubscriptionService, SubscriptionTierInput},
+};
+
+pub fn routes() -> Router<Arc<CtxState>> {
+    Router::new()
+        .route("/api/subscriptions", get(get_subscriptions))
+        .route("/api/subscriptions/subscribers", get(get_subscribers))
+        .route(
+            "/api/subscriptions/{subscription_id}/cancel",
+            post(cancel_subscription),
+        )
+        .route("/api/subscriptions/tiers", post(create_tier))
+        .route("/api/subscriptions/tiers/{tier_id}", delete(disable_tier))
+        .route(
+            "/api/subscriptions/tiers/{tier_id}/subscribe",
+            post(subscribe),
+        )
+        .route(
+            "/api/users/{user_id}/subscription_tiers",
+            get(get_user_tiers),
+        )
+}
+
+async fn create_tier(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Json(data): Json<SubscriptionTierInput>,
+) -> CtxResult<Json<SubscriptionTier>> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let tier = service
+        .create_tier(&auth_data.user_thing_id(), data)
+        .await?;
+    Ok(Json(tier))
+}
+
+async fn disable_tier(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(tier_id): Path<String>,
+) -> CtxResult<()> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    service
+        .disable_tier(&auth_data.user_thing_id(), &tier_id)
+        .await?;
+    Ok(())
+}
+
+async fn get_user_tiers(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(user_id): Path<String>,
+) -> CtxResult<Json<Vec<SubscriptionTier>>> {
+    let user_thing = get_str_thing(&user_id)?;
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let tiers = service.get_tiers(&user_thing.id.to_raw()).await?;
+    Ok(Json(tiers))
+}
+
+async fn subscribe(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(tier_id): Path<String>,
+) -> CtxResult<Json<Subscription>> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let subscription = service
+        .subscribe(&auth_data.user_thing_id(), &tier_id)
+        .await?;
+    Ok(Json(subscription))
+}
+
+async fn cancel_subscription(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+    Path(subscription_id): Path<String>,
+) -> CtxResult<Json<Subscription>> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let subscription = service
+        .cancel(&auth_data.user_thing_id(), &subscription_id)
+        .await?;
+    Ok(Json(subscription))
+}
+
+async fn get_subscriptions(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<Subscription>>> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let subscriptions = service
+        .get_subscriptions(&auth_data.user_thing_id())
+        .await?;
+    Ok(Json(subscriptions))
+}
+
+async fn get_subscribers(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<Subscription>>> {
+    let service = SubscriptionService::new(
+        &state.db.client,
+        &auth_data.ctx,
+        &state.event_sender,
+        &state.db.user_notifications,
+        &state.db.subscriptions,
+        &state.db.access,
+    );
+    let subscriptions = service.get_subscribers(&auth_data.user_thing_id()).await?;
+    Ok(Json(subscriptions))
+}
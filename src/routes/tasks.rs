@@ -26,6 +26,7 @@ pub fn routes(upload_max_size_mb: u64) -> Router<Arc<CtxState>> {
     let max_bytes_val = (1024 * 1024 * upload_max_size_mb) as usize;
     Router::new()
         .route("/api/tasks/{task_id}", get(get_task))
+        .route("/api/tasks/darve", get(get_darve_tasks))
         .route("/api/tasks/received", get(user_requests_received))
         .route("/api/tasks/given", get(user_requests_given))
         .route("/api/tasks/{task_id}/accept", post(accept_task_request))
@@ -257,6 +258,36 @@ async fn danate(
     Ok(Json(donor))
 }
 
+// creates the darve tasks of the current user
+async fn get_darve_tasks(
+    auth_data: BearerAuth,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<Json<Vec<TaskRequestView>>> {
+    let user_id = auth_data.user_thing_id();
+    let user_repository = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    };
+
+    let _ = user_repository.get_by_id(&user_id).await?;
+
+    let super_tasks = state
+        .darve_tasks
+        .create_public(&user_id, &state.event_sender)
+        .await?;
+
+    let weekly_tasks = state
+        .darve_tasks
+        .create_private(&user_id, &state.event_sender)
+        .await?;
+
+    let tasks = super_tasks
+        .into_iter()
+        .chain(weekly_tasks)
+        .collect::<Vec<TaskRequestView>>();
+    Ok(Json(tasks))
+}
+
 async fn get_task(
     auth_data: BearerAuth,
     State(state): State<Arc<CtxState>>,
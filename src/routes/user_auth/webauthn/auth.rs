@@ -20,6 +20,7 @@ use crate::entities::user_auth::authentication_entity::CreateAuthInput;
 use crate::entities::user_auth::{authentication_entity, local_user_entity};
 use crate::middleware;
 use crate::middleware::mw_ctx::JWT_KEY;
+use crate::services::content_filter_service::ContentFilterService;
 use crate::utils::validate_utils::validate_username;
 use authentication_entity::{AuthType, AuthenticationDbService};
 use local_user_entity::{LocalUser, LocalUserDbService};
@@ -95,6 +96,10 @@ pub async fn start_register(
     let username = username;
     validate_username(&username)
         .map_err(|_| WebauthnError::WebauthnApiError("username not valid".to_string()))?;
+    ContentFilterService::new(&state.db.client, &ctx)
+        .validate_username(&username)
+        .await
+        .map_err(|_| WebauthnError::WebauthnApiError("username not valid".to_string()))?;
     let logged_user_id = ctx.user_id().ok();
 
     if logged_user_id.is_none() {
@@ -255,6 +260,10 @@ pub async fn finish_register(
         let username = register_user_ident.clone().trim().to_string();
         validate_username(&username)
             .map_err(|_| WebauthnError::WebauthnApiError("username not valid".to_string()))?;
+        ContentFilterService::new(&state.db.client, &ctx)
+            .validate_username(&username)
+            .await
+            .map_err(|_| WebauthnError::WebauthnApiError("username not valid".to_string()))?;
 
         let username_is_available = user_db_service
             .exists(UsernameIdent(username).into())
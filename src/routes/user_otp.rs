@@ -1,6 +1,11 @@
 use crate::{
-    entities::user_auth::{authentication_entity::AuthType, local_user_entity::UpdateUser},
-    middleware::{auth_with_otp_access::AuthWithOtpAccess, bearer_auth::BearerAuth},
+    entities::{
+        audit_log::{AuditAction, CreateAuditLog},
+        user_auth::{authentication_entity::AuthType, local_user_entity::UpdateUser},
+    },
+    middleware::{
+        auth_with_otp_access::AuthWithOtpAccess, bearer_auth::BearerAuth, request_meta::RequestMeta,
+    },
     models::view::user::LoggedUserView,
     utils::totp::{Totp, TotpResponse},
 };
@@ -75,29 +80,28 @@ async fn otp_verification(
     Ok(Json(true))
 }
 
-async fn otp_disable(auth_data: BearerAuth, State(state): State<Arc<CtxState>>) -> CtxResult<()> {
-    let user_id = auth_data.user_thing_id();
+async fn otp_disable(
+    auth_data: BearerAuth,
+    meta: RequestMeta,
+    State(state): State<Arc<CtxState>>,
+) -> CtxResult<()> {
     let local_user_db_service = LocalUserDbService {
         db: &state.db.client,
         ctx: &auth_data.ctx,
     };
 
-    let _user = local_user_db_service
+    let user = local_user_db_service
         .get_by_id(&auth_data.user_thing_id())
         .await?;
+    let user_thing = user.id.unwrap();
 
-    let update_user = UpdateUser {
-        bio: None,
-        birth_date: None,
-        full_name: None,
-        image_uri: None,
-        is_otp_enabled: Some(false),
-        otp_secret: Some(None),
-        phone: None,
-        social_links: None,
-        username: None,
+    let audit = CreateAuditLog {
+        actor: meta.actor(user_thing.clone()),
+        action: AuditAction::DisableOtp,
+        target: user_thing.clone(),
+        note: None,
     };
-    local_user_db_service.update(&user_id, update_user).await?;
+    local_user_db_service.reset_otp(user_thing, audit).await?;
 
     Ok(())
 }
@@ -41,7 +41,7 @@ use crate::{
         mw_ctx::CtxState,
         utils::extractor_utils::JsonOrFormValidated,
     },
-    services::user_service::UserService,
+    services::{content_filter_service::ContentFilterService, user_service::UserService},
     utils::{self, file::convert::FileUpload},
 };
 
@@ -297,7 +297,7 @@ async fn get_following_posts(
         db: &state.db.client,
         ctx: &auth_data.ctx,
     };
-    let _ = local_user_db_service
+    let user = local_user_db_service
         .get_by_id(&auth_data.user_thing_id())
         .await?;
 
@@ -306,9 +306,10 @@ async fn get_following_posts(
         ctx: &auth_data.ctx,
     };
 
-    let types = query
-        .r#type
-        .map_or(vec![PostType::Idea, PostType::Public], |v| vec![v]);
+    let types = query.r#type.map_or(
+        vec![PostType::Idea, PostType::Public, PostType::Paid],
+        |v| vec![v],
+    );
 
     let pag = Pagination {
         order_by: None,
@@ -316,9 +317,12 @@ async fn get_following_posts(
         count: query.count.unwrap_or(50),
         start: query.start.unwrap_or(0),
     };
-    let posts = post_db_service
+    let mut posts = post_db_service
         .get_by_followers(&auth_data.user_thing_id(), types, pag)
         .await?;
+    posts
+        .iter_mut()
+        .for_each(|post| post.hide_locked_content(user.id.as_ref().unwrap()));
 
     Ok(Json(posts))
 }
@@ -474,6 +478,9 @@ async fn update_user(
         otp_secret: None,
     };
     if let Some(username) = form.username {
+        ContentFilterService::new(&ctx_state.db.client, &auth_data.ctx)
+            .validate_username(&username)
+            .await?;
         if local_user_db_service
             .get_by_username(&username)
             .await
@@ -630,7 +637,7 @@ async fn get_latest_posts(
         start: query.start.unwrap_or(0),
     };
 
-    let data = state
+    let mut data = state
         .db
         .discussion_users
         .get_by_user::<DiscussionUserView>(
@@ -640,6 +647,8 @@ async fn get_latest_posts(
             query.search_text,
         )
         .await?;
+    data.iter_mut()
+        .for_each(|v| v.hide_locked_content(user.id.as_ref().unwrap()));
 
     Ok(Json(data))
 }
@@ -679,7 +688,7 @@ async fn get_users_status(
         .user_ids
         .into_iter()
         .map(|id| UserStatus {
-            is_online: state.online_users.get(&id).is_some(),
+            is_online: state.presence.is_online(&id),
             user_id: id,
         })
         .collect::<Vec<UserStatus>>();
@@ -719,10 +728,14 @@ async fn set_nickname(
                 }
                 .into());
             }
+            // nicknames are visible to their author only, so flagged words are not reported
+            let nickname = ContentFilterService::new(&state.db.client, &auth_data.ctx)
+                .filter(&value)
+                .await?;
             state
                 .db
                 .nicknames
-                .upsert(&current_user_id, &to_user_id, value)
+                .upsert(&current_user_id, &to_user_id, nickname.text)
                 .await?
         }
         None => {
@@ -11,6 +11,7 @@ use crate::middleware;
 use crate::middleware::bearer_auth::BearerAuth;
 use crate::middleware::error::{AppError, CtxResult};
 use crate::middleware::mw_ctx::CtxState;
+use crate::middleware::request_meta::RequestMeta;
 use crate::middleware::utils::db_utils::QryOrder::{self};
 use crate::middleware::utils::extractor_utils::JsonOrFormValidated;
 use crate::models::email::WithdrawPaypal;
@@ -180,6 +181,7 @@ struct WithdrawData {
 async fn withdraw(
     State(state): State<Arc<CtxState>>,
     ctx: Ctx,
+    meta: RequestMeta,
     JsonOrFormValidated(data): JsonOrFormValidated<WithdrawData>,
 ) -> CtxResult<()> {
     let user_service = LocalUserDbService {
@@ -214,6 +216,7 @@ async fn withdraw(
             data.amount,
             None,
             state.withdraw_fee,
+            Some(meta.actor(user.id.clone().unwrap())),
         )
         .await?;
 
@@ -0,0 +1,274 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::entities::user_auth::local_user_entity::LocalUserDbService;
+use crate::interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface;
+use crate::middleware::bearer_auth::BearerAuth;
+use crate::middleware::ctx::Ctx;
+use crate::middleware::error::{AppError, CtxResult};
+use crate::middleware::mw_ctx::CtxState;
+use crate::services::discussion_service::DiscussionService;
+use crate::services::notification_service::NotificationService;
+use crate::services::post_user_service::PostUserService;
+use crate::utils::sse_buffer::{SseCursor, SseEvent};
+use crate::utils::user_presence_guard::UserPresenceGuard;
+
+pub fn routes() -> Router<Arc<CtxState>> {
+    Router::new().route("/api/ws", get(ws))
+}
+
+/// Messages sent by the client over the websocket
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum WsClientMessage {
+    Typing { discussion_id: String },
+    ReadPost { post_id: String },
+    SubscribePresence { user_ids: Vec<String> },
+    UnsubscribePresence { user_ids: Vec<String> },
+}
+
+/// Messages sent to the client, the events are the same as in the SSE stream
+#[derive(Debug, Serialize)]
+pub struct WsServerMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub event: String,
+    pub data: Option<Value>,
+}
+
+impl WsServerMessage {
+    fn new(event: &str, data: Option<Value>) -> Self {
+        Self {
+            id: None,
+            event: event.to_string(),
+            data,
+        }
+    }
+
+    fn error(error: AppError) -> Self {
+        Self::new("Error", Some(Value::String(error.to_string())))
+    }
+}
+
+/// Connection state of the websocket
+pub struct WsConnection {
+    state: Arc<CtxState>,
+    ctx: Ctx,
+    user_id: String,
+    cursor: SseCursor,
+    presence_users: HashSet<String>,
+}
+
+impl WsConnection {
+    pub async fn new(state: Arc<CtxState>, ctx: Ctx, user_id: String) -> Self {
+        let last_id = match state.sse_buffer.last_id(&user_id).await {
+            Ok(id) => id,
+            Err(err) => {
+                println!("Error getting last sse event id: {:?}", err);
+                0
+            }
+        };
+        let cursor = SseCursor::new(last_id);
+        Self {
+            state,
+            ctx,
+            user_id,
+            cursor,
+            presence_users: HashSet::new(),
+        }
+    }
+
+    /// Returns the messages for the events sent to the user,
+    /// the status events are sent only for the subscribed users
+    pub async fn on_events(&self, events: Vec<Arc<SseEvent>>) -> Vec<WsServerMessage> {
+        let mut messages = vec![];
+        for event in events {
+            if !event.is_for(&self.user_id) {
+                continue;
+            }
+            if event.name == "UserStatus" && !self.presence_users.contains(&event.user_id) {
+                continue;
+            }
+            if !self.cursor.accept(&event) {
+                continue;
+            }
+
+            let data = match event.name.as_str() {
+                "UnreadDiscussionsCount" => Some(json!(self.get_unread_count().await)),
+                _ => event
+                    .data
+                    .as_ref()
+                    .map(|v| serde_json::from_str(v).unwrap_or(Value::String(v.clone()))),
+            };
+            messages.push(WsServerMessage {
+                id: event.id,
+                event: event.name.clone(),
+                data,
+            });
+        }
+        messages
+    }
+
+    /// Returns the missed events after the connection lagged
+    pub async fn on_lagged(&self) -> Vec<WsServerMessage> {
+        let events = self
+            .state
+            .sse_buffer
+            .replay_or_resync(&self.user_id, self.cursor.last_id())
+            .await;
+        self.on_events(events).await
+    }
+
+    pub async fn on_client_message(&mut self, text: &str) -> Vec<WsServerMessage> {
+        let message = match serde_json::from_str::<WsClientMessage>(text) {
+            Ok(message) => message,
+            Err(err) => {
+                return vec![WsServerMessage::error(AppError::Generic {
+                    description: err.to_string(),
+                })]
+            }
+        };
+
+        let result = match message {
+            WsClientMessage::Typing { discussion_id } => self.typing(&discussion_id).await,
+            WsClientMessage::ReadPost { post_id } => self.read_post(&post_id).await,
+            WsClientMessage::SubscribePresence { user_ids } => {
+                let messages = user_ids
+                    .iter()
+                    .map(|id| {
+                        WsServerMessage::new(
+                            "UserStatus",
+                            Some(json!({
+                                "is_online": self.state.presence.is_online(id),
+                                "user_id": id,
+                            })),
+                        )
+                    })
+                    .collect();
+                self.presence_users.extend(user_ids);
+                Ok(messages)
+            }
+            WsClientMessage::UnsubscribePresence { user_ids } => {
+                for id in user_ids {
+                    self.presence_users.remove(&id);
+                }
+                Ok(vec![])
+            }
+        };
+
+        result.unwrap_or_else(|err| vec![WsServerMessage::error(err)])
+    }
+
+    async fn typing(&self, discussion_id: &str) -> Result<Vec<WsServerMessage>, AppError> {
+        DiscussionService::new(
+            &self.state,
+            &self.ctx,
+            &self.state.db.access,
+            &self.state.db.discussion_users,
+            &self.state.db.user_notifications,
+        )
+        .typing(&self.user_id, discussion_id)
+        .await?;
+        Ok(vec![])
+    }
+
+    async fn read_post(&self, post_id: &str) -> Result<Vec<WsServerMessage>, AppError> {
+        PostUserService::new(
+            &self.state,
+            &self.ctx,
+            &self.state.db.post_users,
+            &self.state.db.discussion_users,
+            NotificationService::new(
+                &self.state.db.client,
+                &self.ctx,
+                &self.state.event_sender,
+                &self.state.db.user_notifications,
+            ),
+        )
+        .read(&self.user_id, post_id)
+        .await?;
+        Ok(vec![])
+    }
+
+    async fn get_unread_count(&self) -> u32 {
+        self.state
+            .db
+            .discussion_users
+            .get_count_of_unread(&self.user_id)
+            .await
+            .unwrap_or_default()
+    }
+}
+
+async fn ws(
+    State(state): State<Arc<CtxState>>,
+    auth_data: BearerAuth,
+    upgrade: WebSocketUpgrade,
+) -> CtxResult<Response> {
+    let user = LocalUserDbService {
+        db: &state.db.client,
+        ctx: &auth_data.ctx,
+    }
+    .get_ctx_user_thing()
+    .await?;
+
+    let user_id = user.id.to_raw();
+    let ctx = auth_data.ctx;
+    Ok(upgrade.on_upgrade(move |socket| handle_socket(socket, state, ctx, user_id)))
+}
+
+async fn handle_socket(socket: WebSocket, state: Arc<CtxState>, ctx: Ctx, user_id: String) {
+    let _presence = UserPresenceGuard::new(state.clone(), user_id.clone());
+    let mut rx = state.sse_buffer.subscribe();
+    let mut connection = WsConnection::new(state, ctx, user_id).await;
+    let (mut sender, mut receiver) = socket.split();
+
+    let unread_count = connection.get_unread_count().await;
+    let initial = WsServerMessage::new("UnreadDiscussionsCount", Some(json!(unread_count)));
+    if send(&mut sender, vec![initial]).await.is_err() {
+        return;
+    }
+
+    loop {
+        let messages = tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(Ok(Message::Text(text))) => connection.on_client_message(text.as_str()).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => vec![],
+            },
+            event = rx.recv() => match event {
+                Ok(event) => connection.on_events(vec![event]).await,
+                Err(RecvError::Lagged(_)) => connection.on_lagged().await,
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        if send(&mut sender, messages).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn send(
+    sender: &mut (impl SinkExt<Message> + Unpin),
+    messages: Vec<WsServerMessage>,
+) -> Result<(), ()> {
+    for message in messages {
+        let text = json!(message).to_string();
+        sender
+            .send(Message::Text(text.into()))
+            .await
+            .map_err(|_| ())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use crate::{
+    access::{
+        base::{permission::Permission, role::Role},
+        discussion::DiscussionAccess,
+        post::PostAccess,
+    },
+    database::client::Db,
+    entities::{
+        access_invite::AccessInvite,
+        community::{
+            discussion_entity::{
+                DiscussionDbService, DiscussionType, TABLE_NAME as DISC_TABLE_NAME,
+            },
+            post_entity::{PostDbService, PostType, TABLE_NAME as POST_TABLE_NAME},
+        },
+        user_auth::local_user_entity::{LocalUser, LocalUserDbService},
+    },
+    interfaces::repositories::{
+        access::AccessRepositoryInterface, access_invite::AccessInviteRepositoryInterface,
+        discussion_user::DiscussionUserRepositoryInterface,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, AppResult, CtxResult},
+        utils::string_utils::get_str_thing,
+    },
+    models::view::access::{DiscussionAccessView, PostAccessView},
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAccessInvite {
+    pub entity_id: String,
+    #[validate(range(min = 1))]
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub struct AccessGrantService<'a, I, A, DU>
+where
+    I: AccessInviteRepositoryInterface,
+    A: AccessRepositoryInterface,
+    DU: DiscussionUserRepositoryInterface,
+{
+    users_repository: LocalUserDbService<'a>,
+    posts_repository: PostDbService<'a>,
+    discussions_repository: DiscussionDbService<'a>,
+    invites_repository: &'a I,
+    access_repository: &'a A,
+    discussion_users: &'a DU,
+}
+
+impl<'a, I, A, DU> AccessGrantService<'a, I, A, DU>
+where
+    I: AccessInviteRepositoryInterface,
+    A: AccessRepositoryInterface,
+    DU: DiscussionUserRepositoryInterface,
+{
+    pub fn new(
+        db: &'a Db,
+        ctx: &'a Ctx,
+        invites_repository: &'a I,
+        access_repository: &'a A,
+        discussion_users: &'a DU,
+    ) -> Self {
+        Self {
+            users_repository: LocalUserDbService { db, ctx },
+            posts_repository: PostDbService { db, ctx },
+            discussions_repository: DiscussionDbService { db, ctx },
+            invites_repository,
+            access_repository,
+            discussion_users,
+        }
+    }
+
+    pub async fn create_invite(
+        &self,
+        user_id: &str,
+        data: CreateAccessInvite,
+    ) -> CtxResult<AccessInvite> {
+        data.validate()?;
+        if data.expires_at.is_some_and(|v| v <= Utc::now()) {
+            return Err(AppError::Generic {
+                description: "Invite expiration must be in the future".to_string(),
+            }
+            .into());
+        }
+
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let entity = get_str_thing(&data.entity_id)?;
+        self.check_can_invite(&user, &entity).await?;
+
+        let invite = self
+            .invites_repository
+            .create(
+                entity,
+                user.id.as_ref().unwrap().clone(),
+                Role::Member.to_string(),
+                data.max_uses,
+                data.expires_at,
+            )
+            .await?;
+        Ok(invite)
+    }
+
+    pub async fn get_invites(
+        &self,
+        user_id: &str,
+        entity_id: &str,
+    ) -> CtxResult<Vec<AccessInvite>> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let entity = get_str_thing(entity_id)?;
+        self.check_can_invite(&user, &entity).await?;
+        let invites = self.invites_repository.get_by_entity(entity).await?;
+        Ok(invites)
+    }
+
+    pub async fn revoke_invite(&self, user_id: &str, invite_id: &str) -> CtxResult<()> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let invite_thing = get_str_thing(invite_id)?;
+        let invite = self
+            .invites_repository
+            .get_by_id(&invite_thing.id.to_raw())
+            .await?;
+        self.check_can_invite(&user, &invite.entity).await?;
+        self.invites_repository
+            .revoke(&invite.id.id.to_raw())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn redeem_invite(&self, user_id: &str, token: &str) -> CtxResult<Thing> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let invite = self.invites_repository.get_by_token(token).await?;
+        if !invite.is_active() {
+            return Err(AppError::Generic {
+                description: "Invite is expired or revoked".to_string(),
+            }
+            .into());
+        }
+
+        let user_thing = user.id.as_ref().unwrap().clone();
+        let has_access = match invite.entity.tb.as_str() {
+            DISC_TABLE_NAME => self
+                .discussions_repository
+                .get_view_by_id::<DiscussionAccessView>(&invite.entity.to_raw())
+                .await?
+                .get_user_role(&user_thing)
+                .is_some(),
+            _ => {
+                let post = self
+                    .posts_repository
+                    .get_view_by_id::<PostAccessView>(&invite.entity.to_raw(), None)
+                    .await?;
+                if !DiscussionAccess::new(&post.discussion).can_view(&user) {
+                    return Err(AppError::Forbidden.into());
+                }
+                post.get_user_role(&user_thing).is_some()
+            }
+        };
+
+        if has_access {
+            return Ok(invite.entity);
+        }
+
+        if !self
+            .invites_repository
+            .increase_uses(&invite.id.id.to_raw())
+            .await?
+        {
+            return Err(AppError::Generic {
+                description: "Invite is expired or revoked".to_string(),
+            }
+            .into());
+        }
+
+        self.access_repository
+            .add(
+                vec![user_thing.clone()],
+                vec![&invite.entity.to_raw()],
+                invite.role.clone(),
+            )
+            .await?;
+
+        if invite.entity.tb == DISC_TABLE_NAME {
+            self.discussion_users
+                .create(&invite.entity.id.to_raw(), vec![user_thing])
+                .await?;
+        }
+
+        Ok(invite.entity)
+    }
+
+    pub async fn prune_expired(&self) -> AppResult<usize> {
+        let grants = self.access_repository.remove_expired().await?;
+
+        let mut disc_users: HashMap<String, Vec<Thing>> = HashMap::new();
+        grants
+            .iter()
+            .filter(|g| g.entity.tb == DISC_TABLE_NAME)
+            .for_each(|g| {
+                disc_users
+                    .entry(g.entity.id.to_raw())
+                    .or_default()
+                    .push(g.user.clone())
+            });
+
+        for (disc_id, users) in disc_users {
+            self.discussion_users.remove(&disc_id, users).await?;
+        }
+
+        Ok(grants.len())
+    }
+
+    async fn check_can_invite(&self, user: &LocalUser, entity: &Thing) -> CtxResult<()> {
+        match entity.tb.as_str() {
+            DISC_TABLE_NAME => {
+                let disc = self
+                    .discussions_repository
+                    .get_view_by_id::<DiscussionAccessView>(&entity.to_raw())
+                    .await?;
+                if disc.r#type != DiscussionType::Private {
+                    return Err(AppError::Generic {
+                        description: "Invites are available for private discussions only"
+                            .to_string(),
+                    }
+                    .into());
+                }
+                let disc_access = DiscussionAccess::new(&disc);
+                if !disc_access.can_add_member(user) {
+                    return Err(disc_access.denied(user, Permission::AddMember).into());
+                }
+            }
+            POST_TABLE_NAME => {
+                let post = self
+                    .posts_repository
+                    .get_view_by_id::<PostAccessView>(&entity.to_raw(), None)
+                    .await?;
+                if post.r#type != PostType::Private {
+                    return Err(AppError::Generic {
+                        description: "Invites are available for private posts only".to_string(),
+                    }
+                    .into());
+                }
+                let post_access = PostAccess::new(&post);
+                if !post_access.can_add_member(user) {
+                    return Err(post_access.denied(user, Permission::AddMember).into());
+                }
+            }
+            _ => {
+                return Err(AppError::Generic {
+                    description: "Invites are available for discussions and posts only".to_string(),
+                }
+                .into())
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    access::base::{
+        access_control,
+        control::{AccessControl, AccessControlDiff},
+        set_access_control,
+    },
+    interfaces::repositories::access_schema::AccessSchemaRepositoryInterface,
+    middleware::error::{AppError, AppResult, CtxResult},
+};
+use surrealdb::sql::Thing;
+
+pub struct AccessSchemaService<'a, R>
+where
+    R: AccessSchemaRepositoryInterface,
+{
+    schemas_repository: &'a R,
+    schema_path: Option<&'a str>,
+}
+
+impl<'a, R> AccessSchemaService<'a, R>
+where
+    R: AccessSchemaRepositoryInterface,
+{
+    pub fn new(schemas_repository: &'a R, schema_path: Option<&'a str>) -> Self {
+        Self {
+            schemas_repository,
+            schema_path,
+        }
+    }
+
+    // the configured file takes precedence over the db, None means the built-in schema
+    pub async fn get_content(&self) -> AppResult<Option<String>> {
+        if let Some(path) = self.schema_path {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| AppError::Generic {
+                    description: format!("Could not read access schema file {}: {}", path, e),
+                })?;
+            return Ok(Some(content));
+        }
+        let schema = self.schemas_repository.get_latest().await?;
+        Ok(schema.map(|s| s.content))
+    }
+
+    pub async fn load(&self) -> AppResult<AccessControlDiff> {
+        let content = self.get_content().await?;
+        self.apply(content.as_deref())
+    }
+
+    pub fn apply(&self, content: Option<&str>) -> AppResult<AccessControlDiff> {
+        let next = match content {
+            Some(value) => AccessControl::try_from(value)
+                .map_err(|description| AppError::Generic { description })?,
+            None => AccessControl::with_default_schema(),
+        };
+        let diff = access_control().diff(&next);
+        set_access_control(next);
+        Ok(diff)
+    }
+
+    pub async fn reload(&self) -> CtxResult<AccessControlDiff> {
+        Ok(self.load().await?)
+    }
+
+    pub async fn update(&self, created_by: Thing, content: String) -> CtxResult<AccessControlDiff> {
+        if self.schema_path.is_some() {
+            return Err(AppError::Generic {
+                description: "Access schema is loaded from a file and can not be updated"
+                    .to_string(),
+            }
+            .into());
+        }
+
+        AccessControl::try_from(content.as_str())
+            .map_err(|description| AppError::Generic { description })?;
+
+        let schema = self
+            .schemas_repository
+            .create(content, Some(created_by))
+            .await?;
+
+        Ok(self.apply(Some(&schema.content))?)
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    access::{
+        base::{explain::AccessExplain, permission::Permission},
+        discussion::DiscussionAccess,
+        post::PostAccess,
+        task::TaskAccess,
+    },
+    database::{client::Db, table_names::TASK_REQUEST_TABLE_NAME},
+    entities::{
+        community::{
+            discussion_entity::{DiscussionDbService, TABLE_NAME as DISC_TABLE_NAME},
+            post_entity::{PostDbService, TABLE_NAME as POST_TABLE_NAME},
+        },
+        user_auth::local_user_entity::{LocalUserDbService, UserRole},
+    },
+    interfaces::repositories::task_request_ifce::TaskRequestRepositoryInterface,
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        utils::string_utils::get_str_thing,
+    },
+    models::view::access::{DiscussionAccessView, PostAccessView, TaskAccessView},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AccessExplainQuery {
+    pub entity_id: String,
+    pub permission: Option<Permission>,
+    // only admins can explain the access of other users
+    pub user_id: Option<String>,
+}
+
+pub struct AccessService<'a, TR>
+where
+    TR: TaskRequestRepositoryInterface,
+{
+    users_repository: LocalUserDbService<'a>,
+    posts_repository: PostDbService<'a>,
+    discussions_repository: DiscussionDbService<'a>,
+    tasks_repository: &'a TR,
+}
+
+impl<'a, TR> AccessService<'a, TR>
+where
+    TR: TaskRequestRepositoryInterface,
+{
+    pub fn new(db: &'a Db, ctx: &'a Ctx, tasks_repository: &'a TR) -> Self {
+        Self {
+            users_repository: LocalUserDbService { db, ctx },
+            posts_repository: PostDbService { db, ctx },
+            discussions_repository: DiscussionDbService { db, ctx },
+            tasks_repository,
+        }
+    }
+
+    pub async fn explain(
+        &self,
+        user_id: &str,
+        query: AccessExplainQuery,
+    ) -> CtxResult<AccessExplain> {
+        let mut user = self.users_repository.get_by_id(user_id).await?;
+
+        if let Some(ref other_id) = query.user_id {
+            if user.role != UserRole::Admin {
+                return Err(AppError::Forbidden.into());
+            }
+            let other_thing = get_str_thing(other_id)?;
+            user = self
+                .users_repository
+                .get_by_id(&other_thing.id.to_raw())
+                .await?;
+        }
+
+        let entity = get_str_thing(&query.entity_id)?;
+        let explain = match entity.tb.as_str() {
+            POST_TABLE_NAME => {
+                let post = self
+                    .posts_repository
+                    .get_view_by_id::<PostAccessView>(&query.entity_id, None)
+                    .await?;
+                PostAccess::new(&post).explain(&user, query.permission)?
+            }
+            DISC_TABLE_NAME => {
+                let disc = self
+                    .discussions_repository
+                    .get_view_by_id::<DiscussionAccessView>(&query.entity_id)
+                    .await?;
+                DiscussionAccess::new(&disc).explain(&user, query.permission)?
+            }
+            TASK_REQUEST_TABLE_NAME => {
+                let task = self
+                    .tasks_repository
+                    .get_by_id::<TaskAccessView>(&query.entity_id)
+                    .await?;
+                TaskAccess::new(&task).explain(&user, query.permission)?
+            }
+            _ => {
+                return Err(AppError::Generic {
+                    description: "Access can be explained for posts, discussions and tasks only"
+                        .to_string(),
+                }
+                .into())
+            }
+        };
+
+        Ok(explain)
+    }
+}
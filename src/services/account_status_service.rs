@@ -0,0 +1,282 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+use crate::{
+    entities::{
+        account_status_change::{AccountStatusChange, AccountStatusChangeDbService},
+        audit_log::{AuditAction, AuditActor, AuditLogDbService, CreateAuditLog},
+        user_auth::local_user_entity::{AccountStatus, LocalUser, LocalUserDbService, UserRole},
+        wallet::wallet_entity::WalletDbService,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        mw_ctx::CtxState,
+        utils::{db_utils::Pagination, string_utils::get_str_thing},
+    },
+    services::{notification_service::NotificationService, task_service::TaskService},
+};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RestrictAccountInput {
+    pub status: AccountStatus,
+    #[validate(length(min = 1, max = 1000, message = "Reason must have 1 to 1000 characters"))]
+    pub reason: String,
+    /// required for the suspension
+    #[validate(range(min = 1, max = 3650))]
+    pub days: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReinstateAccountInput {
+    #[validate(length(min = 1, max = 1000, message = "Reason must have 1 to 1000 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountStatusChangesQuery {
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+}
+
+pub struct AccountStatusService<'a> {
+    state: &'a CtxState,
+    ctx: &'a Ctx,
+    users_repository: LocalUserDbService<'a>,
+    changes_repository: AccountStatusChangeDbService<'a>,
+    wallets_repository: WalletDbService<'a>,
+    audit_repository: AuditLogDbService<'a>,
+}
+
+impl<'a> AccountStatusService<'a> {
+    pub fn new(state: &'a CtxState, ctx: &'a Ctx) -> Self {
+        Self {
+            state,
+            ctx,
+            users_repository: LocalUserDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            changes_repository: AccountStatusChangeDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            wallets_repository: WalletDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            audit_repository: AuditLogDbService {
+                db: &state.db.client,
+                ctx,
+            },
+        }
+    }
+
+    pub async fn restrict(
+        &self,
+        actor: AuditActor,
+        user_id: &str,
+        data: RestrictAccountInput,
+    ) -> CtxResult<AccountStatusChange> {
+        data.validate()?;
+        let user_data = self.get_user(user_id).await?;
+        let user = user_data.id.clone().unwrap();
+
+        let suspended_until = match data.status {
+            AccountStatus::Active => {
+                return Err(AppError::Generic {
+                    description: "Reinstate the account to activate it".to_string(),
+                }
+                .into())
+            }
+            AccountStatus::Suspended => match data.days {
+                Some(days) => Some(Utc::now() + Duration::days(days as i64)),
+                None => {
+                    return Err(AppError::Generic {
+                        description: "Suspension requires the number of days".to_string(),
+                    }
+                    .into())
+                }
+            },
+            AccountStatus::Banned | AccountStatus::Deactivated => None,
+        };
+
+        let change = self
+            .apply(
+                Some(actor.user.clone()),
+                &user,
+                data.status,
+                suspended_until,
+                data.reason.clone(),
+            )
+            .await?;
+        self.audit_repository
+            .create(
+                CreateAuditLog {
+                    actor,
+                    action: AuditAction::RestrictAccount,
+                    target: user,
+                    note: Some(data.reason),
+                },
+                Some(Self::status_snapshot(&user_data)),
+                Some(json!({ "status": change.status, "suspended_until": change.suspended_until })),
+            )
+            .await?;
+        Ok(change)
+    }
+
+    pub async fn reinstate(
+        &self,
+        actor: AuditActor,
+        user_id: &str,
+        data: ReinstateAccountInput,
+    ) -> CtxResult<AccountStatusChange> {
+        data.validate()?;
+        let user_data = self.get_user(user_id).await?;
+        let user = user_data.id.clone().unwrap();
+        let change = self
+            .lift(Some(actor.user.clone()), &user, data.reason.clone())
+            .await?;
+        self.audit_repository
+            .create(
+                CreateAuditLog {
+                    actor,
+                    action: AuditAction::ReinstateAccount,
+                    target: user,
+                    note: Some(data.reason),
+                },
+                Some(Self::status_snapshot(&user_data)),
+                Some(json!({ "status": change.status, "suspended_until": change.suspended_until })),
+            )
+            .await?;
+        Ok(change)
+    }
+
+    pub async fn get_changes(
+        &self,
+        user_id: &str,
+        query: AccountStatusChangesQuery,
+    ) -> CtxResult<Vec<AccountStatusChange>> {
+        let user_thing = get_str_thing(user_id)?;
+        self.changes_repository
+            .get_by_user(
+                user_thing,
+                Pagination {
+                    order_by: None,
+                    order_dir: None,
+                    count: query.count.unwrap_or(50),
+                    start: query.start.unwrap_or(0),
+                },
+            )
+            .await
+    }
+
+    /// Restricts the account, invalidates its tokens, freezes the wallet,
+    /// hides the public content and cancels the pending tasks with refunds
+    pub(crate) async fn apply(
+        &self,
+        created_by: Option<Thing>,
+        user: &Thing,
+        status: AccountStatus,
+        suspended_until: Option<DateTime<Utc>>,
+        reason: String,
+    ) -> CtxResult<AccountStatusChange> {
+        self.users_repository
+            .set_status(user.clone(), status.clone(), suspended_until)
+            .await?;
+        self.wallets_repository
+            .set_user_wallet_frozen(user, true)
+            .await?;
+        let hidden_entities = self.changes_repository.hide_content(user.clone()).await?;
+        TaskService::new(
+            &self.state.db.client,
+            self.ctx,
+            &self.state.db.task_request,
+            &self.state.db.task_donors,
+            &self.state.db.task_participants,
+            &self.state.db.access,
+            &self.state.db.tags,
+            NotificationService::new(
+                &self.state.db.client,
+                self.ctx,
+                &self.state.event_sender,
+                &self.state.db.user_notifications,
+            ),
+            self.state.file_storage.clone(),
+        )
+        .cancel_by_creator(user)
+        .await?;
+
+        self.changes_repository
+            .create(
+                user.clone(),
+                status,
+                reason,
+                suspended_until,
+                created_by,
+                hidden_entities,
+            )
+            .await
+    }
+
+    /// Activates the account and restores its wallet and content
+    pub(crate) async fn lift(
+        &self,
+        created_by: Option<Thing>,
+        user: &Thing,
+        reason: String,
+    ) -> CtxResult<AccountStatusChange> {
+        self.changes_repository
+            .restore_content(user.clone())
+            .await?;
+        self.wallets_repository
+            .set_user_wallet_frozen(user, false)
+            .await?;
+        self.users_repository
+            .set_status(user.clone(), AccountStatus::Active, None)
+            .await?;
+
+        self.changes_repository
+            .create(
+                user.clone(),
+                AccountStatus::Active,
+                reason,
+                None,
+                created_by,
+                vec![],
+            )
+            .await
+    }
+
+    pub(crate) async fn lift_expired(&self) -> CtxResult<()> {
+        let users = self.users_repository.get_expired_suspensions().await?;
+        for user in users {
+            if let Err(err) = self
+                .lift(None, &user, "Suspension expired".to_string())
+                .await
+            {
+                println!("Error lifting suspension of {}: {:?}", user, err);
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_user(&self, user_id: &str) -> CtxResult<LocalUser> {
+        let user_thing = get_str_thing(user_id)?;
+        let user = self
+            .users_repository
+            .get_by_id(&user_thing.id.to_raw())
+            .await?;
+        if user.role == UserRole::Admin {
+            return Err(AppError::Forbidden.into());
+        }
+        Ok(user)
+    }
+
+    fn status_snapshot(user: &LocalUser) -> Value {
+        json!({ "status": user.status, "suspended_until": user.suspended_until })
+    }
+}
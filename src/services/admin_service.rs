@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+use crate::{
+    entities::{
+        audit_log::{
+            AuditAction, AuditActor, AuditLog, AuditLogDbService, AuditLogFilter, CreateAuditLog,
+        },
+        community::{
+            discussion_entity::TABLE_NAME as DISCUSSION_TABLE_NAME,
+            post_entity::TABLE_NAME as POST_TABLE_NAME,
+        },
+        user_auth::{
+            authentication_entity::AuthenticationDbService,
+            local_user_entity::{
+                AccountStatus, LocalUserDbService, UserFilter, UserRole,
+                TABLE_NAME as USER_TABLE_NAME,
+            },
+        },
+        verification_code::VerificationCodeFor,
+        wallet::{
+            balance_transaction_entity::{BalanceTransactionDbService, TransactionType},
+            gateway_transaction_entity::{
+                GatewayTransaction, GatewayTransactionDbService, GatewayTransactionStatus,
+            },
+            wallet_entity::{WalletBalancesView, WalletDbService},
+        },
+    },
+    interfaces::repositories::{
+        report::ReportsRepositoryInterface,
+        verification_code_ifce::VerificationCodeRepositoryInterface,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        mw_ctx::CtxState,
+        utils::{
+            db_utils::{record_exists, IdentIdName, Pagination},
+            string_utils::get_str_thing,
+        },
+    },
+    models::view::{
+        admin::{AdminUserView, AuthMethodView},
+        balance_tx::CurrencyTransactionView,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct UsersQuery {
+    pub search: Option<String>,
+    pub role: Option<UserRole>,
+    pub status: Option<AccountStatus>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct VerifyEmailInput {
+    /// the email of the pending verification is used if not set
+    #[validate(email)]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeRoleInput {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeleteContentInput {
+    #[validate(length(max = 1000, message = "Note can have max 1000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub target: Option<String>,
+    pub target_type: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditLogExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogExportQuery {
+    pub format: Option<AuditLogExportFormat>,
+}
+
+const MAX_AUDIT_LOG_EXPORT: u16 = 10000;
+
+/// Admin console operations, the caller has to be authorized as admin already
+pub struct AdminService<'a> {
+    state: &'a CtxState,
+    users_repository: LocalUserDbService<'a>,
+    auth_repository: AuthenticationDbService<'a>,
+    wallets_repository: WalletDbService<'a>,
+    transactions_repository: BalanceTransactionDbService<'a>,
+    gateway_repository: GatewayTransactionDbService<'a>,
+    audit_repository: AuditLogDbService<'a>,
+}
+
+impl<'a> AdminService<'a> {
+    pub fn new(state: &'a CtxState, ctx: &'a Ctx) -> Self {
+        Self {
+            state,
+            users_repository: LocalUserDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            auth_repository: AuthenticationDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            wallets_repository: WalletDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            transactions_repository: BalanceTransactionDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            gateway_repository: GatewayTransactionDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            audit_repository: AuditLogDbService {
+                db: &state.db.client,
+                ctx,
+            },
+        }
+    }
+
+    pub async fn get_users(&self, query: UsersQuery) -> CtxResult<Vec<AdminUserView>> {
+        self.users_repository
+            .get_filtered::<AdminUserView>(
+                UserFilter {
+                    search: query.search.filter(|v| !v.trim().is_empty()),
+                    role: query.role,
+                    status: query.status,
+                },
+                Pagination {
+                    order_by: None,
+                    order_dir: None,
+                    count: query.count.unwrap_or(50),
+                    start: query.start.unwrap_or(0),
+                },
+            )
+            .await
+    }
+
+    pub async fn get_wallet(&self, user_id: &str) -> CtxResult<WalletBalancesView> {
+        let user = self.get_user_thing(user_id).await?;
+        self.wallets_repository.get_user_balances(&user).await
+    }
+
+    pub async fn get_transactions(
+        &self,
+        user_id: &str,
+        r#type: Option<TransactionType>,
+        pagination: Pagination,
+    ) -> CtxResult<Vec<CurrencyTransactionView>> {
+        let user = self.get_user_thing(user_id).await?;
+        self.transactions_repository
+            .user_transaction_list(
+                &WalletDbService::get_user_wallet_id(&user),
+                r#type,
+                Some(pagination),
+            )
+            .await
+    }
+
+    pub async fn get_gateway_transactions(
+        &self,
+        user_id: &str,
+        status: Option<GatewayTransactionStatus>,
+        r#type: Option<TransactionType>,
+        pagination: Pagination,
+    ) -> CtxResult<Vec<GatewayTransaction>> {
+        let user = self.get_user_thing(user_id).await?;
+        self.gateway_repository
+            .get_by_user(&user, status, r#type, Some(pagination))
+            .await
+    }
+
+    pub async fn get_auth_methods(&self, user_id: &str) -> CtxResult<Vec<AuthMethodView>> {
+        let user = self.get_user_thing(user_id).await?;
+        self.auth_repository
+            .get_by_user::<AuthMethodView>(user)
+            .await
+    }
+
+    pub async fn verify_email(
+        &self,
+        actor: AuditActor,
+        user_id: &str,
+        data: VerifyEmailInput,
+    ) -> CtxResult<AdminUserView> {
+        data.validate()?;
+        let user = self.get_user_thing(user_id).await?;
+        let before = self.get_user_view(user.clone()).await?.email_verified;
+        let email = match data.email {
+            Some(email) => email,
+            None => {
+                self.state
+                    .db
+                    .verification_code
+                    .get_by_user(&user.id.to_raw(), VerificationCodeFor::EmailVerification)
+                    .await
+                    .map_err(|_| AppError::Generic {
+                        description: "User has no email to verify".to_string(),
+                    })?
+                    .email
+            }
+        };
+
+        self.users_repository
+            .set_user_email(user.clone(), email.clone())
+            .await?;
+        self.audit_repository
+            .create(
+                CreateAuditLog {
+                    actor,
+                    action: AuditAction::VerifyEmail,
+                    target: user.clone(),
+                    note: None,
+                },
+                Some(json!({ "email_verified": before })),
+                Some(json!({ "email_verified": email })),
+            )
+            .await?;
+        self.get_user_view(user).await
+    }
+
+    pub async fn reset_otp(&self, actor: AuditActor, user_id: &str) -> CtxResult<AdminUserView> {
+        let user = self.get_user_thing(user_id).await?;
+        let audit = CreateAuditLog {
+            actor,
+            action: AuditAction::ResetOtp,
+            target: user.clone(),
+            note: None,
+        };
+        self.users_repository.reset_otp(user.clone(), audit).await?;
+        self.get_user_view(user).await
+    }
+
+    pub async fn change_role(
+        &self,
+        actor: AuditActor,
+        user_id: &str,
+        data: ChangeRoleInput,
+    ) -> CtxResult<AdminUserView> {
+        let user = self.get_user_thing(user_id).await?;
+        if user == actor.user {
+            return Err(AppError::Generic {
+                description: "Admin can not change the own role".to_string(),
+            }
+            .into());
+        }
+        let audit = CreateAuditLog {
+            actor,
+            action: AuditAction::ChangeRole,
+            target: user.clone(),
+            note: None,
+        };
+        self.users_repository
+            .set_role(user.clone(), data.role, audit)
+            .await?;
+        self.get_user_view(user).await
+    }
+
+    /// Hides the post or the discussion, the data stays in the database
+    pub async fn delete_content(
+        &self,
+        actor: AuditActor,
+        entity_id: &str,
+        data: DeleteContentInput,
+    ) -> CtxResult<AuditLog> {
+        data.validate()?;
+        let entity = get_str_thing(entity_id)?;
+        let action = match entity.tb.as_str() {
+            POST_TABLE_NAME => AuditAction::DeletePost,
+            DISCUSSION_TABLE_NAME => AuditAction::DeleteDiscussion,
+            _ => {
+                return Err(AppError::Generic {
+                    description: "Only posts and discussions can be deleted".to_string(),
+                }
+                .into())
+            }
+        };
+        record_exists(&self.state.db.client, &entity).await?;
+        self.state.db.reports.set_hidden(&entity, true).await?;
+        self.audit_repository
+            .create(
+                CreateAuditLog {
+                    actor,
+                    action,
+                    target: entity,
+                    note: data.note,
+                },
+                Some(json!({ "hidden": false })),
+                Some(json!({ "hidden": true })),
+            )
+            .await
+    }
+
+    pub async fn get_audit_log(&self, query: AuditLogQuery) -> CtxResult<Vec<AuditLog>> {
+        let pagination = Pagination {
+            order_by: None,
+            order_dir: None,
+            count: query.count.unwrap_or(50),
+            start: query.start.unwrap_or(0),
+        };
+        let filter = self.get_audit_log_filter(query)?;
+        self.audit_repository.get(filter, pagination).await
+    }
+
+    pub async fn export_audit_log(&self, query: AuditLogQuery) -> CtxResult<Vec<AuditLog>> {
+        let pagination = Pagination {
+            order_by: None,
+            order_dir: None,
+            count: query
+                .count
+                .unwrap_or(MAX_AUDIT_LOG_EXPORT)
+                .min(MAX_AUDIT_LOG_EXPORT),
+            start: query.start.unwrap_or(0),
+        };
+        let filter = self.get_audit_log_filter(query)?;
+        self.audit_repository.get(filter, pagination).await
+    }
+
+    fn get_audit_log_filter(&self, query: AuditLogQuery) -> CtxResult<AuditLogFilter> {
+        let actor = match query.actor {
+            Some(ref id) => Some(get_str_thing(id)?),
+            None => None,
+        };
+        let target = match query.target {
+            Some(ref id) => Some(get_str_thing(id)?),
+            None => None,
+        };
+        Ok(AuditLogFilter {
+            actor,
+            action: query.action,
+            target,
+            target_type: query.target_type,
+            from: query.from,
+            to: query.to,
+        })
+    }
+
+    async fn get_user_thing(&self, user_id: &str) -> CtxResult<Thing> {
+        let user = get_str_thing(user_id)?;
+        if user.tb != USER_TABLE_NAME {
+            return Err(AppError::EntityFailIdNotFound {
+                ident: user_id.to_string(),
+            }
+            .into());
+        }
+        record_exists(&self.state.db.client, &user).await?;
+        Ok(user)
+    }
+
+    async fn get_user_view(&self, user: Thing) -> CtxResult<AdminUserView> {
+        self.users_repository
+            .get_view::<AdminUserView>(IdentIdName::Id(user))
+            .await
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use super::content_filter_service::ContentFilterService;
 use super::verification_code_service::VerificationCodeService;
 use crate::access::base::role::Role;
 use crate::entities::community::discussion_entity::DiscussionDbService;
@@ -14,6 +15,7 @@ use crate::utils::verification::twitch::TwitchService;
 use crate::{
     database::client::Db,
     entities::{
+        audit_log::{AuditAction, AuditLogDbService, CreateAuditLog},
         community::community_entity::CommunityDbService,
         user_auth::{
             authentication_entity::{AuthType, AuthenticationDbService, CreateAuthInput},
@@ -27,6 +29,7 @@ use crate::{
     middleware::{
         ctx::Ctx,
         error::{AppError, CtxResult},
+        request_meta::RequestMeta,
         utils::db_utils::{IdentIdName, UsernameIdent},
     },
     utils::{
@@ -97,6 +100,8 @@ where
     user_repository: LocalUserDbService<'a>,
     auth_repository: AuthenticationDbService<'a>,
     community_repository: CommunityDbService<'a>,
+    audit_repository: AuditLogDbService<'a>,
+    content_filter: ContentFilterService<'a>,
     verification_code_service: VerificationCodeService<'a, V>,
     access_repository: &'a A,
     file_storage: Arc<dyn FileStorageInterface + Send + Sync>,
@@ -125,6 +130,8 @@ where
             user_repository: LocalUserDbService { db: &db, ctx: &ctx },
             auth_repository: AuthenticationDbService { db: &db, ctx: &ctx },
             community_repository: CommunityDbService { db: &db, ctx: &ctx },
+            audit_repository: AuditLogDbService { db: &db, ctx: &ctx },
+            content_filter: ContentFilterService::new(db, ctx),
             verification_code_service: VerificationCodeService::new(
                 verification_code_repository,
                 email_sender,
@@ -166,6 +173,8 @@ where
             }
             .into());
         }
+        user.status.check(user.suspended_until)?;
+
         let user_id = user.id.as_ref().unwrap().to_raw();
         let token = match user.is_otp_enabled {
             true => self
@@ -186,6 +195,9 @@ where
         role: Option<UserRole>,
     ) -> CtxResult<(String, LocalUser)> {
         input.validate()?;
+        self.content_filter
+            .validate_username(&input.username)
+            .await?;
         if self.is_exists_by_username(input.username.clone()).await {
             return Err(self.ctx.to_ctx_error(AppError::Generic {
                 description: "The username is already used".to_string(),
@@ -468,7 +480,11 @@ where
         }
     }
 
-    pub async fn reset_password(&self, input: ResetPasswordInput) -> CtxResult<()> {
+    pub async fn reset_password(
+        &self,
+        input: ResetPasswordInput,
+        meta: RequestMeta,
+    ) -> CtxResult<()> {
         input.validate()?;
 
         let user = if input.email_or_username.validate_email() {
@@ -505,6 +521,20 @@ where
             .delete(&verification_data.id)
             .await?;
 
+        let user_id = user.id.unwrap();
+        self.audit_repository
+            .create(
+                CreateAuditLog {
+                    actor: meta.actor(user_id.clone()),
+                    action: AuditAction::ResetPassword,
+                    target: user_id,
+                    note: None,
+                },
+                None,
+                None,
+            )
+            .await?;
+
         Ok(())
     }
 
@@ -604,7 +634,13 @@ where
                 .map(|s| s.to_string())
                 .unwrap_or_default();
 
-            if validate_username(&first_part).is_ok() {
+            if validate_username(&first_part).is_ok()
+                && self
+                    .content_filter
+                    .validate_username(&first_part)
+                    .await
+                    .is_ok()
+            {
                 if !self.is_exists_by_username(first_part.clone()).await {
                     return first_part;
                 }
@@ -613,7 +649,9 @@ where
 
         if let Some(name) = name {
             let name = name.trim().replace(' ', "_").to_lowercase();
-            if validate_username(&name).is_ok() {
+            if validate_username(&name).is_ok()
+                && self.content_filter.validate_username(&name).await.is_ok()
+            {
                 if !self.is_exists_by_username(name.clone()).await {
                     return name;
                 }
@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use serde_json::json;
+use surrealdb::{engine::any, method::Query, sql::Thing};
+use validator::Validate;
+
+use crate::{
+    database::client::Db,
+    entities::blocked_word::{
+        BlockedWord, BlockedWordCategory, BlockedWordCategoryAction, BlockedWordDbService,
+        ContentFilterAction,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        utils::db_utils::Pagination,
+    },
+    utils::content_filter::{mask, normalize, token_variants, tokenize},
+};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct BlockedWordsInput {
+    #[validate(length(min = 1, max = 1000, message = "1 to 1000 words"))]
+    pub words: Vec<String>,
+    pub category: BlockedWordCategory,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RemoveBlockedWordsInput {
+    #[validate(length(min = 1, max = 1000, message = "1 to 1000 words"))]
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockedWordsQuery {
+    pub category: Option<BlockedWordCategory>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryActionInput {
+    pub action: ContentFilterAction,
+}
+
+#[derive(Debug, Clone)]
+pub struct FilteredContent {
+    pub text: String,
+    /// words of the categories with the flag action
+    pub flagged_words: Vec<String>,
+}
+
+pub struct ContentFilterService<'a> {
+    words_repository: BlockedWordDbService<'a>,
+}
+
+impl<'a> ContentFilterService<'a> {
+    pub fn new(db: &'a Db, ctx: &'a Ctx) -> Self {
+        Self {
+            words_repository: BlockedWordDbService { db, ctx },
+        }
+    }
+
+    /// Fails if the text contains a word of a rejected category and masks the words of
+    /// the masked categories
+    pub async fn filter(&self, text: &str) -> CtxResult<FilteredContent> {
+        let tokens = tokenize(text);
+        let variants = tokens
+            .iter()
+            .flat_map(token_variants)
+            .collect::<HashSet<String>>();
+
+        let mut blocked_words = self
+            .words_repository
+            .get_by_words(variants.into_iter().collect())
+            .await?;
+        blocked_words.retain(|w| w.category != BlockedWordCategory::Reserved);
+        if blocked_words.is_empty() {
+            return Ok(FilteredContent {
+                text: text.to_string(),
+                flagged_words: vec![],
+            });
+        }
+
+        let actions = self.get_actions().await?;
+        let mut masked = HashSet::new();
+        let mut flagged_words = vec![];
+        for blocked in blocked_words {
+            match actions[&blocked.category] {
+                ContentFilterAction::Reject => {
+                    return Err(AppError::Generic {
+                        description: "The content contains forbidden words".to_string(),
+                    }
+                    .into())
+                }
+                ContentFilterAction::Mask => {
+                    masked.insert(blocked.word);
+                }
+                ContentFilterAction::Flag => flagged_words.push(blocked.word),
+            }
+        }
+
+        let text = match masked.is_empty() {
+            true => text.to_string(),
+            false => mask(text, &tokens, &masked),
+        };
+        Ok(FilteredContent {
+            text,
+            flagged_words,
+        })
+    }
+
+    pub async fn filter_option(&self, text: Option<String>) -> CtxResult<Option<FilteredContent>> {
+        match text {
+            Some(value) => Ok(Some(self.filter(&value).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fails if the whole username is a blocked word of any category
+    pub async fn validate_username(&self, username: &str) -> CtxResult<()> {
+        let words = HashSet::from([username.trim().to_lowercase(), normalize(username)]);
+        let blocked_words = self
+            .words_repository
+            .get_by_words(words.into_iter().collect())
+            .await?;
+        if !blocked_words.is_empty() {
+            return Err(AppError::ValidationErrors {
+                value: json!({ "username": "This username contains forbidden words" }),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Reports the entity to the moderators if there are flagged words in its content
+    pub async fn flag(&self, entity: &Thing, words: Vec<String>) -> CtxResult<()> {
+        if words.is_empty() {
+            return Ok(());
+        }
+        self.words_repository.flag(entity.clone(), words).await
+    }
+
+    /// Adds the report of the flagged words into the query, so the entity is reported in
+    /// the same transaction as it's saved
+    pub fn build_flag_query<'b>(
+        &self,
+        query: Query<'b, any::Any>,
+        entity: &Thing,
+        words: Vec<String>,
+    ) -> Query<'b, any::Any> {
+        if words.is_empty() {
+            return query;
+        }
+        BlockedWordDbService::build_flag_query(query, entity.clone(), words)
+    }
+
+    pub async fn get_words(&self, query: BlockedWordsQuery) -> CtxResult<Vec<BlockedWord>> {
+        self.words_repository
+            .get(
+                query.category,
+                Pagination {
+                    order_by: None,
+                    order_dir: None,
+                    count: query.count.unwrap_or(100),
+                    start: query.start.unwrap_or(0),
+                },
+            )
+            .await
+    }
+
+    pub async fn add_words(&self, data: BlockedWordsInput) -> CtxResult<Vec<BlockedWord>> {
+        data.validate()?;
+        let words = Self::normalize_words(data.words);
+        self.words_repository.add(words, data.category).await
+    }
+
+    pub async fn remove_words(&self, data: RemoveBlockedWordsInput) -> CtxResult<()> {
+        data.validate()?;
+        let words = Self::normalize_words(data.words);
+        self.words_repository.remove(words).await
+    }
+
+    pub async fn get_categories(&self) -> CtxResult<Vec<BlockedWordCategoryAction>> {
+        let actions = self.get_actions().await?;
+        Ok(BlockedWordCategory::all()
+            .into_iter()
+            .map(|category| BlockedWordCategoryAction {
+                action: actions[&category],
+                category,
+            })
+            .collect())
+    }
+
+    pub async fn set_category_action(
+        &self,
+        category: BlockedWordCategory,
+        data: CategoryActionInput,
+    ) -> CtxResult<()> {
+        self.words_repository
+            .set_category_action(category, data.action)
+            .await
+    }
+
+    async fn get_actions(&self) -> CtxResult<HashMap<BlockedWordCategory, ContentFilterAction>> {
+        let mut actions = BlockedWordCategory::all()
+            .into_iter()
+            .map(|c| (c, c.default_action()))
+            .collect::<HashMap<BlockedWordCategory, ContentFilterAction>>();
+        let configured = self.words_repository.get_category_actions().await?;
+        actions.extend(configured.into_iter().map(|c| (c.category, c.action)));
+        Ok(actions)
+    }
+
+    fn normalize_words(words: Vec<String>) -> Vec<String> {
+        words
+            .iter()
+            .map(|w| normalize(w))
+            .filter(|w| !w.is_empty())
+            .collect::<HashSet<String>>()
+            .into_iter()
+            .collect()
+    }
+}
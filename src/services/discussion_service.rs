@@ -1,13 +1,10 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
-
+use crate::access::base::permission::Permission;
 use crate::access::base::role::Role;
 use crate::access::community::CommunityAccess;
 use crate::access::discussion::DiscussionAccess;
 use crate::entities::access_user::AccessUser;
 use crate::entities::community::community_entity::CommunityDbService;
-use crate::entities::community::discussion_entity::{
-    CreateDiscussionEntity, DiscussionType, TABLE_NAME as DISC_TABLE_NAME,
-};
+use crate::entities::community::discussion_entity::{CreateDiscussionEntity, DiscussionType};
 use crate::interfaces::repositories::access::AccessRepositoryInterface;
 use crate::interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface;
 use crate::interfaces::repositories::user_notifications::UserNotificationsInterface;
@@ -15,11 +12,20 @@ use crate::middleware::utils::db_utils::{record_exist_all, Pagination};
 use crate::middleware::utils::string_utils::get_str_thing;
 use crate::models::view::access::DiscussionAccessView;
 use crate::models::view::discussion::DiscussionView;
+use crate::services::content_filter_service::ContentFilterService;
 use crate::services::notification_service::NotificationService;
 use crate::{
+    database::client::Db,
     entities::{
-        community::discussion_entity::{Discussion, DiscussionDbService},
-        user_auth::local_user_entity::LocalUserDbService,
+        community::discussion_entity::{
+            Discussion, DiscussionDbService, TABLE_NAME as DISC_TABLE_NAME,
+        },
+        user_auth::{
+            block_entity::BlockDbService,
+            follow_entity::FollowDbService,
+            local_user_entity::{LocalUserDbService, TABLE_NAME as USER_TABLE_NAME},
+        },
+        wallet::wallet_entity::check_transaction_custom_error,
     },
     middleware::{
         ctx::Ctx,
@@ -27,6 +33,7 @@ use crate::{
         mw_ctx::CtxState,
     },
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 use validator::Validate;
@@ -42,6 +49,8 @@ pub struct CreateDiscussion {
     pub private_discussion_users_final: bool,
 }
 
+const DIRECT_MESSAGE_TITLE: &str = "Direct message";
+
 #[derive(Debug, Deserialize, Serialize, Validate)]
 pub struct UpdateDiscussion {
     pub title: Option<String>,
@@ -53,13 +62,17 @@ where
     A: AccessRepositoryInterface,
     N: UserNotificationsInterface,
 {
+    db: &'a Db,
     ctx: &'a Ctx,
     user_repository: LocalUserDbService<'a>,
     discussion_repository: DiscussionDbService<'a>,
     community_repository: CommunityDbService<'a>,
     access_repository: &'a A,
     discussion_users: &'a U,
+    blocks_repository: BlockDbService<'a>,
+    follow_repository: FollowDbService<'a>,
     notifications_service: NotificationService<'a, N>,
+    content_filter: ContentFilterService<'a>,
 }
 
 impl<'a, A, U, N> DiscussionService<'a, A, U, N>
@@ -76,6 +89,7 @@ where
         user_notifications: &'a N,
     ) -> Self {
         Self {
+            db: &state.db.client,
             ctx,
             user_repository: LocalUserDbService {
                 db: &state.db.client,
@@ -91,12 +105,21 @@ where
             },
             access_repository,
             discussion_users,
+            blocks_repository: BlockDbService {
+                db: &state.db.client,
+                ctx: &ctx,
+            },
+            follow_repository: FollowDbService {
+                db: &state.db.client,
+                ctx: &ctx,
+            },
             notifications_service: NotificationService::new(
                 &state.db.client,
                 &ctx,
                 &state.event_sender,
                 user_notifications,
             ),
+            content_filter: ContentFilterService::new(&state.db.client, &ctx),
         }
     }
 
@@ -113,16 +136,40 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_edit(&user) {
-            return Err(AppError::Forbidden);
+            return Err(DiscussionAccess::new(&disc).denied(&user, Permission::Edit));
         }
 
+        let title = self
+            .content_filter
+            .filter(&data.title.unwrap_or("".to_string()))
+            .await?;
         self.discussion_repository
-            .update(&disc.id.id.to_raw(), &data.title.unwrap_or("".to_string()))
+            .update(&disc.id.id.to_raw(), &title.text)
+            .await?;
+        self.content_filter
+            .flag(&disc.id, title.flagged_words)
             .await?;
 
         Ok(())
     }
 
+    pub async fn typing(&self, user_id: &str, disc_id: &str) -> AppResult<()> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let disc = self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id)
+            .await?;
+
+        if !DiscussionAccess::new(&disc).can_view(&user) {
+            return Err(DiscussionAccess::new(&disc).denied(&user, Permission::View));
+        }
+
+        self.notifications_service
+            .on_discussion_typing(user.id.as_ref().unwrap(), &disc)
+            .await?;
+        Ok(())
+    }
+
     pub async fn update_alias(
         &self,
         user_id: &str,
@@ -136,7 +183,7 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_set_alias(&user) {
-            return Err(AppError::Forbidden);
+            return Err(DiscussionAccess::new(&disc).denied(&user, Permission::Alias));
         }
 
         self.discussion_users
@@ -151,6 +198,7 @@ where
         user_id: &str,
         disc_id: &str,
         new_user_ids: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
     ) -> CtxResult<Vec<Thing>> {
         if new_user_ids.is_empty() {
             return Err(AppError::Generic {
@@ -159,6 +207,13 @@ where
             .into());
         }
 
+        if expires_at.is_some_and(|v| v <= Utc::now()) {
+            return Err(AppError::Generic {
+                description: "Access expiration must be in the future".to_string(),
+            }
+            .into());
+        }
+
         let user = self.user_repository.get_by_id(&user_id).await?;
         let disc = self
             .discussion_repository
@@ -166,7 +221,9 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_add_member(&user) {
-            return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+            return Err(self
+                .ctx
+                .to_ctx_error(DiscussionAccess::new(&disc).denied(&user, Permission::AddMember)));
         }
 
         let user_ids = record_exist_all(self.user_repository.db, new_user_ids).await?;
@@ -181,12 +238,17 @@ where
             .filter(|id| !disc_user_ids.contains(id))
             .collect::<Vec<Thing>>();
 
+        self.blocks_repository
+            .check_not_blocked_by(user.id.as_ref().unwrap().clone(), new_users.clone())
+            .await?;
+
         let disc_id = disc.id.id.to_raw();
         self.access_repository
-            .add(
+            .add_with_expiry(
                 new_users.clone(),
                 vec![&disc.id.to_raw()],
                 Role::Member.to_string(),
+                expires_at,
             )
             .await?;
 
@@ -223,7 +285,9 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_remove_member(&user) {
-            return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+            return Err(self.ctx.to_ctx_error(
+                DiscussionAccess::new(&disc).denied(&user, Permission::RemoveMember),
+            ));
         }
         let user_things = remove_user_ids
             .iter()
@@ -240,6 +304,205 @@ where
         Ok(())
     }
 
+    pub async fn add_moderator(
+        &self,
+        user_id: &str,
+        disc_id: &str,
+        moderator_id: &str,
+    ) -> CtxResult<()> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let disc = self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id)
+            .await?;
+
+        if !DiscussionAccess::new(&disc).can_manage_roles(&user) {
+            return Err(self.ctx.to_ctx_error(
+                DiscussionAccess::new(&disc).denied(&user, Permission::ManageRoles),
+            ));
+        }
+
+        let moderator = self.get_member(&disc, moderator_id).await?;
+        match disc.get_user_role(&moderator).as_deref() {
+            Some(role) if role == Role::Owner.to_string() => {
+                return Err(self.ctx.to_ctx_error(AppError::Generic {
+                    description: "Owner of the discussion can not be a moderator".to_string(),
+                }));
+            }
+            _ => (),
+        };
+
+        self.set_role(&disc, moderator, Role::Moderator).await?;
+        Ok(())
+    }
+
+    pub async fn remove_moderator(
+        &self,
+        user_id: &str,
+        disc_id: &str,
+        moderator_id: &str,
+    ) -> CtxResult<()> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let disc = self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id)
+            .await?;
+
+        if !DiscussionAccess::new(&disc).can_manage_roles(&user) {
+            return Err(self.ctx.to_ctx_error(
+                DiscussionAccess::new(&disc).denied(&user, Permission::ManageRoles),
+            ));
+        }
+
+        let moderator = get_str_thing(moderator_id)?;
+        if disc.get_user_role(&moderator) != Some(Role::Moderator.to_string()) {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "User is not a moderator of the discussion".to_string(),
+            }));
+        }
+
+        // members of private discussions stay in the chat
+        match disc.r#type {
+            DiscussionType::Private => {
+                self.access_repository
+                    .update(moderator, &disc.id.to_raw(), Role::Member.to_string())
+                    .await?
+            }
+            DiscussionType::Public => {
+                self.access_repository
+                    .remove_by_entity(&disc.id.to_raw(), vec![moderator])
+                    .await?
+            }
+        };
+
+        Ok(())
+    }
+
+    pub async fn set_muted(
+        &self,
+        user_id: &str,
+        disc_id: &str,
+        member_id: &str,
+        muted: bool,
+    ) -> CtxResult<()> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let disc = self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id)
+            .await?;
+
+        if !DiscussionAccess::new(&disc).can_mute_member(&user) {
+            return Err(self
+                .ctx
+                .to_ctx_error(DiscussionAccess::new(&disc).denied(&user, Permission::MuteMember)));
+        }
+
+        let member = self.get_member(&disc, member_id).await?;
+        if &member == user.id.as_ref().unwrap() {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "Can not mute yourself".to_string(),
+            }));
+        }
+
+        let role = disc.get_user_role(&member);
+        let protected_roles = [Role::Owner, Role::Moderator, Role::Editor].map(|r| r.to_string());
+        if role.as_ref().is_some_and(|r| protected_roles.contains(r)) {
+            return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+        }
+
+        // the guests of a public discussion can be muted without getting any access to it
+        if role.is_none() && muted && disc.r#type == DiscussionType::Private {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "User is not a member of the discussion".to_string(),
+            }));
+        }
+
+        self.access_repository
+            .set_muted(member, &disc.id.to_raw(), muted)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn transfer_ownership(
+        &self,
+        user_id: &str,
+        disc_id: &str,
+        new_owner_id: &str,
+    ) -> CtxResult<()> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let disc = self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id)
+            .await?;
+
+        if !DiscussionAccess::new(&disc).can_transfer_ownership(&user) {
+            return Err(self.ctx.to_ctx_error(
+                DiscussionAccess::new(&disc).denied(&user, Permission::TransferOwnership),
+            ));
+        }
+
+        if disc.is_profile() {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "Ownership of a profile discussion can not be transferred".to_string(),
+            }));
+        }
+
+        let new_owner = self.get_member(&disc, new_owner_id).await?;
+        let user_thing = user.id.as_ref().unwrap().clone();
+        if new_owner == user_thing {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "User is the owner of the discussion already".to_string(),
+            }));
+        }
+
+        if disc.muted_users.contains(&new_owner) {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "Muted user can not own the discussion".to_string(),
+            }));
+        }
+
+        let query = self.access_repository.build_transfer_query(
+            self.db.query("BEGIN"),
+            &disc.id,
+            &user_thing,
+            Role::Moderator.to_string(),
+            &new_owner,
+            Role::Owner.to_string(),
+        );
+        let query = DiscussionDbService::build_update_owner_query(query, &disc.id, &new_owner);
+        let mut res = query.query("COMMIT").await?;
+        check_transaction_custom_error(&mut res)?;
+        Ok(())
+    }
+
+    async fn get_member(&self, disc: &DiscussionAccessView, member_id: &str) -> CtxResult<Thing> {
+        let member = get_str_thing(member_id)?;
+        if disc.get_user_role(&member).is_none() {
+            let _ = self.user_repository.get_by_id(&member.id.to_raw()).await?;
+        }
+        Ok(member)
+    }
+
+    async fn set_role(
+        &self,
+        disc: &DiscussionAccessView,
+        user: Thing,
+        role: Role,
+    ) -> AppResult<()> {
+        match disc.get_user_role(&user) {
+            Some(_) => {
+                self.access_repository
+                    .update(user, &disc.id.to_raw(), role.to_string())
+                    .await
+            }
+            None => {
+                self.access_repository
+                    .add(vec![user], vec![&disc.id.to_raw()], role.to_string())
+                    .await
+            }
+        }
+    }
+
     pub async fn get(
         &self,
         user_id: &str,
@@ -261,8 +524,16 @@ where
             .get_view_by_id::<DiscussionAccessView>(&disc_id)
             .await?;
 
+        if disc.hidden {
+            return Err(self.ctx.to_ctx_error(AppError::EntityFailIdNotFound {
+                ident: disc_id.to_string(),
+            }));
+        }
+
         if !DiscussionAccess::new(&disc).can_view(&user) {
-            return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+            return Err(self
+                .ctx
+                .to_ctx_error(DiscussionAccess::new(&disc).denied(&user, Permission::View)));
         }
 
         self.discussion_repository
@@ -282,6 +553,7 @@ where
         if !CommunityAccess::new(&comm).can_create_discussion(&user) {
             return Err(self.ctx.to_ctx_error(AppError::Forbidden));
         }
+        let title = self.content_filter.filter(&data.title).await?;
 
         let private_discussion_user_ids = match data.chat_user_ids {
             Some(ids) => {
@@ -290,7 +562,11 @@ where
                     .into_iter()
                     .filter(|u| u != &user_id_str)
                     .collect::<Vec<String>>();
-                Some(record_exist_all(self.user_repository.db, user_ids).await?)
+                let user_things = record_exist_all(self.user_repository.db, user_ids).await?;
+                self.blocks_repository
+                    .check_not_blocked_by(user.id.as_ref().unwrap().clone(), user_things.clone())
+                    .await?;
+                Some(user_things)
             }
             None => None,
         };
@@ -306,13 +582,7 @@ where
 
                 ids.push(user_id.to_string());
 
-                ids.sort();
-
-                let mut hasher = DefaultHasher::new();
-                ids.join("").hash(&mut hasher);
-                let hash_id = hasher.finish();
-
-                let id = Thing::from((DISC_TABLE_NAME, format!("{:x}", hash_id).as_str()));
+                let id = DiscussionDbService::get_private_discussion_id(ids);
                 let res = self
                     .discussion_repository
                     .get_view_by_id::<DiscussionView>(&id.to_raw())
@@ -331,10 +601,14 @@ where
                                 created_at: v.created_at,
                             })
                             .collect::<Vec<AccessUser>>(),
+                        muted_users: vec![],
+                        hidden: false,
                         created_by: user.id.as_ref().unwrap().clone(),
                     };
                     if !DiscussionAccess::new(&access_view).can_view(&user) {
-                        return Err(self.ctx.to_ctx_error(AppError::Forbidden));
+                        return Err(self.ctx.to_ctx_error(
+                            DiscussionAccess::new(&access_view).denied(&user, Permission::View),
+                        ));
                     }
 
                     return Ok(Discussion {
@@ -366,7 +640,7 @@ where
             .create(CreateDiscussionEntity {
                 id: disc_id,
                 belongs_to: comm.id.clone(),
-                title: data.title,
+                title: title.text,
                 image_uri: None,
                 created_by: user.id.as_ref().unwrap().clone(),
                 r#type: disc_type,
@@ -380,6 +654,9 @@ where
                 owner_role.to_string(),
             )
             .await?;
+        self.content_filter
+            .flag(&disc.id, title.flagged_words)
+            .await?;
 
         if let Some(mut user_ids) = private_discussion_user_ids {
             self.access_repository
@@ -400,4 +677,125 @@ where
         }
         Ok(disc)
     }
+
+    /// Returns the direct message discussion with the other user and creates it on the first call,
+    /// the discussion is a message request for the other user if they don't follow the user
+    pub async fn get_or_create_direct(
+        &self,
+        user_id: &str,
+        other_user_id: &str,
+    ) -> CtxResult<Discussion> {
+        let user = self.user_repository.get_by_id(&user_id).await?;
+        let other_user = self.user_repository.get_by_id(&other_user_id).await?;
+        let user_thing = user.id.as_ref().unwrap();
+        let other_thing = other_user.id.as_ref().unwrap();
+
+        if user_thing == other_thing {
+            return Err(self.ctx.to_ctx_error(AppError::Generic {
+                description: "Direct messages to yourself are not allowed".to_string(),
+            }));
+        }
+
+        let disc_id = DiscussionDbService::get_private_discussion_id(vec![
+            user_thing.id.to_raw(),
+            other_thing.id.to_raw(),
+        ]);
+        let existing = match self
+            .discussion_repository
+            .get_view_by_id::<DiscussionAccessView>(&disc_id.to_raw())
+            .await
+        {
+            Ok(disc) => Some(disc),
+            Err(err) => match err.error {
+                AppError::EntityFailIdNotFound { .. } => None,
+                _ => return Err(err),
+            },
+        };
+        let is_new = existing.is_none();
+
+        // the user has declined the request before and lost the access
+        if existing.is_some_and(|disc| disc.get_user_role(user_thing).is_none()) {
+            self.access_repository
+                .add(
+                    vec![user_thing.clone()],
+                    vec![&disc_id.to_raw()],
+                    Role::Member.to_string(),
+                )
+                .await?;
+        }
+
+        let disc = self
+            .create(
+                &user_thing.id.to_raw(),
+                CreateDiscussion {
+                    community_id: CommunityDbService::get_profile_community_id(user_thing).to_raw(),
+                    title: DIRECT_MESSAGE_TITLE.to_string(),
+                    image_uri: None,
+                    chat_user_ids: Some(vec![other_thing.to_raw()]),
+                    private_discussion_users_final: true,
+                },
+            )
+            .await?;
+
+        let disc_id = disc.id.id.to_raw();
+        if is_new {
+            let is_follower = self
+                .follow_repository
+                .is_following(other_thing.clone(), user_thing.clone())
+                .await?;
+            if !is_follower {
+                self.discussion_users
+                    .set_request(&disc_id, &other_thing.id.to_raw(), true)
+                    .await?;
+            }
+        } else if self
+            .discussion_users
+            .get(&disc_id, &user_thing.id.to_raw())
+            .await?
+            .is_none()
+        {
+            // the user has declined the request before
+            self.discussion_users
+                .create(&disc_id, vec![user_thing.clone()])
+                .await?;
+        }
+
+        Ok(disc)
+    }
+
+    pub async fn accept_request(&self, user_id: &str, disc_id: &str) -> AppResult<()> {
+        let disc_id = self.get_request_discussion_id(user_id, disc_id).await?;
+        self.discussion_users
+            .set_request(&disc_id, user_id, false)
+            .await
+    }
+
+    pub async fn decline_request(&self, user_id: &str, disc_id: &str) -> AppResult<()> {
+        let disc_id = self.get_request_discussion_id(user_id, disc_id).await?;
+        let user = Thing::from((USER_TABLE_NAME, user_id));
+        self.discussion_users
+            .remove(&disc_id, vec![user.clone()])
+            .await?;
+        self.access_repository
+            .remove_by_entity(
+                &Thing::from((DISC_TABLE_NAME, disc_id)).to_raw(),
+                vec![user],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_request_discussion_id(&self, user_id: &str, disc_id: &str) -> AppResult<String> {
+        let disc_id = get_str_thing(disc_id)?.id.to_raw();
+        let is_request = self
+            .discussion_users
+            .get(&disc_id, user_id)
+            .await?
+            .map_or(false, |v| v.is_request);
+
+        if !is_request {
+            return Err(AppError::EntityFailIdNotFound { ident: disc_id });
+        }
+        Ok(disc_id)
+    }
 }
@@ -1,8 +1,20 @@
+pub mod access_grant_service;
+pub mod access_schema_service;
+pub mod access_service;
+pub mod account_status_service;
+pub mod admin_service;
 pub mod auth_service;
+pub mod content_filter_service;
 pub mod discussion_service;
+pub mod notification_email_service;
 pub mod notification_service;
 pub mod post_service;
 pub mod post_user_service;
+pub mod push_service;
+pub mod report_service;
+pub mod revenue_service;
+pub mod subscription_service;
 pub mod task_service;
 pub mod user_service;
+pub mod wallet_adjustment_service;
 mod verification_code_service;
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use askama::Template;
+use chrono::Utc;
+use surrealdb::sql::Thing;
+
+use crate::{
+    entities::{
+        notification_email::{NotificationEmail, NotificationEmailDbService},
+        notification_settings::{
+            DigestMode, NotificationChannel, NotificationSettings, NotificationSettingsDbService,
+        },
+        user_auth::local_user_entity::LocalUserDbService,
+    },
+    interfaces::repositories::user_notifications::{
+        GetNotificationOptions, UserNotificationsInterface,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        mw_ctx::CtxState,
+        utils::db_utils::QryOrder,
+    },
+    models::email::{NotificationsDigestEmail, NotificationsEmail},
+};
+
+const QUEUE_BATCH_SIZE: u16 = 500;
+const DIGEST_MAX_NOTIFICATIONS: u8 = 50;
+const MAX_SEND_ATTEMPTS: u8 = 5;
+
+/// Delivers the queued notification emails and the digests of the unread notifications
+pub struct NotificationEmailService<'a> {
+    state: &'a CtxState,
+    email_repository: NotificationEmailDbService<'a>,
+    settings_repository: NotificationSettingsDbService<'a>,
+    users_repository: LocalUserDbService<'a>,
+}
+
+impl<'a> NotificationEmailService<'a> {
+    pub fn new(state: &'a CtxState, ctx: &'a Ctx) -> Self {
+        Self {
+            state,
+            email_repository: NotificationEmailDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            settings_repository: NotificationSettingsDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            users_repository: LocalUserDbService {
+                db: &state.db.client,
+                ctx,
+            },
+        }
+    }
+
+    /// Sends one email per user with the queued notifications,
+    /// the notifications of the users in the quiet hours and the failed ones stay in the queue
+    pub async fn send_queued(&self) -> CtxResult<()> {
+        let queued = self.email_repository.get_list(QUEUE_BATCH_SIZE).await?;
+        if queued.is_empty() {
+            return Ok(());
+        }
+
+        let mut by_user: HashMap<Thing, Vec<NotificationEmail>> = HashMap::new();
+        for item in queued {
+            by_user.entry(item.user.clone()).or_default().push(item);
+        }
+        let user_ids = by_user.keys().cloned().collect::<Vec<Thing>>();
+        let users = self.users_repository.get_by_ids(user_ids.clone()).await?;
+        let settings = self
+            .settings_repository
+            .get_by_users(user_ids)
+            .await?
+            .into_iter()
+            .map(|s| (s.user.clone(), s))
+            .collect::<HashMap<Thing, NotificationSettings>>();
+
+        let now = Utc::now();
+        let mut processed = vec![];
+        let mut failed = vec![];
+        for user in users {
+            let user_id = user.id.as_ref().unwrap();
+            let is_quiet = settings
+                .get(user_id)
+                .and_then(|s| s.quiet_hours.as_ref())
+                .is_some_and(|q| q.contains(now));
+            if is_quiet {
+                by_user.remove(user_id);
+                continue;
+            }
+            let items = by_user.remove(user_id).unwrap_or_default();
+
+            let email = match user.email_verified {
+                Some(ref email) => email,
+                None => {
+                    processed.extend(items.into_iter().map(|i| i.id));
+                    continue;
+                }
+            };
+            match self.send_queued_email(email, user_id, &items).await {
+                Ok(_) => processed.extend(items.into_iter().map(|i| i.id)),
+                Err(err) => {
+                    println!("Error sending notification email: {:?}", err);
+                    for item in items {
+                        match item.attempts + 1 >= MAX_SEND_ATTEMPTS {
+                            true => processed.push(item.id),
+                            false => failed.push(item.id),
+                        }
+                    }
+                }
+            }
+        }
+
+        // the queued notifications of the deleted users
+        processed.extend(by_user.into_values().flatten().map(|i| i.id));
+        self.email_repository.delete(processed).await?;
+        self.email_repository.increase_attempts(failed).await
+    }
+
+    async fn send_queued_email(
+        &self,
+        email: &str,
+        user: &Thing,
+        items: &[NotificationEmail],
+    ) -> CtxResult<()> {
+        let subject = match items {
+            [item] => item.title.clone(),
+            _ => format!("You have {} new notifications", items.len()),
+        };
+        let unsubscribe_url = self.get_unsubscribe_url(user).await?;
+        let body = NotificationsEmail {
+            titles: items.iter().map(|i| i.title.as_str()).collect(),
+            app_url: &self.state.app_url,
+            unsubscribe_url: &unsubscribe_url,
+        }
+        .render()
+        .map_err(|e| AppError::Generic {
+            description: e.to_string(),
+        })?;
+        self.send(email, &body, &subject).await
+    }
+
+    /// Sends the digest of the unread notifications to the users with the digest mode
+    pub async fn send_digests(&self) -> CtxResult<()> {
+        let now = Utc::now();
+        let due = self.settings_repository.get_digest_due(now).await?;
+        for settings in due {
+            if let Some(ref quiet_hours) = settings.quiet_hours {
+                if quiet_hours.contains(now) {
+                    continue;
+                }
+            }
+            if let Err(err) = self.send_digest(&settings).await {
+                println!("Error sending notification digest: {:?}", err);
+            }
+            self.settings_repository
+                .set_last_digest_at(&settings.user, now)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn send_digest(&self, settings: &NotificationSettings) -> CtxResult<()> {
+        let user = self
+            .users_repository
+            .get_by_id(&settings.user.id.to_raw())
+            .await?;
+        let email = match user.email_verified {
+            Some(ref email) => email,
+            None => return Ok(()),
+        };
+
+        let notifications = self
+            .state
+            .db
+            .user_notifications
+            .get_by_user(
+                &settings.user.id.to_raw(),
+                GetNotificationOptions {
+                    limit: DIGEST_MAX_NOTIFICATIONS,
+                    start: Utc::now(),
+                    filter_by_types: None,
+                    is_read: Some(false),
+                    is_archived: false,
+                    order_dir: QryOrder::DESC,
+                },
+            )
+            .await?
+            .into_iter()
+            .filter(|n| settings.last_digest_at.map_or(true, |v| n.created_at > v))
+            .filter(|n| settings.is_enabled(&n.event, NotificationChannel::Email))
+            .collect::<Vec<_>>();
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        let period = match settings.digest {
+            DigestMode::Weekly => "weekly",
+            _ => "daily",
+        };
+        let unsubscribe_url = self.get_unsubscribe_url(&settings.user).await?;
+        let body = NotificationsDigestEmail {
+            period,
+            titles: notifications.iter().map(|n| n.title.as_str()).collect(),
+            app_url: &self.state.app_url,
+            unsubscribe_url: &unsubscribe_url,
+        }
+        .render()
+        .map_err(|e| AppError::Generic {
+            description: e.to_string(),
+        })?;
+        self.send(email, &body, &format!("Your {} Darve digest", period))
+            .await
+    }
+
+    async fn get_unsubscribe_url(&self, user: &Thing) -> CtxResult<String> {
+        let token = self.settings_repository.get_unsubscribe_token(user).await?;
+        Ok(format!(
+            "{}/api/notifications/email/unsubscribe?token={}",
+            self.state.app_url, token
+        ))
+    }
+
+    async fn send(&self, email: &str, body: &str, subject: &str) -> CtxResult<()> {
+        self.state
+            .email_sender
+            .send(vec![email.to_string()], body, subject)
+            .await
+            .map_err(|description| AppError::Generic { description })?;
+        Ok(())
+    }
+}
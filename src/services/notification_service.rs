@@ -1,33 +1,65 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use serde_json::json;
-use tokio::sync::broadcast::Sender;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
 
 use crate::access::base::role::Role;
 use crate::database::client::Db;
+use crate::database::table_names::NOTIFICATION_TABLE_NAME;
 use crate::entities::community::discussion_entity::{
     DiscussionType, TABLE_NAME as DISC_TABLE_NAME,
 };
 use crate::entities::community::post_entity::{PostType, TABLE_NAME as POST_TABLE_NAME};
 use crate::entities::discussion_user::DiscussionUser;
+use crate::entities::notification_email::NotificationEmailDbService;
+use crate::entities::notification_settings::{
+    DigestMode, NotificationChannel, NotificationSettings, NotificationSettingsDbService,
+};
 use crate::entities::task_request::TaskRequestEntity;
 use crate::entities::task_request::{TaskParticipantUserView, TaskRequestType};
-use crate::entities::user_notification::UserNotificationEvent;
+use crate::entities::user_notification::{UserNotification, UserNotificationEvent};
 use crate::entities::wallet::wallet_entity::CurrencySymbol;
-use crate::interfaces::repositories::user_notifications::UserNotificationsInterface;
+use crate::interfaces::event_bus::EventBusInterface;
+use crate::interfaces::repositories::user_notifications::{
+    NotificationGroupOptions, UserNotificationsInterface,
+};
 use crate::middleware::error::AppResult;
 use crate::models::view::access::{DiscussionAccessView, PostAccessView, TaskAccessView};
 use crate::models::view::post::PostView;
+use crate::models::view::reply::ReplyView;
 use crate::{
-    entities::user_auth::{follow_entity::FollowDbService, local_user_entity::LocalUser},
+    entities::user_auth::{
+        block_entity::{BlockDbService, BlockType},
+        follow_entity::FollowDbService,
+        local_user_entity::{LocalUser, TABLE_NAME as USER_TABLE_NAME},
+    },
     middleware::{
         ctx::Ctx,
         error::{AppError, CtxResult},
-        mw_ctx::{AppEvent, AppEventType},
+        mw_ctx::{AppEvent, AppEventMetadata, AppEventType},
     },
 };
 
-use surrealdb::sql::Thing;
+use surrealdb::sql::{Id, Thing};
+
+// the events of the group raised within the window are collected in one notification
+const NOTIFICATION_GROUP_WINDOW_HOURS: i64 = 24;
+const NOTIFICATION_GROUP_MAX_ACTORS: usize = 10;
+
+/// Target of the grouped notification, e.g. "X and 12 others liked the post"
+struct NotificationGroup<'a> {
+    key: String,
+    actor: &'a str,
+    action: String,
+}
+
+impl NotificationGroup<'_> {
+    // the titles with the other actors are set by the repository with the stored count
+    fn title(&self) -> String {
+        format!("{} {}", self.actor, self.action)
+    }
+}
 
 pub enum OnCreatedTaskView<'a> {
     Post(&'a PostAccessView),
@@ -39,8 +71,11 @@ where
     N: UserNotificationsInterface,
 {
     follow_repository: FollowDbService<'a>,
+    blocks_repository: BlockDbService<'a>,
+    settings_repository: NotificationSettingsDbService<'a>,
+    email_repository: NotificationEmailDbService<'a>,
     notification_repository: &'a N,
-    event_sender: &'a Sender<AppEvent>,
+    event_sender: &'a (dyn EventBusInterface + Send + Sync),
     ctx: &'a Ctx,
 }
 
@@ -51,11 +86,14 @@ where
     pub fn new(
         db: &'a Db,
         ctx: &'a Ctx,
-        event_sender: &'a Sender<AppEvent>,
+        event_sender: &'a (dyn EventBusInterface + Send + Sync),
         notification_repository: &'a N,
     ) -> NotificationService<'a, N> {
         NotificationService {
             follow_repository: FollowDbService { db, ctx },
+            blocks_repository: BlockDbService { db, ctx },
+            settings_repository: NotificationSettingsDbService { db, ctx },
+            email_repository: NotificationEmailDbService { db, ctx },
             notification_repository,
             event_sender,
             ctx,
@@ -104,60 +142,61 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(current_user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
         let user_id_str = current_user_id.id.to_raw();
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id_str,
-                format!("{} liked the post", user.username).as_str(),
-                &UserNotificationEvent::UserLikePost.as_str(),
-                &receivers,
-                Some(json!({
-                    "post_id": post.id.to_raw(),
-                    "media_links": post.media_links,
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
-            user_id: user_id_str,
-            metadata: None,
-            event: AppEventType::UserNotificationEvent(event),
-            content: None,
+        self.notify_grouped(
+            &user_id_str,
+            NotificationGroup {
+                key: post.id.to_raw(),
+                actor: &user.username,
+                action: "liked the post".to_string(),
+            },
+            UserNotificationEvent::UserLikePost,
             receivers,
-        });
+            Some(json!({
+                "post_id": post.id.to_raw(),
+                "media_links": post.media_links,
+            })),
+        )
+        .await?;
 
         Ok(())
     }
 
     pub async fn on_follow(&self, user: &LocalUser, follow: &LocalUser) -> CtxResult<()> {
         let user_id_str = user.id.as_ref().unwrap().id.to_raw();
-        let receivers = vec![follow.id.as_ref().unwrap().id.to_raw()];
-        let event = self
-            .notification_repository
-            .create(
-                &user_id_str,
-                format!("{} started following {}", user.username, follow.username).as_str(),
-                UserNotificationEvent::UserFollowAdded.as_str(),
-                &receivers,
-                Some(json!({
-                    "username": user.username.clone(),
-                    "follows_username": follow.username
-                })),
+        let receivers = self
+            .without_silenced(
+                user.id.as_ref().unwrap(),
+                vec![follow.id.as_ref().unwrap().id.to_raw()],
             )
             .await?;
-        let _ = self.event_sender.send(AppEvent {
+
+        if receivers.is_empty() {
+            return Ok(());
+        }
+
+        self.notify_grouped(
+            &user_id_str,
+            NotificationGroup {
+                key: follow.id.as_ref().unwrap().to_raw(),
+                actor: &user.username,
+                action: format!("started following {}", follow.username),
+            },
+            UserNotificationEvent::UserFollowAdded,
             receivers,
-            user_id: user_id_str,
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "username": user.username.clone(),
+                "follows_username": follow.username
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -204,35 +243,27 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} delivered the task.", user.username).as_str(),
-                UserNotificationEvent::UserTaskRequestDelivered.as_str(),
-                &receivers,
-                Some(json!({
-                    "task_id": task_view.id.to_raw(),
-                    "result_post_id": result_post,
-                    "result_link": result_link,
-                    "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
-                    "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
-
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} delivered the task.", user.username).as_str(),
+            UserNotificationEvent::UserTaskRequestDelivered,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "task_id": task_view.id.to_raw(),
+                "result_post_id": result_post,
+                "result_link": result_link,
+                "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
+                "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
+
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -273,32 +304,24 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} rejected the task.", user.username).as_str(),
-                UserNotificationEvent::UserTaskRequestRejected.as_str(),
-                &receivers,
-                Some(json!({
-                    "task_id": task_view.id.to_raw(),
-                    "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
-                    "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} rejected the task.", user.username).as_str(),
+            UserNotificationEvent::UserTaskRequestRejected,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "task_id": task_view.id.to_raw(),
+                "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
+                "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -343,32 +366,24 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} accepted the task.", user.username).as_str(),
-                UserNotificationEvent::UserTaskRequestAccepted.as_str(),
-                &receivers,
-                Some(json!({
-                    "task_id": task_view.id.to_raw(),
-                    "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
-                    "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} accepted the task.", user.username).as_str(),
+            UserNotificationEvent::UserTaskRequestAccepted,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "task_id": task_view.id.to_raw(),
+                "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
+                "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -429,6 +444,8 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
@@ -439,34 +456,24 @@ where
             .and_then(|p| p.media_links.as_ref())
             .and_then(|links| links.first());
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!(
-                    "{} donated ${} on the task.",
-                    user.username,
-                    (amount as f64 / 100.0)
-                )
-                .as_str(),
-                UserNotificationEvent::DonateTaskRequest.as_str(),
-                &receivers,
-                Some(json!({
-                    "task_id": task_view.id.to_raw(),
-                    "media_link": link,
-                    "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
-                    "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
-                })),
+        self.notify(
+            &user_id.id.to_raw(),
+            format!(
+                "{} donated ${} on the task.",
+                user.username,
+                (amount as f64 / 100.0)
             )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+            .as_str(),
+            UserNotificationEvent::DonateTaskRequest,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "task_id": task_view.id.to_raw(),
+                "media_link": link,
+                "post_id": task_view.post.as_ref().map(|p| p.id.to_raw()),
+                "discussion_id": task_view.discussion.as_ref().map(|p| p.id.to_raw()),
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -488,28 +495,18 @@ where
             _ => ("".to_string(), "".to_string()),
         };
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} received reward for the task.", user.username).as_str(),
-                UserNotificationEvent::DonateTaskRequest.as_str(),
-                &receivers,
-                Some(json!({
-                    "task_id": task_id,
-                    "post_id": post_id,
-                    "discussion_id":  discussion_id
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} received reward for the task.", user.username).as_str(),
+            UserNotificationEvent::TaskRewardReceived,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "task_id": task_id,
+                "post_id": post_id,
+                "discussion_id":  discussion_id
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -543,29 +540,22 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                &format!("{} created the reply", user.username),
-                UserNotificationEvent::CommentAdded.as_str(),
-                &receivers,
-                Some(json!({
-                    "post_id": post.id.to_raw(),
-                })),
-            )
-            .await?;
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            &format!("{} created the reply", user.username),
+            UserNotificationEvent::CommentAdded,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "post_id": post.id.to_raw(),
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -585,75 +575,74 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                &format!("{} liked the reply", user.username),
-                UserNotificationEvent::UserLikeComment.as_str(),
-                &receivers,
-                Some(json!({
-                    "post_id": post.id.to_raw(),
-                })),
-            )
-            .await?;
-        let _ = self.event_sender.send(AppEvent {
+        self.notify_grouped(
+            &user_id.id.to_raw(),
+            NotificationGroup {
+                key: post.id.to_raw(),
+                actor: &user.username,
+                action: "liked the reply".to_string(),
+            },
+            UserNotificationEvent::UserLikeComment,
             receivers,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "post_id": post.id.to_raw(),
+            })),
+        )
+        .await?;
 
         Ok(())
     }
 
     pub async fn on_completed_deposit(&self, user: &Thing) -> CtxResult<()> {
         let receivers = vec![user.id.to_raw()];
-        let event = self
-            .notification_repository
-            .create(
-                &user.id.to_raw(),
-                "Deposit completed",
-                UserNotificationEvent::DepositCompleted.as_str(),
-                &receivers,
-                None,
-            )
-            .await?;
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user.id.to_raw(),
+            "Deposit completed",
+            UserNotificationEvent::DepositCompleted,
             receivers,
-            user_id: user.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            None,
+        )
+        .await?;
 
         Ok(())
     }
 
     pub async fn on_completed_withdraw(&self, user: &Thing) -> CtxResult<()> {
         let receivers = vec![user.id.to_raw()];
-        let event = self
-            .notification_repository
-            .create(
-                &user.id.to_raw(),
-                "Withdraw completed",
-                UserNotificationEvent::WithdrawCompleted.as_str(),
-                &receivers,
-                None,
-            )
-            .await?;
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user.id.to_raw(),
+            "Withdraw completed",
+            UserNotificationEvent::WithdrawCompleted,
             receivers,
-            user_id: user.id.to_raw(),
-            metadata: None,
-            content: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            None,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn on_moderation_warning(
+        &self,
+        user: &Thing,
+        entity: &Thing,
+        note: Option<String>,
+    ) -> CtxResult<()> {
+        let receivers = vec![user.id.to_raw()];
+        let metadata = json!({ "entity_id": entity.to_raw(), "note": note });
+        self.notify(
+            &user.id.to_raw(),
+            "Your content violates the community rules",
+            UserNotificationEvent::ModerationWarning,
+            receivers,
+            Some(metadata),
+        )
+        .await?;
 
         Ok(())
     }
@@ -694,35 +683,32 @@ where
             .map(|u| u.id.as_ref().unwrap())
             .collect::<Vec<&Thing>>();
 
-        if !participant_ids.is_empty() {
-            let ids = participant_ids
-                .iter()
-                .map(|id| id.id.to_raw())
-                .collect::<Vec<String>>();
+        let participant_receivers = self
+            .without_silenced(
+                user_id,
+                participant_ids
+                    .iter()
+                    .map(|id| id.id.to_raw())
+                    .collect::<Vec<String>>(),
+            )
+            .await?;
+
+        if !participant_receivers.is_empty() {
+            let ids = participant_receivers;
 
             let amount_meesage = match amount {
                 Some(v) if v > 0 => format!("${}", v as f64 / 100.0),
                 _ => "a".to_string(),
             };
 
-            let event = self
-                .notification_repository
-                .create(
-                    &user_id.id.to_raw(),
-                    format!("{} created {} task for you", user.username, amount_meesage).as_str(),
-                    UserNotificationEvent::UserTaskRequestReceived.as_str(),
-                    &ids,
-                    metadata.clone(),
-                )
-                .await?;
-
-            let _ = self.event_sender.send(AppEvent {
-                receivers: ids,
-                content: None,
-                user_id: user_id.id.to_raw(),
-                metadata: None,
-                event: AppEventType::UserNotificationEvent(event),
-            });
+            self.notify(
+                &user_id.id.to_raw(),
+                format!("{} created {} task for you", user.username, amount_meesage).as_str(),
+                UserNotificationEvent::UserTaskRequestReceived,
+                ids,
+                metadata.clone(),
+            )
+            .await?;
         };
 
         let receiver_things = match view {
@@ -760,27 +746,19 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} created a task", user.username).as_str(),
-                UserNotificationEvent::UserTaskRequestCreated.as_str(),
-                &receivers.clone(),
-                metadata,
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} created a task", user.username).as_str(),
+            UserNotificationEvent::UserTaskRequestCreated,
             receivers,
-            content: None,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            metadata,
+        )
+        .await?;
 
         Ok(())
     }
@@ -791,15 +769,108 @@ where
         receivers: Vec<String>,
         post: &PostView,
     ) -> CtxResult<()> {
-        let post_json = serde_json::to_string(&post).map_err(|_| {
+        self.send_post_event(user_id, receivers, post, AppEventType::DiscussionPostAdded)
+            .await
+    }
+
+    pub async fn on_discussion_post_updated(
+        &self,
+        user_id: &str,
+        receivers: Vec<String>,
+        post: &PostView,
+    ) -> CtxResult<()> {
+        self.send_post_event(
+            user_id,
+            receivers,
+            post,
+            AppEventType::DiscussionPostUpdated,
+        )
+        .await
+    }
+
+    pub async fn on_discussion_reply_updated(
+        &self,
+        user_id: &str,
+        post_access: &PostAccessView,
+        reply: &ReplyView,
+    ) -> CtxResult<()> {
+        let receivers = post_access.get_receiver_ids();
+        self.send_discussion_event(
+            user_id,
+            receivers,
+            reply,
+            AppEventType::DiscussionReplyUpdated,
+        )
+        .await
+    }
+
+    // the locked content of the paid post is unlocked only for the receivers who can view it
+    async fn send_post_event(
+        &self,
+        user_id: &str,
+        receivers: Vec<String>,
+        post: &PostView,
+        event: AppEventType,
+    ) -> CtxResult<()> {
+        let mut groups: HashMap<(bool, bool), Vec<String>> = HashMap::new();
+        for receiver in receivers {
+            let user = Thing::from((USER_TABLE_NAME, receiver.as_str()));
+            let reply_unlocked = post
+                .reply_to
+                .as_ref()
+                .map_or(true, |reply_to| reply_to.is_unlocked_for(&user));
+            groups
+                .entry((post.is_unlocked_for(&user), reply_unlocked))
+                .or_default()
+                .push(receiver);
+        }
+
+        for ((post_unlocked, reply_unlocked), receivers) in groups {
+            let mut data = post.clone();
+            if !post_unlocked {
+                data.lock_content();
+            }
+            if let Some(reply_to) = data.reply_to.as_mut().filter(|_| !reply_unlocked) {
+                reply_to.lock_content();
+            }
+            self.send_discussion_event(user_id, receivers, &data, event.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn send_discussion_event<T: Serialize>(
+        &self,
+        user_id: &str,
+        receivers: Vec<String>,
+        data: &T,
+        event: AppEventType,
+    ) -> CtxResult<()> {
+        let post_json = serde_json::to_string(data).map_err(|_| {
             self.ctx.to_ctx_error(AppError::Generic {
                 description: "Post to json error for notification event".to_string(),
             })
         })?;
 
+        let blocking_users = self
+            .blocks_repository
+            .get_blocking_users(
+                Thing::from((USER_TABLE_NAME, user_id)),
+                Self::to_user_things(&receivers),
+                vec![BlockType::Block],
+            )
+            .await?
+            .into_iter()
+            .map(|id| id.id.to_raw())
+            .collect::<HashSet<String>>();
+        let receivers = receivers
+            .into_iter()
+            .filter(|id| !blocking_users.contains(id))
+            .collect::<Vec<String>>();
+
         let _ = self.event_sender.send(AppEvent {
             user_id: user_id.to_string(),
-            event: AppEventType::DiscussionPostAdded,
+            event,
             content: Some(post_json),
             receivers,
             metadata: None,
@@ -808,6 +879,67 @@ where
         Ok(())
     }
 
+    pub async fn on_discussion_typing(
+        &self,
+        user_id: &Thing,
+        discussion: &DiscussionAccessView,
+    ) -> CtxResult<()> {
+        let receivers = discussion
+            .get_user_ids()
+            .into_iter()
+            .filter(|id| id != user_id)
+            .map(|id| id.id.to_raw())
+            .collect::<Vec<String>>();
+
+        if receivers.is_empty() {
+            return Ok(());
+        }
+
+        let _ = self.event_sender.send(AppEvent {
+            user_id: user_id.id.to_raw(),
+            event: AppEventType::DiscussionTyping,
+            content: None,
+            receivers,
+            metadata: Some(AppEventMetadata {
+                discussion_id: Some(discussion.id.clone()),
+                post_id: None,
+            }),
+        });
+
+        Ok(())
+    }
+
+    pub async fn on_post_read(&self, user_id: &Thing, post: &PostAccessView) -> CtxResult<()> {
+        if post.discussion.r#type != DiscussionType::Private {
+            return Ok(());
+        }
+
+        let receivers = post
+            .discussion
+            .get_user_ids()
+            .into_iter()
+            .filter(|id| id != user_id)
+            .map(|id| id.id.to_raw())
+            .collect::<Vec<String>>();
+
+        if receivers.is_empty() {
+            return Ok(());
+        }
+
+        let _ = self.event_sender.send(AppEvent {
+            user_id: user_id.id.to_raw(),
+            event: AppEventType::DiscussionPostRead,
+            content: None,
+            receivers,
+            metadata: Some(AppEventMetadata {
+                discussion_id: Some(post.discussion.id.clone()),
+                post_id: Some(post.id.clone()),
+            }),
+        });
+
+        Ok(())
+    }
+
     pub async fn on_updated_users_discussions(
         &self,
         user_id: &Thing,
@@ -847,30 +979,22 @@ where
             })
             .collect::<Vec<String>>();
 
+        let receivers = self.without_silenced(user_id, receivers).await?;
+
         if receivers.is_empty() {
             return Ok(());
         }
 
-        let event = self
-            .notification_repository
-            .create(
-                &user_id.id.to_raw(),
-                format!("{} created the discussion", user.username).as_str(),
-                UserNotificationEvent::CreatedDiscussion.as_str(),
-                &receivers.clone(),
-                Some(json!({
-                    "discussion_id": discussion_id.to_raw()
-                })),
-            )
-            .await?;
-
-        let _ = self.event_sender.send(AppEvent {
+        self.notify(
+            &user_id.id.to_raw(),
+            format!("{} created the discussion", user.username).as_str(),
+            UserNotificationEvent::CreatedDiscussion,
             receivers,
-            content: None,
-            user_id: user_id.id.to_raw(),
-            metadata: None,
-            event: AppEventType::UserNotificationEvent(event),
-        });
+            Some(json!({
+                "discussion_id": discussion_id.to_raw()
+            })),
+        )
+        .await?;
 
         Ok(())
     }
@@ -900,4 +1024,221 @@ where
     async fn get_follower_ids(&self, user: Thing) -> CtxResult<Vec<Thing>> {
         Ok(self.follow_repository.user_follower_ids(user).await?)
     }
+
+    /// Creates and sends the notification to the receivers respecting their notification settings
+    async fn notify(
+        &self,
+        creator: &str,
+        title: &str,
+        event: UserNotificationEvent,
+        receivers: Vec<String>,
+        metadata: Option<Value>,
+    ) -> CtxResult<()> {
+        self.send(creator, title, event, receivers, metadata, None)
+            .await
+    }
+
+    /// Same as notify but updates the notification of the group created within the window
+    /// instead of creating a new one
+    async fn notify_grouped(
+        &self,
+        creator: &str,
+        group: NotificationGroup<'_>,
+        event: UserNotificationEvent,
+        receivers: Vec<String>,
+        metadata: Option<Value>,
+    ) -> CtxResult<()> {
+        let title = group.title();
+        self.send(creator, &title, event, receivers, metadata, Some(group))
+            .await
+    }
+
+    async fn send(
+        &self,
+        creator: &str,
+        title: &str,
+        event: UserNotificationEvent,
+        receivers: Vec<String>,
+        metadata: Option<Value>,
+        group: Option<NotificationGroup<'_>>,
+    ) -> CtxResult<()> {
+        let settings = self
+            .settings_repository
+            .get_by_users(Self::to_user_things(&receivers))
+            .await?
+            .into_iter()
+            .map(|s| (s.user.id.to_raw(), s))
+            .collect::<HashMap<String, NotificationSettings>>();
+        let now = Utc::now();
+        let allowed = |channel: NotificationChannel| {
+            receivers
+                .iter()
+                .filter(|id| {
+                    settings
+                        .get(*id)
+                        .map_or(true, |s| s.allows(&event, channel, now))
+                })
+                .cloned()
+                .collect::<Vec<String>>()
+        };
+        let in_app_receivers = allowed(NotificationChannel::InApp);
+        let sse_receivers = allowed(NotificationChannel::Sse);
+        let push_receivers = allowed(NotificationChannel::Push);
+
+        if event.is_emailed() {
+            // the users with the digest get the unread notifications in the digest email
+            // and the quiet hours only postpone the queued emails
+            let email_receivers = receivers
+                .iter()
+                .filter(|id| {
+                    settings.get(*id).map_or(true, |s| {
+                        s.digest == DigestMode::Off
+                            && s.is_enabled(&event, NotificationChannel::Email)
+                    })
+                })
+                .cloned()
+                .collect::<Vec<String>>();
+            if !email_receivers.is_empty() {
+                self.email_repository
+                    .create(Self::to_user_things(&email_receivers), event.clone(), title)
+                    .await?;
+            }
+        }
+
+        let mut deliveries = Vec::new();
+        match group {
+            // every receiver has an own grouped notification with the actors who notified them
+            Some(group) if !in_app_receivers.is_empty() => {
+                let notifications = self
+                    .notification_repository
+                    .upsert_groups(
+                        creator,
+                        title,
+                        event.as_str(),
+                        &in_app_receivers,
+                        metadata.clone(),
+                        NotificationGroupOptions {
+                            key: &group.key,
+                            actor: group.actor,
+                            action: &group.action,
+                            max_actors: NOTIFICATION_GROUP_MAX_ACTORS,
+                            since: now - Duration::hours(NOTIFICATION_GROUP_WINDOW_HOURS),
+                        },
+                    )
+                    .await?;
+                for (notification, receiver) in notifications.into_iter().zip(&in_app_receivers) {
+                    let is_updated = notification.updated_at.is_some();
+                    deliveries.push((notification, is_updated, vec![receiver.clone()]));
+                }
+            }
+            Some(_) => (),
+            None if !in_app_receivers.is_empty() => {
+                let notification = self
+                    .notification_repository
+                    .create(
+                        creator,
+                        title,
+                        event.as_str(),
+                        &in_app_receivers,
+                        metadata.clone(),
+                        None,
+                    )
+                    .await?;
+                deliveries.push((notification, false, in_app_receivers.clone()));
+            }
+            None => (),
+        };
+
+        // the live events are still sent to the users without the stored notifications
+        let live_receivers = sse_receivers
+            .iter()
+            .chain(push_receivers.iter())
+            .filter(|id| !in_app_receivers.contains(id))
+            .cloned()
+            .collect::<HashSet<String>>();
+        if !live_receivers.is_empty() {
+            let notification = UserNotification {
+                // not stored, the id only tells the events apart on the clients
+                id: Thing::from((NOTIFICATION_TABLE_NAME, Id::ulid())).to_raw(),
+                created_by: creator.to_string(),
+                event,
+                title: title.to_string(),
+                is_read: false,
+                metadata,
+                created_at: now,
+                actors: vec![creator.to_string()],
+                all_actors: vec![],
+                actors_count: 1,
+                updated_at: None,
+                is_ephemeral: true,
+            };
+            deliveries.push((notification, false, live_receivers.into_iter().collect()));
+        }
+
+        for (notification, is_updated, receivers) in deliveries {
+            let push = receivers
+                .iter()
+                .filter(|id| push_receivers.contains(id))
+                .cloned()
+                .collect::<Vec<String>>();
+            let sse = receivers
+                .into_iter()
+                .filter(|id| sse_receivers.contains(id))
+                .collect::<Vec<String>>();
+
+            // the devices are notified only about the first event of the group
+            if !push.is_empty() && !is_updated {
+                let _ = self.event_sender.send(AppEvent {
+                    receivers: push,
+                    user_id: creator.to_string(),
+                    metadata: None,
+                    content: None,
+                    event: AppEventType::PushNotification(notification.clone()),
+                });
+            }
+            if !sse.is_empty() {
+                let _ = self.event_sender.send(AppEvent {
+                    receivers: sse,
+                    user_id: creator.to_string(),
+                    metadata: None,
+                    content: None,
+                    event: match is_updated {
+                        true => AppEventType::UserNotificationUpdated(notification),
+                        false => AppEventType::UserNotificationEvent(notification),
+                    },
+                });
+            }
+        }
+        Ok(())
+    }
+
+    // removes the receivers who have blocked or muted the user
+    async fn without_silenced(
+        &self,
+        user: &Thing,
+        receivers: Vec<String>,
+    ) -> CtxResult<Vec<String>> {
+        let silenced_by = self
+            .blocks_repository
+            .get_blocking_users(
+                user.clone(),
+                Self::to_user_things(&receivers),
+                vec![BlockType::Block, BlockType::Mute],
+            )
+            .await?
+            .into_iter()
+            .map(|id| id.id.to_raw())
+            .collect::<HashSet<String>>();
+
+        Ok(receivers
+            .into_iter()
+            .filter(|id| !silenced_by.contains(id))
+            .collect())
+    }
+
+    fn to_user_things(ids: &Vec<String>) -> Vec<Thing> {
+        ids.iter()
+            .map(|id| Thing::from((USER_TABLE_NAME, id.as_str())))
+            .collect()
+    }
 }
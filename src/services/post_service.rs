@@ -1,16 +1,31 @@
 use std::sync::Arc;
 
 use crate::{
-    access::{base::role::Role, discussion::DiscussionAccess, post::PostAccess},
-    database::client::Db,
+    access::{
+        base::{permission::Permission, role::Role},
+        discussion::DiscussionAccess,
+        post::PostAccess,
+    },
+    database::{client::Db, table_names::ACCESS_TABLE_NAME},
     entities::{
+        audit_log::{AuditAction, CreateAuditLog},
         community::{
             discussion_entity::{DiscussionDbService, DiscussionType},
             post_entity::{CreatePost, PostDbService, PostType},
         },
-        user_auth::local_user_entity::{LocalUser, LocalUserDbService},
+        user_auth::{
+            block_entity::BlockDbService,
+            local_user_entity::{LocalUser, LocalUserDbService},
+        },
+        wallet::{
+            balance_transaction_entity::{BalanceTransactionDbService, TransactionType},
+            wallet_entity::{
+                check_transaction_custom_error, CurrencySymbol, WalletDbService, DARVE_WALLET,
+            },
+        },
     },
     interfaces::{
+        event_bus::EventBusInterface,
         file_storage::FileStorageInterface,
         repositories::{
             access::AccessRepositoryInterface, discussion_user::DiscussionUserRepositoryInterface,
@@ -21,7 +36,7 @@ use crate::{
     middleware::{
         ctx::Ctx,
         error::{AppError, AppResult, CtxResult},
-        mw_ctx::AppEvent,
+        request_meta::RequestMeta,
         utils::{
             db_utils::{CursorPagination, QryOrder},
             string_utils::get_str_thing,
@@ -33,7 +48,9 @@ use crate::{
         post::{PostUsersView, PostView},
         user::UserView,
     },
-    services::notification_service::NotificationService,
+    services::{
+        content_filter_service::ContentFilterService, notification_service::NotificationService,
+    },
     utils::{
         file::convert::{convert_field_file_data, FileUpload},
         validate_utils::validate_tags,
@@ -41,13 +58,15 @@ use crate::{
 };
 
 use axum_typed_multipart::{FieldData, TryFromMultipart};
+use chrono::{DateTime, Duration, Utc};
 use futures::future::join_all;
 use serde::Deserialize;
 use surrealdb::sql::Thing;
 use tempfile::NamedTempFile;
-use tokio::sync::broadcast::Sender;
 use validator::Validate;
 
+const THROW_POST_ALREADY_BOUGHT: &str = "Post already bought";
+
 #[derive(Debug, Deserialize)]
 pub struct GetPostsParams {
     pub filter_by_type: Option<PostType>,
@@ -62,6 +81,17 @@ pub struct PostLikeData {
     pub count: Option<u16>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct PostUpdateInput {
+    #[validate(length(min = 5, message = "Min 5 characters"))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, message = "Content cannot be empty"))]
+    pub content: Option<String>,
+    #[validate(length(max = 5, message = "Max 5 tags"))]
+    #[validate(custom(function=validate_tags))]
+    pub tags: Option<Vec<String>>,
+}
+
 #[derive(Debug, Validate, TryFromMultipart)]
 pub struct PostInput {
     #[validate(length(min = 5, message = "Min 5 characters"))]
@@ -76,6 +106,10 @@ pub struct PostInput {
     pub is_idea: Option<bool>,
     pub users: Vec<String>,
     pub reply_to: Option<String>,
+    pub for_subscribers: Option<bool>,
+    #[validate(range(min = 1, message = "Min price is 1"))]
+    pub price: Option<u64>,
+    pub currency: Option<CurrencySymbol>,
 }
 
 pub struct PostService<'a, N, T, L, A, DU>
@@ -86,6 +120,7 @@ where
     A: AccessRepositoryInterface,
     DU: DiscussionUserRepositoryInterface,
 {
+    db: &'a Db,
     users_repository: LocalUserDbService<'a>,
     discussions_repository: DiscussionDbService<'a>,
     posts_repository: PostDbService<'a>,
@@ -95,6 +130,8 @@ where
     tags_repository: &'a T,
     access_repository: &'a A,
     discussion_users: &'a DU,
+    blocks_repository: BlockDbService<'a>,
+    content_filter: ContentFilterService<'a>,
 }
 
 impl<'a, N, T, L, A, DU> PostService<'a, N, T, L, A, DU>
@@ -108,7 +145,7 @@ where
     pub fn new(
         db: &'a Db,
         ctx: &'a Ctx,
-        event_sender: &'a Sender<AppEvent>,
+        event_sender: &'a (dyn EventBusInterface + Send + Sync),
         notification_repository: &'a N,
         file_storage: Arc<dyn FileStorageInterface + Send + Sync>,
         tags_repository: &'a T,
@@ -117,6 +154,7 @@ where
         discussion_users: &'a DU,
     ) -> Self {
         Self {
+            db,
             users_repository: LocalUserDbService { db: &db, ctx: &ctx },
             posts_repository: PostDbService { db: &db, ctx: &ctx },
             notification_service: NotificationService::new(
@@ -131,6 +169,8 @@ where
             likes_repository,
             access_repository,
             discussion_users,
+            blocks_repository: BlockDbService { db: &db, ctx },
+            content_filter: ContentFilterService::new(db, ctx),
         }
     }
 
@@ -142,11 +182,20 @@ where
             .get_view_by_id::<PostAccessView>(post_id, None)
             .await?;
 
+        if post.is_hidden() {
+            return Err(AppError::EntityFailIdNotFound {
+                ident: post_id.to_string(),
+            }
+            .into());
+        }
+
         let likes = data.count.unwrap_or(1);
         let by_credits = data.count.is_some();
 
         if !PostAccess::new(&post).can_like(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(PostAccess::new(&post)
+                .denied(&user, Permission::Like)
+                .into());
         }
 
         if by_credits && user.credits < likes as u64 {
@@ -181,8 +230,17 @@ where
             .get_view_by_id::<PostAccessView>(post_id, None)
             .await?;
 
+        if post.is_hidden() {
+            return Err(AppError::EntityFailIdNotFound {
+                ident: post_id.to_string(),
+            }
+            .into());
+        }
+
         if !PostAccess::new(&post).can_like(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(PostAccess::new(&post)
+                .denied(&user, Permission::Like)
+                .into());
         }
 
         let likes_count = self
@@ -198,7 +256,15 @@ where
         user_id: &str,
         post_id: &str,
         user_ids: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
     ) -> CtxResult<()> {
+        if expires_at.is_some_and(|v| v <= Utc::now()) {
+            return Err(AppError::Generic {
+                description: "Access expiration must be in the future".to_string(),
+            }
+            .into());
+        }
+
         let user = self.users_repository.get_by_id(user_id).await?;
 
         let post = self
@@ -208,7 +274,7 @@ where
 
         let post_access = PostAccess::new(&post);
         if !post_access.can_add_member(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(post_access.denied(&user, Permission::AddMember).into());
         }
 
         let disc_access = DiscussionAccess::new(&post.discussion);
@@ -232,12 +298,17 @@ where
             return Ok(());
         }
 
+        self.blocks_repository
+            .check_not_blocked_by(user.id.as_ref().unwrap().clone(), new_members.clone())
+            .await?;
+
         let _ = self
             .access_repository
-            .add(
+            .add_with_expiry(
                 new_members,
                 vec![post.id.to_raw().as_ref()],
                 Role::Member.to_string(),
+                expires_at,
             )
             .await?;
 
@@ -266,7 +337,7 @@ where
 
         let post_access = PostAccess::new(&post);
         if !post_access.can_remove_member(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(post_access.denied(&user, Permission::RemoveMember).into());
         }
 
         let user_things = user_ids
@@ -324,7 +395,9 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_view(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(DiscussionAccess::new(&disc)
+                .denied(&user, Permission::View)
+                .into());
         }
 
         let pag = CursorPagination {
@@ -336,7 +409,7 @@ where
                 .map(|value| get_str_thing(&value))
                 .transpose()?,
         };
-        let items = self
+        let mut items = self
             .posts_repository
             .get_by_disc(
                 &user.id.as_ref().unwrap().id.to_raw(),
@@ -346,6 +419,10 @@ where
             )
             .await?;
 
+        items
+            .iter_mut()
+            .for_each(|post| post.hide_locked_content(user.id.as_ref().unwrap()));
+
         Ok(items)
     }
 
@@ -362,7 +439,9 @@ where
             .await?;
 
         if !DiscussionAccess::new(&disc).can_view(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(DiscussionAccess::new(&disc)
+                .denied(&user, Permission::View)
+                .into());
         }
 
         let count = self
@@ -384,7 +463,9 @@ where
             .get_view_by_id::<PostAccessView>(post_id, None)
             .await?;
         if !PostAccess::new(&post).can_view(&user) {
-            return Err(AppError::Forbidden.into());
+            return Err(PostAccess::new(&post)
+                .denied(&user, Permission::View)
+                .into());
         }
 
         let post = self
@@ -415,6 +496,16 @@ where
             .await?;
 
         self.check_create_access(&disc, &post_data, &user).await?;
+        self.blocks_repository
+            .check_not_blocked_by(
+                user.id.as_ref().unwrap().clone(),
+                post_data
+                    .members
+                    .iter()
+                    .map(|u| u.id.as_ref().unwrap().clone())
+                    .collect(),
+            )
+            .await?;
 
         let media_links = if let Some(file) = post_data.file {
             let file_name = format!(
@@ -450,6 +541,8 @@ where
                 r#type: post_data.r#type.clone(),
                 delivered_for_task: None,
                 reply_to: post_data.reply_to,
+                price: post_data.price,
+                currency: post_data.currency,
             })
             .await;
 
@@ -503,8 +596,8 @@ where
                 .await?;
         }
 
-        let disc_all_users = match post_data.r#type {
-            PostType::Private => member_ids
+        let disc_all_users = match (&post_data.r#type, &disc.r#type) {
+            (PostType::Private, DiscussionType::Private) => member_ids
                 .iter()
                 .map(|id| id.id.to_raw())
                 .chain(std::iter::once(user_id.to_string()))
@@ -536,6 +629,10 @@ where
                 .await?;
         }
 
+        self.content_filter
+            .flag(&post_view.id, post_data.flagged_words)
+            .await?;
+
         let _ = self
             .notification_service
             .on_updated_users_discussions(&user.id.as_ref().unwrap(), &updated_discs_users)
@@ -543,7 +640,95 @@ where
 
         let _ = self
             .notification_service
-            .on_discussion_post(&user_id, disc_all_users, &post_view)
+            .on_discussion_post(
+                &user_id,
+                self.without_requests(&disc, disc_all_users).await?,
+                &post_view,
+            )
+            .await?;
+
+        Ok(post_view)
+    }
+
+    /// Authors edit their posts within the edit window, the editors of the discussion at any time
+    pub async fn update(
+        &self,
+        user_id: &str,
+        post_id: &str,
+        data: PostUpdateInput,
+        edit_window: Duration,
+    ) -> CtxResult<PostView> {
+        data.validate()?;
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let user_thing = user.id.as_ref().unwrap();
+        let post = self
+            .posts_repository
+            .get_view_by_id::<PostAccessView>(post_id, None)
+            .await?;
+
+        if !PostAccess::new(&post).can_edit(&user) {
+            return Err(PostAccess::new(&post)
+                .denied(&user, Permission::Edit)
+                .into());
+        }
+
+        let current = self
+            .posts_repository
+            .get_view_by_id::<PostView>(post_id, Some(user_id))
+            .await?;
+        let is_editor = post.discussion.get_user_role(user_thing) == Some(Role::Editor.to_string());
+        if !is_editor && current.created_at + edit_window < Utc::now() {
+            return Err(AppError::Generic {
+                description: "The edit window of the post has expired".to_string(),
+            }
+            .into());
+        }
+
+        let title = self
+            .content_filter
+            .filter(&data.title.unwrap_or(current.title))
+            .await?;
+        let content = self
+            .content_filter
+            .filter_option(data.content.or(current.content))
+            .await?;
+
+        let mut query = PostDbService::build_update_query(
+            self.db.query("BEGIN TRANSACTION;"),
+            post.id.clone(),
+            title.text,
+            content.as_ref().map(|c| c.text.clone()),
+            user_thing.clone(),
+        );
+        if let Some(tags) = data.tags {
+            let tags = tags.iter().map(|t| t.to_lowercase()).collect();
+            query = self
+                .tags_repository
+                .build_replace_query(query, tags, post.id.clone());
+        }
+        let flagged_words = content
+            .map(|c| c.flagged_words)
+            .unwrap_or_default()
+            .into_iter()
+            .chain(title.flagged_words)
+            .collect();
+        query = self
+            .content_filter
+            .build_flag_query(query, &post.id, flagged_words);
+
+        let mut res = query.query("COMMIT TRANSACTION;").await?;
+        check_transaction_custom_error(&mut res)?;
+
+        let post_view = self
+            .posts_repository
+            .get_view_by_id::<PostView>(post_id, Some(user_id))
+            .await?;
+
+        let receivers = self
+            .without_requests(&post.discussion, post.get_receiver_ids())
+            .await?;
+        self.notification_service
+            .on_discussion_post_updated(user_id, receivers, &post_view)
             .await?;
 
         Ok(post_view)
@@ -556,17 +741,144 @@ where
             .get_view_by_id::<PostAccessView>(post_id, None)
             .await?;
 
-        if !PostAccess::new(&post).can_view(&user) {
-            return Err(AppError::Forbidden.into());
+        if post.is_hidden() {
+            return Err(AppError::EntityFailIdNotFound {
+                ident: post_id.to_string(),
+            });
         }
 
+        let post_access = PostAccess::new(&post);
+        if !post_access.can_view(&user) {
+            return Err(post_access.denied(&user, Permission::View).into());
+        }
+
+        let mut post_view = self
+            .posts_repository
+            .get_view_by_id::<FullPostView>(post_id, Some(user_id))
+            .await?;
+
+        if !post_access.can_view_content(&user) {
+            post_view.lock_content();
+        }
+
+        Ok(post_view)
+    }
+
+    pub async fn buy(
+        &self,
+        user_id: &str,
+        post_id: &str,
+        fee_rate: f64,
+    ) -> AppResult<FullPostView> {
+        let user = self.users_repository.get_by_id(&user_id).await?;
+        let user_thing = user.id.as_ref().unwrap();
+        let post = self
+            .posts_repository
+            .get_view_by_id::<PostAccessView>(post_id, None)
+            .await?;
+
+        if !PostAccess::new(&post).can_buy(&user) {
+            return Err(PostAccess::new(&post).denied(&user, Permission::Buy).into());
+        }
+
+        let price = post.price.ok_or(AppError::Generic {
+            description: "Post has no price".to_string(),
+        })?;
+        let author = post.get_by_role(&Role::Owner.to_string()).pop().ok_or(
+            AppError::EntityFailIdNotFound {
+                ident: "post owner".to_string(),
+            },
+        )?;
+
+        let fee = (price as f64 * fee_rate) as u64;
+        // posts created before the currency was stored are priced in USD
+        let currency = post.currency.clone().unwrap_or(CurrencySymbol::USD);
+        let user_wallet = WalletDbService::get_user_wallet_id(user_thing);
+        let description = Some(format!("Purchase of post {}", post.id.to_raw()));
+
+        // checked in the transaction so the concurrent purchases are not charged twice
+        let mut query = self
+            .db
+            .query("BEGIN")
+            .query(format!(
+                "IF $buyer IN $bought_post<-{ACCESS_TABLE_NAME}.in {{ THROW \"{THROW_POST_ALREADY_BOUGHT}\"; }};"
+            ))
+            .bind(("buyer", user_thing.clone()))
+            .bind(("bought_post", post.id.clone()));
+        query = BalanceTransactionDbService::build_transfer_qry(
+            query,
+            &user_wallet,
+            &WalletDbService::get_user_wallet_id(&author),
+            (price - fee) as i64,
+            &currency,
+            None,
+            description.clone(),
+            TransactionType::Purchase,
+            "purchase",
+        );
+        if fee > 0 {
+            query = BalanceTransactionDbService::build_transfer_qry(
+                query,
+                &user_wallet,
+                &DARVE_WALLET,
+                fee as i64,
+                &currency,
+                None,
+                description,
+                TransactionType::Fee,
+                "purchase_fee",
+            );
+        }
+        query = self.access_repository.build_add_query(
+            query,
+            user_thing,
+            &post.id,
+            Role::Buyer.to_string(),
+        );
+
+        let mut res = query.query("COMMIT").await?;
+        check_transaction_custom_error(&mut res).map_err(|e| match e {
+            AppError::SurrealDb { source } if source.contains(THROW_POST_ALREADY_BOUGHT) => {
+                AppError::Generic {
+                    description: THROW_POST_ALREADY_BOUGHT.to_string(),
+                }
+            }
+            e => e,
+        })?;
+
+        self.notification_service
+            .on_update_balance(user_thing)
+            .await?;
+        self.notification_service.on_update_balance(&author).await?;
+
         self.posts_repository
             .get_view_by_id::<FullPostView>(post_id, Some(user_id))
             .await
             .map_err(|e| e.into())
     }
 
-    pub async fn delete_post(&self, user_id: &str, post_id: &str) -> AppResult<()> {
+    pub async fn pin(&self, user_id: &str, post_id: &str, pinned: bool) -> AppResult<()> {
+        let user = self.users_repository.get_by_id(&user_id).await?;
+        let post = self
+            .posts_repository
+            .get_view_by_id::<PostAccessView>(post_id, None)
+            .await?;
+
+        if !PostAccess::new(&post).can_pin(&user) {
+            return Err(PostAccess::new(&post).denied(&user, Permission::Pin));
+        }
+
+        self.posts_repository
+            .set_pinned(&post.id.id.to_raw(), pinned)
+            .await
+    }
+
+    pub async fn delete_post(
+        &self,
+        user_id: &str,
+        post_id: &str,
+        meta: &RequestMeta,
+    ) -> AppResult<()> {
         let user = self.users_repository.get_by_id(&user_id).await?;
         let post = self
             .posts_repository
@@ -577,7 +889,15 @@ where
             return Err(AppError::Forbidden.into());
         }
 
-        self.posts_repository.delete(&post.id.id.to_raw()).await?;
+        let audit = CreateAuditLog {
+            actor: meta.actor(user.id.unwrap()),
+            action: AuditAction::DeletePost,
+            target: post.id.clone(),
+            note: None,
+        };
+        self.posts_repository
+            .delete(&post.id.id.to_raw(), Some(audit))
+            .await?;
 
         if post.discussion.r#type == DiscussionType::Private {
             let disc_id = post.discussion.id.id.to_raw();
@@ -603,9 +923,15 @@ where
             }
             .into());
         }
+        let title = self.content_filter.filter(&data.title).await?;
+        let content = self.content_filter.filter_option(data.content).await?;
 
         let (r#type, members) = if data.is_idea.unwrap_or_default() {
             (PostType::Idea, Vec::new())
+        } else if data.price.is_some() {
+            (PostType::Paid, Vec::new())
+        } else if data.for_subscribers.unwrap_or_default() {
+            (PostType::Private, Vec::new())
         } else if !data.users.is_empty() {
             let members = self.get_users_by_ids(data.users).await?;
             (PostType::Private, members)
@@ -615,7 +941,14 @@ where
 
         Ok(PostCreationData {
             id: PostDbService::get_new_post_thing(),
-            title: data.title,
+            flagged_words: content
+                .as_ref()
+                .map(|c| c.flagged_words.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .chain(title.flagged_words)
+                .collect(),
+            title: title.text,
             tags: data
                 .tags
                 .iter()
@@ -626,12 +959,42 @@ where
                 .map(|v| convert_field_file_data(v))
                 .transpose()?,
             members,
+            price: match r#type {
+                PostType::Paid => data.price,
+                _ => None,
+            },
+            currency: match r#type {
+                PostType::Paid => Some(data.currency.unwrap_or(CurrencySymbol::USD)),
+                _ => None,
+            },
             r#type,
-            content: data.content,
+            content: content.map(|c| c.text),
             reply_to: data.reply_to.map(|d| get_str_thing(&d).unwrap()),
         })
     }
 
+    // the users get the posts of the message request after accepting it
+    async fn without_requests(
+        &self,
+        disc: &DiscussionAccessView,
+        receivers: Vec<String>,
+    ) -> CtxResult<Vec<String>> {
+        if disc.r#type != DiscussionType::Private {
+            return Ok(receivers);
+        }
+        let request_users = self
+            .discussion_users
+            .get_request_users(&disc.id.id.to_raw())
+            .await?
+            .into_iter()
+            .map(|id| id.id.to_raw())
+            .collect::<Vec<String>>();
+        Ok(receivers
+            .into_iter()
+            .filter(|id| !request_users.contains(id))
+            .collect())
+    }
+
     async fn get_users_by_ids(&self, user_ids: Vec<String>) -> CtxResult<Vec<LocalUser>> {
         let user_things = user_ids
             .iter()
@@ -656,8 +1019,15 @@ where
         let members_access = data.members.iter().all(|u| disc_access.can_view(u));
         let owner_access = match &data.r#type {
             PostType::Public => disc_access.can_create_public_post(&user),
-            PostType::Private => disc_access.can_create_private_post(&user),
+            PostType::Private => match disc.r#type {
+                // private posts of a public discussion are visible to its subscribers only
+                DiscussionType::Public => {
+                    data.members.is_empty() && disc_access.can_create_private_post(&user)
+                }
+                DiscussionType::Private => disc_access.can_create_private_post(&user),
+            },
             PostType::Idea => disc_access.can_idea_post(&user),
+            PostType::Paid => disc_access.can_create_paid_post(&user),
         };
 
         if data.reply_to.is_some() {
@@ -670,7 +1040,9 @@ where
                 })?;
 
             if !disc_access.can_create_post_for_post(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(disc_access
+                    .denied(&user, Permission::CreatePostForPost)
+                    .into());
             }
         }
 
@@ -690,4 +1062,7 @@ struct PostCreationData {
     content: Option<String>,
     title: String,
     reply_to: Option<Thing>,
+    price: Option<u64>,
+    currency: Option<CurrencySymbol>,
+    flagged_words: Vec<String>,
 }
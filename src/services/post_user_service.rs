@@ -1,5 +1,5 @@
 use crate::{
-    access::post::PostAccess,
+    access::{base::permission::Permission, post::PostAccess},
     database::{client::Db, repositories::user_notifications::UserNotificationsRepository},
     entities::{
         community::post_entity::{PostDbService, PostUserStatus},
@@ -69,7 +69,7 @@ where
         let post_access = PostAccess::new(&post);
 
         if !post_access.can_view(&user) {
-            return Err(AppError::Forbidden);
+            return Err(post_access.denied(&user, Permission::View));
         }
 
         let status = self
@@ -101,7 +101,7 @@ where
             .get_view_by_id::<PostAccessView>(post_id, None)
             .await?;
         if !PostAccess::new(&post).can_view(&user) {
-            return Err(AppError::Forbidden);
+            return Err(PostAccess::new(&post).denied(&user, Permission::View));
         }
         let status = self
             .post_user_repository
@@ -121,6 +121,13 @@ where
             PostUserStatus::Seen as u8,
         );
 
+        query = self.discussion_users.build_last_read_query(
+            query,
+            &post.discussion.id.id.to_raw(),
+            user_id,
+            post.id.clone(),
+        );
+
         query = self.discussion_users.build_decrease_query(
             query,
             &post.discussion.id.id.to_raw(),
@@ -135,6 +142,9 @@ where
             .notification_service
             .on_updated_users_discussions(&user_thing, &data)
             .await?;
+        self.notification_service
+            .on_post_read(&user_thing, &post)
+            .await?;
         Ok(())
     }
 }
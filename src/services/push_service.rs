@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+use crate::{
+    entities::{
+        push_device::{DevicePlatform, PushDevice, PushDeviceDbService},
+        user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME,
+        user_notification::UserNotification,
+    },
+    interfaces::{
+        push_sender::{PushError, PushMessage},
+        repositories::user_notifications::UserNotificationsInterface,
+    },
+    middleware::{ctx::Ctx, error::CtxResult, mw_ctx::CtxState},
+};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RegisterDeviceInput {
+    #[validate(length(min = 1, max = 4096, message = "Invalid device token"))]
+    pub token: String,
+    pub platform: DevicePlatform,
+}
+
+/// Delivers the notifications to the mobile devices of the offline users
+pub struct PushService<'a> {
+    state: &'a CtxState,
+    devices_repository: PushDeviceDbService<'a>,
+}
+
+impl<'a> PushService<'a> {
+    pub fn new(state: &'a CtxState, ctx: &'a Ctx) -> Self {
+        Self {
+            state,
+            devices_repository: PushDeviceDbService {
+                db: &state.db.client,
+                ctx,
+            },
+        }
+    }
+
+    pub async fn register_device(
+        &self,
+        user: Thing,
+        data: RegisterDeviceInput,
+    ) -> CtxResult<PushDevice> {
+        data.validate()?;
+        self.devices_repository
+            .upsert(user, data.token, data.platform)
+            .await
+    }
+
+    pub async fn unregister_device(&self, user: Thing, token: String) -> CtxResult<()> {
+        self.devices_repository.delete(user, token).await
+    }
+
+    pub async fn get_devices(&self, user: Thing) -> CtxResult<Vec<PushDevice>> {
+        self.devices_repository.get_by_users(vec![user]).await
+    }
+
+    /// Sends the notification to the devices of the receivers without an open connection,
+    /// the devices with the tokens rejected by the provider are removed
+    pub async fn send(
+        &self,
+        notification: &UserNotification,
+        receivers: &[String],
+    ) -> CtxResult<()> {
+        let offline_users = receivers
+            .iter()
+            .filter(|id| !self.state.presence.is_online(id))
+            .map(|id| Thing::from((USER_TABLE_NAME, id.as_str())))
+            .collect::<Vec<Thing>>();
+        if offline_users.is_empty() {
+            return Ok(());
+        }
+
+        let mut devices_by_user: HashMap<Thing, Vec<PushDevice>> = HashMap::new();
+        for device in self.devices_repository.get_by_users(offline_users).await? {
+            devices_by_user
+                .entry(device.user.clone())
+                .or_default()
+                .push(device);
+        }
+
+        let mut invalid = vec![];
+        for (user, devices) in devices_by_user {
+            let badge = self
+                .state
+                .db
+                .user_notifications
+                .get_count(&user.to_raw(), Some(false))
+                .await?;
+            let message = PushMessage {
+                title: notification.title.clone(),
+                badge,
+                data: Some(serde_json::json!({
+                    "notification_id": notification.id,
+                    "event": notification.event,
+                    "metadata": notification.metadata,
+                })),
+            };
+
+            let mut delivered = vec![];
+            for device in devices {
+                match self
+                    .state
+                    .push_sender
+                    .send(&device.platform, &device.token, &message)
+                    .await
+                {
+                    Ok(_) => delivered.push(device.id),
+                    Err(PushError::InvalidToken) => invalid.push(device.id),
+                    Err(PushError::Provider(err)) => {
+                        println!("Error sending push to {}: {}", device.id.to_raw(), err)
+                    }
+                }
+            }
+            if !delivered.is_empty() {
+                self.devices_repository.set_badge(delivered, badge).await?;
+            }
+        }
+
+        if !invalid.is_empty() {
+            self.devices_repository.delete_by_ids(invalid).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{
+    database::{
+        client::Db,
+        repositories::reply::RepliesRepository,
+        table_names::{REPLY_TABLE_NAME, TASK_REQUEST_TABLE_NAME},
+    },
+    entities::{
+        audit_log::{AuditAction, AuditActor, CreateAuditLog},
+        community::{
+            discussion_entity::{DiscussionType, TABLE_NAME as DISC_TABLE_NAME},
+            post_entity::{PostDbService, TABLE_NAME as POST_TABLE_NAME},
+        },
+        report::{
+            ModerationAction, ModerationActionType, Report, ReportFilter, ReportReason,
+            ReportStatus,
+        },
+        user_auth::local_user_entity::{
+            AccountStatus, LocalUserDbService, UserRole, TABLE_NAME as USER_TABLE_NAME,
+        },
+    },
+    interfaces::repositories::{
+        discussion_user::DiscussionUserRepositoryInterface, report::ReportsRepositoryInterface,
+        user_notifications::UserNotificationsInterface,
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        mw_ctx::CtxState,
+        utils::{
+            db_utils::{record_exists, Pagination, QryOrder},
+            string_utils::get_str_thing,
+        },
+    },
+    models::view::access::PostAccessView,
+    services::{
+        account_status_service::AccountStatusService, notification_service::NotificationService,
+    },
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+const REPORTABLE_TABLES: [&str; 5] = [
+    POST_TABLE_NAME,
+    REPLY_TABLE_NAME,
+    DISC_TABLE_NAME,
+    TASK_REQUEST_TABLE_NAME,
+    USER_TABLE_NAME,
+];
+
+const DEFAULT_SUSPEND_DAYS: u16 = 7;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReport {
+    pub entity_id: String,
+    pub reason: ReportReason,
+    #[validate(length(min = 1, max = 1000, message = "Text must have 1 to 1000 characters"))]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportsQuery {
+    pub status: Option<ReportStatus>,
+    pub reason: Option<ReportReason>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub assigned_to: Option<String>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+    pub order_dir: Option<QryOrder>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignReport {
+    /// unassigns the report if none
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TakeModerationAction {
+    pub action: ModerationActionType,
+    #[validate(length(min = 1, max = 1000, message = "Note must have 1 to 1000 characters"))]
+    pub note: Option<String>,
+    #[validate(range(min = 1, max = 3650))]
+    pub suspend_days: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationActionsQuery {
+    pub entity_id: Option<String>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+    pub order_dir: Option<QryOrder>,
+}
+
+pub struct ReportService<'a, R, DU, N>
+where
+    R: ReportsRepositoryInterface,
+    DU: DiscussionUserRepositoryInterface,
+    N: UserNotificationsInterface,
+{
+    db: &'a Db,
+    users_repository: LocalUserDbService<'a>,
+    posts_repository: PostDbService<'a>,
+    replies_repository: &'a RepliesRepository,
+    reports_repository: &'a R,
+    discussion_users: &'a DU,
+    notification_service: NotificationService<'a, N>,
+    account_status_service: AccountStatusService<'a>,
+    hide_threshold: u32,
+}
+
+impl<'a, R, DU, N> ReportService<'a, R, DU, N>
+where
+    R: ReportsRepositoryInterface,
+    DU: DiscussionUserRepositoryInterface,
+    N: UserNotificationsInterface,
+{
+    pub fn new(
+        state: &'a CtxState,
+        ctx: &'a Ctx,
+        reports_repository: &'a R,
+        discussion_users: &'a DU,
+        user_notifications: &'a N,
+    ) -> Self {
+        Self {
+            db: &state.db.client,
+            users_repository: LocalUserDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            posts_repository: PostDbService {
+                db: &state.db.client,
+                ctx,
+            },
+            replies_repository: &state.db.replies,
+            reports_repository,
+            discussion_users,
+            notification_service: NotificationService::new(
+                &state.db.client,
+                ctx,
+                &state.event_sender,
+                user_notifications,
+            ),
+            account_status_service: AccountStatusService::new(state, ctx),
+            hide_threshold: state.report_hide_threshold,
+        }
+    }
+
+    pub async fn create(&self, user_id: &str, data: CreateReport) -> CtxResult<Report> {
+        data.validate()?;
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let user_thing = user.id.as_ref().unwrap();
+        let entity = get_str_thing(&data.entity_id)?;
+
+        if !REPORTABLE_TABLES.contains(&entity.tb.as_str()) {
+            return Err(AppError::Generic {
+                description: "Entity can not be reported".to_string(),
+            }
+            .into());
+        }
+        record_exists(self.db, &entity).await?;
+
+        let owner = self.reports_repository.get_owner(&entity).await?;
+        if owner.as_ref() == Some(user_thing) {
+            return Err(AppError::Generic {
+                description: "You can not report yourself".to_string(),
+            }
+            .into());
+        }
+
+        let open_reports = self
+            .reports_repository
+            .get(
+                ReportFilter {
+                    status: Some(ReportStatus::Open),
+                    entity: Some(entity.clone()),
+                    reporter: Some(user_thing.clone()),
+                    ..Default::default()
+                },
+                Pagination {
+                    order_by: None,
+                    order_dir: None,
+                    count: 1,
+                    start: 0,
+                },
+            )
+            .await?;
+        if !open_reports.is_empty() {
+            return Err(AppError::Generic {
+                description: "Already reported".to_string(),
+            }
+            .into());
+        }
+
+        let report = self
+            .reports_repository
+            .create(entity.clone(), user_thing.clone(), data.reason, data.text)
+            .await?;
+
+        // users can not be hidden, their reports wait for a moderator
+        if entity.tb != USER_TABLE_NAME {
+            let reporters = self.reports_repository.get_reporters_count(&entity).await?;
+            if reporters == self.hide_threshold {
+                self.reports_repository.set_hidden(&entity, true).await?;
+                self.reports_repository
+                    .create_action(
+                        entity,
+                        None,
+                        ModerationActionType::Hide,
+                        Some(format!("Reported by {reporters} users")),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub async fn get_queue(&self, query: ReportsQuery) -> CtxResult<Vec<Report>> {
+        let filter = ReportFilter {
+            status: query.status,
+            reason: query.reason,
+            entity_type: query.entity_type,
+            assigned_to: query.assigned_to.map(|id| get_str_thing(&id)).transpose()?,
+            entity: query.entity_id.map(|id| get_str_thing(&id)).transpose()?,
+            reporter: None,
+        };
+        let pagination = Pagination {
+            order_by: None,
+            order_dir: query.order_dir,
+            count: query.count.unwrap_or(50),
+            start: query.start.unwrap_or(0),
+        };
+        Ok(self.reports_repository.get(filter, pagination).await?)
+    }
+
+    pub async fn assign(&self, report_id: &str, data: AssignReport) -> CtxResult<Report> {
+        let report_thing = get_str_thing(report_id)?;
+        let assigned_to = match data.user_id {
+            Some(id) => {
+                let assignee_thing = get_str_thing(&id)?;
+                let assignee = self
+                    .users_repository
+                    .get_by_id(&assignee_thing.id.to_raw())
+                    .await?;
+                if assignee.role != UserRole::Admin {
+                    return Err(AppError::Generic {
+                        description: "Reports can be assigned to admins only".to_string(),
+                    }
+                    .into());
+                }
+                assignee.id
+            }
+            None => None,
+        };
+
+        Ok(self
+            .reports_repository
+            .assign(&report_thing.id.to_raw(), assigned_to)
+            .await?)
+    }
+
+    /// Applies the action to the reported entity and closes all its open reports
+    pub async fn take_action(
+        &self,
+        actor: AuditActor,
+        report_id: &str,
+        data: TakeModerationAction,
+    ) -> CtxResult<ModerationAction> {
+        data.validate()?;
+        let moderator = actor.user.clone();
+        let report_thing = get_str_thing(report_id)?;
+        let report = self
+            .reports_repository
+            .get_by_id(&report_thing.id.to_raw())
+            .await?;
+        let entity = report.entity;
+
+        let status = match data.action {
+            ModerationActionType::Dismiss => {
+                // restores the content hidden automatically by the reports
+                if entity.tb != USER_TABLE_NAME {
+                    self.reports_repository.set_hidden(&entity, false).await?;
+                }
+                ReportStatus::Dismissed
+            }
+            ModerationActionType::Hide => {
+                if entity.tb == USER_TABLE_NAME {
+                    return Err(AppError::Generic {
+                        description: "Users can not be hidden".to_string(),
+                    }
+                    .into());
+                }
+                self.reports_repository.set_hidden(&entity, true).await?;
+                ReportStatus::Resolved
+            }
+            ModerationActionType::Delete => {
+                self.delete_entity(&entity, actor).await?;
+                ReportStatus::Resolved
+            }
+            ModerationActionType::Warn => {
+                let owner = self.get_owner(&entity).await?;
+                self.notification_service
+                    .on_moderation_warning(&owner, &entity, data.note.clone())
+                    .await?;
+                ReportStatus::Resolved
+            }
+            ModerationActionType::Suspend => {
+                let owner = self.get_owner(&entity).await?;
+                let days = data.suspend_days.unwrap_or(DEFAULT_SUSPEND_DAYS);
+                let reason = data
+                    .note
+                    .clone()
+                    .unwrap_or(format!("Reported {}", entity.to_raw()));
+                self.account_status_service
+                    .apply(
+                        Some(moderator.clone()),
+                        &owner,
+                        AccountStatus::Suspended,
+                        Some(Utc::now() + Duration::days(days as i64)),
+                        reason,
+                    )
+                    .await?;
+                ReportStatus::Resolved
+            }
+        };
+
+        let action = self
+            .reports_repository
+            .create_action(entity.clone(), Some(moderator), data.action, data.note)
+            .await?;
+        self.reports_repository
+            .resolve(&entity, status, &action.id)
+            .await?;
+
+        Ok(action)
+    }
+
+    pub async fn get_actions(
+        &self,
+        query: ModerationActionsQuery,
+    ) -> CtxResult<Vec<ModerationAction>> {
+        let entity = query.entity_id.map(|id| get_str_thing(&id)).transpose()?;
+        let pagination = Pagination {
+            order_by: None,
+            order_dir: query.order_dir,
+            count: query.count.unwrap_or(50),
+            start: query.start.unwrap_or(0),
+        };
+        Ok(self
+            .reports_repository
+            .get_actions(entity, pagination)
+            .await?)
+    }
+
+    async fn delete_entity(&self, entity: &Thing, actor: AuditActor) -> CtxResult<()> {
+        match entity.tb.as_str() {
+            POST_TABLE_NAME => {
+                let post = self
+                    .posts_repository
+                    .get_view_by_id::<PostAccessView>(&entity.id.to_raw(), None)
+                    .await?;
+                if post.tasks_nr > 0 {
+                    return Err(AppError::Generic {
+                        description: "Post with tasks can be hidden only".to_string(),
+                    }
+                    .into());
+                }
+                let audit = CreateAuditLog {
+                    actor,
+                    action: AuditAction::DeletePost,
+                    target: post.id.clone(),
+                    note: Some("Reported post".to_string()),
+                };
+                self.posts_repository
+                    .delete(&post.id.id.to_raw(), Some(audit))
+                    .await?;
+                if post.discussion.r#type == DiscussionType::Private {
+                    let users = post
+                        .discussion
+                        .users
+                        .into_iter()
+                        .map(|u| u.user.id.to_raw())
+                        .collect::<Vec<String>>();
+                    self.discussion_users
+                        .update_latest_post(&post.discussion.id.id.to_raw(), users)
+                        .await?;
+                }
+                Ok(())
+            }
+            REPLY_TABLE_NAME => {
+                self.replies_repository.delete(&entity.id.to_raw()).await?;
+                Ok(())
+            }
+            _ => Err(AppError::Generic {
+                description: format!("Delete is not supported for {}, hide it instead", entity.tb),
+            }
+            .into()),
+        }
+    }
+
+    async fn get_owner(&self, entity: &Thing) -> CtxResult<Thing> {
+        let owner = self.reports_repository.get_owner(entity).await?;
+        owner.ok_or(
+            AppError::EntityFailIdNotFound {
+                ident: entity.to_raw(),
+            }
+            .into(),
+        )
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    database::client::Db,
+    entities::wallet::{
+        balance_transaction_entity::{BalanceTransactionDbService, RevenuePeriod},
+        wallet_entity::{WalletDbService, ADJUSTMENTS_WALLET, APP_GATEWAY_WALLET, DARVE_WALLET},
+    },
+    middleware::{ctx::Ctx, error::CtxResult},
+    models::view::revenue::{
+        BalanceTotalsView, LiabilitiesView, RevenueByCurrencyView, RevenueByPeriodView,
+        RevenueByTypeView,
+    },
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct RevenueQuery {
+    pub period: Option<RevenuePeriod>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub struct RevenueService<'a> {
+    transactions_repository: BalanceTransactionDbService<'a>,
+    wallets_repository: WalletDbService<'a>,
+}
+
+impl<'a> RevenueService<'a> {
+    pub fn new(db: &'a Db, ctx: &'a Ctx) -> Self {
+        Self {
+            transactions_repository: BalanceTransactionDbService { db, ctx },
+            wallets_repository: WalletDbService { db, ctx },
+        }
+    }
+
+    pub async fn get_by_period(&self, query: RevenueQuery) -> CtxResult<Vec<RevenueByPeriodView>> {
+        self.transactions_repository
+            .get_income_by_period(
+                &DARVE_WALLET,
+                &query.period.unwrap_or(RevenuePeriod::Day),
+                query.from,
+                query.to,
+            )
+            .await
+    }
+
+    pub async fn get_by_type(&self, query: RevenueQuery) -> CtxResult<Vec<RevenueByTypeView>> {
+        self.transactions_repository
+            .get_income_by_type(&DARVE_WALLET, query.from, query.to)
+            .await
+    }
+
+    pub async fn get_by_currency(
+        &self,
+        query: RevenueQuery,
+    ) -> CtxResult<Vec<RevenueByCurrencyView>> {
+        self.transactions_repository
+            .get_income_by_currency(&DARVE_WALLET, query.from, query.to)
+            .await
+    }
+
+    pub async fn get_liabilities(&self) -> CtxResult<LiabilitiesView> {
+        let user_liabilities = self
+            .wallets_repository
+            .get_total_balances(vec![
+                APP_GATEWAY_WALLET.clone(),
+                DARVE_WALLET.clone(),
+                ADJUSTMENTS_WALLET.clone(),
+            ])
+            .await?;
+        let platform = self.wallets_repository.get_balance(&DARVE_WALLET).await?;
+        // the gateway wallet goes negative by every deposit it sends into the app
+        let gateway = self
+            .wallets_repository
+            .get_balance(&APP_GATEWAY_WALLET)
+            .await?;
+        // goes negative by every credit and positive by every debit of the admins
+        let adjustments = self
+            .wallets_repository
+            .get_balance(&ADJUSTMENTS_WALLET)
+            .await?;
+
+        Ok(LiabilitiesView {
+            user_liabilities,
+            gateway_holdings: BalanceTotalsView {
+                balance_usd: -gateway.balance_usd,
+                balance_reef: -gateway.balance_reef,
+                balance_eth: -gateway.balance_eth,
+            },
+            platform_balance: BalanceTotalsView {
+                balance_usd: platform.balance_usd,
+                balance_reef: platform.balance_reef,
+                balance_eth: platform.balance_eth,
+            },
+            adjustments_balance: BalanceTotalsView {
+                balance_usd: adjustments.balance_usd,
+                balance_reef: adjustments.balance_reef,
+                balance_eth: adjustments.balance_eth,
+            },
+        })
+    }
+}
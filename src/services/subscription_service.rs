@@ -0,0 +1,385 @@
+use crate::{
+    access::base::role::Role,
+    database::{client::Db, table_names::SUBSCRIPTION_TABLE_NAME},
+    entities::{
+        community::discussion_entity::DiscussionDbService,
+        subscription::{
+            BillingInterval, Subscription, SubscriptionStatus, SubscriptionTier,
+            SubscriptionTierCreate, THROW_SUBSCRIPTION_NOT_RENEWABLE,
+        },
+        user_auth::local_user_entity::LocalUserDbService,
+        wallet::{
+            balance_transaction_entity::{BalanceTransactionDbService, TransactionType},
+            wallet_entity::{check_transaction_custom_error, CurrencySymbol, WalletDbService},
+        },
+    },
+    interfaces::{
+        event_bus::EventBusInterface,
+        repositories::{
+            access::AccessRepositoryInterface, subscriptions::SubscriptionsRepositoryInterface,
+            user_notifications::UserNotificationsInterface,
+        },
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, AppResult, CtxResult},
+    },
+    services::notification_service::NotificationService,
+};
+use chrono::{TimeDelta, Utc};
+use serde::Deserialize;
+use surrealdb::sql::{Id, Thing};
+use validator::Validate;
+
+// days a past due subscription keeps its access while the renewal is retried
+const GRACE_PERIOD_DAYS: i64 = 3;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubscriptionTierInput {
+    #[validate(length(min = 3, message = "Min 3 characters"))]
+    pub title: String,
+    pub description: Option<String>,
+    #[validate(range(min = 1))]
+    pub price: u64,
+    pub currency: Option<CurrencySymbol>,
+    pub interval: BillingInterval,
+    #[validate(length(max = 10, message = "Max 10 perks"))]
+    #[serde(default)]
+    pub perks: Vec<String>,
+}
+
+pub struct SubscriptionService<'a, S, A, N>
+where
+    S: SubscriptionsRepositoryInterface,
+    A: AccessRepositoryInterface,
+    N: UserNotificationsInterface,
+{
+    db: &'a Db,
+    users_repository: LocalUserDbService<'a>,
+    subscriptions_repository: &'a S,
+    access_repository: &'a A,
+    notification_service: NotificationService<'a, N>,
+}
+
+impl<'a, S, A, N> SubscriptionService<'a, S, A, N>
+where
+    S: SubscriptionsRepositoryInterface,
+    A: AccessRepositoryInterface,
+    N: UserNotificationsInterface,
+{
+    pub fn new(
+        db: &'a Db,
+        ctx: &'a Ctx,
+        event_sender: &'a (dyn EventBusInterface + Send + Sync),
+        notification_repository: &'a N,
+        subscriptions_repository: &'a S,
+        access_repository: &'a A,
+    ) -> Self {
+        Self {
+            db,
+            users_repository: LocalUserDbService { db, ctx },
+            subscriptions_repository,
+            access_repository,
+            notification_service: NotificationService::new(
+                db,
+                ctx,
+                event_sender,
+                notification_repository,
+            ),
+        }
+    }
+
+    pub async fn create_tier(
+        &self,
+        user_id: &str,
+        data: SubscriptionTierInput,
+    ) -> CtxResult<SubscriptionTier> {
+        data.validate()?;
+        let user = self.users_repository.get_by_id(user_id).await?;
+
+        let tier = self
+            .subscriptions_repository
+            .create_tier(SubscriptionTierCreate {
+                user: user.id.unwrap(),
+                title: data.title,
+                description: data.description,
+                price: data.price,
+                currency: data.currency.unwrap_or(CurrencySymbol::USD),
+                interval: data.interval,
+                perks: data.perks,
+            })
+            .await?;
+
+        Ok(tier)
+    }
+
+    pub async fn get_tiers(&self, user_id: &str) -> CtxResult<Vec<SubscriptionTier>> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let tiers = self
+            .subscriptions_repository
+            .get_tiers_by_user(user.id.as_ref().unwrap(), true)
+            .await?;
+        Ok(tiers)
+    }
+
+    pub async fn disable_tier(&self, user_id: &str, tier_id: &str) -> CtxResult<()> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let tier = self.subscriptions_repository.get_tier(tier_id).await?;
+
+        if &tier.user != user.id.as_ref().unwrap() {
+            return Err(AppError::Forbidden.into());
+        }
+
+        // existing subscribers keep their subscription until they cancel
+        self.subscriptions_repository
+            .set_tier_active(tier_id, false)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn subscribe(&self, user_id: &str, tier_id: &str) -> CtxResult<Subscription> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let user_thing = user.id.as_ref().unwrap();
+        let tier = self.subscriptions_repository.get_tier(tier_id).await?;
+
+        if !tier.is_active || &tier.user == user_thing {
+            return Err(AppError::Forbidden.into());
+        }
+
+        let existing = self
+            .subscriptions_repository
+            .get_by_subscriber_and_creator(user_thing, &tier.user)
+            .await?;
+
+        if let Some(ref subscription) = existing {
+            match subscription.status {
+                SubscriptionStatus::Active | SubscriptionStatus::PastDue => {
+                    return Err(AppError::Generic {
+                        description: "Already subscribed".to_string(),
+                    }
+                    .into());
+                }
+                SubscriptionStatus::Canceled if subscription.tier == tier.id => {
+                    // the current period is already paid
+                    let subscription = self
+                        .subscriptions_repository
+                        .resume(&subscription.id)
+                        .await?;
+                    return Ok(subscription);
+                }
+                _ => (),
+            };
+        }
+
+        let subscription_id =
+            existing.map_or(Thing::from((SUBSCRIPTION_TABLE_NAME, Id::ulid())), |s| s.id);
+        let period_end = Utc::now() + tier.interval.period();
+
+        let mut query = self.db.query("BEGIN");
+        query = BalanceTransactionDbService::build_transfer_qry(
+            query,
+            &WalletDbService::get_user_wallet_id(user_thing),
+            &WalletDbService::get_user_wallet_id(&tier.user),
+            tier.price as i64,
+            &tier.currency,
+            None,
+            Some(format!("Subscription {}", tier.title)),
+            TransactionType::Subscription,
+            "subscription",
+        );
+        query = self.subscriptions_repository.build_upsert_query(
+            query,
+            &subscription_id,
+            &tier,
+            user_thing,
+            period_end,
+        );
+        query = self.access_repository.build_add_query(
+            query,
+            user_thing,
+            &DiscussionDbService::get_profile_discussion_id(&tier.user),
+            Role::Subscriber.to_string(),
+        );
+
+        let mut res = query.query("RETURN $subscription;").query("COMMIT").await?;
+        check_transaction_custom_error(&mut res)?;
+
+        let subscription =
+            res.take::<Option<Subscription>>(0)?
+                .ok_or(AppError::EntityFailIdNotFound {
+                    ident: subscription_id.to_raw(),
+                })?;
+
+        self.notification_service
+            .on_update_balance(user_thing)
+            .await?;
+        self.notification_service
+            .on_update_balance(&tier.user)
+            .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn cancel(&self, user_id: &str, subscription_id: &str) -> CtxResult<Subscription> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        let subscription = self
+            .subscriptions_repository
+            .get_by_id(subscription_id)
+            .await?;
+
+        if &subscription.subscriber != user.id.as_ref().unwrap() {
+            return Err(AppError::Forbidden.into());
+        }
+
+        match subscription.status {
+            SubscriptionStatus::Active => Ok(self
+                .subscriptions_repository
+                .cancel(&subscription.id)
+                .await?),
+            // nothing was paid for the running period, so access ends right away
+            SubscriptionStatus::PastDue => {
+                self.expire(&subscription).await?;
+                Ok(self
+                    .subscriptions_repository
+                    .get_by_id(subscription_id)
+                    .await?)
+            }
+            SubscriptionStatus::Canceled | SubscriptionStatus::Expired => Err(AppError::Generic {
+                description: "Subscription is not active".to_string(),
+            }
+            .into()),
+        }
+    }
+
+    pub async fn get_subscriptions(&self, user_id: &str) -> CtxResult<Vec<Subscription>> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        Ok(self
+            .subscriptions_repository
+            .get_by_subscriber(user.id.as_ref().unwrap())
+            .await?)
+    }
+
+    pub async fn get_subscribers(&self, user_id: &str) -> CtxResult<Vec<Subscription>> {
+        let user = self.users_repository.get_by_id(user_id).await?;
+        Ok(self
+            .subscriptions_repository
+            .get_by_creator(user.id.as_ref().unwrap())
+            .await?)
+    }
+
+    pub async fn process_subscriptions(&self) -> AppResult<()> {
+        let ended = self.subscriptions_repository.get_ended_canceled().await?;
+        for subscription in ended {
+            if let Err(err) = self.expire(&subscription).await {
+                println!(
+                    "Error expiring subscription {}: {:?}",
+                    subscription.id.to_raw(),
+                    err
+                )
+            }
+        }
+
+        let due = self.subscriptions_repository.get_due_for_renewal().await?;
+        for subscription in due {
+            match self.renew(&subscription).await {
+                Ok(_) => (),
+                Err(AppError::BalanceTooLow | AppError::WalletFrozen) => {
+                    if let Err(err) = self.on_renew_failed(&subscription).await {
+                        println!(
+                            "Error updating past due subscription {}: {:?}",
+                            subscription.id.to_raw(),
+                            err
+                        )
+                    }
+                }
+                Err(err) => {
+                    println!(
+                        "Error renewing subscription {}: {:?}",
+                        subscription.id.to_raw(),
+                        err
+                    )
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn renew(&self, subscription: &Subscription) -> AppResult<()> {
+        // a late payment starts a new period instead of covering the missed time
+        let period_start = match subscription.status {
+            SubscriptionStatus::PastDue => Utc::now(),
+            _ => subscription.current_period_end,
+        };
+        let period_end = period_start + subscription.interval.period();
+
+        // the subscription is checked before the charge, so it's not charged twice
+        let mut query = self.subscriptions_repository.build_renew_query(
+            self.db.query("BEGIN"),
+            &subscription.id,
+            subscription.current_period_end,
+            period_end,
+        );
+        query = BalanceTransactionDbService::build_transfer_qry(
+            query,
+            &WalletDbService::get_user_wallet_id(&subscription.subscriber),
+            &WalletDbService::get_user_wallet_id(&subscription.creator),
+            subscription.price as i64,
+            &subscription.currency,
+            None,
+            Some(format!("Subscription {}", subscription.id.to_raw())),
+            TransactionType::Subscription,
+            "subscription",
+        );
+
+        let mut res = query.query("COMMIT").await?;
+        match check_transaction_custom_error(&mut res) {
+            Err(AppError::SurrealDb { source })
+                if source.contains(THROW_SUBSCRIPTION_NOT_RENEWABLE) =>
+            {
+                return Ok(())
+            }
+            res => res?,
+        };
+
+        self.notification_service
+            .on_update_balance(&subscription.subscriber)
+            .await?;
+        self.notification_service
+            .on_update_balance(&subscription.creator)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn on_renew_failed(&self, subscription: &Subscription) -> AppResult<()> {
+        match subscription.grace_period_end {
+            None => {
+                self.subscriptions_repository
+                    .set_past_due(
+                        &subscription.id,
+                        Utc::now() + TimeDelta::days(GRACE_PERIOD_DAYS),
+                    )
+                    .await
+            }
+            Some(grace_period_end) if grace_period_end <= Utc::now() => {
+                self.expire(subscription).await
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    async fn expire(&self, subscription: &Subscription) -> AppResult<()> {
+        self.subscriptions_repository
+            .expire(&subscription.id)
+            .await?;
+        self.access_repository
+            .remove_by_role(
+                &DiscussionDbService::get_profile_discussion_id(&subscription.creator).to_raw(),
+                vec![subscription.subscriber.clone()],
+                Role::Subscriber.to_string(),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -2,7 +2,12 @@ use std::sync::Arc;
 
 use crate::utils::validate_utils::deserialize_thing_or_string;
 use crate::{
-    access::{base::role::Role, discussion::DiscussionAccess, post::PostAccess, task::TaskAccess},
+    access::{
+        base::{permission::Permission, role::Role},
+        discussion::DiscussionAccess,
+        post::PostAccess,
+        task::TaskAccess,
+    },
     database::{client::Db, table_names::TASK_REQUEST_TABLE_NAME},
     entities::{
         access_user::AccessUser,
@@ -17,7 +22,10 @@ use crate::{
             TaskRequestCreate, TaskRequestEntity, TaskRequestStatus, TaskRequestType,
         },
         task_request_user::{TaskParticipant, TaskParticipantResult, TaskParticipantStatus},
-        user_auth::local_user_entity::{LocalUser, LocalUserDbService},
+        user_auth::{
+            block_entity::BlockDbService,
+            local_user_entity::{LocalUser, LocalUserDbService},
+        },
         wallet::{
             balance_transaction_entity::{BalanceTransactionDbService, TransactionType},
             wallet_entity::{
@@ -48,7 +56,10 @@ use crate::{
         access::{DiscussionAccessView, PostAccessView, TaskAccessView},
         task::TaskRequestView,
     },
-    services::notification_service::{NotificationService, OnCreatedTaskView},
+    services::{
+        content_filter_service::ContentFilterService,
+        notification_service::{NotificationService, OnCreatedTaskView},
+    },
     utils::file::convert::FileUpload,
 };
 use chrono::{DateTime, TimeDelta, Utc};
@@ -138,6 +149,8 @@ where
     access_repository: &'a A,
     tags_repository: &'a TG,
     file_storage: Arc<dyn FileStorageInterface + Send + Sync>,
+    blocks_repository: BlockDbService<'a>,
+    content_filter: ContentFilterService<'a>,
     db: &'a Db,
 }
 
@@ -174,6 +187,8 @@ where
             tags_repository,
             file_storage,
             notification_service: notification_service,
+            blocks_repository: BlockDbService { db: &db, ctx },
+            content_filter: ContentFilterService::new(db, ctx),
             db: db,
         }
     }
@@ -186,7 +201,7 @@ where
             .await?;
 
         if !TaskAccess::new(&task_view).can_view(&user) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_view).denied(&user, Permission::View));
         }
 
         let task = self
@@ -216,7 +231,9 @@ where
 
         if participants.is_empty() {
             if !PostAccess::new(&post).can_create_public_task(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(PostAccess::new(&post)
+                    .denied(&user, Permission::CreatePublicTask)
+                    .into());
             }
         } else {
             for participant in participants.iter() {
@@ -227,8 +244,19 @@ where
                     return Err(AppError::Forbidden.into());
                 }
             }
+            self.blocks_repository
+                .check_not_blocked_by(
+                    user.id.as_ref().unwrap().clone(),
+                    participants
+                        .iter()
+                        .map(|u| u.id.as_ref().unwrap().clone())
+                        .collect(),
+                )
+                .await?;
             if !PostAccess::new(&post).can_create_private_task(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(PostAccess::new(&post)
+                    .denied(&user, Permission::CreatePrivateTask)
+                    .into());
             }
         };
 
@@ -254,7 +282,9 @@ where
             };
 
             if !TaskAccess::new(&task).can_donate(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(TaskAccess::new(&task)
+                    .denied(&user, Permission::Donate)
+                    .into());
             }
         }
 
@@ -302,7 +332,9 @@ where
 
         if participants.is_empty() {
             if !DiscussionAccess::new(&discussion).can_create_public_task(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(DiscussionAccess::new(&discussion)
+                    .denied(&user, Permission::CreatePublicTask)
+                    .into());
             }
         } else {
             for participant in participants.iter() {
@@ -313,8 +345,19 @@ where
                     return Err(AppError::Forbidden.into());
                 }
             }
+            self.blocks_repository
+                .check_not_blocked_by(
+                    user.id.as_ref().unwrap().clone(),
+                    participants
+                        .iter()
+                        .map(|u| u.id.as_ref().unwrap().clone())
+                        .collect(),
+                )
+                .await?;
             if !DiscussionAccess::new(&discussion).can_create_private_task(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(DiscussionAccess::new(&discussion)
+                    .denied(&user, Permission::CreatePrivateTask)
+                    .into());
             }
         };
         let r#type = if participants.is_empty() {
@@ -339,7 +382,9 @@ where
             };
 
             if !TaskAccess::new(&task).can_donate(&user) {
-                return Err(AppError::Forbidden.into());
+                return Err(TaskAccess::new(&task)
+                    .denied(&user, Permission::Donate)
+                    .into());
             }
         }
 
@@ -381,7 +426,7 @@ where
         let donor = self.users_repository.get_by_id(&donor_id).await?;
 
         if !TaskAccess::new(&task_view).can_donate(&donor) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_view).denied(&donor, Permission::Donate));
         }
 
         let task = self
@@ -389,7 +434,10 @@ where
             .get_by_id::<TaskView>(&task_id)
             .await?;
 
-        if !self.can_still_use(task.created_at, Some(task.acceptance_period)) || data.amount <= 0 {
+        if !self.can_still_use(task.created_at, Some(task.acceptance_period))
+            || data.amount <= 0
+            || task.status == TaskRequestStatus::Cancelled
+        {
             return Err(AppError::Forbidden.into());
         }
 
@@ -505,7 +553,7 @@ where
         let donor = self.users_repository.get_by_id(&donor_id).await?;
 
         if !TaskAccess::new(&task_view).can_donate(&donor) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_view).denied(&donor, Permission::Donate));
         }
 
         let task = self
@@ -513,7 +561,10 @@ where
             .get_by_id::<TaskView>(&task_id)
             .await?;
 
-        if !self.can_still_use(task.created_at, Some(task.acceptance_period)) || data.amount <= 0 {
+        if !self.can_still_use(task.created_at, Some(task.acceptance_period))
+            || data.amount <= 0
+            || task.status == TaskRequestStatus::Cancelled
+        {
             return Err(AppError::Forbidden.into());
         }
 
@@ -581,7 +632,7 @@ where
             .await?;
 
         if !TaskAccess::new(&task_access_view).can_reject(&user) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_access_view).denied(&user, Permission::RejectTask));
         }
 
         let task: TaskView = self
@@ -638,7 +689,7 @@ where
             .await?;
 
         if !TaskAccess::new(&task_view).can_accept(&user) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_view).denied(&user, Permission::AcceptTask));
         }
 
         let task = self
@@ -653,6 +704,13 @@ where
             .into());
         }
 
+        if task.status == TaskRequestStatus::Cancelled {
+            return Err(AppError::Generic {
+                description: "The task is cancelled".to_string(),
+            }
+            .into());
+        }
+
         if task
             .donors
             .iter()
@@ -733,7 +791,7 @@ where
             .await?;
 
         if !TaskAccess::new(&task_view).can_deliver(&user) {
-            return Err(AppError::Forbidden);
+            return Err(TaskAccess::new(&task_view).denied(&user, Permission::DeliverTask));
         }
 
         let task = self
@@ -799,6 +857,8 @@ where
                         r#type: PostType::Public,
                         delivered_for_task: Some(task_view.id.clone()),
                         reply_to: None,
+                        price: None,
+                        currency: None,
                     })
                     .await?;
                 let _ = self
@@ -911,25 +971,7 @@ where
 
         let mut is_completed = true;
         if delivered_users.is_empty() {
-            for p in task.donors {
-                let user_wallet = WalletDbService::get_user_wallet_id(&p.id);
-                let res = self
-                    .transactions_repository
-                    .transfer_currency(
-                        wallet_id,
-                        &user_wallet,
-                        p.amount as i64,
-                        &task.currency,
-                        Some("Refund by task".to_owned()),
-                        TransactionType::Refund,
-                    )
-                    .await;
-                if res.is_ok() {
-                    let _ = self.notification_service.on_update_balance(&p.id).await;
-                } else {
-                    is_completed = false;
-                }
-            }
+            is_completed = self.refund_donors(&task).await;
         } else {
             let task_users: Vec<&TaskParticipantForReward> = delivered_users
                 .into_iter()
@@ -977,6 +1019,61 @@ where
         Ok(())
     }
 
+    /// Cancels the not delivered tasks created by the user and refunds their donors
+    pub(crate) async fn cancel_by_creator(&self, user: &Thing) -> AppResult<()> {
+        let tasks = self
+            .tasks_repository
+            .get_unfinished_by_creator(user)
+            .await
+            .map_err(|e| AppError::SurrealDb {
+                source: e.to_string(),
+            })?;
+
+        for task in tasks {
+            let is_delivered = task
+                .participants
+                .iter()
+                .any(|p| p.status == TaskParticipantStatus::Delivered);
+            if is_delivered {
+                continue;
+            }
+            if task.balance.is_some() && !self.refund_donors(&task).await {
+                continue;
+            }
+            let _ = self
+                .tasks_repository
+                .update_status(&task.id, TaskRequestStatus::Cancelled)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Returns false if some of the refunds have failed
+    async fn refund_donors(&self, task: &TaskForReward) -> bool {
+        let wallet_id = task.wallet.id.as_ref().unwrap();
+        let mut is_refunded = true;
+        for p in task.donors.iter() {
+            let user_wallet = WalletDbService::get_user_wallet_id(&p.id);
+            let res = self
+                .transactions_repository
+                .transfer_currency(
+                    wallet_id,
+                    &user_wallet,
+                    p.amount as i64,
+                    &task.currency,
+                    Some("Refund by task".to_owned()),
+                    TransactionType::Refund,
+                )
+                .await;
+            if res.is_ok() {
+                let _ = self.notification_service.on_update_balance(&p.id).await;
+            } else {
+                is_refunded = false;
+            }
+        }
+        is_refunded
+    }
+
     async fn create(
         &self,
         user: &LocalUser,
@@ -988,6 +1085,7 @@ where
     ) -> CtxResult<TaskRequestEntity> {
         let offer_currency = CurrencySymbol::USD;
         let user_thing = user.id.as_ref().unwrap();
+        let request_txt = self.content_filter.filter(&data.content).await?;
         let mut query = self.db.query("BEGIN");
 
         let id = surrealdb::sql::Id::ulid();
@@ -995,7 +1093,7 @@ where
             belongs_to,
             r#type,
             from_user: user_thing.clone(),
-            request_txt: data.content,
+            request_txt: request_txt.text,
             deliverable_type: DeliverableType::PublicPost,
             reward_type: RewardType::OnDelivery,
             currency: offer_currency.clone(),
@@ -1058,6 +1156,10 @@ where
         let task: Option<TaskRequestEntity> = res.take(0)?;
         let task = task.unwrap();
 
+        self.content_filter
+            .flag(&task_data.task_id, request_txt.flagged_words)
+            .await?;
+
         if !participant_ids.is_empty() {
             self.access_repository
                 .add(
@@ -0,0 +1,233 @@
+use serde::Deserialize;
+use surrealdb::sql::Thing;
+use validator::Validate;
+
+use crate::{
+    entities::{
+        audit_log::{AuditAction, AuditActor, CreateAuditLog},
+        user_auth::local_user_entity::TABLE_NAME as USER_TABLE_NAME,
+        wallet::{
+            wallet_adjustment_entity::{
+                AdjustmentStatus, WalletAdjustment, WalletAdjustmentDbService,
+            },
+            wallet_entity::CurrencySymbol,
+        },
+    },
+    middleware::{
+        ctx::Ctx,
+        error::{AppError, CtxResult},
+        mw_ctx::CtxState,
+        utils::{
+            db_utils::{record_exists, Pagination},
+            string_utils::get_str_thing,
+        },
+    },
+    services::notification_service::NotificationService,
+};
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAdjustmentInput {
+    /// positive amount credits and negative debits the user wallet
+    pub amount: i64,
+    pub currency: Option<CurrencySymbol>,
+    #[validate(length(min = 1, max = 1000, message = "Reason must have 1 to 1000 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RejectAdjustmentInput {
+    #[validate(length(max = 1000, message = "Note can have max 1000 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdjustmentsQuery {
+    pub status: Option<AdjustmentStatus>,
+    pub start: Option<u32>,
+    pub count: Option<u16>,
+}
+
+/// Manual corrections of the user balances, the caller has to be authorized as admin already.
+/// Once the adjustments an admin made to a wallet without review add up above the approval
+/// threshold, they wait for the approval of another admin.
+pub struct WalletAdjustmentService<'a> {
+    state: &'a CtxState,
+    ctx: &'a Ctx,
+    adjustments_repository: WalletAdjustmentDbService<'a>,
+}
+
+impl<'a> WalletAdjustmentService<'a> {
+    pub fn new(state: &'a CtxState, ctx: &'a Ctx) -> Self {
+        Self {
+            state,
+            ctx,
+            adjustments_repository: WalletAdjustmentDbService {
+                db: &state.db.client,
+                ctx,
+            },
+        }
+    }
+
+    pub async fn create(
+        &self,
+        actor: AuditActor,
+        user_id: &str,
+        data: CreateAdjustmentInput,
+    ) -> CtxResult<WalletAdjustment> {
+        data.validate()?;
+        if data.amount == 0 {
+            return Err(AppError::Generic {
+                description: "Amount can not be zero".to_string(),
+            }
+            .into());
+        }
+        let user = self.get_user_thing(user_id).await?;
+        if user == actor.user {
+            return Err(AppError::Generic {
+                description: "Admin can not adjust own wallet".to_string(),
+            }
+            .into());
+        }
+
+        let unreviewed_total = self
+            .adjustments_repository
+            .get_unreviewed_total(user.clone(), actor.user.clone())
+            .await?;
+        let id = WalletAdjustmentDbService::get_new_id();
+        let apply_audit = match unreviewed_total + data.amount.unsigned_abs()
+            > self.state.adjustment_approval_threshold
+        {
+            true => None,
+            false => Some(CreateAuditLog {
+                actor: actor.clone(),
+                action: AuditAction::AdjustWallet,
+                target: id.clone(),
+                note: Some(data.reason.clone()),
+            }),
+        };
+        let adjustment = self
+            .adjustments_repository
+            .create(
+                id,
+                user,
+                data.amount,
+                data.currency.unwrap_or(CurrencySymbol::USD),
+                data.reason,
+                actor.user,
+                apply_audit,
+            )
+            .await?;
+
+        if adjustment.status == AdjustmentStatus::Approved {
+            self.on_update_balance(&adjustment.user).await;
+        }
+        Ok(adjustment)
+    }
+
+    pub async fn approve(
+        &self,
+        actor: AuditActor,
+        adjustment_id: &str,
+    ) -> CtxResult<WalletAdjustment> {
+        let adjustment = self.get_pending(adjustment_id).await?;
+        if adjustment.created_by == actor.user {
+            return Err(AppError::Generic {
+                description: "Adjustment must be approved by another admin".to_string(),
+            }
+            .into());
+        }
+        let reviewed_by = actor.user.clone();
+        self.apply(&adjustment, actor, Some(reviewed_by)).await
+    }
+
+    pub async fn reject(
+        &self,
+        actor: AuditActor,
+        adjustment_id: &str,
+        data: RejectAdjustmentInput,
+    ) -> CtxResult<WalletAdjustment> {
+        data.validate()?;
+        let adjustment = self.get_pending(adjustment_id).await?;
+        let audit = CreateAuditLog {
+            actor: actor.clone(),
+            action: AuditAction::RejectWalletAdjustment,
+            target: adjustment.id.clone(),
+            note: data.note.clone(),
+        };
+        self.adjustments_repository
+            .reject(&adjustment.id, actor.user, data.note, audit)
+            .await
+    }
+
+    pub async fn get_list(&self, query: AdjustmentsQuery) -> CtxResult<Vec<WalletAdjustment>> {
+        self.adjustments_repository
+            .get_list(
+                query.status,
+                Pagination {
+                    order_by: None,
+                    order_dir: None,
+                    count: query.count.unwrap_or(50),
+                    start: query.start.unwrap_or(0),
+                },
+            )
+            .await
+    }
+
+    async fn apply(
+        &self,
+        adjustment: &WalletAdjustment,
+        actor: AuditActor,
+        reviewed_by: Option<Thing>,
+    ) -> CtxResult<WalletAdjustment> {
+        let audit = CreateAuditLog {
+            actor,
+            action: AuditAction::AdjustWallet,
+            target: adjustment.id.clone(),
+            note: Some(adjustment.reason.clone()),
+        };
+        let adjustment = self
+            .adjustments_repository
+            .apply(adjustment, reviewed_by, audit)
+            .await?;
+
+        self.on_update_balance(&adjustment.user).await;
+        Ok(adjustment)
+    }
+
+    async fn on_update_balance(&self, user: &Thing) {
+        let _ = NotificationService::new(
+            &self.state.db.client,
+            self.ctx,
+            &self.state.event_sender,
+            &self.state.db.user_notifications,
+        )
+        .on_update_balance(user)
+        .await;
+    }
+
+    async fn get_pending(&self, adjustment_id: &str) -> CtxResult<WalletAdjustment> {
+        let adjustment = self
+            .adjustments_repository
+            .get(get_str_thing(adjustment_id)?)
+            .await?;
+        if adjustment.status != AdjustmentStatus::Pending {
+            return Err(AppError::Generic {
+                description: "Adjustment is already reviewed".to_string(),
+            }
+            .into());
+        }
+        Ok(adjustment)
+    }
+
+    async fn get_user_thing(&self, user_id: &str) -> CtxResult<Thing> {
+        let user = get_str_thing(user_id)?;
+        if user.tb != USER_TABLE_NAME {
+            return Err(AppError::EntityFailIdNotFound {
+                ident: user_id.to_string(),
+            }
+            .into());
+        }
+        record_exists(&self.state.db.client, &user).await?;
+        Ok(user)
+    }
+}
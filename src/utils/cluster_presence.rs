@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Online users of the server instance shared with the other instances over the event bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstancePresence {
+    pub instance_id: String,
+    pub users: Vec<String>,
+}
+
+struct InstanceUsers {
+    users: HashSet<String>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Tells if the user is online on any server instance,
+/// the users of the instances not heard within the ttl are considered offline
+pub struct ClusterPresence {
+    pub instance_id: String,
+    online_users: Arc<DashMap<String, usize>>,
+    instances: DashMap<String, InstanceUsers>,
+    ttl: Duration,
+}
+
+impl ClusterPresence {
+    pub fn new(
+        instance_id: String,
+        online_users: Arc<DashMap<String, usize>>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            instance_id,
+            online_users,
+            instances: DashMap::new(),
+            ttl,
+        }
+    }
+
+    pub fn is_online(&self, user_id: &str) -> bool {
+        self.online_users.contains_key(user_id) || self.is_online_remote(user_id)
+    }
+
+    /// Checks only the users connected to the other instances
+    pub fn is_online_remote(&self, user_id: &str) -> bool {
+        let expired_at = Utc::now() - self.ttl;
+        self.instances
+            .iter()
+            .any(|i| i.updated_at > expired_at && i.users.contains(user_id))
+    }
+
+    /// Returns the online users of the instance
+    pub fn snapshot(&self) -> InstancePresence {
+        InstancePresence {
+            instance_id: self.instance_id.clone(),
+            users: self.online_users.iter().map(|e| e.key().clone()).collect(),
+        }
+    }
+
+    /// Saves the online users of the other instance
+    pub fn apply(&self, presence: InstancePresence) {
+        if presence.instance_id == self.instance_id {
+            return;
+        }
+        self.instances.insert(
+            presence.instance_id,
+            InstanceUsers {
+                users: presence.users.into_iter().collect(),
+                updated_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn remove_stale(&self) {
+        let expired_at = Utc::now() - self.ttl;
+        self.instances.retain(|_, i| i.updated_at > expired_at);
+    }
+}
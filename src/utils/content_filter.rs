@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+
+/// A word of the filtered text with its byte range in the original string
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterToken {
+    pub start: usize,
+    pub end: usize,
+    pub word: String,
+}
+
+/// Symbols which are used instead of letters
+fn leet_char(c: char) -> Option<char> {
+    match c {
+        '@' => Some('a'),
+        '$' => Some('s'),
+        '!' | '|' => Some('i'),
+        '+' => Some('t'),
+        _ => None,
+    }
+}
+
+fn base_char(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        '0' => 'o',
+        '1' => 'i',
+        '3' => 'e',
+        '4' => 'a',
+        '5' => 's',
+        '7' => 't',
+        '8' => 'b',
+        _ => c,
+    }
+}
+
+fn normalize_char(c: char) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    leet_char(lower).unwrap_or_else(|| base_char(lower))
+}
+
+/// Lowercases the word and replaces diacritics and leetspeak symbols by plain letters
+pub fn normalize(word: &str) -> String {
+    word.trim().chars().map(normalize_char).collect()
+}
+
+/// Collapses the repeated letters, e.g. `baaad` into `bad`
+pub fn squeeze(word: &str) -> String {
+    let mut res = String::with_capacity(word.len());
+    let mut last = None;
+    for c in word.chars() {
+        if last != Some(c) {
+            res.push(c);
+        }
+        last = Some(c);
+    }
+    res
+}
+
+/// Splits the text into normalized words. The leetspeak symbols are a part of a word
+/// only when they are next to a letter, so the punctuation stays a separator.
+pub fn tokenize(text: &str) -> Vec<FilterToken> {
+    let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+    let mut tokens = vec![];
+    let mut current: Option<FilterToken> = None;
+
+    for (index, (pos, c)) in chars.iter().enumerate() {
+        let is_word_char = c.is_alphanumeric()
+            || (leet_char(*c).is_some()
+                && (current.is_some()
+                    || chars
+                        .get(index + 1)
+                        .is_some_and(|(_, next)| next.is_alphanumeric())));
+
+        if is_word_char {
+            let token = current.get_or_insert(FilterToken {
+                start: *pos,
+                end: *pos,
+                word: String::new(),
+            });
+            token.end = pos + c.len_utf8();
+            token.word.push(normalize_char(*c));
+        } else if let Some(token) = current.take() {
+            tokens.push(token);
+        }
+    }
+    if let Some(token) = current.take() {
+        tokens.push(token);
+    }
+
+    tokens
+        .into_iter()
+        .filter_map(|mut token| {
+            // sentence punctuation like `bad!` must not change the word
+            while text[..token.end].ends_with(['!', '|', '+']) {
+                token.end -= 1;
+                token.word.pop();
+            }
+            (!token.word.is_empty()).then_some(token)
+        })
+        .collect()
+}
+
+/// The variants of the token to look up in the word list
+pub fn token_variants(token: &FilterToken) -> Vec<String> {
+    let squeezed = squeeze(&token.word);
+    if squeezed == token.word {
+        vec![squeezed]
+    } else {
+        vec![token.word.clone(), squeezed]
+    }
+}
+
+/// Replaces every character of the tokens containing one of `words` with `*`
+pub fn mask(text: &str, tokens: &[FilterToken], words: &HashSet<String>) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut last = 0;
+    for token in tokens {
+        if !token_variants(token).iter().any(|v| words.contains(v)) {
+            continue;
+        }
+        res.push_str(&text[last..token.start]);
+        res.push_str(&"*".repeat(text[token.start..token.end].chars().count()));
+        last = token.end;
+    }
+    res.push_str(&text[last..]);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_leetspeak_and_diacritics() {
+        assert_eq!(normalize("Ćr@p"), "crap");
+        assert_eq!(normalize("h3ll0"), "hello");
+        assert_eq!(normalize("Bäd"), "bad");
+    }
+
+    #[test]
+    fn tokenizes_with_positions() {
+        let tokens = tokenize("What a $hit, b@d!");
+        let words = tokens.iter().map(|t| t.word.as_str()).collect::<Vec<_>>();
+        assert_eq!(words, vec!["what", "a", "shit", "bad"]);
+        assert_eq!(&"What a $hit, b@d!"[tokens[3].start..tokens[3].end], "b@d");
+    }
+
+    #[test]
+    fn masks_words() {
+        let text = "So baaad and Bäd!";
+        let words = HashSet::from(["bad".to_string()]);
+        assert_eq!(mask(text, &tokenize(text), &words), "So ***** and ***!");
+    }
+}
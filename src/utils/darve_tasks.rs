@@ -3,7 +3,6 @@ use rand::{rng, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use surrealdb::sql::Thing;
-use tokio::sync::broadcast::Sender;
 use tokio::sync::Mutex;
 
 use crate::{
@@ -17,13 +16,13 @@ use crate::{
         },
     },
     interfaces::{
+        event_bus::EventBusInterface,
         file_storage::FileStorageInterface,
         repositories::task_request_ifce::TaskRequestRepositoryInterface,
     },
     middleware::{
         ctx::Ctx,
         error::{AppError, AppResult},
-        mw_ctx::AppEvent,
         utils::db_utils::{Pagination, QryOrder, ViewFieldSelector},
     },
     models::view::task::TaskRequestView,
@@ -81,7 +80,7 @@ impl DarveTasksUtils {
     pub async fn create_public(
         &self,
         user_id: &str,
-        sender_event: &Sender<AppEvent>,
+        sender_event: &(dyn EventBusInterface + Send + Sync),
     ) -> AppResult<Vec<TaskRequestView>> {
         let _lock = self.create_public_lock.lock().await;
         let darve_id = self.get_darve_profile_id().await?;
@@ -182,7 +181,7 @@ impl DarveTasksUtils {
     pub async fn create_private(
         &self,
         user_id: &str,
-        sender_event: &Sender<AppEvent>,
+        sender_event: &(dyn EventBusInterface + Send + Sync),
     ) -> AppResult<Vec<TaskRequestView>> {
         let darve_id = self.get_darve_profile_id().await?;
 
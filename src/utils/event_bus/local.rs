@@ -0,0 +1,32 @@
+use tokio::sync::broadcast;
+
+use crate::{interfaces::event_bus::EventBusInterface, middleware::mw_ctx::AppEvent};
+
+/// Delivers the events to the subscribers of the current server instance only
+pub struct LocalEventBus {
+    sender: broadcast::Sender<AppEvent>,
+}
+
+impl LocalEventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+}
+
+impl EventBusInterface for LocalEventBus {
+    fn send(&self, event: AppEvent) -> Result<(), String> {
+        self.sender
+            .send(event)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+
+    fn subscribe_own(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,25 @@
+pub mod local;
+pub mod surreal;
+
+use std::sync::Arc;
+
+use crate::{config::AppConfig, database::client::Db, interfaces::event_bus::EventBusInterface};
+use local::LocalEventBus;
+use surreal::SurrealEventBus;
+
+const EVENTS_CAPACITY: usize = 100;
+
+pub async fn create_event_bus(
+    config: &AppConfig,
+    db: Arc<Db>,
+    instance_id: &str,
+) -> Arc<dyn EventBusInterface + Send + Sync> {
+    match config.event_bus.as_str() {
+        "surrealdb" => Arc::new(
+            SurrealEventBus::new(db, instance_id, EVENTS_CAPACITY)
+                .await
+                .expect("SurrealDB event bus should start"),
+        ),
+        _ => Arc::new(LocalEventBus::new(EVENTS_CAPACITY)),
+    }
+}
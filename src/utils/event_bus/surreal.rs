@@ -0,0 +1,192 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use surrealdb::{method::Stream, Action, Notification};
+use tokio::sync::{
+    broadcast,
+    mpsc::{self, error::TrySendError},
+};
+
+use crate::{
+    database::client::Db,
+    interfaces::event_bus::EventBusInterface,
+    middleware::{
+        error::AppError,
+        mw_ctx::{AppEvent, AppEventType},
+    },
+};
+
+pub const TABLE_NAME: &str = "app_event";
+/// the events are read right away by the live queries, the older ones are removed
+const EVENT_TTL: &str = "1m";
+/// the delay before subscribing again to the closed live query, doubled after each failure
+const RECONNECT_DELAY_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BusEvent {
+    event: AppEvent,
+    receivers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventRecord {
+    instance_id: String,
+    payload: String,
+}
+
+/// Shares the events between the server instances through a SurrealDB table and live queries,
+/// the events are delivered to the subscribers of the current instance right away
+pub struct SurrealEventBus {
+    local: broadcast::Sender<AppEvent>,
+    /// the events raised on the current instance
+    own: broadcast::Sender<AppEvent>,
+    outgoing: mpsc::Sender<AppEvent>,
+}
+
+impl SurrealEventBus {
+    pub async fn new(db: Arc<Db>, instance_id: &str, capacity: usize) -> Result<Self, AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS instance_id ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS payload ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE {TABLE_NAME} COLUMNS created_at;
+");
+        db.query(sql).await?.check()?;
+
+        let (local, _) = broadcast::channel(capacity);
+        let (own, _) = broadcast::channel(capacity);
+        let (outgoing, outgoing_rx) = mpsc::channel(capacity);
+        let stream = db.select::<Vec<EventRecord>>(TABLE_NAME).live().await?;
+
+        tokio::spawn(Self::publish(
+            db.clone(),
+            instance_id.to_string(),
+            outgoing_rx,
+        ));
+        tokio::spawn(Self::listen(
+            db,
+            local.clone(),
+            instance_id.to_string(),
+            stream,
+        ));
+
+        Ok(Self {
+            local,
+            own,
+            outgoing,
+        })
+    }
+
+    /// Delivers the events of the other instances and subscribes again when the live query closes
+    async fn listen(
+        db: Arc<Db>,
+        sender: broadcast::Sender<AppEvent>,
+        instance_id: String,
+        mut stream: Stream<Vec<EventRecord>>,
+    ) {
+        let mut delay = RECONNECT_DELAY_MIN;
+        loop {
+            while let Some(notification) = stream.next().await {
+                match notification {
+                    Ok(notification) => {
+                        delay = RECONNECT_DELAY_MIN;
+                        Self::receive(&sender, &instance_id, notification)
+                    }
+                    Err(err) => println!("Error receiving bus event: {:?}", err),
+                }
+            }
+            println!("Bus live query closed, subscribing again");
+
+            stream = loop {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RECONNECT_DELAY_MAX);
+                match db.select::<Vec<EventRecord>>(TABLE_NAME).live().await {
+                    Ok(stream) => break stream,
+                    Err(err) => println!("Error subscribing to bus events: {:?}", err),
+                }
+            };
+        }
+    }
+
+    async fn publish(db: Arc<Db>, instance_id: String, mut rx: mpsc::Receiver<AppEvent>) {
+        while let Some(event) = rx.recv().await {
+            let receivers = event.receivers.clone();
+            let payload = match serde_json::to_string(&BusEvent { event, receivers }) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    println!("Error serializing bus event: {:?}", err);
+                    continue;
+                }
+            };
+
+            let res = db
+                .query(format!(
+                    "CREATE {TABLE_NAME} SET instance_id=$instance_id, payload=$payload;
+                     DELETE {TABLE_NAME} WHERE created_at < time::now() - {EVENT_TTL};"
+                ))
+                .bind(("instance_id", instance_id.clone()))
+                .bind(("payload", payload))
+                .await
+                .and_then(|res| res.check());
+            if let Err(err) = res {
+                println!("Error publishing bus event: {:?}", err);
+            }
+        }
+    }
+
+    fn receive(
+        sender: &broadcast::Sender<AppEvent>,
+        instance_id: &str,
+        notification: Notification<EventRecord>,
+    ) {
+        let record = match notification.action {
+            Action::Create => notification.data,
+            _ => return,
+        };
+        if record.instance_id == instance_id {
+            return;
+        }
+        match serde_json::from_str::<BusEvent>(&record.payload) {
+            Ok(BusEvent {
+                mut event,
+                receivers,
+            }) => {
+                event.receivers = receivers;
+                let _ = sender.send(event);
+            }
+            Err(err) => println!("Error deserializing bus event: {:?}", err),
+        }
+    }
+}
+
+impl EventBusInterface for SurrealEventBus {
+    fn send(&self, event: AppEvent) -> Result<(), String> {
+        // the push notifications are sent only by the instance that raised them
+        if !matches!(event.event, AppEventType::PushNotification(_)) {
+            match self.outgoing.try_send(event.clone()) {
+                Ok(_) => (),
+                // the other instances miss the event but the local subscribers still get it
+                Err(TrySendError::Full(_)) => {
+                    println!("Error publishing bus event: the outgoing queue is full")
+                }
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+        let _ = self.own.send(event.clone());
+        self.local
+            .send(event)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.local.subscribe()
+    }
+
+    fn subscribe_own(&self) -> broadcast::Receiver<AppEvent> {
+        self.own.subscribe()
+    }
+}
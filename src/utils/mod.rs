@@ -1,12 +1,16 @@
 pub mod askama_filter_util;
-pub mod blocked_words;
+pub mod cluster_presence;
+pub mod content_filter;
 pub mod darve_tasks;
 pub mod email_sender;
+pub mod event_bus;
 pub mod file;
 pub mod generate;
 pub mod hash;
 pub mod jwt;
 pub mod paypal;
+pub mod push;
+pub mod sse_buffer;
 pub mod template_utils;
 pub mod totp;
 pub mod user_presence_guard;
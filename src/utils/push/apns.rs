@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+
+use chrono::Utc;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::interfaces::push_sender::{PushError, PushMessage};
+
+const PRODUCTION_URL: &str = "https://api.push.apple.com";
+const SANDBOX_URL: &str = "https://api.sandbox.push.apple.com";
+/// apple rejects the provider tokens older than one hour
+const TOKEN_TTL_SECS: i64 = 50 * 60;
+const INVALID_TOKEN_REASONS: [&str; 3] =
+    ["BadDeviceToken", "Unregistered", "DeviceTokenNotForTopic"];
+
+#[derive(Debug, Serialize)]
+struct ProviderClaims<'a> {
+    iss: &'a str,
+    iat: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    reason: String,
+}
+
+pub struct ApnsPushSender {
+    client: Client,
+    key: EncodingKey,
+    key_id: String,
+    team_id: String,
+    topic: String,
+    base_url: &'static str,
+    /// the provider token with the issue timestamp
+    token: Mutex<Option<(String, i64)>>,
+}
+
+impl ApnsPushSender {
+    pub fn new(
+        key_pem: &[u8],
+        key_id: &str,
+        team_id: &str,
+        topic: &str,
+        sandbox: bool,
+    ) -> Result<Self, String> {
+        let key = EncodingKey::from_ec_pem(key_pem).map_err(|e| e.to_string())?;
+        // apns accepts only http2 connections
+        let client = Client::builder()
+            .http2_prior_knowledge()
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            key,
+            key_id: key_id.to_string(),
+            team_id: team_id.to_string(),
+            topic: topic.to_string(),
+            base_url: if sandbox { SANDBOX_URL } else { PRODUCTION_URL },
+            token: Mutex::new(None),
+        })
+    }
+
+    fn get_provider_token(&self) -> Result<String, PushError> {
+        let now = Utc::now().timestamp();
+        let mut token = self.token.lock().unwrap();
+        if let Some((ref value, issued_at)) = *token {
+            if now - issued_at < TOKEN_TTL_SECS {
+                return Ok(value.clone());
+            }
+        }
+
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.key_id.clone());
+        let claims = ProviderClaims {
+            iss: &self.team_id,
+            iat: now,
+        };
+        let value =
+            encode(&header, &claims, &self.key).map_err(|e| PushError::Provider(e.to_string()))?;
+        *token = Some((value.clone(), now));
+        Ok(value)
+    }
+
+    pub async fn send(&self, device_token: &str, message: &PushMessage) -> Result<(), PushError> {
+        let mut payload = json!({
+            "aps": {
+                "alert": { "title": message.title },
+                "badge": message.badge,
+                "sound": "default",
+            }
+        });
+        if let Some(ref data) = message.data {
+            payload["data"] = data.clone();
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/3/device/{}", self.base_url, device_token))
+            .bearer_auth(self.get_provider_token()?)
+            .header("apns-topic", &self.topic)
+            .header("apns-push-type", "alert")
+            .header("apns-priority", "10")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| PushError::Provider(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let reason = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|r| r.reason)
+            .unwrap_or_default();
+        if status == StatusCode::GONE || INVALID_TOKEN_REASONS.contains(&reason.as_str()) {
+            return Err(PushError::InvalidToken);
+        }
+        Err(PushError::Provider(format!("APNs {}: {}", status, reason)))
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::Utc;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::interfaces::push_sender::{PushError, PushMessage};
+
+const SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const ASSERTION_TTL_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Deserialize)]
+struct ServiceAccount {
+    project_id: String,
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AssertionClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessTokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+pub struct FcmPushSender {
+    client: Client,
+    account: ServiceAccount,
+    key: EncodingKey,
+    /// the oauth access token with the expiration timestamp
+    access_token: Mutex<Option<(String, i64)>>,
+}
+
+impl FcmPushSender {
+    /// Takes the json of the firebase service account
+    pub fn new(credentials: &str) -> Result<Self, String> {
+        let account =
+            serde_json::from_str::<ServiceAccount>(credentials).map_err(|e| e.to_string())?;
+        let key =
+            EncodingKey::from_rsa_pem(account.private_key.as_bytes()).map_err(|e| e.to_string())?;
+        Ok(Self {
+            client: Client::new(),
+            account,
+            key,
+            access_token: Mutex::new(None),
+        })
+    }
+
+    async fn get_access_token(&self) -> Result<String, PushError> {
+        let now = Utc::now().timestamp();
+        let mut access_token = self.access_token.lock().await;
+        if let Some((ref value, expires_at)) = *access_token {
+            if now < expires_at - 60 {
+                return Ok(value.clone());
+            }
+        }
+
+        let claims = AssertionClaims {
+            iss: &self.account.client_email,
+            scope: SCOPE,
+            aud: &self.account.token_uri,
+            iat: now,
+            exp: now + ASSERTION_TTL_SECS,
+        };
+        let assertion = encode(&Header::new(Algorithm::RS256), &claims, &self.key)
+            .map_err(|e| PushError::Provider(e.to_string()))?;
+        let response = self
+            .client
+            .post(&self.account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| PushError::Provider(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PushError::Provider(e.to_string()))?
+            .json::<AccessTokenResponse>()
+            .await
+            .map_err(|e| PushError::Provider(e.to_string()))?;
+
+        *access_token = Some((response.access_token.clone(), now + response.expires_in));
+        Ok(response.access_token)
+    }
+
+    pub async fn send(&self, device_token: &str, message: &PushMessage) -> Result<(), PushError> {
+        let mut payload = json!({
+            "message": {
+                "token": device_token,
+                "notification": { "title": message.title },
+                "android": {
+                    "notification": { "notification_count": message.badge }
+                },
+            }
+        });
+        if let Some(ref data) = message.data {
+            // fcm accepts only string values of the data
+            payload["message"]["data"] = json!({ "payload": data.to_string() });
+        }
+
+        let response = self
+            .client
+            .post(format!(
+                "https://fcm.googleapis.com/v1/projects/{}/messages:send",
+                self.account.project_id
+            ))
+            .bearer_auth(self.get_access_token().await?)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| PushError::Provider(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        if status == StatusCode::NOT_FOUND || body.contains("UNREGISTERED") {
+            return Err(PushError::InvalidToken);
+        }
+        Err(PushError::Provider(format!("FCM {}: {}", status, body)))
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::{
+    entities::push_device::DevicePlatform,
+    interfaces::push_sender::{PushError, PushMessage, PushSenderInterface},
+};
+
+pub const MOCK_INVALID_TOKEN_PREFIX: &str = "invalid";
+
+/// Local sender which only logs the messages, the tokens with the invalid prefix are rejected
+pub struct MockPushSender;
+
+#[async_trait]
+impl PushSenderInterface for MockPushSender {
+    async fn send(
+        &self,
+        platform: &DevicePlatform,
+        token: &str,
+        message: &PushMessage,
+    ) -> Result<(), PushError> {
+        if token.starts_with(MOCK_INVALID_TOKEN_PREFIX) {
+            return Err(PushError::InvalidToken);
+        }
+        info!(
+            "Push to {:?} device {}: {} (badge {})",
+            platform, token, message.title, message.badge
+        );
+        Ok(())
+    }
+}
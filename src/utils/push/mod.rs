@@ -0,0 +1,67 @@
+pub mod apns;
+pub mod fcm;
+pub mod mock;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::AppConfig,
+    entities::push_device::DevicePlatform,
+    interfaces::push_sender::{PushError, PushMessage, PushSenderInterface},
+};
+use apns::ApnsPushSender;
+use fcm::FcmPushSender;
+use mock::MockPushSender;
+
+/// Routes the messages to the provider of the device platform
+pub struct ProviderPushSender {
+    apns: Option<ApnsPushSender>,
+    fcm: Option<FcmPushSender>,
+}
+
+#[async_trait]
+impl PushSenderInterface for ProviderPushSender {
+    async fn send(
+        &self,
+        platform: &DevicePlatform,
+        token: &str,
+        message: &PushMessage,
+    ) -> Result<(), PushError> {
+        match platform {
+            DevicePlatform::Ios => match self.apns {
+                Some(ref apns) => apns.send(token, message).await,
+                None => Err(PushError::Provider("APNs is not configured".to_string())),
+            },
+            DevicePlatform::Android => match self.fcm {
+                Some(ref fcm) => fcm.send(token, message).await,
+                None => Err(PushError::Provider("FCM is not configured".to_string())),
+            },
+        }
+    }
+}
+
+pub fn create_push_sender(config: &AppConfig) -> Arc<dyn PushSenderInterface + Send + Sync> {
+    let apns = config.apns_key_path.as_ref().map(|path| {
+        let key = std::fs::read(path).expect("APNS_KEY_PATH should be readable");
+        ApnsPushSender::new(
+            &key,
+            &config.apns_key_id,
+            &config.apns_team_id,
+            &config.apns_topic,
+            config.apns_sandbox,
+        )
+        .expect("APNs key should be valid")
+    });
+    let fcm = config.fcm_credentials_path.as_ref().map(|path| {
+        let credentials =
+            std::fs::read_to_string(path).expect("FCM_CREDENTIALS_PATH should be readable");
+        FcmPushSender::new(&credentials).expect("FCM credentials should be valid")
+    });
+
+    match (apns, fcm) {
+        (None, None) => Arc::new(MockPushSender),
+        (apns, fcm) => Arc::new(ProviderPushSender { apns, fcm }),
+    }
+}
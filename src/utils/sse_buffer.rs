@@ -0,0 +1,372 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use futures::StreamExt;
+use serde::Deserialize;
+use surrealdb::{method::Stream, Action};
+use tokio::{sync::broadcast, task::JoinHandle};
+
+use crate::{
+    database::client::Db, entities::wallet::wallet_entity::check_transaction_custom_error,
+    middleware::error::AppError,
+};
+
+pub const RESYNC_EVENT_NAME: &str = "Resync";
+pub const TABLE_NAME: &str = "sse_event";
+const SEQ_TABLE_NAME: &str = "sse_event_seq";
+const PUBLISH_ATTEMPTS: u32 = 3;
+/// the delay before subscribing again to the closed live query
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+/// the events created this long before the live query closed are loaded again
+const RECONNECT_MARGIN_SECS: i64 = 5;
+
+/// Event delivered to the SSE connections
+#[derive(Debug, Clone)]
+pub struct SseEvent {
+    /// the events without the id are not buffered and can not be replayed
+    pub id: Option<u64>,
+    /// the user who raised the event
+    pub user_id: String,
+    pub name: String,
+    pub data: Option<String>,
+    /// the event is delivered to all the users if not set
+    pub receivers: Option<Vec<String>>,
+}
+
+impl SseEvent {
+    pub fn is_for(&self, user_id: &str) -> bool {
+        self.receivers
+            .as_ref()
+            .map_or(true, |r| r.iter().any(|id| id == user_id))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SseEventRecord {
+    seq: u64,
+    user_id: String,
+    name: String,
+    data: Option<String>,
+    receiver: String,
+}
+
+impl From<SseEventRecord> for SseEvent {
+    fn from(value: SseEventRecord) -> Self {
+        Self {
+            id: Some(value.seq),
+            user_id: value.user_id,
+            name: value.name,
+            data: value.data,
+            receivers: Some(vec![value.receiver]),
+        }
+    }
+}
+
+/// Short-term buffer of the SSE events used to replay the missed events on reconnect.
+/// The events are stored in a SurrealDB table shared by the server instances, one record
+/// per receiver with the id taken from the sequence of the receiver, so the last event id
+/// is valid on any instance and only the events of the same receiver conflict.
+pub struct SseBuffer {
+    db: Arc<Db>,
+    sender: broadcast::Sender<Arc<SseEvent>>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl SseBuffer {
+    pub fn new(db: Arc<Db>, capacity: usize, ttl: Duration) -> Self {
+        let (sender, _) = broadcast::channel(100);
+        Self {
+            db,
+            sender,
+            capacity,
+            ttl,
+        }
+    }
+
+    pub async fn mutate_db(db: &Db) -> Result<(), AppError> {
+        let sql = format!("
+    DEFINE TABLE IF NOT EXISTS {TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS seq ON TABLE {TABLE_NAME} TYPE int;
+    DEFINE FIELD IF NOT EXISTS receiver ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS user_id ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS name ON TABLE {TABLE_NAME} TYPE string;
+    DEFINE FIELD IF NOT EXISTS data ON TABLE {TABLE_NAME} TYPE option<string>;
+    DEFINE FIELD IF NOT EXISTS created_at ON TABLE {TABLE_NAME} TYPE datetime DEFAULT time::now() VALUE $before OR time::now();
+    DEFINE INDEX IF NOT EXISTS receiver_seq_idx ON TABLE {TABLE_NAME} COLUMNS receiver, seq UNIQUE;
+    DEFINE INDEX IF NOT EXISTS created_at_idx ON TABLE {TABLE_NAME} COLUMNS created_at;
+
+    DEFINE TABLE IF NOT EXISTS {SEQ_TABLE_NAME} SCHEMAFULL;
+    DEFINE FIELD IF NOT EXISTS value ON TABLE {SEQ_TABLE_NAME} TYPE int DEFAULT 0;
+");
+        let mutation = db.query(sql).await?;
+        mutation.check().expect("should mutate sse_event");
+
+        Ok(())
+    }
+
+    /// Starts delivering the stored events of all the instances to the connections
+    pub async fn listen(self: Arc<Self>) -> Result<JoinHandle<()>, AppError> {
+        let stream = self
+            .db
+            .select::<Vec<SseEventRecord>>(TABLE_NAME)
+            .live()
+            .await?;
+        Ok(tokio::spawn(async move { self.receive(stream).await }))
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<SseEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the last event id stored for the user
+    pub async fn last_id(&self, user_id: &str) -> Result<u64, AppError> {
+        let mut res = self
+            .db
+            .query(format!(
+                "SELECT VALUE value FROM ONLY type::thing('{SEQ_TABLE_NAME}', $user_id);"
+            ))
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        Ok(res.take::<Option<u64>>(0)?.unwrap_or_default())
+    }
+
+    /// Stores the event for the receivers, it is sent to the connections by the live query
+    pub async fn publish(
+        &self,
+        user_id: &str,
+        name: &str,
+        data: Option<String>,
+        receivers: Vec<String>,
+    ) -> Result<(), AppError> {
+        let mut attempt = 1;
+        loop {
+            match self.create(user_id, name, &data, &receivers).await {
+                Ok(()) => return Ok(()),
+                // the concurrent events of the same receivers conflict on their sequences
+                Err(err) if attempt < PUBLISH_ATTEMPTS => {
+                    println!("Error storing sse event, retrying: {:?}", err);
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Sends the event to the connections of the current instance without buffering,
+    /// the event is sent to all the users if there are no receivers
+    pub fn publish_ephemeral(
+        &self,
+        user_id: &str,
+        name: &str,
+        data: Option<String>,
+        receivers: Option<Vec<String>>,
+    ) {
+        let _ = self.sender.send(Arc::new(SseEvent {
+            id: None,
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+            data,
+            receivers,
+        }));
+    }
+
+    /// Tells the connections of the receivers on the current instance to resync,
+    /// used when the events are not stored, all the users resync if there are no receivers
+    pub fn resync(&self, receivers: Option<Vec<String>>) {
+        let _ = self.sender.send(Arc::new(SseEvent {
+            id: None,
+            user_id: "".to_string(),
+            name: RESYNC_EVENT_NAME.to_string(),
+            data: None,
+            receivers,
+        }));
+    }
+
+    /// Returns the stored events of the user after the id
+    /// or none if some of them are not available anymore
+    pub async fn replay(&self, user_id: &str, last_id: u64) -> Option<Vec<Arc<SseEvent>>> {
+        let res = self
+            .db
+            .query(format!(
+                "SELECT VALUE value FROM ONLY type::thing('{SEQ_TABLE_NAME}', $user_id);
+                 SELECT VALUE seq FROM {TABLE_NAME} WHERE receiver=$user_id ORDER BY seq LIMIT 1;
+                 SELECT * FROM {TABLE_NAME} WHERE receiver=$user_id AND seq > $last_id
+                    ORDER BY seq LIMIT $limit;"
+            ))
+            .bind(("last_id", last_id))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("limit", self.capacity + 1))
+            .await;
+        let (seq, oldest_id, records) = match res {
+            Ok(mut res) => (
+                res.take::<Option<u64>>(0).ok()?.unwrap_or_default(),
+                res.take::<Option<u64>>(1).ok()?,
+                res.take::<Vec<SseEventRecord>>(2).ok()?,
+            ),
+            Err(err) => {
+                println!("Error replaying sse events: {:?}", err);
+                return None;
+            }
+        };
+
+        // the id is unknown or the events after it are expired
+        if last_id > seq || (last_id < seq && oldest_id.map_or(true, |id| id > last_id + 1)) {
+            return None;
+        }
+        if records.len() > self.capacity {
+            return None;
+        }
+        Some(
+            records
+                .into_iter()
+                .map(|r| Arc::new(SseEvent::from(r)))
+                .collect(),
+        )
+    }
+
+    /// Returns the stored events of the user after the id or the resync event
+    pub async fn replay_or_resync(&self, user_id: &str, last_id: u64) -> Vec<Arc<SseEvent>> {
+        if let Some(events) = self.replay(user_id, last_id).await {
+            return events;
+        }
+        // the cursor moves to the last id so the next replay starts after the resync
+        let id = match self.last_id(user_id).await {
+            Ok(id) => Some(id),
+            Err(err) => {
+                println!("Error getting last sse event id: {:?}", err);
+                None
+            }
+        };
+        vec![Arc::new(SseEvent {
+            id,
+            user_id: "".to_string(),
+            name: RESYNC_EVENT_NAME.to_string(),
+            data: None,
+            receivers: Some(vec![user_id.to_string()]),
+        })]
+    }
+
+    /// Removes the events older than the ttl
+    pub async fn remove_expired(&self) -> Result<(), AppError> {
+        self.db
+            .query(format!(
+                "DELETE {TABLE_NAME} WHERE created_at < time::now() - {}s;",
+                self.ttl.num_seconds()
+            ))
+            .await?
+            .check()?;
+        Ok(())
+    }
+
+    async fn create(
+        &self,
+        user_id: &str,
+        name: &str,
+        data: &Option<String>,
+        receivers: &[String],
+    ) -> Result<(), AppError> {
+        let mut res = self
+            .db
+            .query("BEGIN")
+            .query(format!(
+                "FOR $receiver IN $receivers {{
+                    LET $seq = (UPSERT ONLY type::thing('{SEQ_TABLE_NAME}', $receiver) SET value += 1).value;
+                    CREATE {TABLE_NAME} SET seq=$seq, receiver=$receiver, user_id=$user_id, name=$name, data=$data;
+                }};"
+            ))
+            .query("COMMIT")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("name", name.to_string()))
+            .bind(("data", data.clone()))
+            .bind(("receivers", receivers.to_vec()))
+            .await?;
+        check_transaction_custom_error(&mut res)?;
+        Ok(())
+    }
+
+    /// Sends the created events to the connections and subscribes again when the live query closes,
+    /// the events created in the meantime are loaded from the table
+    /// and the cursors of the connections skip the ones already sent
+    async fn receive(&self, mut stream: Stream<Vec<SseEventRecord>>) {
+        loop {
+            while let Some(notification) = stream.next().await {
+                match notification {
+                    Ok(notification) => {
+                        if let Action::Create = notification.action {
+                            self.deliver(notification.data)
+                        }
+                    }
+                    Err(err) => println!("Error receiving sse event: {:?}", err),
+                }
+            }
+            println!("SSE events live query closed, subscribing again");
+            let since = Utc::now() - Duration::seconds(RECONNECT_MARGIN_SECS);
+
+            stream = loop {
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                match self
+                    .db
+                    .select::<Vec<SseEventRecord>>(TABLE_NAME)
+                    .live()
+                    .await
+                {
+                    Ok(stream) => break stream,
+                    Err(err) => println!("Error subscribing to sse events: {:?}", err),
+                }
+            };
+
+            let missed = self
+                .db
+                .query(format!(
+                    "SELECT * FROM {TABLE_NAME} WHERE created_at > <datetime>$since ORDER BY seq;"
+                ))
+                .bind(("since", since))
+                .await
+                .and_then(|mut res| res.take::<Vec<SseEventRecord>>(0));
+            match missed {
+                Ok(records) => records.into_iter().for_each(|r| self.deliver(r)),
+                Err(err) => {
+                    println!("Error loading missed sse events: {:?}", err);
+                    // the receivers of the missed events are unknown
+                    self.resync(None);
+                }
+            }
+        }
+    }
+
+    fn deliver(&self, record: SseEventRecord) {
+        let _ = self.sender.send(Arc::new(SseEvent::from(record)));
+    }
+}
+
+/// Last event id of the user sent to the connection, used to skip the duplicated events
+/// and to replay the events after the connection lagged
+pub struct SseCursor {
+    last_id: AtomicU64,
+}
+
+impl SseCursor {
+    pub fn new(last_id: u64) -> Self {
+        Self {
+            last_id: AtomicU64::new(last_id),
+        }
+    }
+
+    pub fn last_id(&self) -> u64 {
+        self.last_id.load(Ordering::SeqCst)
+    }
+
+    /// Moves the cursor to the event or returns false if the event was already sent
+    pub fn accept(&self, event: &SseEvent) -> bool {
+        match event.id {
+            Some(id) if event.name == RESYNC_EVENT_NAME => {
+                self.last_id.fetch_max(id, Ordering::SeqCst);
+                true
+            }
+            Some(id) => self.last_id.fetch_max(id, Ordering::SeqCst) < id,
+            None => true,
+        }
+    }
+}
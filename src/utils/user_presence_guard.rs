@@ -21,7 +21,8 @@ impl UserPresenceGuard {
         let mut count = online_users.entry(user_id.clone()).or_insert(0);
         *count += 1;
 
-        if *count == 1 {
+        // the status is already sent if the user is connected to another instance
+        if *count == 1 && !state.presence.is_online_remote(&user_id) {
             let _ = state.event_sender.send(AppEvent {
                 user_id: user_id.clone(),
                 metadata: None,
@@ -44,6 +45,7 @@ impl Drop for UserPresenceGuard {
         let user_id = self.user_id.clone();
         let online_users = self.state.online_users.clone();
         let event = self.state.event_sender.clone();
+        let presence = self.state.presence.clone();
         let db = self.state.db.client.clone();
         info!("UserPresenceGuard drop: user_id: {:?}", user_id);
         tokio::spawn(async move {
@@ -54,15 +56,16 @@ impl Drop for UserPresenceGuard {
                     return;
                 }
             }
-            let _ = event.send(AppEvent {
-                user_id: user_id.clone(),
-                metadata: None,
-                content: None,
-                receivers: vec![],
-                event: AppEventType::UserStatus(AppEventUsetStatus { is_online: false }),
-            });
-
             online_users.remove(&user_id);
+            if !presence.is_online_remote(&user_id) {
+                let _ = event.send(AppEvent {
+                    user_id: user_id.clone(),
+                    metadata: None,
+                    content: None,
+                    receivers: vec![],
+                    event: AppEventType::UserStatus(AppEventUsetStatus { is_online: false }),
+                });
+            }
             let user_db_service = LocalUserDbService {
                 db: &db,
                 ctx: &Ctx::new(Ok(user_id.clone()), false),
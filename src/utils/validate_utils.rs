@@ -1,4 +1,3 @@
-use crate::utils::blocked_words::BLOCKED_WORDS;
 use chrono::{DateTime, Months, Utc};
 use core::fmt;
 use regex::Regex;
@@ -25,12 +24,6 @@ pub fn validate_username(u: &String) -> Result<(), ValidationError> {
         return Err(ValidationError::new("")
             .with_message("Letters, numbers and '_'. Minimum 5 characters".into()));
     }
-    let username_lower = u.to_lowercase();
-    if BLOCKED_WORDS.contains(&username_lower) {
-        return Err(
-            ValidationError::new("").with_message("This username contains forbidden words".into())
-        );
-    }
 
     Ok(())
 }
@@ -114,6 +107,18 @@ where
     deserializer.deserialize_any(IdExtractor)
 }
 
+pub fn deserialize_thing_or_string_ids<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(transparent)]
+    struct Id(#[serde(deserialize_with = "deserialize_thing_or_string_id")] String);
+
+    let ids = Option::<Vec<Id>>::deserialize(deserializer)?;
+    Ok(ids.unwrap_or_default().into_iter().map(|id| id.0).collect())
+}
+
 pub fn deserialize_thing_or_string<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -0,0 +1,105 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::access::base::explain::AccessExplain;
+use darve_server::access::base::permission::Permission;
+use darve_server::access::base::role::Role;
+use darve_server::entities::community::discussion_entity::DiscussionDbService;
+use darve_server::models::view::post::PostView;
+
+test_with_server!(explain_post_access, |server, ctx_state, config| {
+    let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, user_token) = create_fake_login_test_user(&server).await;
+
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+    let post = create_post(server, &disc_id, data, &creator_token)
+        .await
+        .json::<PostView>();
+
+    let explain = server
+        .get(&format!(
+            "/api/access/explain?entity_id={}&permission=EDIT",
+            post.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AccessExplain>();
+    assert_eq!(
+        explain.path,
+        "APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST"
+    );
+    assert_eq!(explain.roles.len(), 2);
+    assert!(explain.roles.iter().all(|r| r.role.is_none()));
+    assert!(explain.permissions.contains(&Permission::View));
+    assert_eq!(explain.allowed, Some(false));
+    assert_eq!(explain.required_roles, vec![Role::Owner]);
+
+    let explain = server
+        .get(&format!(
+            "/api/access/explain?entity_id={}&permission=EDIT",
+            post.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", creator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AccessExplain>();
+    assert_eq!(explain.allowed, Some(true));
+    assert_eq!(explain.roles[1].role, Some(Role::Owner.to_string()));
+
+    let explain = server
+        .get(&format!(
+            "/api/access/explain?entity_id={}",
+            disc_id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AccessExplain>();
+    assert_eq!(explain.path, "APP->MEMBER->DISCUSSION:PUBLIC->GUEST");
+    assert!(explain.allowed.is_none());
+
+    server
+        .get(&format!(
+            "/api/access/explain?entity_id={}&user_id={}",
+            post.id.to_raw(),
+            creator.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+});
+
+test_with_server!(forbidden_error_has_reason, |server, ctx_state, config| {
+    let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, user_token) = create_fake_login_test_user(&server).await;
+
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+    let post = create_post(server, &disc_id, data, &creator_token)
+        .await
+        .json::<PostView>();
+
+    let res = server
+        .post(&format!("/api/posts/{}/buy", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await;
+    res.assert_status_forbidden();
+    let body = res.json::<serde_json::Value>();
+    assert_eq!(body["error"], "Forbidden");
+    assert_eq!(body["reason"]["permission"], "BUY");
+    assert_eq!(body["reason"]["allowed"], false);
+    assert_eq!(
+        body["reason"]["path"],
+        "APP->MEMBER->DISCUSSION:PUBLIC->GUEST->POST:PUBLIC->GUEST"
+    );
+});
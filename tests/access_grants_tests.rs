@@ -0,0 +1,189 @@
+mod helpers;
+
+use std::time::Duration;
+
+use chrono::Utc;
+use darve_server::entities::access_invite::AccessInvite;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::middleware::ctx::Ctx;
+use darve_server::routes::access::RedeemInviteResponse;
+use darve_server::services::access_grant_service::AccessGrantService;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+
+use crate::helpers::create_fake_login_test_user;
+
+test_with_server!(redeem_discussion_invite, |server, ctx_state, config| {
+    let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+    let (server, member, _, member_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, other_token) = create_fake_login_test_user(&server).await;
+
+    let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "Private discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: vec![member.id.as_ref().unwrap().to_raw()].into(),
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    server
+        .post("/api/access/invites")
+        .json(&json!({ "entity_id": disc.id.to_raw(), "max_uses": 1 }))
+        .add_header("Authorization", format!("Bearer {}", member_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let invite_res = server
+        .post("/api/access/invites")
+        .json(&json!({ "entity_id": disc.id.to_raw(), "max_uses": 1 }))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await;
+    invite_res.assert_status_success();
+    let invite = invite_res.json::<AccessInvite>();
+
+    server
+        .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let redeemed = server
+        .post(&format!("/api/access/invites/{}/redeem", invite.token))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<RedeemInviteResponse>();
+    assert_eq!(redeemed.entity, disc.id);
+
+    server
+        .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    // the invite is used up
+    server
+        .post(&format!("/api/access/invites/{}/redeem", invite.token))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_failure();
+
+    let invite = server
+        .post("/api/access/invites")
+        .json(&json!({ "entity_id": disc.id.to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AccessInvite>();
+
+    server
+        .delete(&format!("/api/access/invites/{}", invite.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/access/invites/{}/redeem", invite.token))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_failure();
+
+    let invites = server
+        .get(&format!(
+            "/api/access/invites?entity_id={}",
+            disc.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AccessInvite>>();
+    assert_eq!(invites.len(), 2);
+    assert!(invites.iter().all(|i| !i.is_active()));
+});
+
+test_with_server!(
+    time_limited_discussion_access,
+    |server, ctx_state, config| {
+        let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+        let (server, member, _, member_token) = create_fake_login_test_user(&server).await;
+        let (server, guest, _, guest_token) = create_fake_login_test_user(&server).await;
+
+        let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "Private discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: vec![member.id.as_ref().unwrap().to_raw()].into(),
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        server
+            .post(&format!("/api/discussions/{}/chat_users", disc.id.to_raw()))
+            .json(&json!({
+                "user_ids": [guest.id.as_ref().unwrap().to_raw()],
+                "expires_at": Utc::now() + chrono::Duration::seconds(2),
+            }))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        server
+            .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", guest_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        server
+            .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", guest_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        server
+            .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", member_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let ctx = Ctx::new(Ok("".to_string()), false);
+        let pruned = AccessGrantService::new(
+            &ctx_state.db.client,
+            &ctx,
+            &ctx_state.db.access_invites,
+            &ctx_state.db.access,
+            &ctx_state.db.discussion_users,
+        )
+        .prune_expired()
+        .await
+        .unwrap();
+        assert_eq!(pruned, 1);
+    }
+);
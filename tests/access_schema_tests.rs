@@ -0,0 +1,77 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use darve_server::access::base::control::{AccessControl, AccessControlDiff};
+use darve_server::access::base::permission::Permission;
+use darve_server::access::base::{access_control, path::AccessPath, set_access_control};
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::middleware::ctx::Ctx;
+
+test_with_server!(update_access_schema, |server, ctx_state, config| {
+    let (server, _, _, user_token) = create_fake_login_test_user(&server).await;
+
+    let mut schema: serde_json::Value =
+        serde_json::from_str(include_str!("../src/access/base/schema.json")).unwrap();
+    schema["APP"]["GUEST"]["permissions"] = serde_json::json!(["CREATE_DISCUSSION"]);
+
+    server
+        .put("/api/admin/access/schema")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .json(&schema)
+        .await
+        .assert_status_forbidden();
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&serde_json::json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+
+    let res = server
+        .put("/api/admin/access/schema")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .json(&serde_json::json!({ "APP": { "VISITOR": {} } }))
+        .await;
+    res.assert_status_bad_request();
+
+    let res = server
+        .put("/api/admin/access/schema")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .json(&schema)
+        .await;
+    res.assert_status_success();
+    let diff = res.json::<AccessControlDiff>();
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].path, "APP->GUEST");
+    assert_eq!(diff.changed[0].added, vec![Permission::CreateDiscussion]);
+    assert!(access_control().can(
+        &AccessPath::try_from("APP->GUEST").unwrap(),
+        &Permission::CreateDiscussion
+    ));
+
+    let res = server
+        .post("/api/admin/access/schema/reload")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await;
+    res.assert_status_success();
+    assert!(res.json::<AccessControlDiff>().is_empty());
+
+    set_access_control(AccessControl::with_default_schema());
+});
@@ -0,0 +1,222 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::account_status_change::AccountStatusChange;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::entities::task_request::{TaskRequestEntity, TaskRequestStatus};
+use darve_server::entities::user_auth::local_user_entity::{
+    AccountStatus, LocalUserDbService, UserRole,
+};
+use darve_server::entities::wallet::balance_transaction_entity::{
+    BalanceTransactionDbService, TransactionType,
+};
+use darve_server::entities::wallet::wallet_entity::{CurrencySymbol, WalletDbService};
+use darve_server::middleware::ctx::Ctx;
+use darve_server::middleware::error::AppError;
+use darve_server::models::view::post::PostView;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+use std::time::Duration;
+use surrealdb::sql::Thing;
+
+test_with_server!(
+    suspend_and_reinstate_account,
+    |server, ctx_state, config| {
+        let (server, user, user_pwd, user_token) = create_fake_login_test_user(&server).await;
+        let (server, viewer, _, viewer_token) = create_fake_login_test_user(&server).await;
+
+        let comm_id = CommunityDbService::get_profile_community_id(&user.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "The Discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: Some(vec![viewer.id.as_ref().unwrap().to_raw()]),
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        let data = MultipartForm::new()
+            .add_text("title", "Post title")
+            .add_text("content", "Post content");
+        let post = create_post(server, &disc.id, data, &user_token)
+            .await
+            .json::<PostView>();
+
+        server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let task = server
+            .post(&format!("/api/discussions/{}/tasks", disc.id.to_raw()))
+            .json(&json!({ "offer_amount": 100, "content": "Do it for me please" }))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<TaskRequestEntity>();
+
+        let ctx = Ctx::new(Ok("".to_string()), false);
+        let user_repository = LocalUserDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        };
+        let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+        let admin = admins.first().unwrap();
+        let login_response = server
+            .post("/api/login")
+            .add_header("Accept", "application/json")
+            .json(&json!({
+                "username_or_email": admin.username,
+                "password": config.init_server_password
+            }))
+            .await;
+        let json_response = login_response.json::<serde_json::Value>();
+        let admin_token = json_response["token"].as_str().unwrap();
+        let user_id = user.id.as_ref().unwrap().to_raw();
+
+        server
+            .post(&format!("/api/admin/users/{}/suspension", user_id))
+            .json(&json!({ "status": "Suspended", "reason": "Spam", "days": 3 }))
+            .add_header("Authorization", format!("Bearer {}", viewer_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        server
+            .post(&format!("/api/admin/users/{}/suspension", user_id))
+            .json(&json!({ "status": "Suspended", "reason": "Spam" }))
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_bad_request();
+
+        let change = server
+            .post(&format!("/api/admin/users/{}/suspension", user_id))
+            .json(&json!({ "status": "Suspended", "reason": "Spam", "days": 3 }))
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<AccountStatusChange>();
+        assert_eq!(change.status, AccountStatus::Suspended);
+        assert!(change.hidden_entities.contains(&post.id));
+
+        let response = server
+            .get("/api/wallet/balance")
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        response.assert_status_forbidden();
+        assert!(response.text().contains("suspended"));
+
+        server
+            .post("/api/login")
+            .json(&json!({ "username_or_email": user.username, "password": user_pwd }))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", viewer_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<PostView>>();
+        assert!(posts.iter().all(|p| p.id != post.id));
+
+        let mut res = ctx_state
+            .db
+            .client
+            .query("SELECT VALUE status FROM ONLY $task;")
+            .bind(("task", Thing::try_from(task.id.as_str()).unwrap()))
+            .await
+            .unwrap();
+        let status = res.take::<Option<TaskRequestStatus>>(0).unwrap();
+        assert_eq!(status, Some(TaskRequestStatus::Cancelled));
+
+        let wallet_repository = WalletDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        };
+        let balance = wallet_repository
+            .get_user_balance(user.id.as_ref().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(balance.balance_usd, 1000);
+
+        let transfer = BalanceTransactionDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        }
+        .transfer_currency(
+            &Thing::from(("wallet", user.id.as_ref().unwrap().id.clone())),
+            &Thing::from(("wallet", viewer.id.as_ref().unwrap().id.clone())),
+            100,
+            &CurrencySymbol::USD,
+            None,
+            TransactionType::Donate,
+        )
+        .await;
+        assert_eq!(transfer.unwrap_err().error, AppError::WalletFrozen);
+
+        server
+            .delete(&format!("/api/admin/users/{}/suspension", user_id))
+            .json(&json!({ "reason": "Appeal accepted" }))
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        // the tokens issued before the suspension stay invalid
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        server
+            .get("/api/wallet/balance")
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_unauthorized();
+
+        let login_response = server
+            .post("/api/login")
+            .json(&json!({ "username_or_email": user.username, "password": user_pwd }))
+            .add_header("Accept", "application/json")
+            .await;
+        login_response.assert_status_success();
+        let json_response = login_response.json::<serde_json::Value>();
+        let new_token = json_response["token"].as_str().unwrap();
+
+        server
+            .get("/api/wallet/balance")
+            .add_header("Authorization", format!("Bearer {}", new_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", viewer_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<PostView>>();
+        assert!(posts.iter().any(|p| p.id == post.id));
+
+        let history = server
+            .get(&format!("/api/admin/users/{}/status_history", user_id))
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<AccountStatusChange>>();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, AccountStatus::Active);
+        assert_eq!(history[0].created_by, admin.id);
+    }
+);
@@ -0,0 +1,214 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::audit_log::{AuditAction, AuditLog};
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::entities::wallet::wallet_entity::WalletBalancesView;
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::admin::{AdminUserView, AuthMethodView};
+use darve_server::models::view::post::PostView;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+
+test_with_server!(manage_users_and_content, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, viewer, _, viewer_token) = create_fake_login_test_user(&server).await;
+
+    server
+        .get("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+    let user_id = user.id.as_ref().unwrap().to_raw();
+
+    let users = server
+        .get(&format!(
+            "/api/admin/users?search={}&role=User",
+            user.username.to_uppercase()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AdminUserView>>();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id, *user.id.as_ref().unwrap());
+
+    let methods = server
+        .get(&format!("/api/admin/users/{}/auth_methods", user_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AuthMethodView>>();
+    assert_eq!(methods.len(), 1);
+    assert_eq!(methods[0].auth_type, "PASSWORD");
+
+    server
+        .get(&format!("/api/admin/users/{}/wallet", user_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletBalancesView>();
+
+    server
+        .post(&format!("/api/admin/users/{}/email/verify", user_id))
+        .json(&json!({}))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let updated = server
+        .post(&format!("/api/admin/users/{}/email/verify", user_id))
+        .json(&json!({ "email": "verified@email.com" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AdminUserView>();
+    assert_eq!(
+        updated.email_verified.as_deref(),
+        Some("verified@email.com")
+    );
+
+    let updated = server
+        .delete(&format!("/api/admin/users/{}/otp", user_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AdminUserView>();
+    assert!(!updated.is_otp_enabled);
+
+    server
+        .put(&format!(
+            "/api/admin/users/{}/role",
+            admin.id.as_ref().unwrap().to_raw()
+        ))
+        .json(&json!({ "role": "User" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let updated = server
+        .put(&format!("/api/admin/users/{}/role", user_id))
+        .json(&json!({ "role": "Admin" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AdminUserView>();
+    assert_eq!(updated.role, UserRole::Admin);
+
+    server
+        .get("/api/admin/users")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let comm_id = CommunityDbService::get_profile_community_id(&viewer.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", viewer_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let post = create_post(server, &disc.id, data, &viewer_token)
+        .await
+        .json::<PostView>();
+
+    let log = server
+        .delete(&format!("/api/admin/posts/{}", post.id.to_raw()))
+        .json(&json!({ "note": "Off topic" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AuditLog>();
+    assert_eq!(log.action, AuditAction::DeletePost);
+    assert_eq!(log.target, post.id);
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", viewer_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().all(|p| p.id != post.id));
+
+    server
+        .get(&format!("/api/posts/{}", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", viewer_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_not_found();
+
+    let data = MultipartForm::new()
+        .add_text("title", "Other post")
+        .add_text("content", "Post content");
+    let other_post = create_post(server, &disc.id, data, &viewer_token)
+        .await
+        .json::<PostView>();
+
+    let log = server
+        .delete(&format!("/api/admin/discussions/{}", disc.id.to_raw()))
+        .json(&json!({ "note": "Off topic" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<AuditLog>();
+    assert_eq!(log.action, AuditAction::DeleteDiscussion);
+
+    server
+        .get(&format!("/api/posts/{}", other_post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", viewer_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_not_found();
+
+    let logs = server
+        .get(&format!("/api/admin/audit_log?target={}", user_id))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AuditLog>>();
+    let actions = logs.iter().map(|l| l.action.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::ChangeRole,
+            AuditAction::ResetOtp,
+            AuditAction::VerifyEmail
+        ]
+    );
+    assert!(logs.iter().all(|l| &l.actor == admin.id.as_ref().unwrap()));
+});
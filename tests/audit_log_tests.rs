@@ -0,0 +1,109 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use darve_server::entities::audit_log::{AuditAction, AuditLog};
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::middleware::ctx::Ctx;
+use serde_json::json;
+
+test_with_server!(records_request_origin_and_changes, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, viewer_token) = create_fake_login_test_user(&server).await;
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let admin_id = admin.id.as_ref().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+    let user_id = user.id.as_ref().unwrap();
+
+    server
+        .put(&format!("/api/admin/users/{}/role", user_id.to_raw()))
+        .json(&json!({ "role": "Admin" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .add_header("X-Forwarded-For", "10.0.0.1, 10.0.0.2")
+        .add_header("User-Agent", "audit-test-agent")
+        .await
+        .assert_status_success();
+
+    server
+        .post("/api/users/current/otp/disable")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("X-Forwarded-For", "10.0.0.3")
+        .await
+        .assert_status_success();
+
+    let logs = server
+        .get(&format!(
+            "/api/admin/audit_log?action=ChangeRole&actor={}",
+            admin_id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AuditLog>>();
+    assert_eq!(logs.len(), 1);
+    let log = &logs[0];
+    assert_eq!(&log.target, user_id);
+    assert_eq!(log.ip.as_deref(), Some("10.0.0.1"));
+    assert_eq!(log.user_agent.as_deref(), Some("audit-test-agent"));
+    assert_eq!(log.before, Some(json!({ "role": "User" })));
+    assert_eq!(log.after, Some(json!({ "role": "Admin" })));
+
+    let logs = server
+        .get(&format!(
+            "/api/admin/audit_log?target_type=local_user&actor={}",
+            user_id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<AuditLog>>();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].action, AuditAction::DisableOtp);
+    assert_eq!(logs[0].ip.as_deref(), Some("10.0.0.3"));
+    assert_eq!(logs[0].after, Some(json!({ "is_otp_enabled": false })));
+
+    let response = server
+        .get("/api/admin/audit_log/export?format=csv&action=ChangeRole")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await;
+    response.assert_status_success();
+    assert_eq!(response.header("Content-Type"), "text/csv");
+    let csv = response.text();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("id,created_at,actor,action,target,note,before,after,ip,user_agent")
+    );
+    let row = lines.next().unwrap();
+    assert!(row.contains(",ChangeRole,"));
+    assert!(row.contains("\"{\"\"role\"\":\"\"User\"\"}\""));
+    assert_eq!(lines.next(), None);
+
+    let logs = server
+        .get("/api/admin/audit_log/export?format=json&action=DisableOtp")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .await
+        .json::<Vec<AuditLog>>();
+    assert_eq!(logs.len(), 1);
+
+    server
+        .get("/api/admin/audit_log/export")
+        .add_header("Authorization", format!("Bearer {}", viewer_token))
+        .await
+        .assert_status_forbidden();
+});
@@ -0,0 +1,179 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::entities::report::Report;
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::post::PostView;
+use darve_server::models::view::reply::ReplyView;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+
+test_with_server!(filter_blocked_words, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+    server
+        .post("/api/admin/blocked_words")
+        .json(&json!({ "words": ["darn"], "category": "Profanity" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+
+    for (word, category) in [
+        ("Darn", "Profanity"),
+        ("hateword", "HateSpeech"),
+        ("spamword", "Spam"),
+    ] {
+        server
+            .post("/api/admin/blocked_words")
+            .json(&json!({ "words": [word], "category": category }))
+            .add_header("Authorization", format!("Bearer {}", admin_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+    }
+
+    let comm_id = CommunityDbService::get_profile_community_id(&user.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The h4tew0rd discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let data = MultipartForm::new()
+        .add_text("title", "Well d@rn it")
+        .add_text("content", "What a DÄRN day!");
+    let post = create_post(server, &disc.id, data, &user_token)
+        .await
+        .json::<PostView>();
+    assert_eq!(post.title, "Well **** it");
+    assert_eq!(post.content.as_deref(), Some("What a **** day!"));
+
+    let reply = server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "Buy my spamword now" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<ReplyView>();
+    assert_eq!(reply.content, "Buy my spamword now");
+
+    let reports = server
+        .get(&format!(
+            "/api/admin/reports?entity_id={}",
+            reply.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<Report>>();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].reporter.is_none());
+
+    server
+        .put("/api/admin/blocked_words/categories/Profanity")
+        .json(&json!({ "action": "Reject" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "darn" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    server
+        .delete("/api/admin/blocked_words")
+        .json(&json!({ "words": ["darn"] }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "darn" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+});
+
+test_with_server!(reject_reserved_usernames, |server, ctx_state, config| {
+    for username in ["moorland", "M00rland"] {
+        let data = MultipartForm::new()
+            .add_text("username", username)
+            .add_text("password", "password123");
+        server
+            .post("/api/register")
+            .multipart(data)
+            .await
+            .assert_status_unprocessable_entity();
+    }
+
+    // the reserved words are not filtered in the content
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let comm_id = CommunityDbService::get_profile_community_id(&user.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The moorland".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+    assert_eq!(disc.title, Some("The moorland".to_string()));
+});
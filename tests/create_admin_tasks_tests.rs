@@ -170,7 +170,7 @@ test_with_server!(get_admins_tasks_by_user, |server, ctx_state, config| {
 
     let (_, _, _password, user_token) = create_fake_login_test_user(&server).await;
     let res = server
-        .get("/api/admin/tasks")
+        .get("/api/tasks/darve")
         .add_header("Authorization", format!("Bearer {}", user_token))
         .add_header("Accept", "application/json")
         .await;
@@ -223,7 +223,7 @@ test_with_server!(get_admins_tasks_by_user_1, |server, ctx_state, config| {
     let (_, _user, _user_pwd, user_token) = create_fake_login_test_user(&server).await;
     let (_, _user1, _user1_pwd, user1_token) = create_fake_login_test_user(&server).await;
     let res = server
-        .get("/api/admin/tasks")
+        .get("/api/tasks/darve")
         .add_header("Authorization", format!("Bearer {}", user_token))
         .add_header("Accept", "application/json")
         .await;
@@ -243,7 +243,7 @@ test_with_server!(get_admins_tasks_by_user_1, |server, ctx_state, config| {
     assert_eq!(super_tasks.len(), 1);
     assert_eq!(weekly_tasks.len(), 3);
     let res = server
-        .get("/api/admin/tasks")
+        .get("/api/tasks/darve")
         .add_header("Authorization", format!("Bearer {}", user1_token))
         .add_header("Accept", "application/json")
         .await;
@@ -0,0 +1,225 @@
+mod helpers;
+
+use crate::helpers::{create_fake_login_test_user, post_helpers::create_fake_post};
+use darve_server::{
+    entities::community::discussion_entity::Discussion,
+    interfaces::repositories::discussion_user::DiscussionUserRepositoryInterface,
+    models::view::discussion_user::DiscussionUserView,
+};
+use serde_json::json;
+
+test_with_server!(
+    create_direct_message_reuses_discussion,
+    |server, ctx_state, config| {
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+
+        let response = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user1.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await;
+        response.assert_status_success();
+        let disc = response.json::<Discussion>();
+
+        let response = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user0.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await;
+        response.assert_status_success();
+        assert_eq!(response.json::<Discussion>().id, disc.id);
+
+        let response = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user0.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await;
+        response.assert_status_failure();
+    }
+);
+
+test_with_server!(
+    direct_message_request_for_non_follower,
+    |server, ctx_state, config| {
+        let (server, _, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+
+        let disc = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user1.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .json::<Discussion>();
+        let _ = create_fake_post(&server, &disc.id, None, None, &token0).await;
+
+        let inbox = server
+            .get("/api/direct_messages")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert!(inbox.is_empty());
+
+        let requests = server
+            .get("/api/direct_messages/requests")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].is_request);
+        assert_eq!(requests[0].discussion.id, disc.id);
+
+        let inbox = server
+            .get("/api/direct_messages")
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert_eq!(inbox.len(), 1);
+
+        let response = server
+            .post(&format!(
+                "/api/direct_messages/requests/{}/accept",
+                disc.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await;
+        response.assert_status_success();
+
+        let requests = server
+            .get("/api/direct_messages/requests")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert!(requests.is_empty());
+
+        let inbox = server
+            .get("/api/direct_messages")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].nr_unread, 1);
+
+        let response = server
+            .post(&format!(
+                "/api/direct_messages/requests/{}/accept",
+                disc.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await;
+        response.assert_status_not_found();
+    }
+);
+
+test_with_server!(
+    decline_direct_message_request,
+    |server, ctx_state, config| {
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+
+        let disc = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user1.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .json::<Discussion>();
+        let _ = create_fake_post(&server, &disc.id, None, None, &token0).await;
+
+        let response = server
+            .delete(&format!(
+                "/api/direct_messages/requests/{}",
+                disc.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await;
+        response.assert_status_success();
+
+        let requests = server
+            .get("/api/direct_messages/requests")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert!(requests.is_empty());
+
+        let disc_user = ctx_state
+            .db
+            .discussion_users
+            .get(
+                &disc.id.id.to_raw(),
+                &user1.id.as_ref().unwrap().id.to_raw(),
+            )
+            .await
+            .unwrap();
+        assert!(disc_user.is_none());
+
+        server
+            .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .assert_status_forbidden();
+
+        let response = server
+            .post("/api/direct_messages")
+            .json(&json!({ "user_id": user0.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await;
+        response.assert_status_success();
+        assert_eq!(response.json::<Discussion>().id, disc.id);
+
+        server
+            .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .assert_status_success();
+    }
+);
+
+test_with_server!(
+    direct_messages_from_followed_user_in_inbox,
+    |server, ctx_state, config| {
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+        let (server, user2, _, token2) = create_fake_login_test_user(&server).await;
+
+        for user in [&user0, &user2] {
+            server
+                .post(&format!(
+                    "/api/following/{}",
+                    user.id.as_ref().unwrap().to_raw()
+                ))
+                .json("")
+                .add_header("Authorization", format!("Bearer {}", token1))
+                .await
+                .assert_status_success();
+        }
+
+        let mut disc_ids = vec![];
+        for token in [&token0, &token2] {
+            let disc = server
+                .post("/api/direct_messages")
+                .json(&json!({ "user_id": user1.id.as_ref().unwrap().to_raw() }))
+                .add_header("Authorization", format!("Bearer {}", token))
+                .await
+                .json::<Discussion>();
+            let _ = create_fake_post(&server, &disc.id, None, None, token).await;
+            disc_ids.push(disc.id);
+        }
+
+        let requests = server
+            .get("/api/direct_messages/requests")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert!(requests.is_empty());
+
+        let inbox = server
+            .get("/api/direct_messages")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        assert_eq!(inbox.len(), 2);
+        assert_eq!(inbox[0].discussion.id, disc_ids[1]);
+        assert_eq!(inbox[1].discussion.id, disc_ids[0]);
+        assert!(inbox.iter().all(|v| v.nr_unread == 1));
+    }
+);
@@ -0,0 +1,385 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::{Discussion, DiscussionDbService};
+use darve_server::models::view::discussion::DiscussionView;
+use darve_server::models::view::full_post::FullPostView;
+use darve_server::models::view::post::PostView;
+use darve_server::models::view::reply::ReplyView;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+
+test_with_server!(moderator_manages_discussion, |server, ctx_state, config| {
+    let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+    let (server, moderator, _, moderator_token) = create_fake_login_test_user(&server).await;
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+    let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let post = create_post(server, &disc.id, data, &owner_token)
+        .await
+        .json::<PostView>();
+
+    let reply = server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "reply content" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<ReplyView>();
+
+    server
+        .post(&format!("/api/discussions/{}/moderators", disc.id.to_raw()))
+        .json(&json!({ "user_id": moderator.id.as_ref().unwrap().to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    server
+        .post(&format!("/api/discussions/{}/moderators", disc.id.to_raw()))
+        .json(&json!({ "user_id": moderator.id.as_ref().unwrap().to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/pin", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    server
+        .post(&format!("/api/posts/{}/pin", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let pinned = server
+        .get(&format!("/api/posts/{}", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<FullPostView>();
+    assert!(pinned.pinned_at.is_some());
+
+    server
+        .delete(&format!("/api/replies/{}", reply.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let replies = server
+        .get(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<ReplyView>>();
+    assert!(replies.is_empty());
+
+    server
+        .post(&format!(
+            "/api/discussions/{}/muted_users",
+            disc.id.to_raw()
+        ))
+        .json(&json!({ "user_id": owner.id.as_ref().unwrap().to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    server
+        .post(&format!(
+            "/api/discussions/{}/muted_users",
+            disc.id.to_raw()
+        ))
+        .json(&json!({ "user_id": user.id.as_ref().unwrap().to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "muted reply" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    server
+        .delete(&format!(
+            "/api/discussions/{}/muted_users/{}",
+            disc.id.to_raw(),
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/replies", post.id.to_raw()))
+        .json(&json!({ "content": "unmuted reply" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .delete(&format!(
+            "/api/discussions/{}/moderators/{}",
+            disc.id.to_raw(),
+            moderator.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", owner_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .delete(&format!("/api/posts/{}/pin", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", moderator_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+});
+
+test_with_server!(
+    transfer_discussion_ownership,
+    |server, ctx_state, config| {
+        let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "The Discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: None,
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        server
+            .post(&format!(
+                "/api/discussions/{}/transfer_ownership",
+                disc.id.to_raw()
+            ))
+            .json(&json!({ "user_id": owner.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        let profile_disc_id =
+            DiscussionDbService::get_profile_discussion_id(owner.id.as_ref().unwrap());
+        server
+            .post(&format!(
+                "/api/discussions/{}/transfer_ownership",
+                profile_disc_id.to_raw()
+            ))
+            .json(&json!({ "user_id": user.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_failure();
+
+        server
+            .post(&format!(
+                "/api/discussions/{}/transfer_ownership",
+                disc.id.to_raw()
+            ))
+            .json(&json!({ "user_id": user.id.as_ref().unwrap().to_raw() }))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        server
+            .patch(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .json(&json!({ "title": "New title" }))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        server
+            .patch(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .json(&json!({ "title": "New title" }))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let updated_disc = server
+            .get(&format!("/api/discussions/{}", disc.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<DiscussionView>();
+        assert_eq!(updated_disc.created_by.id, user.id.clone().unwrap());
+
+        // the previous owner stays as a moderator
+        let data = MultipartForm::new()
+            .add_text("title", "Post title")
+            .add_text("content", "Post content");
+        let post = create_post(server, &disc.id, data, &user_token)
+            .await
+            .json::<PostView>();
+        server
+            .post(&format!("/api/posts/{}/pin", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+    }
+);
+
+test_with_server!(
+    pinned_posts_are_listed_first,
+    |server, ctx_state, config| {
+        let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+
+        let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "The Discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: None,
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        let mut post_ids = vec![];
+        for i in 0..4 {
+            let data = MultipartForm::new()
+                .add_text("title", format!("Post title {i}"))
+                .add_text("content", "Post content");
+            let post = create_post(server, &disc.id, data, &owner_token)
+                .await
+                .json::<PostView>();
+            post_ids.push(post.id);
+        }
+        let pinned_id = post_ids[2].clone();
+        server
+            .post(&format!("/api/posts/{}/pin", pinned_id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        for order_dir in ["ASC", "DESC"] {
+            let mut listed = vec![];
+            let mut cursor: Option<String> = None;
+            loop {
+                let cursor_query = cursor
+                    .as_ref()
+                    .map_or("".to_string(), |id| format!("&cursor={id}"));
+                let posts = server
+                    .get(&format!(
+                        "/api/discussions/{}/posts?order_dir={order_dir}&count=2{cursor_query}",
+                        disc.id.to_raw()
+                    ))
+                    .add_header("Authorization", format!("Bearer {}", owner_token))
+                    .add_header("Accept", "application/json")
+                    .await
+                    .json::<Vec<PostView>>();
+                if posts.is_empty() {
+                    break;
+                }
+                cursor = posts.last().map(|p| p.id.to_raw());
+                listed.extend(posts.into_iter().map(|p| p.id));
+            }
+
+            assert_eq!(listed[0], pinned_id);
+            let mut unpinned = post_ids
+                .iter()
+                .filter(|id| **id != pinned_id)
+                .cloned()
+                .collect::<Vec<_>>();
+            unpinned.sort_by_key(|id| id.id.to_raw());
+            if order_dir == "DESC" {
+                unpinned.reverse();
+            }
+            assert_eq!(listed[1..], unpinned[..]);
+        }
+    }
+);
+
+test_with_server!(
+    owner_can_not_delete_member_posts,
+    |server, ctx_state, config| {
+        let (server, owner, _, owner_token) = create_fake_login_test_user(&server).await;
+        let (server, member, _, member_token) = create_fake_login_test_user(&server).await;
+
+        let comm_id = CommunityDbService::get_profile_community_id(&owner.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "The Discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: Some(vec![member.id.as_ref().unwrap().to_raw()]),
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        let data = MultipartForm::new()
+            .add_text("title", "Post title")
+            .add_text("content", "Post content");
+        let post = create_post(server, &disc.id, data, &member_token)
+            .await
+            .json::<PostView>();
+
+        server
+            .delete(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", owner_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        server
+            .delete(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", member_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+    }
+);
@@ -0,0 +1,90 @@
+mod helpers;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use darve_server::interfaces::event_bus::EventBusInterface;
+use darve_server::middleware::mw_ctx::{AppEvent, AppEventType};
+use darve_server::utils::cluster_presence::{ClusterPresence, InstancePresence};
+use darve_server::utils::event_bus::surreal::SurrealEventBus;
+use dashmap::DashMap;
+use tokio::time::timeout;
+
+fn balance_event(receivers: Vec<String>) -> AppEvent {
+    AppEvent {
+        user_id: "user".to_string(),
+        metadata: None,
+        content: None,
+        receivers,
+        event: AppEventType::UpdatedUserBalance,
+    }
+}
+
+test_with_server!(
+    surreal_event_bus_between_instances,
+    |_server, ctx_state, config| {
+        let bus_a = SurrealEventBus::new(ctx_state.db.client.clone(), "instance_a", 100)
+            .await
+            .unwrap();
+        let bus_b = SurrealEventBus::new(ctx_state.db.client.clone(), "instance_b", 100)
+            .await
+            .unwrap();
+        let mut rx_a = bus_a.subscribe();
+        let mut rx_b = bus_b.subscribe();
+
+        bus_a
+            .send(balance_event(vec!["receiver".to_string()]))
+            .unwrap();
+
+        let event = timeout(Duration::from_secs(1), rx_a.recv())
+            .await
+            .expect("Should receive local event within timeout")
+            .unwrap();
+        assert_eq!(event.receivers, vec!["receiver".to_string()]);
+
+        let event = timeout(Duration::from_secs(5), rx_b.recv())
+            .await
+            .expect("Should receive remote event within timeout")
+            .unwrap();
+        assert!(matches!(event.event, AppEventType::UpdatedUserBalance));
+        assert_eq!(event.receivers, vec!["receiver".to_string()]);
+
+        // the own events are not received twice
+        assert!(timeout(Duration::from_millis(500), rx_a.recv())
+            .await
+            .is_err());
+    }
+);
+
+test_with_server!(cluster_presence, |_server, ctx_state, config| {
+    let online_users = Arc::new(DashMap::new());
+    online_users.insert("local_user".to_string(), 1);
+    let presence = ClusterPresence::new(
+        "instance_a".to_string(),
+        online_users,
+        chrono::Duration::seconds(30),
+    );
+    assert!(presence.is_online("local_user"));
+    assert!(!presence.is_online_remote("local_user"));
+    assert!(!presence.is_online("remote_user"));
+
+    presence.apply(InstancePresence {
+        instance_id: "instance_a".to_string(),
+        users: vec!["remote_user".to_string()],
+    });
+    assert!(!presence.is_online("remote_user"));
+
+    presence.apply(InstancePresence {
+        instance_id: "instance_b".to_string(),
+        users: vec!["remote_user".to_string()],
+    });
+    assert!(presence.is_online("remote_user"));
+    assert!(presence.is_online_remote("remote_user"));
+
+    presence.apply(InstancePresence {
+        instance_id: "instance_b".to_string(),
+        users: vec![],
+    });
+    assert!(!presence.is_online("remote_user"));
+    assert_eq!(presence.snapshot().users, vec!["local_user".to_string()]);
+});
@@ -6,7 +6,7 @@ pub mod user_helpers;
 use axum_test::{multipart::MultipartForm, TestServer};
 use chrono::{DateTime, Months, Utc};
 use darve_server::{
-    entities::user_auth::local_user_entity::LocalUser, utils::blocked_words::BLOCKED_WORDS,
+    entities::user_auth::local_user_entity::LocalUser, utils::content_filter::normalize,
 };
 use fake::{faker, Fake};
 use serde_json::Value;
@@ -51,8 +51,16 @@ pub async fn create_fake_login_test_user(
 #[allow(dead_code)]
 pub fn fake_username_min_len(min_len: usize) -> String {
     use fake::{faker::internet::en::Username, Fake};
+    // the words are seeded into the blocked words as reserved usernames
+    let reserved = include_str!("../../blocked_words.txt");
     (0..)
         .map(|_| Username().fake::<String>().replace(".", "_"))
-        .find(|u| u.len() >= min_len && !BLOCKED_WORDS.contains(u))
+        .find(|u| {
+            let (lower, normalized) = (u.to_lowercase(), normalize(u));
+            u.len() >= min_len
+                && !reserved
+                    .lines()
+                    .any(|w| w.to_lowercase() == lower || normalize(w) == normalized)
+        })
         .unwrap()
 }
@@ -10,13 +10,16 @@ macro_rules! test_with_server {
             use dashmap::DashMap;
             use async_trait::async_trait;
             use darve_server::interfaces::send_email::SendEmailInterface;
+            use darve_server::utils::push::mock::MockPushSender;
+            use darve_server::utils::sse_buffer::SseBuffer;
+            use darve_server::utils::event_bus::local::LocalEventBus;
+            use darve_server::utils::cluster_presence::ClusterPresence;
             use darve_server::{
 
                 init::create_default_profiles,
                 middleware::mw_ctx::CtxState,
                 utils::{file::local_file_storage::LocalFileStorage, jwt::JWT, darve_tasks},
             };
-            use tokio::sync::broadcast;
             use axum_test::{TestServer, TestServerConfig};
             use darve_server::database::client::{Database, DbConfig};
             use darve_server::config::AppConfig;
@@ -36,8 +39,9 @@ macro_rules! test_with_server {
             }
 
             fn create_ctx_state(db: Database, config: &AppConfig) -> Arc<CtxState> {
-                let (event_sender, _) = broadcast::channel(100);
+                let online_users = Arc::new(DashMap::new());
                 let database = Arc::new(db);
+                let sse_buffer = Arc::new(SseBuffer::new(database.client.clone(), 200, chrono::Duration::minutes(10)));
                 let file_storage = Arc::new(LocalFileStorage::new("target/tests_media".to_string(), "".to_string()));
                 let ctx_state = CtxState {
                     db: database.clone(),
@@ -57,15 +61,26 @@ macro_rules! test_with_server {
                     paypal_webhook_id: config.paypal_webhook_id.clone(),
                     paypal_client_id: config.paypal_client_id.clone(),
                     paypal_client_key: config.paypal_client_key.clone(),
-                    event_sender,
+                    event_sender: Arc::new(LocalEventBus::new(100)),
                     withdraw_fee: 0.05,
-                    online_users: Arc::new(DashMap::new()),
+                    paid_post_fee: 0.1,
+                    online_users: online_users.clone(),
                     support_email: config.support_email.clone(),
                     darve_tasks: Arc::new(darve_tasks::DarveTasksUtils::new(database, file_storage.clone())),
                     twitch_service: TwitchService::new(
                         config.twitch_client_id.clone(),
                         config.twitch_client_secret.clone(),
                     ),
+                    access_schema_path: config.access_schema_path.clone(),
+                    report_hide_threshold: config.report_hide_threshold,
+                    adjustment_approval_threshold: config.adjustment_approval_threshold,
+                    app_url: config.app_url.clone(),
+                    push_sender: Arc::new(MockPushSender {}),
+                    sse_buffer,
+                    presence: Arc::new(ClusterPresence::new("test".to_string(), online_users, chrono::Duration::seconds(30))),
+                    notification_retention: chrono::Duration::days(config.notification_retention_days as i64),
+                    notification_max_per_user: config.notification_max_per_user,
+                    edit_window: chrono::Duration::minutes(config.edit_window_minutes as i64),
                 };
                 Arc::new(ctx_state)
             }
@@ -100,6 +115,21 @@ macro_rules! test_with_server {
                 support_email: "".to_string(),
                 twitch_client_id: "".to_string(),
                 twitch_client_secret: "".to_string(),
+                access_schema_path: None,
+                report_hide_threshold: 2,
+                adjustment_approval_threshold: 1000,
+                app_url: "http://localhost:8080".to_string(),
+                apns_key_path: None,
+                apns_key_id: "".to_string(),
+                apns_team_id: "".to_string(),
+                apns_topic: "".to_string(),
+                apns_sandbox: false,
+                fcm_credentials_path: None,
+                event_bus: "local".to_string(),
+                instance_id: None,
+                notification_retention_days: 90,
+                notification_max_per_user: 1000,
+                edit_window_minutes: 15,
             };
 
             let $ctx_state = {
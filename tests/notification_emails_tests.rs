@@ -0,0 +1,151 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use axum_test::TestServer;
+use darve_server::{
+    entities::{
+        community::discussion_entity::DiscussionDbService,
+        notification_email::NotificationEmailDbService,
+        notification_settings::{
+            NotificationChannel, NotificationSettings, NotificationSettingsDbService,
+        },
+        user_notification::UserNotificationEvent,
+    },
+    middleware::ctx::Ctx,
+    services::notification_email_service::NotificationEmailService,
+};
+use fake::{faker, Fake};
+use helpers::post_helpers::create_fake_post;
+use serde_json::json;
+
+async fn create_task(server: &TestServer, post_id: &str, participant_id: &str, token: &str) {
+    server
+        .post(format!("/api/posts/{}/tasks", post_id).as_str())
+        .json(&json!({
+            "offer_amount": Some(100),
+            "participants": vec![participant_id],
+            "content": faker::lorem::en::Sentence(7..20).fake::<String>()
+        }))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+}
+
+test_with_server!(queue_and_unsubscribe, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+    let user0_id = user0.id.as_ref().unwrap();
+    let ctx = Ctx::new(Ok("".to_string()), false);
+    let email_repository = NotificationEmailDbService {
+        db: &ctx_state.db.client,
+        ctx: &ctx,
+    };
+
+    ctx_state
+        .db
+        .client
+        .query("UPDATE $user SET email_verified = 'user0@test.com';")
+        .bind(("user", user0_id.clone()))
+        .await
+        .unwrap();
+    server
+        .get(&format!("/test/api/deposit/{}/{}", user1.username, 1000))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user1.id.as_ref().unwrap());
+    let post = create_fake_post(server, &disc_id, None, None, &token1).await;
+
+    create_task(server, &post.id, &user0_id.to_raw(), &token1).await;
+    let queued = email_repository.get_list(100).await.unwrap();
+    assert_eq!(queued.len(), 1);
+    assert_eq!(&queued[0].user, user0_id);
+    assert_eq!(
+        queued[0].event,
+        UserNotificationEvent::UserTaskRequestReceived
+    );
+
+    NotificationEmailService::new(&ctx_state, &ctx)
+        .send_queued()
+        .await
+        .unwrap();
+    assert!(email_repository.get_list(100).await.unwrap().is_empty());
+
+    server
+        .get("/api/notifications/email/unsubscribe?token=invalid")
+        .await
+        .assert_status_not_found();
+
+    let token = NotificationSettingsDbService {
+        db: &ctx_state.db.client,
+        ctx: &ctx,
+    }
+    .get_unsubscribe_token(user0_id)
+    .await
+    .unwrap();
+    server
+        .get(&format!(
+            "/api/notifications/email/unsubscribe?token={}",
+            token
+        ))
+        .await
+        .assert_status_success();
+
+    let settings = server
+        .get("/api/users/current/notification_settings")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<NotificationSettings>();
+    assert_eq!(settings.disabled_channels, vec![NotificationChannel::Email]);
+
+    create_task(server, &post.id, &user0_id.to_raw(), &token1).await;
+    assert!(email_repository.get_list(100).await.unwrap().is_empty());
+});
+
+test_with_server!(digest_instead_of_queue, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+    let user0_id = user0.id.as_ref().unwrap();
+    let ctx = Ctx::new(Ok("".to_string()), false);
+
+    server
+        .put("/api/users/current/notification_settings")
+        .json(&json!({ "digest": "Daily" }))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    server
+        .get(&format!("/test/api/deposit/{}/{}", user1.username, 1000))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user1.id.as_ref().unwrap());
+    let post = create_fake_post(server, &disc_id, None, None, &token1).await;
+
+    create_task(server, &post.id, &user0_id.to_raw(), &token1).await;
+
+    let queued = NotificationEmailDbService {
+        db: &ctx_state.db.client,
+        ctx: &ctx,
+    }
+    .get_list(100)
+    .await
+    .unwrap();
+    assert!(queued.is_empty());
+
+    NotificationEmailService::new(&ctx_state, &ctx)
+        .send_digests()
+        .await
+        .unwrap();
+
+    let settings = server
+        .get("/api/users/current/notification_settings")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<NotificationSettings>();
+    assert!(settings.last_digest_at.is_some());
+});
@@ -0,0 +1,107 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use darve_server::{
+    entities::{
+        notification_settings::{DigestMode, NotificationChannel, NotificationSettings},
+        user_notification::UserNotificationEvent,
+    },
+    models::view::notification::UserNotificationView,
+};
+use serde_json::json;
+
+test_with_server!(disabled_event_is_not_stored, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+    let (server, _, _, token2) = create_fake_login_test_user(&server).await;
+
+    let settings = server
+        .get("/api/users/current/notification_settings")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<NotificationSettings>();
+    assert!(settings.disabled_events.is_empty());
+    assert_eq!(settings.digest, DigestMode::Off);
+
+    let settings = server
+        .put("/api/users/current/notification_settings")
+        .json(&json!({
+            "disabled_events": [{ "event": "UserFollowAdded", "channels": ["InApp", "Sse"] }],
+            "quiet_hours": { "start": 1320, "end": 420, "utc_offset": 60 },
+            "digest": "Daily"
+        }))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<NotificationSettings>();
+    assert_eq!(settings.digest, DigestMode::Daily);
+    assert!(!settings.is_enabled(
+        &UserNotificationEvent::UserFollowAdded,
+        NotificationChannel::InApp
+    ));
+    assert!(settings.is_enabled(
+        &UserNotificationEvent::UserLikePost,
+        NotificationChannel::InApp
+    ));
+
+    let settings = server
+        .get("/api/users/current/notification_settings")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<NotificationSettings>();
+    assert_eq!(settings.quiet_hours.unwrap().start, 1320);
+
+    for user in [&user0, &user1] {
+        server
+            .post(&format!(
+                "/api/following/{}",
+                user.id.as_ref().unwrap().to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", token2))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+    }
+
+    let notifications = server
+        .get("/api/notifications")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserNotificationView>>();
+    assert!(notifications.is_empty());
+
+    let notifications = server
+        .get("/api/notifications")
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserNotificationView>>();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(
+        notifications[0].event,
+        UserNotificationEvent::UserFollowAdded
+    );
+});
+
+test_with_server!(invalid_quiet_hours, |server, ctx_state, config| {
+    let (server, _, _, token) = create_fake_login_test_user(&server).await;
+
+    server
+        .put("/api/users/current/notification_settings")
+        .json(&json!({ "quiet_hours": { "start": 1440, "end": 420, "utc_offset": 0 } }))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    server
+        .put("/api/users/current/notification_settings")
+        .json(&json!({ "quiet_hours": { "start": 0, "end": 420, "utc_offset": 900 } }))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+});
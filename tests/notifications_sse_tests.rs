@@ -1,7 +1,9 @@
 mod helpers;
 
 use crate::helpers::create_fake_login_test_user;
-use darve_server::middleware::mw_ctx::AppEventType;
+use darve_server::jobs;
+use darve_server::middleware::mw_ctx::{AppEvent, AppEventType};
+use darve_server::utils::sse_buffer::{SseBuffer, RESYNC_EVENT_NAME};
 use darve_server::utils::user_presence_guard::UserPresenceGuard;
 use serde_json::Value;
 use std::time::Duration;
@@ -248,3 +250,137 @@ test_with_server!(
         );
     }
 );
+
+test_with_server!(test_sse_buffer_replay, |server, ctx_state, config| {
+    let _sse_handle = jobs::sse_events::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let (_, user1, _password1, _token1) = create_fake_login_test_user(&server).await;
+    let (_, user2, _password2, _token2) = create_fake_login_test_user(&server).await;
+    let user1_id = user1.id.unwrap().id.to_raw();
+    let user2_id = user2.id.unwrap().id.to_raw();
+
+    let start_id = ctx_state.sse_buffer.last_id(&user1_id).await.unwrap();
+    let mut sse_receiver = ctx_state.sse_buffer.subscribe();
+    let _ = ctx_state.event_sender.send(AppEvent {
+        user_id: user2_id.clone(),
+        metadata: None,
+        content: None,
+        receivers: vec![user1_id.clone()],
+        event: AppEventType::UpdatedUserBalance,
+    });
+
+    let event = timeout(Duration::from_secs(1), sse_receiver.recv())
+        .await
+        .expect("Should receive event within timeout")
+        .expect("Should receive valid event");
+    assert_eq!(event.name, "UpdatedUserBalance");
+    let event_id = event.id.expect("Buffered event should have id");
+    assert!(event_id > start_id);
+
+    let replayed = ctx_state
+        .sse_buffer
+        .replay(&user1_id, start_id)
+        .await
+        .unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].id, Some(event_id));
+    assert!(ctx_state
+        .sse_buffer
+        .replay(&user1_id, event_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(ctx_state
+        .sse_buffer
+        .replay(&user2_id, 0)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // another instance replays the same ids from the shared table
+    let other_instance = SseBuffer::new(
+        ctx_state.db.client.clone(),
+        200,
+        chrono::Duration::minutes(10),
+    );
+    let replayed = other_instance.replay(&user1_id, start_id).await.unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].id, Some(event_id));
+
+    // the ids which are not issued yet are unknown
+    assert!(ctx_state
+        .sse_buffer
+        .replay(&user1_id, event_id + 1)
+        .await
+        .is_none());
+
+    // each receiver has its own ids
+    let _ = ctx_state.event_sender.send(AppEvent {
+        user_id: user2_id.clone(),
+        metadata: None,
+        content: None,
+        receivers: vec![user1_id.clone(), user2_id.clone()],
+        event: AppEventType::UpdatedUserBalance,
+    });
+    for _ in 0..2 {
+        timeout(Duration::from_secs(1), sse_receiver.recv())
+            .await
+            .expect("Should receive event within timeout")
+            .expect("Should receive valid event");
+    }
+    let replayed = ctx_state
+        .sse_buffer
+        .replay(&user1_id, event_id)
+        .await
+        .unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].id, Some(event_id + 1));
+    let replayed = ctx_state.sse_buffer.replay(&user2_id, 0).await.unwrap();
+    assert_eq!(replayed.len(), 1);
+    assert_eq!(replayed[0].id, Some(1));
+});
+
+test_with_server!(test_sse_buffer_resync, |_server, ctx_state, config| {
+    let buffer = Arc::new(SseBuffer::new(
+        ctx_state.db.client.clone(),
+        2,
+        chrono::Duration::minutes(10),
+    ));
+    let _listen_handle = buffer.clone().listen().await.unwrap();
+    let mut receiver = buffer.subscribe();
+    let receivers = vec!["user".to_string()];
+
+    for _ in 0..3 {
+        buffer
+            .publish("", "UpdatedUserBalance", None, receivers.clone())
+            .await
+            .unwrap();
+    }
+    let mut ids = vec![];
+    for _ in 0..3 {
+        let event = timeout(Duration::from_secs(1), receiver.recv())
+            .await
+            .expect("Should receive event within timeout")
+            .expect("Should receive valid event");
+        ids.push(event.id.unwrap());
+    }
+    let first_id = ids[0];
+
+    // there are more events than the capacity
+    assert!(buffer.replay("user", first_id - 1).await.is_none());
+    assert_eq!(buffer.replay("user", first_id).await.unwrap().len(), 2);
+
+    // the replay resyncs from the last id of the user
+    let events = buffer.replay_or_resync("user", first_id - 1).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name, RESYNC_EVENT_NAME);
+    assert_eq!(events[0].id, Some(ids[2]));
+    assert!(buffer.replay("user", ids[2]).await.unwrap().is_empty());
+
+    // the event which is not stored resyncs only its receivers
+    buffer.resync(Some(receivers));
+    let event = receiver.recv().await.unwrap();
+    assert_eq!(event.name, RESYNC_EVENT_NAME);
+    assert!(event.is_for("user"));
+    assert!(!event.is_for("other_user"));
+    assert_eq!(buffer.replay("user", first_id).await.unwrap().len(), 2);
+});
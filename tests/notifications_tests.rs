@@ -1,5 +1,7 @@
 mod helpers;
 
+use std::time::Duration;
+
 use crate::helpers::create_fake_login_test_user;
 use darve_server::{
     entities::{
@@ -9,13 +11,16 @@ use darve_server::{
         },
         user_notification::UserNotificationEvent,
     },
+    interfaces::repositories::user_notifications::UserNotificationsInterface,
+    jobs,
     models::view::notification::UserNotificationView,
     services::discussion_service::CreateDiscussion,
 };
 
 use fake::{faker, Fake};
-use helpers::post_helpers::create_fake_post;
+use helpers::post_helpers::{self, create_fake_post};
 use serde_json::json;
+use tokio::time::timeout;
 
 test_with_server!(on_create_private_task, |server, ctx_state, config| {
     let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
@@ -245,3 +250,250 @@ test_with_server!(filter_by_types_notification, |server, ctx_state, config| {
 
     assert_eq!(notifications.len(), 0);
 });
+
+test_with_server!(
+    grouped_post_like_notifications,
+    |server, ctx_state, config| {
+        let _sse_handle = jobs::sse_events::run(ctx_state.clone(), Duration::from_secs(60)).await;
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+        let (server, _user2, _, token2) = create_fake_login_test_user(&server).await;
+        let (server, _user3, _, token3) = create_fake_login_test_user(&server).await;
+        let disc_id = DiscussionDbService::get_profile_discussion_id(user0.id.as_ref().unwrap());
+        let post = create_fake_post(server, &disc_id, None, None, &token0).await;
+
+        post_helpers::create_post_like(&server, &post.id, None, &token1)
+            .await
+            .assert_status_ok();
+        post_helpers::create_post_like(&server, &post.id, None, &token2)
+            .await
+            .assert_status_ok();
+
+        let mut rx = ctx_state.sse_buffer.subscribe();
+        post_helpers::create_post_like(&server, &post.id, None, &token3)
+            .await
+            .assert_status_ok();
+        let event = timeout(Duration::from_secs(1), async {
+            loop {
+                let event = rx.recv().await.expect("Should receive valid event");
+                if event.name == "NotificationUpdated" {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("Should receive event within timeout");
+        assert!(event.is_for(&user0.id.as_ref().unwrap().id.to_raw()));
+
+        // the same actor is counted once
+        post_helpers::delete_post_like(&server, &post.id, &token1)
+            .await
+            .assert_status_ok();
+        post_helpers::create_post_like(&server, &post.id, None, &token1)
+            .await
+            .assert_status_ok();
+
+        let notifications = server
+            .get("/api/notifications")
+            .add_query_param(
+                "filter_by_types",
+                UserNotificationEvent::UserLikePost.as_str(),
+            )
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<UserNotificationView>>();
+
+        assert_eq!(notifications.len(), 1);
+        let notification = &notifications[0];
+        assert_eq!(notification.actors_count, 3);
+        assert_eq!(notification.actors.as_ref().unwrap().len(), 3);
+        assert!(notification.title.ends_with("and 2 others liked the post"));
+        assert!(notification.updated_at.is_some());
+        assert!(!notification.is_read);
+        // the latest actor is shown first
+        assert_eq!(notification.created_by.id, user1.id.clone().unwrap());
+    }
+);
+
+test_with_server!(
+    concurrent_post_likes_are_grouped,
+    |server, ctx_state, config| {
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, _, _, token1) = create_fake_login_test_user(&server).await;
+        let (server, _, _, token2) = create_fake_login_test_user(&server).await;
+        let disc_id = DiscussionDbService::get_profile_discussion_id(user0.id.as_ref().unwrap());
+        let post = create_fake_post(server, &disc_id, None, None, &token0).await;
+
+        let (like1, like2) = tokio::join!(
+            post_helpers::create_post_like(&server, &post.id, None, &token1),
+            post_helpers::create_post_like(&server, &post.id, None, &token2)
+        );
+        like1.assert_status_ok();
+        like2.assert_status_ok();
+
+        let notifications = server
+            .get("/api/notifications")
+            .add_query_param(
+                "filter_by_types",
+                UserNotificationEvent::UserLikePost.as_str(),
+            )
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<UserNotificationView>>();
+
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].actors_count, 2);
+        assert!(notifications[0].title.ends_with("and 1 other liked the post"));
+    }
+);
+
+test_with_server!(
+    delete_and_archive_notifications,
+    |server, ctx_state, config| {
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, _) = create_fake_login_test_user(&server).await;
+        let user0_id = user0.id.as_ref().unwrap().id.to_raw();
+        let user1_id = user1.id.as_ref().unwrap().id.to_raw();
+
+        let mut ids = vec![];
+        for _ in 0..3 {
+            let notification = ctx_state
+                .db
+                .user_notifications
+                .create(
+                    &user1_id,
+                    "title",
+                    UserNotificationEvent::CreatedPost.as_str(),
+                    &vec![user0_id.clone()],
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            ids.push(notification.id);
+        }
+
+        let get_notifications = |is_archived: bool| {
+            server
+                .get("/api/notifications")
+                .add_query_param("is_archived", is_archived)
+                .add_header("Authorization", format!("Bearer {}", token0))
+                .add_header("Accept", "application/json")
+        };
+
+        server
+            .post(&format!("/api/notifications/{}/archive", ids[0]))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .assert_status_success();
+        let notifications = get_notifications(false)
+            .await
+            .json::<Vec<UserNotificationView>>();
+        assert_eq!(notifications.len(), 2);
+        let notifications = get_notifications(true)
+            .await
+            .json::<Vec<UserNotificationView>>();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].id, ids[0]);
+
+        server
+            .delete(&format!("/api/notifications/{}", ids[1]))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .assert_status_success();
+        let notifications = get_notifications(false)
+            .await
+            .json::<Vec<UserNotificationView>>();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].id, ids[2]);
+
+        server
+            .delete("/api/notifications")
+            .add_query_param("ids", &ids[0])
+            .add_query_param("ids", &ids[2])
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .assert_status_success();
+        assert!(get_notifications(false)
+            .await
+            .json::<Vec<UserNotificationView>>()
+            .is_empty());
+        assert!(get_notifications(true)
+            .await
+            .json::<Vec<UserNotificationView>>()
+            .is_empty());
+    }
+);
+
+test_with_server!(notification_retention, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, user1, _, _) = create_fake_login_test_user(&server).await;
+    let user0_id = user0.id.as_ref().unwrap().id.to_raw();
+    let user1_id = user1.id.as_ref().unwrap().id.to_raw();
+    let user0_thing = user0.id.as_ref().unwrap().to_raw();
+    let user1_thing = user1.id.as_ref().unwrap().to_raw();
+    let notifications = &ctx_state.db.user_notifications;
+    let since = chrono::Utc::now();
+
+    for _ in 0..4 {
+        notifications
+            .create(
+                &user1_id,
+                "title",
+                UserNotificationEvent::CreatedPost.as_str(),
+                &vec![user0_id.clone(), user1_id.clone()],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    notifications.read_all(&user0_thing).await.unwrap();
+
+    notifications
+        .remove_read_before(chrono::Utc::now() - chrono::Duration::days(1))
+        .await
+        .unwrap();
+    assert_eq!(
+        notifications.get_count(&user0_thing, None).await.unwrap(),
+        4
+    );
+
+    notifications
+        .remove_read_before(chrono::Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(
+        notifications.get_count(&user0_thing, None).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        notifications.get_count(&user1_thing, None).await.unwrap(),
+        4
+    );
+
+    // the users without new notifications are not checked
+    notifications
+        .remove_over_limit(1, chrono::Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(
+        notifications.get_count(&user1_thing, None).await.unwrap(),
+        4
+    );
+
+    notifications.remove_over_limit(1, since).await.unwrap();
+    assert_eq!(
+        notifications.get_count(&user1_thing, None).await.unwrap(),
+        1
+    );
+
+    let count = server
+        .get("/api/notifications/count")
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .await
+        .json::<u64>();
+    assert_eq!(count, 0);
+});
@@ -0,0 +1,162 @@
+mod helpers;
+
+use std::fs;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::{MultipartForm, Part};
+use darve_server::entities::community::discussion_entity::DiscussionDbService;
+use darve_server::entities::community::post_entity::PostType;
+use darve_server::entities::wallet::wallet_entity::{CurrencySymbol, WalletDbService};
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::full_post::FullPostView;
+use darve_server::models::view::post::PostView;
+
+test_with_server!(
+    paid_post_is_unlocked_after_purchase,
+    |server, ctx_state, config| {
+        let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let content = "Paid content ".repeat(20);
+        let file = fs::read("tests/dummy/file_example_PNG_1MB.png").unwrap();
+        let part = Part::bytes(file)
+            .file_name("file_example_PNG_1MB.png")
+            .mime_type("image/jpeg");
+        let data = MultipartForm::new()
+            .add_text("title", "Paid post title")
+            .add_text("content", content.clone())
+            .add_text("price", 100)
+            .add_part("file_1", part);
+
+        let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+        let post_res = create_post(server, &disc_id, data, &creator_token).await;
+        post_res.assert_status_success();
+        let post = post_res.json::<PostView>();
+        assert_eq!(post.r#type, PostType::Paid);
+        assert_eq!(post.price, Some(100));
+        assert!(matches!(post.currency, Some(CurrencySymbol::USD)));
+        assert!(post.media_links.is_some());
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        get_post.assert_status_success();
+        let teaser = get_post.json::<FullPostView>();
+        assert!(teaser.media_links.is_none());
+        assert!(teaser.content.unwrap().len() < content.len());
+
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc_id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<PostView>>();
+        let listed = posts.iter().find(|p| p.id == post.id).unwrap();
+        assert!(listed.media_links.is_none());
+
+        let buy_res = server
+            .post(&format!("/api/posts/{}/buy", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        buy_res.assert_status_failure();
+
+        server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let buy_res = server
+            .post(&format!("/api/posts/{}/buy", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        buy_res.assert_status_success();
+        let bought = buy_res.json::<FullPostView>();
+        assert!(bought.media_links.is_some());
+        assert_eq!(bought.content, Some(content.clone()));
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<FullPostView>();
+        assert!(get_post.media_links.is_some());
+
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc_id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<PostView>>();
+        let listed = posts.iter().find(|p| p.id == post.id).unwrap();
+        assert!(listed.media_links.is_some());
+
+        // the post is bought once only
+        server
+            .post(&format!("/api/posts/{}/buy", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_forbidden();
+
+        let ctx = Ctx::new(Ok(user.id.as_ref().unwrap().to_raw()), false);
+        let wallet_service = WalletDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        };
+        let user_balance = wallet_service
+            .get_user_balance(user.id.as_ref().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(user_balance.balance_usd, 900);
+        let creator_balance = wallet_service
+            .get_user_balance(creator.id.as_ref().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(creator_balance.balance_usd, 90);
+
+        // an expired grant doesn't unlock the content anymore
+        ctx_state
+            .db
+            .client
+            .query("UPDATE has_access SET expires_at=time::now() - 1d WHERE in=$user AND out=$post;")
+            .bind(("user", user.id.clone().unwrap()))
+            .bind(("post", post.id.clone()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc_id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<PostView>>();
+        let listed = posts.iter().find(|p| p.id == post.id).unwrap();
+        assert!(listed.media_links.is_none());
+    }
+);
+
+test_with_server!(
+    only_owner_can_create_paid_post,
+    |server, ctx_state, config| {
+        let (server, creator, _, _) = create_fake_login_test_user(&server).await;
+        let (server, _, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let data = MultipartForm::new()
+            .add_text("title", "Paid post title")
+            .add_text("content", "Paid content")
+            .add_text("price", 100);
+
+        let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+        let post_res = create_post(server, &disc_id, data, &user_token).await;
+        post_res.assert_status_forbidden();
+    }
+);
@@ -0,0 +1,171 @@
+mod helpers;
+
+use crate::helpers::{
+    create_fake_login_test_user,
+    post_helpers::{create_fake_post, create_fake_reply},
+};
+use darve_server::{
+    entities::{
+        community::{
+            community_entity::CommunityDbService,
+            discussion_entity::{Discussion, DiscussionDbService},
+            post_entity::Post,
+        },
+        edit_history::EditHistory,
+    },
+    interfaces::repositories::tags::TagsRepositoryInterface,
+    middleware::utils::db_utils::Pagination,
+    models::view::{full_post::FullPostView, post::PostView, reply::ReplyView},
+    services::discussion_service::CreateDiscussion,
+};
+use serde_json::json;
+
+test_with_server!(edit_post_saves_history, |server, ctx_state, config| {
+    let (server, user, _, token) = create_fake_login_test_user(&server).await;
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user.id.as_ref().unwrap());
+    let post = create_fake_post(
+        &server,
+        &disc_id,
+        None,
+        Some(vec!["old".to_string()]),
+        &token,
+    )
+    .await;
+
+    let before = server
+        .get(&format!("/api/posts/{}", post.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json::<FullPostView>();
+    assert!(before.edited_at.is_none());
+
+    let response = server
+        .patch(&format!("/api/posts/{}", post.id))
+        .json(&json!({ "title": "Edited title", "content": "Edited content", "tags": ["new"] }))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await;
+    response.assert_status_success();
+    let edited = response.json::<PostView>();
+    assert_eq!(edited.title, "Edited title");
+    assert_eq!(edited.content, Some("Edited content".to_string()));
+    assert!(edited.edited_at.is_some());
+
+    let history = server
+        .get(&format!("/api/posts/{}/history", post.id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json::<Vec<EditHistory>>();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].title, Some(before.title));
+    assert_eq!(history[0].content, before.content);
+    assert_eq!(&history[0].edited_by, user.id.as_ref().unwrap());
+
+    let pagination = Pagination {
+        count: 10,
+        start: 0,
+        order_by: None,
+        order_dir: None,
+    };
+    let old_tag_posts: Vec<Post> = ctx_state
+        .db
+        .tags
+        .get_by_tag("old", pagination)
+        .await
+        .unwrap();
+    assert!(old_tag_posts.is_empty());
+});
+
+test_with_server!(edit_post_of_other_user, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, _, _, token1) = create_fake_login_test_user(&server).await;
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user0.id.as_ref().unwrap());
+    let post = create_fake_post(&server, &disc_id, None, None, &token0).await;
+
+    let response = server
+        .patch(&format!("/api/posts/{}", post.id))
+        .json(&json!({ "title": "Edited title" }))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await;
+    response.assert_status_forbidden();
+
+    let history = server
+        .get(&format!("/api/posts/{}/history", post.id))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .await
+        .json::<Vec<EditHistory>>();
+    assert!(history.is_empty());
+});
+
+test_with_server!(edit_reply_saves_history, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, _, _, token1) = create_fake_login_test_user(&server).await;
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user0.id.as_ref().unwrap());
+    let post = create_fake_post(&server, &disc_id, None, None, &token0).await;
+    let reply = create_fake_reply(&server, &post.id, &token0).await;
+
+    let response = server
+        .patch(&format!("/api/replies/{}", reply.id.to_raw()))
+        .json(&json!({ "content": "Edited reply" }))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await;
+    response.assert_status_forbidden();
+
+    let response = server
+        .patch(&format!("/api/replies/{}", reply.id.to_raw()))
+        .json(&json!({ "content": "Edited reply" }))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .await;
+    response.assert_status_success();
+    let edited = response.json::<ReplyView>();
+    assert_eq!(edited.content, "Edited reply");
+    assert!(edited.edited_at.is_some());
+
+    let history = server
+        .get(&format!("/api/replies/{}/history", reply.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await
+        .json::<Vec<EditHistory>>();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].content, Some(reply.content));
+    assert!(history[0].title.is_none());
+});
+
+test_with_server!(editor_edits_reply_of_member, |server, ctx_state, config| {
+    let (server, member, _, member_token) = create_fake_login_test_user(&server).await;
+    let (server, editor, _, editor_token) = create_fake_login_test_user(&server).await;
+
+    let comm_id = CommunityDbService::get_profile_community_id(editor.id.as_ref().unwrap());
+    let create_response = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: vec![member.id.as_ref().unwrap().to_raw()].into(),
+            private_discussion_users_final: true,
+        })
+        .add_header("Authorization", format!("Bearer {}", editor_token))
+        .add_header("Accept", "application/json")
+        .await;
+    create_response.assert_status_ok();
+    let disc_id = create_response.json::<Discussion>().id;
+    let post = create_fake_post(&server, &disc_id, None, None, &editor_token).await;
+    let reply = create_fake_reply(&server, &post.id, &member_token).await;
+
+    let response = server
+        .patch(&format!("/api/replies/{}", reply.id.to_raw()))
+        .json(&json!({ "content": "Edited by the editor" }))
+        .add_header("Authorization", format!("Bearer {}", editor_token))
+        .await;
+    response.assert_status_success();
+    let edited = response.json::<ReplyView>();
+    assert_eq!(edited.content, "Edited by the editor");
+
+    let history = server
+        .get(&format!("/api/replies/{}/history", reply.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", member_token))
+        .await
+        .json::<Vec<EditHistory>>();
+    assert_eq!(history.len(), 1);
+    assert_eq!(&history[0].edited_by, editor.id.as_ref().unwrap());
+});
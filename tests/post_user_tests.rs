@@ -1,5 +1,7 @@
 mod helpers;
 
+use std::time::Duration;
+
 use crate::helpers::{create_fake_login_test_user, post_helpers::create_fake_post};
 use darve_server::{
     entities::community::{
@@ -8,10 +10,12 @@ use darve_server::{
         post_entity::PostUserStatus,
     },
     interfaces::repositories::post_user::PostUserRepositoryInterface,
+    jobs,
     middleware::utils::string_utils::get_str_thing,
-    models::view::discussion_user::DiscussionUserView,
+    models::view::{discussion_user::DiscussionUserView, post::PostView},
     services::discussion_service::CreateDiscussion,
 };
+use tokio::time::timeout;
 
 test_with_server!(
     test_post_mark_as_deliver_success,
@@ -260,3 +264,103 @@ test_with_server!(
         assert_eq!(users_status[0].status, PostUserStatus::Seen);
     }
 );
+
+test_with_server!(
+    read_receipts_in_private_discussion,
+    |server, ctx_state, config| {
+        let _sse_handle = jobs::sse_events::run(ctx_state.clone(), Duration::from_secs(60)).await;
+        let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+        let (server, user1, _, token1) = create_fake_login_test_user(&server).await;
+        let user0_id = user0.id.as_ref().unwrap().id.to_raw();
+        let user1_id = user1.id.as_ref().unwrap().id.to_raw();
+
+        let comm_id = CommunityDbService::get_profile_community_id(user0.id.as_ref().unwrap());
+        let disc = server
+            .post("/api/discussions")
+            .json(&CreateDiscussion {
+                community_id: comm_id.to_raw(),
+                title: "The Discussion".to_string(),
+                image_uri: None,
+                chat_user_ids: Some(vec![user1_id.clone()]),
+                private_discussion_users_final: false,
+            })
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Discussion>();
+
+        let post0 = create_fake_post(&server, &disc.id, None, None, &token0).await;
+        let post1 = create_fake_post(&server, &disc.id, None, None, &token0).await;
+
+        let mut rx = ctx_state.sse_buffer.subscribe();
+        server
+            .post(&format!("/api/posts/{}/read", post1.id))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .assert_status_success();
+
+        let event = timeout(Duration::from_secs(1), async {
+            loop {
+                let event = rx.recv().await.expect("Should receive valid event");
+                if event.name == "DiscussionPostRead" {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("Should receive event within timeout");
+        assert_eq!(event.user_id, user1_id);
+        assert!(event.is_for(&user0_id));
+        assert!(!event.is_for(&user1_id));
+
+        // reading the older post keeps the marker on the latest read post
+        server
+            .post(&format!("/api/posts/{}/read", post0.id))
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .assert_status_success();
+
+        let posts = server
+            .get(&format!("/api/discussions/{}/posts", disc.id))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .await
+            .json::<Vec<PostView>>();
+        assert_eq!(posts.len(), 2);
+        for post in posts {
+            assert_eq!(post.read_by, Some(vec![user1.id.clone().unwrap()]));
+        }
+
+        let latest_posts = server
+            .get("/api/users/current/latest_posts")
+            .add_header("Authorization", format!("Bearer {}", token1))
+            .await
+            .json::<Vec<DiscussionUserView>>();
+        let disc_user = latest_posts
+            .iter()
+            .find(|d| d.discussion.id == disc.id)
+            .unwrap();
+        assert_eq!(
+            disc_user.last_read_post,
+            Some(get_str_thing(&post1.id).unwrap())
+        );
+    }
+);
+
+test_with_server!(typing_in_discussion, |server, ctx_state, config| {
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (_, _, _, token1) = create_fake_login_test_user(&server).await;
+    let disc_id = DiscussionDbService::get_profile_discussion_id(user0.id.as_ref().unwrap());
+
+    server
+        .post(&format!("/api/discussions/{}/typing", disc_id))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .await
+        .assert_status_success();
+
+    let disc_id = "discussion:nonexistent";
+    server
+        .post(&format!("/api/discussions/{}/typing", disc_id))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .await
+        .assert_status_not_found();
+});
@@ -0,0 +1,102 @@
+mod helpers;
+
+use std::time::Duration;
+
+use crate::helpers::create_fake_login_test_user;
+use axum_test::TestServer;
+use darve_server::{
+    entities::push_device::{DevicePlatform, PushDevice},
+    jobs,
+};
+use serde_json::json;
+
+async fn get_devices(server: &TestServer, token: &str) -> Vec<PushDevice> {
+    server
+        .get("/api/users/current/devices")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PushDevice>>()
+}
+
+test_with_server!(register_device, |server, ctx_state, config| {
+    let (server, _, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, _, _, token1) = create_fake_login_test_user(&server).await;
+
+    server
+        .post("/api/users/current/devices")
+        .json(&json!({ "token": "", "platform": "Ios" }))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_failure();
+
+    server
+        .post("/api/users/current/devices")
+        .json(&json!({ "token": "device_token", "platform": "Ios" }))
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    assert_eq!(get_devices(server, &token0).await.len(), 1);
+
+    // the same token registered by another user is moved to that user
+    server
+        .post("/api/users/current/devices")
+        .json(&json!({ "token": "device_token", "platform": "Android" }))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    assert!(get_devices(server, &token0).await.is_empty());
+    let devices = get_devices(server, &token1).await;
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].platform, DevicePlatform::Android);
+
+    server
+        .delete("/api/users/current/devices/device_token")
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    assert!(get_devices(server, &token1).await.is_empty());
+});
+
+test_with_server!(push_to_offline_user, |server, ctx_state, config| {
+    let _push_handle = jobs::push_notifications::run(ctx_state.clone()).await;
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, _, _, token1) = create_fake_login_test_user(&server).await;
+
+    for token in ["valid_token", "invalid_token"] {
+        server
+            .post("/api/users/current/devices")
+            .json(&json!({ "token": token, "platform": "Android" }))
+            .add_header("Authorization", format!("Bearer {}", token0))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+    }
+
+    server
+        .post(&format!(
+            "/api/following/{}",
+            user0.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", token1))
+        .add_header("Accept", "application/json")
+        .json("")
+        .await
+        .assert_status_success();
+
+    let mut devices = get_devices(server, &token0).await;
+    for _ in 0..20 {
+        if devices.len() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        devices = get_devices(server, &token0).await;
+    }
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].token, "valid_token");
+    assert_eq!(devices[0].badge, 1);
+});
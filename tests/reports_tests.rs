@@ -0,0 +1,199 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::entities::report::{
+    ModerationAction, ModerationActionType, Report, ReportStatus,
+};
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::post::PostView;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+
+test_with_server!(reported_post_moderation, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, reporter_token) = create_fake_login_test_user(&server).await;
+    let (server, _, _, reporter1_token) = create_fake_login_test_user(&server).await;
+
+    let comm_id = CommunityDbService::get_profile_community_id(&user.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let post = create_post(server, &disc.id, data, &user_token)
+        .await
+        .json::<PostView>();
+
+    server
+        .post("/api/reports")
+        .json(&json!({ "entity_id": post.id.to_raw(), "reason": "Spam" }))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_failure();
+
+    server
+        .post("/api/reports")
+        .json(&json!({ "entity_id": post.id.to_raw(), "reason": "Spam", "text": "ads" }))
+        .add_header("Authorization", format!("Bearer {}", reporter_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post("/api/reports")
+        .json(&json!({ "entity_id": post.id.to_raw(), "reason": "Scam" }))
+        .add_header("Authorization", format!("Bearer {}", reporter_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_failure();
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().any(|p| p.id == post.id));
+
+    server
+        .post("/api/reports")
+        .json(&json!({ "entity_id": post.id.to_raw(), "reason": "Scam" }))
+        .add_header("Authorization", format!("Bearer {}", reporter1_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    // the threshold of the test config is 2 reporters
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().all(|p| p.id != post.id));
+
+    server
+        .get(&format!("/api/posts/{}", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_not_found();
+
+    server
+        .post(&format!("/api/posts/{}/like", post.id.to_raw()))
+        .json(&json!({}))
+        .add_header("Authorization", format!("Bearer {}", reporter_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_not_found();
+
+    server
+        .get("/api/admin/reports")
+        .add_header("Authorization", format!("Bearer {}", reporter_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+
+    let reports = server
+        .get(&format!(
+            "/api/admin/reports?status=Open&entity_id={}",
+            post.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<Report>>();
+    assert_eq!(reports.len(), 2);
+
+    let report = server
+        .post(&format!(
+            "/api/admin/reports/{}/assign",
+            reports[0].id.to_raw()
+        ))
+        .json(&json!({ "user_id": admin.id.as_ref().unwrap().to_raw() }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Report>();
+    assert_eq!(report.assigned_to, admin.id);
+
+    server
+        .post(&format!(
+            "/api/admin/reports/{}/actions",
+            report.id.to_raw()
+        ))
+        .json(&json!({ "action": "Dismiss", "note": "Not a spam" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let reports = server
+        .get(&format!(
+            "/api/admin/reports?status=Dismissed&entity_id={}",
+            post.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<Report>>();
+    assert_eq!(reports.len(), 2);
+    assert!(reports.iter().all(|r| r.status == ReportStatus::Dismissed));
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().any(|p| p.id == post.id));
+
+    let actions = server
+        .get(&format!(
+            "/api/admin/moderation/actions?entity_id={}",
+            post.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<ModerationAction>>();
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].action, ModerationActionType::Dismiss);
+    assert_eq!(actions[1].action, ModerationActionType::Hide);
+    assert!(actions[1].moderator.is_none());
+});
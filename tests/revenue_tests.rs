@@ -0,0 +1,104 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::discussion_entity::DiscussionDbService;
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::entities::wallet::balance_transaction_entity::TransactionType;
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::post::PostView;
+use darve_server::models::view::revenue::{
+    LiabilitiesView, RevenueByCurrencyView, RevenueByPeriodView, RevenueByTypeView,
+};
+
+test_with_server!(get_platform_revenue, |server, ctx_state, config| {
+    let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+    let data = MultipartForm::new()
+        .add_text("title", "Paid post title")
+        .add_text("content", "Paid content")
+        .add_text("price", 100);
+    let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+    let post = create_post(server, &disc_id, data, &creator_token)
+        .await
+        .json::<PostView>();
+
+    server
+        .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/posts/{}/buy", post.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .get("/api/admin/revenue/types")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&serde_json::json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+
+    let by_type = server
+        .get("/api/admin/revenue/types")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<RevenueByTypeView>>();
+    assert_eq!(by_type.len(), 1);
+    assert_eq!(by_type[0].r#type, Some(TransactionType::Fee));
+    assert_eq!(by_type[0].amount, 10);
+
+    let by_currency = server
+        .get("/api/admin/revenue/currencies")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<RevenueByCurrencyView>>();
+    assert_eq!(by_currency.len(), 1);
+    assert_eq!(by_currency[0].amount, 10);
+    assert_eq!(by_currency[0].count, 1);
+
+    let by_period = server
+        .get("/api/admin/revenue?period=month")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<RevenueByPeriodView>>();
+    assert_eq!(by_period.len(), 1);
+    assert_eq!(by_period[0].amount, 10);
+
+    let liabilities = server
+        .get("/api/admin/revenue/liabilities")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<LiabilitiesView>();
+    assert_eq!(liabilities.user_liabilities.balance_usd, 990);
+    assert_eq!(liabilities.gateway_holdings.balance_usd, 1000);
+    assert_eq!(liabilities.platform_balance.balance_usd, 10);
+});
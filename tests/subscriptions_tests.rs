@@ -0,0 +1,406 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::discussion_entity::DiscussionDbService;
+use darve_server::entities::community::post_entity::PostType;
+use darve_server::entities::subscription::{Subscription, SubscriptionStatus, SubscriptionTier};
+use darve_server::entities::wallet::wallet_entity::WalletDbService;
+use darve_server::middleware::ctx::Ctx;
+use darve_server::models::view::post::PostView;
+use darve_server::services::subscription_service::SubscriptionService;
+use fake::{faker, Fake};
+use serde_json::json;
+
+test_with_server!(
+    subscriber_can_view_subscriber_posts,
+    |server, ctx_state, config| {
+        let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let tier_res = server
+            .post("/api/subscriptions/tiers")
+            .json(&json!({
+                "title": "Gold",
+                "price": 100,
+                "interval": "Monthly",
+                "perks": ["Private posts"]
+            }))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await;
+        tier_res.assert_status_success();
+        let tier = tier_res.json::<SubscriptionTier>();
+
+        let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+        let data = MultipartForm::new()
+            .add_text("title", faker::name::en::Name().fake::<String>())
+            .add_text("for_subscribers", true)
+            .add_text(
+                "content",
+                faker::lorem::en::Sentence(7..20).fake::<String>(),
+            );
+        let post_res = create_post(server, &disc_id, data, &creator_token).await;
+        post_res.assert_status_success();
+        let post = post_res.json::<PostView>();
+        assert_eq!(post.r#type, PostType::Private);
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        get_post.assert_status_forbidden();
+
+        let subscribe_res = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        subscribe_res.assert_status_failure();
+
+        let endow_user_response = server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        endow_user_response.assert_status_success();
+
+        let subscribe_res = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        subscribe_res.assert_status_success();
+        let subscription = subscribe_res.json::<Subscription>();
+        assert_eq!(subscription.status, SubscriptionStatus::Active);
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        get_post.assert_status_success();
+
+        let posts_res = server
+            .get(&format!("/api/discussions/{}/posts", disc_id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        posts_res.assert_status_success();
+        let posts = posts_res.json::<Vec<PostView>>();
+        assert!(posts.iter().any(|p| p.id == post.id));
+
+        let ctx = Ctx::new(Ok(user.id.as_ref().unwrap().to_raw()), false);
+        let creator_balance = WalletDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        }
+        .get_user_balance(creator.id.as_ref().unwrap())
+        .await
+        .unwrap();
+        assert_eq!(creator_balance.balance_usd, 100);
+    }
+);
+
+test_with_server!(
+    expired_subscription_removes_access,
+    |server, ctx_state, config| {
+        let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let tier_res = server
+            .post("/api/subscriptions/tiers")
+            .json(&json!({
+                "title": "Silver",
+                "price": 100,
+                "interval": "Weekly",
+            }))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await;
+        tier_res.assert_status_success();
+        let tier = tier_res.json::<SubscriptionTier>();
+
+        let disc_id = DiscussionDbService::get_profile_discussion_id(creator.id.as_ref().unwrap());
+        let data = MultipartForm::new()
+            .add_text("title", faker::name::en::Name().fake::<String>())
+            .add_text("for_subscribers", true)
+            .add_text(
+                "content",
+                faker::lorem::en::Sentence(7..20).fake::<String>(),
+            );
+        let post = create_post(server, &disc_id, data, &creator_token)
+            .await
+            .json::<PostView>();
+
+        server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 100))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let subscription = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Subscription>();
+
+        let ctx = Ctx::new(Ok("".to_string()), false);
+        let service = SubscriptionService::new(
+            &ctx_state.db.client,
+            &ctx,
+            &ctx_state.event_sender,
+            &ctx_state.db.user_notifications,
+            &ctx_state.db.subscriptions,
+            &ctx_state.db.access,
+        );
+
+        // the renewal fails because the wallet is empty now
+        let _ = ctx_state
+            .db
+            .client
+            .query("UPDATE $id SET current_period_end=time::now();")
+            .bind(("id", subscription.id.clone()))
+            .await;
+        service.process_subscriptions().await.unwrap();
+
+        let subscriptions = server
+            .get("/api/subscriptions")
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<Subscription>>();
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(subscriptions[0].status, SubscriptionStatus::PastDue);
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        get_post.assert_status_success();
+
+        let _ = ctx_state
+            .db
+            .client
+            .query("UPDATE $id SET grace_period_end=time::now();")
+            .bind(("id", subscription.id.clone()))
+            .await;
+        service.process_subscriptions().await.unwrap();
+
+        let subscriptions = server
+            .get("/api/subscriptions")
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<Subscription>>();
+        assert_eq!(subscriptions.len(), 0);
+
+        let get_post = server
+            .get(&format!("/api/posts/{}", post.id.to_raw()))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        get_post.assert_status_forbidden();
+    }
+);
+
+test_with_server!(
+    canceled_subscription_keeps_access_until_period_end,
+    |server, ctx_state, config| {
+        let (server, _creator, _, creator_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let tier = server
+            .post("/api/subscriptions/tiers")
+            .json(&json!({
+                "title": "Bronze",
+                "price": 10,
+                "interval": "Monthly",
+            }))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<SubscriptionTier>();
+
+        server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let subscription = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Subscription>();
+
+        let cancel_res = server
+            .post(&format!(
+                "/api/subscriptions/{}/cancel",
+                subscription.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await;
+        cancel_res.assert_status_forbidden();
+
+        let cancel_res = server
+            .post(&format!(
+                "/api/subscriptions/{}/cancel",
+                subscription.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        cancel_res.assert_status_success();
+        let canceled = cancel_res.json::<Subscription>();
+        assert_eq!(canceled.status, SubscriptionStatus::Canceled);
+        assert_eq!(canceled.current_period_end, subscription.current_period_end);
+
+        let subscribers = server
+            .get("/api/subscriptions/subscribers")
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Vec<Subscription>>();
+        assert_eq!(subscribers.len(), 1);
+
+        // subscribing again to the same tier resumes without a new charge
+        let resumed = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Subscription>();
+        assert_eq!(resumed.status, SubscriptionStatus::Active);
+        assert_eq!(resumed.current_period_end, subscription.current_period_end);
+
+        // switching to another tier keeps the existing subscriber access
+        server
+            .post(&format!(
+                "/api/subscriptions/{}/cancel",
+                subscription.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let other_tier = server
+            .post("/api/subscriptions/tiers")
+            .json(&json!({
+                "title": "Gold",
+                "price": 20,
+                "interval": "Monthly",
+            }))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<SubscriptionTier>();
+
+        let switch_res = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                other_tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await;
+        switch_res.assert_status_success();
+        let switched = switch_res.json::<Subscription>();
+        assert_eq!(switched.status, SubscriptionStatus::Active);
+        assert_eq!(switched.tier, other_tier.id);
+    }
+);
+
+test_with_server!(
+    concurrent_renewals_charge_once,
+    |server, ctx_state, config| {
+        let (server, creator, _, creator_token) = create_fake_login_test_user(&server).await;
+        let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+
+        let tier = server
+            .post("/api/subscriptions/tiers")
+            .json(&json!({
+                "title": "Silver",
+                "price": 100,
+                "interval": "Weekly",
+            }))
+            .add_header("Authorization", format!("Bearer {}", creator_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<SubscriptionTier>();
+
+        server
+            .get(&format!("/test/api/deposit/{}/{}", user.username, 1000))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .assert_status_success();
+
+        let subscription = server
+            .post(&format!(
+                "/api/subscriptions/tiers/{}/subscribe",
+                tier.id.to_raw()
+            ))
+            .add_header("Authorization", format!("Bearer {}", user_token))
+            .add_header("Accept", "application/json")
+            .await
+            .json::<Subscription>();
+
+        let ctx = Ctx::new(Ok("".to_string()), false);
+        let service = SubscriptionService::new(
+            &ctx_state.db.client,
+            &ctx,
+            &ctx_state.event_sender,
+            &ctx_state.db.user_notifications,
+            &ctx_state.db.subscriptions,
+            &ctx_state.db.access,
+        );
+
+        let _ = ctx_state
+            .db
+            .client
+            .query("UPDATE $id SET current_period_end=time::now();")
+            .bind(("id", subscription.id.clone()))
+            .await;
+        let (first, second) = tokio::join!(
+            service.process_subscriptions(),
+            service.process_subscriptions()
+        );
+        first.unwrap();
+        second.unwrap();
+        service.process_subscriptions().await.unwrap();
+
+        let creator_balance = WalletDbService {
+            db: &ctx_state.db.client,
+            ctx: &ctx,
+        }
+        .get_user_balance(creator.id.as_ref().unwrap())
+        .await
+        .unwrap();
+        assert_eq!(creator_balance.balance_usd, 200);
+    }
+);
@@ -0,0 +1,230 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use crate::helpers::post_helpers::create_post;
+use axum_test::multipart::MultipartForm;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::models::view::notification::UserNotificationView;
+use darve_server::models::view::post::PostView;
+use darve_server::routes::follows::UserItemView;
+use darve_server::services::discussion_service::CreateDiscussion;
+
+test_with_server!(blocked_user_is_hidden, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, blocked, _, blocked_token) = create_fake_login_test_user(&server).await;
+
+    let comm_id = CommunityDbService::get_profile_community_id(&user.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: None,
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    let data = MultipartForm::new()
+        .add_text("title", "Post title")
+        .add_text("content", "Post content");
+    let post = create_post(server, &disc.id, data, &blocked_token)
+        .await
+        .json::<PostView>();
+
+    server
+        .post(&format!(
+            "/api/users/{}/block",
+            blocked.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let blocked_users = server
+        .get("/api/users/current/blocked")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserItemView>>();
+    assert_eq!(blocked_users.len(), 1);
+    assert_eq!(&blocked_users[0].id, blocked.id.as_ref().unwrap());
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().all(|p| p.id != post.id));
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", blocked_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().any(|p| p.id == post.id));
+
+    server
+        .post(&format!(
+            "/api/following/{}",
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", blocked_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    let comm_id = CommunityDbService::get_profile_community_id(&blocked.id.as_ref().unwrap());
+    server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "Private discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: vec![user.id.as_ref().unwrap().to_raw()].into(),
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", blocked_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_forbidden();
+
+    server
+        .delete(&format!(
+            "/api/users/{}/block",
+            blocked.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!(
+            "/api/following/{}",
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", blocked_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let posts = server
+        .get(&format!("/api/discussions/{}/posts", disc.id.to_raw()))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<PostView>>();
+    assert!(posts.iter().any(|p| p.id == post.id));
+});
+
+test_with_server!(muted_user_notifications, |server, ctx_state, config| {
+    let (server, user, _, user_token) = create_fake_login_test_user(&server).await;
+    let (server, muted, _, muted_token) = create_fake_login_test_user(&server).await;
+
+    server
+        .post(&format!(
+            "/api/users/{}/mute",
+            muted.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!(
+            "/api/following/{}",
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", muted_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let notifications = server
+        .get("/api/notifications")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserNotificationView>>();
+    assert!(notifications.is_empty());
+
+    let muted_users = server
+        .get("/api/users/current/muted")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserItemView>>();
+    assert_eq!(muted_users.len(), 1);
+
+    server
+        .delete(&format!(
+            "/api/users/{}/mute",
+            muted.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .delete(&format!(
+            "/api/following/{}",
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", muted_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!(
+            "/api/following/{}",
+            user.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", muted_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    let notifications = server
+        .get("/api/notifications")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<UserNotificationView>>();
+    assert_eq!(notifications.len(), 1);
+
+    // the muted user keeps full access
+    server
+        .post(&format!(
+            "/api/users/{}/mute",
+            muted.id.as_ref().unwrap().to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+    let comm_id = CommunityDbService::get_profile_community_id(&muted.id.as_ref().unwrap());
+    server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "Private discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: vec![user.id.as_ref().unwrap().to_raw()].into(),
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", muted_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+});
@@ -0,0 +1,188 @@
+mod helpers;
+
+use crate::helpers::create_fake_login_test_user;
+use axum_test::TestServer;
+use darve_server::entities::user_auth::local_user_entity::{LocalUserDbService, UserRole};
+use darve_server::entities::wallet::wallet_adjustment_entity::{
+    AdjustmentStatus, WalletAdjustment,
+};
+use darve_server::entities::wallet::wallet_entity::WalletBalancesView;
+use darve_server::middleware::ctx::Ctx;
+use serde_json::json;
+
+async fn get_balance(server: &TestServer, user_id: &str, token: &str) -> i64 {
+    server
+        .get(&format!("/api/admin/users/{}/wallet", user_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletBalancesView>()
+        .balance
+        .balance_usd
+}
+
+test_with_server!(adjust_user_wallet, |server, ctx_state, config| {
+    let (server, user, _, _) = create_fake_login_test_user(&server).await;
+    let (server, second_admin, _, second_admin_token) = create_fake_login_test_user(&server).await;
+
+    let user_repository = LocalUserDbService {
+        db: &ctx_state.db.client,
+        ctx: &Ctx::new(Ok("".to_string()), false),
+    };
+    let admins = user_repository.get_by_role(UserRole::Admin).await.unwrap();
+    let admin = admins.first().unwrap();
+    let login_response = server
+        .post("/api/login")
+        .add_header("Accept", "application/json")
+        .json(&json!({
+            "username_or_email": admin.username,
+            "password": config.init_server_password
+        }))
+        .await;
+    let json_response = login_response.json::<serde_json::Value>();
+    let admin_token = json_response["token"].as_str().unwrap();
+    let user_id = user.id.as_ref().unwrap().to_raw();
+
+    server
+        .put(&format!(
+            "/api/admin/users/{}/role",
+            second_admin.id.as_ref().unwrap().to_raw()
+        ))
+        .json(&json!({ "role": "Admin" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_success();
+
+    server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": 500, "reason": "" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let adjustment = server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": 500, "reason": "Missing deposit" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(adjustment.status, AdjustmentStatus::Approved);
+    assert!(adjustment.balance_tx.is_some());
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 500);
+
+    let adjustment = server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": 5000, "reason": "Lost payout" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(adjustment.status, AdjustmentStatus::Pending);
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 500);
+
+    server
+        .post(&format!(
+            "/api/admin/wallet/adjustments/{}/approve",
+            adjustment.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let approved = server
+        .post(&format!(
+            "/api/admin/wallet/adjustments/{}/approve",
+            adjustment.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", second_admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(approved.status, AdjustmentStatus::Approved);
+    assert_eq!(approved.reviewed_by, second_admin.id);
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 5500);
+
+    server
+        .post(&format!(
+            "/api/admin/wallet/adjustments/{}/approve",
+            adjustment.id.to_raw()
+        ))
+        .add_header("Authorization", format!("Bearer {}", second_admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+
+    let adjustment = server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": -300, "reason": "Duplicate reward" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(adjustment.status, AdjustmentStatus::Approved);
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 5200);
+
+    let adjustment = server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": -3000, "reason": "Chargeback" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(adjustment.status, AdjustmentStatus::Pending);
+
+    let rejected = server
+        .post(&format!(
+            "/api/admin/wallet/adjustments/{}/reject",
+            adjustment.id.to_raw()
+        ))
+        .json(&json!({ "note": "Chargeback was cancelled" }))
+        .add_header("Authorization", format!("Bearer {}", second_admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(rejected.status, AdjustmentStatus::Rejected);
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 5200);
+
+    let pending = server
+        .get("/api/admin/wallet/adjustments?status=Pending")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<WalletAdjustment>>();
+    assert!(pending.is_empty());
+
+    let all = server
+        .get("/api/admin/wallet/adjustments")
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Vec<WalletAdjustment>>();
+    assert_eq!(all.len(), 4);
+
+    // the earlier unreviewed adjustments of the admin count towards the threshold
+    let adjustment = server
+        .post(&format!("/api/admin/users/{}/wallet/adjustments", user_id))
+        .json(&json!({ "amount": 300, "reason": "Missing deposit" }))
+        .add_header("Authorization", format!("Bearer {}", admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<WalletAdjustment>();
+    assert_eq!(adjustment.status, AdjustmentStatus::Pending);
+    assert_eq!(get_balance(server, &user_id, admin_token).await, 5200);
+
+    server
+        .post(&format!(
+            "/api/admin/users/{}/wallet/adjustments",
+            second_admin.id.as_ref().unwrap().to_raw()
+        ))
+        .json(&json!({ "amount": 100, "reason": "Missing deposit" }))
+        .add_header("Authorization", format!("Bearer {}", second_admin_token))
+        .add_header("Accept", "application/json")
+        .await
+        .assert_status_bad_request();
+});
@@ -0,0 +1,137 @@
+mod helpers;
+
+use std::time::Duration;
+
+use crate::helpers::create_fake_login_test_user;
+use darve_server::entities::community::community_entity::CommunityDbService;
+use darve_server::entities::community::discussion_entity::Discussion;
+use darve_server::jobs;
+use darve_server::middleware::ctx::Ctx;
+use darve_server::routes::websocket::WsConnection;
+use darve_server::services::discussion_service::CreateDiscussion;
+use serde_json::json;
+use tokio::time::timeout;
+
+test_with_server!(ws_invalid_message, |server, ctx_state, config| {
+    let (_, user, _, _) = create_fake_login_test_user(&server).await;
+    let user_id = user.id.as_ref().unwrap().id.to_raw();
+    let ctx = Ctx::new(Ok(user.id.as_ref().unwrap().to_raw()), false);
+    let mut connection = WsConnection::new(ctx_state.clone(), ctx, user_id).await;
+
+    let messages = connection.on_client_message("{\"type\":\"Unknown\"}").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].event, "Error");
+});
+
+test_with_server!(ws_presence_subscription, |server, ctx_state, config| {
+    let (_, user0, _, _) = create_fake_login_test_user(&server).await;
+    let (_, user1, _, _) = create_fake_login_test_user(&server).await;
+    let (_, user2, _, _) = create_fake_login_test_user(&server).await;
+    let user0_id = user0.id.as_ref().unwrap().id.to_raw();
+    let user1_id = user1.id.as_ref().unwrap().id.to_raw();
+    let user2_id = user2.id.as_ref().unwrap().id.to_raw();
+    let ctx = Ctx::new(Ok(user0.id.as_ref().unwrap().to_raw()), false);
+    let mut connection = WsConnection::new(ctx_state.clone(), ctx, user0_id).await;
+
+    let messages = connection
+        .on_client_message(
+            &json!({ "type": "SubscribePresence", "user_ids": [user1_id] }).to_string(),
+        )
+        .await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].event, "UserStatus");
+    assert_eq!(messages[0].data.as_ref().unwrap()["is_online"], false);
+
+    let mut rx = ctx_state.sse_buffer.subscribe();
+    for user_id in [&user1_id, &user2_id] {
+        let data = json!({ "is_online": true, "user_id": user_id }).to_string();
+        ctx_state
+            .sse_buffer
+            .publish_ephemeral(user_id, "UserStatus", Some(data), None);
+    }
+    let mut events = vec![];
+    for _ in 0..2 {
+        events.push(rx.recv().await.unwrap());
+    }
+
+    // only the status of the subscribed user is sent
+    let messages = connection.on_events(events.clone()).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].data.as_ref().unwrap()["user_id"], user1_id);
+
+    connection
+        .on_client_message(
+            &json!({ "type": "UnsubscribePresence", "user_ids": [user1_id] }).to_string(),
+        )
+        .await;
+    assert!(connection.on_events(events).await.is_empty());
+});
+
+test_with_server!(ws_typing_in_discussion, |server, ctx_state, config| {
+    let _sse_handle = jobs::sse_events::run(ctx_state.clone(), Duration::from_secs(60)).await;
+    let (server, user0, _, token0) = create_fake_login_test_user(&server).await;
+    let (server, user1, _, _) = create_fake_login_test_user(&server).await;
+    let (_, user2, _, _) = create_fake_login_test_user(&server).await;
+    let user0_id = user0.id.as_ref().unwrap().id.to_raw();
+    let user1_id = user1.id.as_ref().unwrap().id.to_raw();
+    let user2_id = user2.id.as_ref().unwrap().id.to_raw();
+
+    let comm_id = CommunityDbService::get_profile_community_id(user0.id.as_ref().unwrap());
+    let disc = server
+        .post("/api/discussions")
+        .json(&CreateDiscussion {
+            community_id: comm_id.to_raw(),
+            title: "The Discussion".to_string(),
+            image_uri: None,
+            chat_user_ids: Some(vec![user1_id.clone()]),
+            private_discussion_users_final: false,
+        })
+        .add_header("Authorization", format!("Bearer {}", token0))
+        .add_header("Accept", "application/json")
+        .await
+        .json::<Discussion>();
+
+    // not a member of the private discussion
+    let ctx2 = Ctx::new(Ok(user2.id.as_ref().unwrap().to_raw()), false);
+    let mut connection2 = WsConnection::new(ctx_state.clone(), ctx2, user2_id).await;
+    let messages = connection2
+        .on_client_message(
+            &json!({ "type": "Typing", "discussion_id": disc.id.to_raw() }).to_string(),
+        )
+        .await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].event, "Error");
+
+    let mut rx = ctx_state.sse_buffer.subscribe();
+    let ctx0 = Ctx::new(Ok(user0.id.as_ref().unwrap().to_raw()), false);
+    let mut connection0 = WsConnection::new(ctx_state.clone(), ctx0, user0_id.clone()).await;
+    let messages = connection0
+        .on_client_message(
+            &json!({ "type": "Typing", "discussion_id": disc.id.to_raw() }).to_string(),
+        )
+        .await;
+    assert!(messages.is_empty());
+
+    let event = timeout(Duration::from_secs(1), async {
+        loop {
+            let event = rx.recv().await.expect("Should receive valid event");
+            if event.name == "DiscussionTyping" {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("Should receive event within timeout");
+    assert_eq!(event.user_id, user0_id);
+    assert!(event.id.is_none());
+
+    let ctx1 = Ctx::new(Ok(user1.id.as_ref().unwrap().to_raw()), false);
+    let connection1 = WsConnection::new(ctx_state.clone(), ctx1, user1_id).await;
+    let messages = connection1.on_events(vec![event.clone()]).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(
+        messages[0].data.as_ref().unwrap()["discussion_id"],
+        disc.id.to_raw()
+    );
+    assert!(connection0.on_events(vec![event]).await.is_empty());
+});
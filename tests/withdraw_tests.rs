@@ -41,6 +41,7 @@ test_with_server!(
                 200000,
                 None,
                 ctx_state.withdraw_fee,
+                None,
             )
             .await;
         assert!(res.is_err());
@@ -72,6 +73,7 @@ test_with_server!(withdraw_complete, |server, ctx_state, config| {
             amount,
             None,
             ctx_state.withdraw_fee,
+            None,
         )
         .await;
     assert!(res.is_ok());
@@ -159,6 +161,7 @@ test_with_server!(withdraw_revert, |server, ctx_state, config| {
             amount,
             None,
             ctx_state.withdraw_fee,
+            None,
         )
         .await;
     assert!(res.is_ok());